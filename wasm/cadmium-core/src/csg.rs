@@ -1,15 +1,33 @@
 // CSG (Constructive Solid Geometry) Implementation
-// Implements boolean operations: union, subtract, intersect
+// Implements boolean operations: union, subtract, intersect, xor
 
 use nalgebra::{Vector3, Point3};
 use std::collections::HashMap;
+#[cfg(test)]
+use std::cell::Cell;
 
 const EPSILON: f64 = 1e-10;
 
+#[cfg(test)]
+thread_local! {
+    // Meshes cut by split_against on this thread, so tests can check that
+    // fast paths skip the backend
+    static SPLIT_COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+#[cfg(test)]
+pub fn split_count() -> usize {
+    SPLIT_COUNT.with(Cell::get)
+}
+
 #[derive(Clone, Debug)]
 pub struct Triangle {
     pub vertices: [Point3<f64>; 3],
     pub normal: Vector3<f64>,
+    // ID of the input mesh this triangle (or the one it was cut from) came from
+    pub source: u32,
+    // RGBA face color, opaque white unless the input mesh was colored
+    pub color: [f32; 4],
 }
 
 impl Triangle {
@@ -21,6 +39,8 @@ impl Triangle {
         Triangle {
             vertices: [v0, v1, v2],
             normal,
+            source: 0,
+            color: [1.0; 4],
         }
     }
     
@@ -91,6 +111,13 @@ impl AABB {
         p.z >= self.min.z && p.z <= self.max.z
     }
     
+    /// Distance from `p` to the nearest point of the box, 0 inside it
+    pub fn distance_to_point(&self, p: &Point3<f64>) -> f64 {
+        let below = self.min - p;
+        let above = p - self.max;
+        below.sup(&above).sup(&Vector3::zeros()).norm()
+    }
+    
     pub fn expand(&mut self, p: &Point3<f64>) {
         self.min.x = self.min.x.min(p.x);
         self.min.y = self.min.y.min(p.y);
@@ -139,6 +166,32 @@ impl CSGMesh {
         CSGMesh { triangles, aabb }
     }
     
+    // Tag triangles with per-face source IDs; faces without one get 0
+    pub fn with_sources(mut self, source_ids: &[u32]) -> Self {
+        for (i, tri) in self.triangles.iter_mut().enumerate() {
+            tri.source = source_ids.get(i).copied().unwrap_or(0);
+        }
+        self
+    }
+    
+    // Source ID of every face, in the order to_buffers writes them
+    pub fn source_ids(&self) -> Vec<u32> {
+        self.triangles.iter().map(|tri| tri.source).collect()
+    }
+    
+    // Color triangles per face; faces without a color keep opaque white
+    pub fn with_colors(mut self, colors: &[[f32; 4]]) -> Self {
+        for (tri, &color) in self.triangles.iter_mut().zip(colors) {
+            tri.color = color;
+        }
+        self
+    }
+    
+    // Color of every face, in the order to_buffers writes them
+    pub fn face_colors(&self) -> Vec<[f32; 4]> {
+        self.triangles.iter().map(|tri| tri.color).collect()
+    }
+    
     pub fn to_buffers(&self) -> (Vec<f64>, Vec<u32>, Vec<f64>) {
         let mut vertices = Vec::new();
         let mut faces = Vec::new();
@@ -184,9 +237,12 @@ impl CSGMesh {
             return false;
         }
         
-        // Cast ray in +X direction and count intersections
+        // Cast a ray roughly along +X and count intersections. It is skewed
+        // off the axes so that rays from points on a symmetry plane of an
+        // axis-aligned mesh do not run along the diagonal of a face, where
+        // both triangles would count the hit
         let ray_origin = *point;
-        let ray_dir = Vector3::new(1.0, 0.0, 0.0);
+        let ray_dir = Vector3::new(1.0, 1.3e-3, 2.9e-3);
         let mut intersection_count = 0;
         
         for tri in &self.triangles {
@@ -251,12 +307,127 @@ fn ray_intersects_triangle(origin: &Point3<f64>, direction: &Vector3<f64>, tri:
     t > EPSILON
 }
 
+// Whether two non-coplanar triangles cross. Where such triangles meet,
+// the ends of the shared segment lie on edges, so one triangle must have
+// an edge passing through the other.
+pub fn triangles_intersect(a: &[Point3<f64>; 3], b: &[Point3<f64>; 3]) -> bool {
+    let crosses = |edges: &[Point3<f64>; 3], tri: &[Point3<f64>; 3]| {
+        (0..3).any(|k| segment_hits_triangle(&edges[k], &edges[(k + 1) % 3], tri))
+    };
+    crosses(a, b) || crosses(b, a)
+}
+
+// Möller-Trumbore test of the segment p-q against a triangle
+fn segment_hits_triangle(p: &Point3<f64>, q: &Point3<f64>, tri: &[Point3<f64>; 3]) -> bool {
+    let direction = q - p;
+    let edge1 = tri[1] - tri[0];
+    let edge2 = tri[2] - tri[0];
+    let h = direction.cross(&edge2);
+    let det = edge1.dot(&h);
+    
+    // Parallel to (or lying in) the triangle's plane
+    if det.abs() <= EPSILON * direction.norm() * edge1.norm() * edge2.norm() {
+        return false;
+    }
+    
+    let s = p - tri[0];
+    let u = s.dot(&h) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return false;
+    }
+    let r = s.cross(&edge1);
+    let v = direction.dot(&r) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return false;
+    }
+    let t = edge2.dot(&r) / det;
+    (0.0..=1.0).contains(&t)
+}
+
+// Cut the triangles of `subject` along the surface of `cutter`. Every
+// triangle is split by the plane of each cutter triangle it crosses, so
+// no piece passes through the cutter and its centroid classifies the
+// whole piece.
+fn split_against(subject: &CSGMesh, cutter: &CSGMesh) -> Vec<Triangle> {
+    #[cfg(test)]
+    SPLIT_COUNT.with(|count| count.set(count.get() + 1));
+    
+    if !subject.aabb.intersects(&cutter.aabb) {
+        return subject.triangles.clone();
+    }
+    
+    let mut result = Vec::with_capacity(subject.triangles.len());
+    for tri in &subject.triangles {
+        let aabb = tri.compute_aabb();
+        let mut pieces = vec![tri.clone()];
+        for plane in &cutter.triangles {
+            if !plane.compute_aabb().intersects(&aabb) {
+                continue;
+            }
+            pieces = pieces
+                .into_iter()
+                .flat_map(|piece| {
+                    if triangles_intersect(&piece.vertices, &plane.vertices) {
+                        split_by_plane(&piece, plane)
+                    } else {
+                        vec![piece]
+                    }
+                })
+                .collect();
+        }
+        result.extend(pieces);
+    }
+    result
+}
+
+// Split a triangle by the plane of another, fanning each side back into
+// triangles that keep the original normal
+fn split_by_plane(tri: &Triangle, plane: &Triangle) -> Vec<Triangle> {
+    let origin = plane.vertices[0];
+    let distance: Vec<f64> = tri.vertices.iter().map(|v| plane.normal.dot(&(v - origin))).collect();
+    if !distance.iter().any(|&d| d > EPSILON) || !distance.iter().any(|&d| d < -EPSILON) {
+        return vec![tri.clone()];
+    }
+    
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        let (a, b) = (tri.vertices[i], tri.vertices[j]);
+        let (da, db) = (distance[i], distance[j]);
+        if da >= -EPSILON {
+            front.push(a);
+        }
+        if da <= EPSILON {
+            back.push(a);
+        }
+        if (da > EPSILON && db < -EPSILON) || (da < -EPSILON && db > EPSILON) {
+            let crossing = a + (b - a) * (da / (da - db));
+            front.push(crossing);
+            back.push(crossing);
+        }
+    }
+    
+    let mut pieces = Vec::new();
+    for polygon in [front, back] {
+        for k in 1..polygon.len().saturating_sub(1) {
+            pieces.push(Triangle {
+                vertices: [polygon[0], polygon[k], polygon[k + 1]],
+                normal: tri.normal,
+                source: tri.source,
+                color: tri.color,
+            });
+        }
+    }
+    pieces
+}
+
 // Simplified CSG operations
 pub fn csg_union(mesh_a: &CSGMesh, mesh_b: &CSGMesh) -> CSGMesh {
     let mut result_triangles = Vec::new();
     
     // Add triangles from A that are outside B
-    for tri in &mesh_a.triangles {
+    for tri in &split_against(mesh_a, mesh_b) {
         let centroid = compute_triangle_centroid(tri);
         if !mesh_b.is_point_inside(&centroid) {
             result_triangles.push(tri.clone());
@@ -264,7 +435,7 @@ pub fn csg_union(mesh_a: &CSGMesh, mesh_b: &CSGMesh) -> CSGMesh {
     }
     
     // Add triangles from B that are outside A
-    for tri in &mesh_b.triangles {
+    for tri in &split_against(mesh_b, mesh_a) {
         let centroid = compute_triangle_centroid(tri);
         if !mesh_a.is_point_inside(&centroid) {
             result_triangles.push(tri.clone());
@@ -283,7 +454,7 @@ pub fn csg_subtract(base: &CSGMesh, tool: &CSGMesh) -> CSGMesh {
     let mut result_triangles = Vec::new();
     
     // Keep triangles from base that are outside tool
-    for tri in &base.triangles {
+    for tri in &split_against(base, tool) {
         let centroid = compute_triangle_centroid(tri);
         if !tool.is_point_inside(&centroid) {
             result_triangles.push(tri.clone());
@@ -291,7 +462,7 @@ pub fn csg_subtract(base: &CSGMesh, tool: &CSGMesh) -> CSGMesh {
     }
     
     // Add inverted triangles from tool that are inside base
-    for tri in &tool.triangles {
+    for tri in &split_against(tool, base) {
         let centroid = compute_triangle_centroid(tri);
         if base.is_point_inside(&centroid) {
             let mut inverted = tri.clone();
@@ -310,11 +481,67 @@ pub fn csg_subtract(base: &CSGMesh, tool: &CSGMesh) -> CSGMesh {
     }
 }
 
+// Subtract several tools at once. Pieces are classified against the
+// original closed inputs rather than an intermediate result, so tools may
+// overlap each other without being combined first.
+pub fn csg_subtract_all(base: &CSGMesh, tools: &[CSGMesh]) -> CSGMesh {
+    let mut result_triangles = Vec::new();
+    let inside_tool = |point: &Point3<f64>, skip: Option<usize>| {
+        tools
+            .iter()
+            .enumerate()
+            .any(|(j, tool)| Some(j) != skip && tool.is_point_inside(point))
+    };
+    
+    // Keep pieces of base that are outside every tool
+    let all_tools: Vec<&CSGMesh> = tools.iter().collect();
+    for tri in split_against_all(base, &all_tools) {
+        if !inside_tool(&compute_triangle_centroid(&tri), None) {
+            result_triangles.push(tri);
+        }
+    }
+    
+    // Add inverted pieces of each tool that are inside base and no other tool
+    for (i, tool) in tools.iter().enumerate() {
+        let others: Vec<&CSGMesh> = std::iter::once(base)
+            .chain(tools.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, t)| t))
+            .collect();
+        for mut tri in split_against_all(tool, &others) {
+            let centroid = compute_triangle_centroid(&tri);
+            if base.is_point_inside(&centroid) && !inside_tool(&centroid, Some(i)) {
+                tri.vertices.swap(1, 2);
+                tri.normal = -tri.normal;
+                result_triangles.push(tri);
+            }
+        }
+    }
+    
+    let aabb = compute_mesh_aabb(&result_triangles);
+    
+    CSGMesh {
+        triangles: result_triangles,
+        aabb,
+    }
+}
+
+// Cut `subject` along the surfaces of every cutter in turn
+fn split_against_all(subject: &CSGMesh, cutters: &[&CSGMesh]) -> Vec<Triangle> {
+    let mut pieces = subject.triangles.clone();
+    for cutter in cutters {
+        let current = CSGMesh {
+            aabb: compute_mesh_aabb(&pieces),
+            triangles: pieces,
+        };
+        pieces = split_against(&current, cutter);
+    }
+    pieces
+}
+
 pub fn csg_intersect(mesh_a: &CSGMesh, mesh_b: &CSGMesh) -> CSGMesh {
     let mut result_triangles = Vec::new();
     
     // Keep triangles from A that are inside B
-    for tri in &mesh_a.triangles {
+    for tri in &split_against(mesh_a, mesh_b) {
         let centroid = compute_triangle_centroid(tri);
         if mesh_b.is_point_inside(&centroid) {
             result_triangles.push(tri.clone());
@@ -322,7 +549,7 @@ pub fn csg_intersect(mesh_a: &CSGMesh, mesh_b: &CSGMesh) -> CSGMesh {
     }
     
     // Keep triangles from B that are inside A
-    for tri in &mesh_b.triangles {
+    for tri in &split_against(mesh_b, mesh_a) {
         let centroid = compute_triangle_centroid(tri);
         if mesh_a.is_point_inside(&centroid) {
             result_triangles.push(tri.clone());
@@ -337,6 +564,26 @@ pub fn csg_intersect(mesh_a: &CSGMesh, mesh_b: &CSGMesh) -> CSGMesh {
     }
 }
 
+// Symmetric difference: union(a, b) - intersect(a, b). The overlap's
+// triangles are the ones the union drops, added back inverted.
+pub fn csg_xor(mesh_a: &CSGMesh, mesh_b: &CSGMesh) -> CSGMesh {
+    let mut result_triangles = csg_union(mesh_a, mesh_b).triangles;
+    
+    for tri in csg_intersect(mesh_a, mesh_b).triangles {
+        let mut inverted = tri;
+        inverted.vertices.swap(1, 2);
+        inverted.normal = -inverted.normal;
+        result_triangles.push(inverted);
+    }
+    
+    let aabb = compute_mesh_aabb(&result_triangles);
+    
+    CSGMesh {
+        triangles: result_triangles,
+        aabb,
+    }
+}
+
 fn compute_triangle_centroid(tri: &Triangle) -> Point3<f64> {
     Point3::new(
         (tri.vertices[0].x + tri.vertices[1].x + tri.vertices[2].x) / 3.0,
@@ -0,0 +1,84 @@
+// Involute spur gears: a standard full-depth tooth profile (addendum of
+// one module, dedendum of 1.25 modules) around the Z axis, extruded
+// along +Z. Flanks below the base circle run radially to the root.
+
+use crate::sketch::{extrude_sketch, Sketch};
+use crate::validation::ValidationResult;
+use crate::Mesh;
+
+/// Points along each involute flank
+const FLANK_SAMPLES: usize = 8;
+
+/// Segments across each tooth tip and each root gap
+const ARC_SEGMENTS: usize = 3;
+
+/// Closed counter-clockwise outline of a spur gear centred on the origin,
+/// with the first tooth centred on +X
+pub fn gear_profile(module: f64, teeth: u32, pressure_angle_deg: f64) -> Vec<[f64; 2]> {
+    let z = teeth as f64;
+    let pitch = module * z / 2.0;
+    let base = pitch * pressure_angle_deg.to_radians().cos();
+    let tip = pitch + module;
+    let root = pitch - 1.25 * module;
+    let flank_start = base.max(root);
+
+    // Involute parameter at a radius, and the tooth's angular half-width
+    // there (measured from the tooth centre line)
+    let roll = |radius: f64| ((radius / base).powi(2) - 1.0).max(0.0).sqrt();
+    let half_width_at_pitch = std::f64::consts::PI / (2.0 * z) + involute(roll(pitch));
+    let half_width = |t: f64| half_width_at_pitch - involute(t);
+
+    let (t_start, t_tip) = (roll(flank_start), roll(tip));
+    let flank: Vec<(f64, f64)> = (0..=FLANK_SAMPLES)
+        .map(|i| {
+            let t = t_start + (t_tip - t_start) * i as f64 / FLANK_SAMPLES as f64;
+            (base * (1.0 + t * t).sqrt(), half_width(t))
+        })
+        .collect();
+    let root_half_width = half_width(t_start);
+    let tip_half_width = half_width(t_tip);
+
+    let polar = |radius: f64, angle: f64| [radius * angle.cos(), radius * angle.sin()];
+    let pitch_angle = 2.0 * std::f64::consts::PI / z;
+    let mut points = Vec::new();
+    for k in 0..teeth {
+        let centre = pitch_angle * k as f64;
+        if root < base {
+            points.push(polar(root, centre - root_half_width));
+        }
+        points.extend(flank.iter().map(|&(r, w)| polar(r, centre - w)));
+        for j in 1..ARC_SEGMENTS {
+            let s = j as f64 / ARC_SEGMENTS as f64;
+            points.push(polar(tip, centre - tip_half_width + 2.0 * tip_half_width * s));
+        }
+        points.extend(flank.iter().rev().map(|&(r, w)| polar(r, centre + w)));
+        if root < base {
+            points.push(polar(root, centre + root_half_width));
+        }
+        let gap = pitch_angle - 2.0 * root_half_width;
+        for j in 1..ARC_SEGMENTS {
+            let s = j as f64 / ARC_SEGMENTS as f64;
+            points.push(polar(root, centre + root_half_width + gap * s));
+        }
+    }
+    points
+}
+
+/// Spur gear of the given face width, from z = 0 to z = `thickness`
+pub fn generate_gear_mesh(
+    module: f64,
+    teeth: u32,
+    thickness: f64,
+    pressure_angle_deg: f64,
+) -> ValidationResult<Mesh> {
+    extrude_sketch(&Sketch {
+        outer: gear_profile(module, teeth, pressure_angle_deg),
+        holes: Vec::new(),
+        distance: thickness,
+    })
+}
+
+/// inv(φ) = tan φ - φ, written in terms of the roll parameter t = tan φ
+fn involute(t: f64) -> f64 {
+    t - t.atan()
+}
@@ -0,0 +1,318 @@
+// Self-intersection detection: triangle pairs of one mesh that cross
+// A bounding volume hierarchy limits the exact triangle-triangle tests to
+// pairs whose bounds overlap. The same pairing between two meshes gives
+// the segments of their intersection curve, and the hierarchy also
+// answers nearest-triangle distance queries.
+
+use nalgebra::{Point3, Vector3};
+use std::collections::{HashMap, HashSet};
+
+use crate::csg::{triangles_intersect, AABB};
+use crate::{compute_bounding_box, weld_vertices, weld_vertices_within, Mesh};
+
+/// Triangles per BVH leaf
+const LEAF_SIZE: usize = 4;
+
+/// Pairs `(i, j)` with `i < j` of triangles that intersect each other
+///
+/// Vertices are welded by position first, and triangles sharing a vertex
+/// or an edge are never reported, so a closed mesh whose faces only meet
+/// along their edges yields no pairs. Coplanar overlaps are not detected.
+/// Pairs are sorted.
+pub fn find_self_intersections(mesh: &Mesh) -> Vec<(usize, usize)> {
+    let (vertices, remap) = weld_vertices(mesh);
+    let point = |i: u32| {
+        let i = remap[i as usize] as usize * 3;
+        Point3::new(vertices[i], vertices[i + 1], vertices[i + 2])
+    };
+
+    let triangles: Vec<([u32; 3], [Point3<f64>; 3])> = mesh
+        .faces
+        .chunks_exact(3)
+        .map(|f| {
+            let ids = [remap[f[0] as usize], remap[f[1] as usize], remap[f[2] as usize]];
+            (ids, [point(f[0]), point(f[1]), point(f[2])])
+        })
+        .collect();
+
+    let boxes: Vec<AABB> = triangles.iter().map(|(_, t)| triangle_aabb(t)).collect();
+    let bvh = Bvh::build(&boxes);
+
+    let mut pairs = Vec::new();
+    for (i, (ids, tri)) in triangles.iter().enumerate() {
+        for j in bvh.overlapping(&boxes, &boxes[i]) {
+            if j <= i {
+                continue;
+            }
+            let (other_ids, other) = &triangles[j];
+            if ids.iter().any(|id| other_ids.contains(id)) {
+                continue;
+            }
+            if triangles_intersect(tri, other) {
+                pairs.push((i, j));
+            }
+        }
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Polylines along which the surfaces of `a` and `b` cross
+///
+/// Every pair of crossing triangles contributes one segment; segments are
+/// joined where their ends meet, within 1e-9 of the size of `a`. Chains
+/// are split at branch points. A closed loop repeats its first point at the end,
+/// so a chain is open exactly when its ends differ. Coplanar overlaps do
+/// not produce segments.
+pub fn intersection_curve(a: &Mesh, b: &Mesh) -> Vec<Vec<[f64; 3]>> {
+    let triangles_a = triangle_points(a);
+    let triangles_b = triangle_points(b);
+    let boxes_b: Vec<AABB> = triangles_b.iter().map(triangle_aabb).collect();
+    let bvh = Bvh::build(&boxes_b);
+
+    let mut ends: Vec<f64> = Vec::new();
+    for tri in &triangles_a {
+        for j in bvh.overlapping(&boxes_b, &triangle_aabb(tri)) {
+            if let Some([p, q]) = crossing_segment(tri, &triangles_b[j]) {
+                ends.extend_from_slice(&[p.x, p.y, p.z, q.x, q.y, q.z]);
+            }
+        }
+    }
+    if ends.is_empty() {
+        return Vec::new();
+    }
+
+    // Ends found from neighbouring triangle pairs agree up to rounding
+    let bounds = compute_bounding_box(a);
+    let size = Vector3::new(
+        bounds.max_x - bounds.min_x,
+        bounds.max_y - bounds.min_y,
+        bounds.max_z - bounds.min_z,
+    )
+    .norm();
+    let (points, remap) = weld_vertices_within(&ends, 1e-9 * size.max(1.0));
+
+    // A segment along an edge shared by two triangles is found twice
+    let mut adjacency: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut seen = HashSet::new();
+    for pair in remap.chunks_exact(2) {
+        let (p, q) = (pair[0], pair[1]);
+        if p != q && seen.insert((p.min(q), p.max(q))) {
+            adjacency.entry(p).or_default().push(q);
+            adjacency.entry(q).or_default().push(p);
+        }
+    }
+
+    // Open chains start at their ends (or at branch points), then what is
+    // left are closed loops
+    let mut starts: Vec<u32> = adjacency.keys().copied().collect();
+    starts.sort_unstable_by_key(|&p| (adjacency[&p].len() == 2, p));
+    let mut used = HashSet::new();
+    let mut take = |p: u32| {
+        let q = adjacency[&p].iter().copied().find(|&q| !used.contains(&(p.min(q), p.max(q))))?;
+        used.insert((p.min(q), p.max(q)));
+        Some(q)
+    };
+    let mut chains = Vec::new();
+    for start in starts {
+        while let Some(next) = take(start) {
+            let mut chain = vec![start, next];
+            while let Some(step) = take(chain[chain.len() - 1]) {
+                chain.push(step);
+            }
+            chains.push(chain);
+        }
+    }
+
+    chains
+        .into_iter()
+        .map(|chain| {
+            chain
+                .into_iter()
+                .map(|p| {
+                    let i = p as usize * 3;
+                    [points[i], points[i + 1], points[i + 2]]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn triangle_points(mesh: &Mesh) -> Vec<[Point3<f64>; 3]> {
+    let point = |i: u32| {
+        let i = i as usize * 3;
+        Point3::new(mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2])
+    };
+    mesh.faces.chunks_exact(3).map(|f| [point(f[0]), point(f[1]), point(f[2])]).collect()
+}
+
+/// Segment shared by two triangles that cross, if they cross along more
+/// than a point
+///
+/// Each triangle meets the other's plane in a segment on the line where
+/// the two planes meet; the answer is the overlap of those two segments.
+fn crossing_segment(a: &[Point3<f64>; 3], b: &[Point3<f64>; 3]) -> Option<[Point3<f64>; 2]> {
+    let normal_a = (a[1] - a[0]).cross(&(a[2] - a[0]));
+    let normal_b = (b[1] - b[0]).cross(&(b[2] - b[0]));
+    let direction = normal_a.cross(&normal_b);
+    if direction.norm_squared() == 0.0 {
+        return None; // Parallel, coplanar or degenerate
+    }
+
+    let on_a = plane_section(b, a[0], &normal_a)?;
+    let on_b = plane_section(a, b[0], &normal_b)?;
+    let along = |p: &Point3<f64>| direction.dot(&p.coords);
+    let sorted = |[p, q]: [Point3<f64>; 2]| if along(&p) <= along(&q) { [p, q] } else { [q, p] };
+    let ([a_lo, a_hi], [b_lo, b_hi]) = (sorted(on_a), sorted(on_b));
+
+    let lo = if along(&a_lo) >= along(&b_lo) { a_lo } else { b_lo };
+    let hi = if along(&a_hi) <= along(&b_hi) { a_hi } else { b_hi };
+    (along(&lo) < along(&hi)).then_some([lo, hi])
+}
+
+/// Where a triangle meets the plane through `origin` with `normal`, if it
+/// meets it along a segment
+fn plane_section(
+    tri: &[Point3<f64>; 3],
+    origin: Point3<f64>,
+    normal: &Vector3<f64>,
+) -> Option<[Point3<f64>; 2]> {
+    let d = tri.map(|p| normal.dot(&(p - origin)));
+    let mut points = Vec::with_capacity(3);
+    for k in 0..3 {
+        let (i, j) = (k, (k + 1) % 3);
+        if d[i] == 0.0 {
+            points.push(tri[i]);
+        } else if d[j] != 0.0 && (d[i] < 0.0) != (d[j] < 0.0) {
+            let t = d[i] / (d[i] - d[j]);
+            points.push(tri[i] + (tri[j] - tri[i]) * t);
+        }
+    }
+    match points[..] {
+        [p, q] => Some([p, q]),
+        _ => None, // Misses, touches at a vertex, or lies in the plane
+    }
+}
+
+/// Median-split hierarchy over triangle bounds
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    order: Vec<usize>,
+}
+
+struct BvhNode {
+    aabb: AABB,
+    /// Child node indices, or `None` for a leaf over `order[start..end]`
+    children: Option<(usize, usize)>,
+    start: usize,
+    end: usize,
+}
+
+impl Bvh {
+    pub fn build(boxes: &[AABB]) -> Self {
+        let mut bvh = Bvh { nodes: Vec::new(), order: (0..boxes.len()).collect() };
+        if !boxes.is_empty() {
+            bvh.build_node(boxes, 0, boxes.len());
+        }
+        bvh
+    }
+
+    fn build_node(&mut self, boxes: &[AABB], start: usize, end: usize) -> usize {
+        let mut aabb = boxes[self.order[start]].clone();
+        for &i in &self.order[start + 1..end] {
+            aabb.expand(&boxes[i].min);
+            aabb.expand(&boxes[i].max);
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(BvhNode { aabb, children: None, start, end });
+        if end - start <= LEAF_SIZE {
+            return index;
+        }
+
+        // Split at the median centre along the longest axis
+        let extent = self.nodes[index].aabb.max - self.nodes[index].aabb.min;
+        let axis = extent.imax();
+        let centre = |i: &usize| boxes[*i].min[axis] + boxes[*i].max[axis];
+        let mid = (start + end) / 2;
+        self.order[start..end]
+            .select_nth_unstable_by(mid - start, |a, b| centre(a).total_cmp(&centre(b)));
+
+        let left = self.build_node(boxes, start, mid);
+        let right = self.build_node(boxes, mid, end);
+        self.nodes[index].children = Some((left, right));
+        index
+    }
+
+    /// Indices of the boxes overlapping `query`
+    fn overlapping(&self, boxes: &[AABB], query: &AABB) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut stack = if self.nodes.is_empty() { vec![] } else { vec![0] };
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if !node.aabb.intersects(query) {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => stack.extend([left, right]),
+                None => result.extend(
+                    self.order[node.start..node.end]
+                        .iter()
+                        .filter(|&&i| boxes[i].intersects(query)),
+                ),
+            }
+        }
+        result
+    }
+
+    /// Smallest `distance(i)` over the boxes, visiting only those that
+    /// could still hold something nearer to `p` than the best so far
+    ///
+    /// `distance(i)` must be at least the distance from `p` to `boxes[i]`.
+    /// Returns infinity when there are no boxes.
+    pub fn nearest(
+        &self,
+        boxes: &[AABB],
+        p: &Point3<f64>,
+        distance: impl Fn(usize) -> f64,
+    ) -> f64 {
+        let mut best = f64::INFINITY;
+        let mut stack = if self.nodes.is_empty() { vec![] } else { vec![0] };
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if node.aabb.distance_to_point(p) >= best {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => {
+                    // Pop the nearer child first so it tightens `best`
+                    let (left_distance, right_distance) = (
+                        self.nodes[left].aabb.distance_to_point(p),
+                        self.nodes[right].aabb.distance_to_point(p),
+                    );
+                    if left_distance < right_distance {
+                        stack.extend([right, left]);
+                    } else {
+                        stack.extend([left, right]);
+                    }
+                }
+                None => {
+                    for &i in &self.order[node.start..node.end] {
+                        if boxes[i].distance_to_point(p) < best {
+                            best = best.min(distance(i));
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+pub fn triangle_aabb(tri: &[Point3<f64>; 3]) -> AABB {
+    let mut aabb = AABB { min: tri[0], max: tri[0] };
+    aabb.expand(&tri[1]);
+    aabb.expand(&tri[2]);
+    aabb
+}
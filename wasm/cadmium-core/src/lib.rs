@@ -4,13 +4,24 @@
 use std::collections::HashMap;
 use std::f64::consts::PI;
 use wasm_bindgen::prelude::*;
-use nalgebra::{Vector3 as Vec3, Point3};
+use nalgebra::{Vector3 as Vec3, Point3, UnitQuaternion};
 
 mod csg;
 mod validation;
 mod material;
+mod sketch;
+mod intersect;
+mod slice;
+mod gear;
+mod isosurface;
 
-use csg::{CSGMesh, csg_union, csg_subtract, csg_intersect};
+use csg::{CSGMesh, AABB, csg_union, csg_subtract, csg_subtract_all, csg_intersect, csg_xor};
+use sketch::{extrude_sketch, parse_sketch};
+use intersect::{find_self_intersections, triangle_aabb, Bvh};
+pub use intersect::intersection_curve;
+use slice::{section_properties, slice_at_height};
+use gear::generate_gear_mesh;
+use isosurface::ScalarGrid;
 use validation::*;
 pub use material::*;
 
@@ -31,6 +42,10 @@ pub struct Mesh {
     faces: Vec<u32>,
     normals: Vec<f64>,
     material: Option<Material>,
+    /// Per-face ID of the input a face came from; empty when untagged
+    source_ids: Vec<u32>,
+    /// RGBA per vertex in 0-1; empty when uncolored
+    colors: Vec<f32>,
 }
 
 #[wasm_bindgen]
@@ -42,6 +57,8 @@ impl Mesh {
             faces,
             normals,
             material: None,
+            source_ids: Vec::new(),
+            colors: Vec::new(),
         }
     }
 
@@ -69,6 +86,22 @@ impl Mesh {
     pub fn face_count(&self) -> usize {
         self.faces.len() / 3
     }
+
+    /// Positions and normals in one copy, as `[x, y, z, nx, ny, nz]` per
+    /// vertex, ready for a GPU vertex buffer; read `faces` for the indices
+    ///
+    /// Vertices without a normal get a zero normal.
+    pub fn to_interleaved_buffer(&self) -> Vec<f32> {
+        let mut buffer = Vec::with_capacity(self.vertex_count() * 6);
+        for (i, position) in self.vertices.chunks_exact(3).enumerate() {
+            buffer.extend(position.iter().map(|&c| c as f32));
+            match self.normals.get(i * 3..i * 3 + 3) {
+                Some(normal) => buffer.extend(normal.iter().map(|&c| c as f32)),
+                None => buffer.extend([0.0; 3]),
+            }
+        }
+        buffer
+    }
     
     #[wasm_bindgen(getter)]
     pub fn material(&self) -> Option<Material> {
@@ -79,6 +112,45 @@ impl Mesh {
     pub fn set_material(&mut self, material: Material) {
         self.material = Some(material);
     }
+
+    /// Per-face source IDs carried through boolean operations, for coloring
+    /// results by origin; empty for meshes that were never tagged
+    #[wasm_bindgen(getter)]
+    pub fn source_ids(&self) -> Vec<u32> {
+        self.source_ids.clone()
+    }
+
+    /// Tag every face with one source ID
+    pub fn set_source_id(&mut self, id: u32) {
+        self.source_ids = vec![id; self.faces.len() / 3];
+    }
+
+    /// Per-vertex RGBA colors carried through booleans and merges and
+    /// written by `export_gltf` and `export_3mf_model`; empty when uncolored
+    #[wasm_bindgen(getter)]
+    pub fn colors(&self) -> Vec<f32> {
+        self.colors.clone()
+    }
+
+    /// Color every vertex, four components each; pass an empty vector to
+    /// remove the colors
+    pub fn set_colors(&mut self, colors: Vec<f32>) -> Result<(), JsValue> {
+        if !colors.is_empty() {
+            validate_colors(&colors, self.vertex_count(), "vertex").map_err(|e| e.to_js_value())?;
+        }
+        self.colors = colors.iter().map(|c| c.clamp(0.0, 1.0)).collect();
+        Ok(())
+    }
+
+    /// Color every face, four components each
+    ///
+    /// Each face gets its own three corners so neighbouring colors do not
+    /// blend.
+    pub fn set_face_colors(&mut self, colors: Vec<f32>) -> Result<(), JsValue> {
+        validate_colors(&colors, self.face_count(), "face").map_err(|e| e.to_js_value())?;
+        *self = unshare_face_colors(self, &colors);
+        Ok(())
+    }
 }
 
 #[wasm_bindgen]
@@ -92,6 +164,64 @@ pub struct BoundingBox {
     pub max_z: f64,
 }
 
+/// Mesh of planar polygon faces (quads for the sides of a box)
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct QuadMesh {
+    vertices: Vec<f64>,
+    /// Vertex indices of all polygons, concatenated
+    polygon_indices: Vec<u32>,
+    /// Start of each polygon in `polygon_indices`, plus a final end offset
+    polygon_offsets: Vec<u32>,
+    /// One unit normal per polygon
+    polygon_normals: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl QuadMesh {
+    #[wasm_bindgen(getter)]
+    pub fn vertices(&self) -> Vec<f64> {
+        self.vertices.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn polygon_indices(&self) -> Vec<u32> {
+        self.polygon_indices.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn polygon_offsets(&self) -> Vec<u32> {
+        self.polygon_offsets.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn polygon_normals(&self) -> Vec<f64> {
+        self.polygon_normals.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len() / 3
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn polygon_count(&self) -> usize {
+        self.polygon_offsets.len().saturating_sub(1)
+    }
+}
+
+/// How face normals are weighted when averaged onto shared vertices
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalWeighting {
+    /// Weight by triangle area (raw cross products)
+    Area,
+    /// Weight by the triangle's interior angle at the vertex
+    Angle,
+    /// Every adjacent triangle counts equally
+    Uniform,
+}
+
 // ============ BASIC SHAPE CREATION ============
 
 #[wasm_bindgen]
@@ -104,7 +234,7 @@ pub fn create_box(width: f64, height: f64, depth: f64) -> Result<Mesh, JsValue>
 
 #[wasm_bindgen]
 pub fn create_cylinder(radius: f64, height: f64, segments: Option<u32>) -> Result<Mesh, JsValue> {
-    let segs = segments.unwrap_or(32);
+    let segs = clamp_segments(segments.unwrap_or(32));
     validate_cylinder(radius, height, segs)
         .map_err(|e| e.to_js_value())?;
     
@@ -113,8 +243,8 @@ pub fn create_cylinder(radius: f64, height: f64, segments: Option<u32>) -> Resul
 
 #[wasm_bindgen]
 pub fn create_sphere(radius: f64, segments_lat: Option<u32>, segments_lon: Option<u32>) -> Result<Mesh, JsValue> {
-    let lat = segments_lat.unwrap_or(32);
-    let lon = segments_lon.unwrap_or(32);
+    let lat = clamp_segments(segments_lat.unwrap_or(32));
+    let lon = clamp_segments(segments_lon.unwrap_or(32));
     validate_sphere(radius, lat, lon)
         .map_err(|e| e.to_js_value())?;
     
@@ -123,17 +253,41 @@ pub fn create_sphere(radius: f64, segments_lat: Option<u32>, segments_lon: Optio
 
 #[wasm_bindgen]
 pub fn create_cone(radius: f64, height: f64, segments: Option<u32>) -> Result<Mesh, JsValue> {
-    let segs = segments.unwrap_or(32);
+    let segs = clamp_segments(segments.unwrap_or(32));
     validate_cone(radius, height, segs)
         .map_err(|e| e.to_js_value())?;
     
     Ok(generate_cone_mesh(radius, height, segs))
 }
 
+/// Truncated cone with both caps; `top_radius` 0 gives a cone and
+/// `top_radius == bottom_radius` a cylinder
+#[wasm_bindgen]
+pub fn create_frustum(bottom_radius: f64, top_radius: f64, height: f64, segments: Option<u32>) -> Result<Mesh, JsValue> {
+    let segs = clamp_segments(segments.unwrap_or(32));
+    validate_frustum(bottom_radius, top_radius, height, segs)
+        .map_err(|e| e.to_js_value())?;
+    
+    if top_radius == 0.0 {
+        return Ok(generate_cone_mesh(bottom_radius, height, segs));
+    }
+    Ok(generate_frustum_mesh(bottom_radius, top_radius, height, segs))
+}
+
+/// Involute spur gear centred on the Z axis, extruded from z = 0 to
+/// `thickness`; the pitch diameter is `module * teeth`
+#[wasm_bindgen]
+pub fn create_gear(module: f64, teeth: u32, thickness: f64, pressure_angle_deg: f64) -> Result<Mesh, JsValue> {
+    validate_gear(module, teeth, thickness, pressure_angle_deg)
+        .map_err(|e| e.to_js_value())?;
+    
+    generate_gear_mesh(module, teeth, thickness, pressure_angle_deg).map_err(|e| e.to_js_value())
+}
+
 #[wasm_bindgen]
 pub fn create_torus(major_radius: f64, minor_radius: f64, segments_major: Option<u32>, segments_minor: Option<u32>) -> Result<Mesh, JsValue> {
-    let maj = segments_major.unwrap_or(32);
-    let min = segments_minor.unwrap_or(16);
+    let maj = clamp_segments(segments_major.unwrap_or(32));
+    let min = clamp_segments(segments_minor.unwrap_or(16));
     validate_torus(major_radius, minor_radius, maj, min)
         .map_err(|e| e.to_js_value())?;
     
@@ -144,13 +298,23 @@ pub fn create_torus(major_radius: f64, minor_radius: f64, segments_major: Option
 
 #[wasm_bindgen]
 pub fn boolean_union(mesh_a: &Mesh, mesh_b: &Mesh) -> Result<Mesh, JsValue> {
-    let csg_a = CSGMesh::from_buffers(&mesh_a.vertices, &mesh_a.faces);
-    let csg_b = CSGMesh::from_buffers(&mesh_b.vertices, &mesh_b.faces);
+    validate_index_space(merged_vertex_count(&[mesh_a, mesh_b])).map_err(|e| e.to_js_value())?;
+    
+    let (sources_a, sources_b) = operand_sources(mesh_a, mesh_b);
+    let (colors_a, colors_b) = operand_colors(mesh_a, mesh_b);
+    let csg_a = CSGMesh::from_buffers(&mesh_a.vertices, &mesh_a.faces)
+        .with_sources(&sources_a)
+        .with_colors(&colors_a);
+    let csg_b = CSGMesh::from_buffers(&mesh_b.vertices, &mesh_b.faces)
+        .with_sources(&sources_b)
+        .with_colors(&colors_b);
     
     let result = csg_union(&csg_a, &csg_b);
     let (vertices, faces, normals) = result.to_buffers();
     
-    let mut mesh = Mesh { vertices, faces, normals, material: None };
+    let source_ids = result.source_ids();
+    let colors = result_colors(&result, &faces, vertices.len() / 3, !colors_a.is_empty());
+    let mut mesh = Mesh { vertices, faces, normals, material: None, source_ids, colors };
     
     // Preserve material from first mesh
     if let Some(mat) = &mesh_a.material {
@@ -162,13 +326,28 @@ pub fn boolean_union(mesh_a: &Mesh, mesh_b: &Mesh) -> Result<Mesh, JsValue> {
 
 #[wasm_bindgen]
 pub fn boolean_subtract(base_mesh: &Mesh, tool_mesh: &Mesh) -> Result<Mesh, JsValue> {
-    let csg_base = CSGMesh::from_buffers(&base_mesh.vertices, &base_mesh.faces);
-    let csg_tool = CSGMesh::from_buffers(&tool_mesh.vertices, &tool_mesh.faces);
+    // A tool that misses the base cannot remove anything
+    if !bounds_overlap(base_mesh, tool_mesh) {
+        let mut mesh = base_mesh.clone();
+        mesh.source_ids = operand_sources(base_mesh, tool_mesh).0;
+        return Ok(mesh);
+    }
+    
+    let (sources_base, sources_tool) = operand_sources(base_mesh, tool_mesh);
+    let (colors_base, colors_tool) = operand_colors(base_mesh, tool_mesh);
+    let csg_base = CSGMesh::from_buffers(&base_mesh.vertices, &base_mesh.faces)
+        .with_sources(&sources_base)
+        .with_colors(&colors_base);
+    let csg_tool = CSGMesh::from_buffers(&tool_mesh.vertices, &tool_mesh.faces)
+        .with_sources(&sources_tool)
+        .with_colors(&colors_tool);
     
     let result = csg_subtract(&csg_base, &csg_tool);
     let (vertices, faces, normals) = result.to_buffers();
     
-    let mut mesh = Mesh { vertices, faces, normals, material: None };
+    let source_ids = result.source_ids();
+    let colors = result_colors(&result, &faces, vertices.len() / 3, !colors_base.is_empty());
+    let mut mesh = Mesh { vertices, faces, normals, material: None, source_ids, colors };
     
     // Preserve material from base mesh
     if let Some(mat) = &base_mesh.material {
@@ -180,13 +359,33 @@ pub fn boolean_subtract(base_mesh: &Mesh, tool_mesh: &Mesh) -> Result<Mesh, JsVa
 
 #[wasm_bindgen]
 pub fn boolean_intersect(mesh_a: &Mesh, mesh_b: &Mesh) -> Result<Mesh, JsValue> {
-    let csg_a = CSGMesh::from_buffers(&mesh_a.vertices, &mesh_a.faces);
-    let csg_b = CSGMesh::from_buffers(&mesh_b.vertices, &mesh_b.faces);
+    // Disjoint inputs have no common volume
+    if !bounds_overlap(mesh_a, mesh_b) {
+        return Ok(Mesh {
+            vertices: Vec::new(),
+            faces: Vec::new(),
+            normals: Vec::new(),
+            material: mesh_a.material.clone(),
+            source_ids: Vec::new(),
+            colors: Vec::new(),
+        });
+    }
+    
+    let (sources_a, sources_b) = operand_sources(mesh_a, mesh_b);
+    let (colors_a, colors_b) = operand_colors(mesh_a, mesh_b);
+    let csg_a = CSGMesh::from_buffers(&mesh_a.vertices, &mesh_a.faces)
+        .with_sources(&sources_a)
+        .with_colors(&colors_a);
+    let csg_b = CSGMesh::from_buffers(&mesh_b.vertices, &mesh_b.faces)
+        .with_sources(&sources_b)
+        .with_colors(&colors_b);
     
     let result = csg_intersect(&csg_a, &csg_b);
     let (vertices, faces, normals) = result.to_buffers();
     
-    let mut mesh = Mesh { vertices, faces, normals, material: None };
+    let source_ids = result.source_ids();
+    let colors = result_colors(&result, &faces, vertices.len() / 3, !colors_a.is_empty());
+    let mut mesh = Mesh { vertices, faces, normals, material: None, source_ids, colors };
     
     // Preserve material from first mesh
     if let Some(mat) = &mesh_a.material {
@@ -196,8 +395,153 @@ pub fn boolean_intersect(mesh_a: &Mesh, mesh_b: &Mesh) -> Result<Mesh, JsValue>
     Ok(mesh)
 }
 
+#[wasm_bindgen]
+pub fn boolean_xor(mesh_a: &Mesh, mesh_b: &Mesh) -> Result<Mesh, JsValue> {
+    let (sources_a, sources_b) = operand_sources(mesh_a, mesh_b);
+    let (colors_a, colors_b) = operand_colors(mesh_a, mesh_b);
+    let csg_a = CSGMesh::from_buffers(&mesh_a.vertices, &mesh_a.faces)
+        .with_sources(&sources_a)
+        .with_colors(&colors_a);
+    let csg_b = CSGMesh::from_buffers(&mesh_b.vertices, &mesh_b.faces)
+        .with_sources(&sources_b)
+        .with_colors(&colors_b);
+    
+    let result = csg_xor(&csg_a, &csg_b);
+    let (vertices, faces, normals) = result.to_buffers();
+    
+    let source_ids = result.source_ids();
+    let colors = result_colors(&result, &faces, vertices.len() / 3, !colors_a.is_empty());
+    let mut mesh = Mesh { vertices, faces, normals, material: None, source_ids, colors };
+    
+    // Preserve material from first mesh
+    if let Some(mat) = &mesh_a.material {
+        mesh.material = Some(mat.clone());
+    }
+    
+    Ok(mesh)
+}
+
+/// Vertices in the index space of meshes combined into one
+fn merged_vertex_count(meshes: &[&Mesh]) -> u64 {
+    meshes.iter().map(|m| (m.vertices.len() / 3) as u64).sum()
+}
+
+/// Face indices shifted past `offset` earlier vertices
+///
+/// Fails instead of wrapping when a shifted index does not fit in `u32`.
+fn offset_face_indices(faces: &[u32], offset: usize) -> ValidationResult<Vec<u32>> {
+    let max_index = faces.iter().max().map_or(0, |&f| u64::from(f) + 1);
+    validate_index_space(offset as u64 + max_index)?;
+    
+    let offset = offset as u32;
+    Ok(faces.iter().map(|&f| f + offset).collect())
+}
+
+/// Per-face source IDs of two boolean operands
+///
+/// Tagged meshes keep their IDs. An untagged first operand becomes one
+/// source numbered after the second operand's IDs (0 if neither is
+/// tagged), and an untagged second operand is numbered after the first.
+/// Tag inputs with `set_source_id` to keep IDs apart across nested
+/// operations.
+fn operand_sources(mesh_a: &Mesh, mesh_b: &Mesh) -> (Vec<u32>, Vec<u32>) {
+    let next = |ids: &[u32]| ids.iter().max().map_or(0, |&max| max + 1);
+    let sources_a = if mesh_a.source_ids.is_empty() {
+        vec![next(&mesh_b.source_ids); mesh_a.faces.len() / 3]
+    } else {
+        mesh_a.source_ids.clone()
+    };
+    let sources_b = if mesh_b.source_ids.is_empty() {
+        vec![next(&sources_a); mesh_b.faces.len() / 3]
+    } else {
+        mesh_b.source_ids.clone()
+    };
+    (sources_a, sources_b)
+}
+
+/// Per-face colors of two boolean operands, each face the mean of its
+/// corners
+///
+/// An uncolored operand takes its `base_color` throughout. Both are empty
+/// when neither operand is colored, so uncolored booleans stay uncolored.
+fn operand_colors(mesh_a: &Mesh, mesh_b: &Mesh) -> (Vec<[f32; 4]>, Vec<[f32; 4]>) {
+    if mesh_a.colors.is_empty() && mesh_b.colors.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    
+    let face_colors = |mesh: &Mesh| -> Vec<[f32; 4]> {
+        if mesh.colors.is_empty() {
+            return vec![base_color(mesh); mesh.faces.len() / 3];
+        }
+        mesh.faces
+            .chunks_exact(3)
+            .map(|tri| {
+                let mut color = [0.0; 4];
+                for &i in tri {
+                    let corner = &mesh.colors[i as usize * 4..i as usize * 4 + 4];
+                    for (c, v) in color.iter_mut().zip(corner) {
+                        *c += v / 3.0;
+                    }
+                }
+                color
+            })
+            .collect()
+    };
+    (face_colors(mesh_a), face_colors(mesh_b))
+}
+
+/// Color standing in for an uncolored mesh combined with colored ones: its
+/// material's, or opaque white
+fn base_color(mesh: &Mesh) -> [f32; 4] {
+    mesh.material.as_ref().map_or([1.0; 4], |material| {
+        let rgb = material.color();
+        [rgb[0], rgb[1], rgb[2], material.opacity()]
+    })
+}
+
+/// Per-vertex colors of a boolean result, each vertex taking the color of
+/// the first face that uses it, as its normal does; empty unless `colored`
+fn result_colors(result: &CSGMesh, faces: &[u32], vertex_count: usize, colored: bool) -> Vec<f32> {
+    if !colored {
+        return Vec::new();
+    }
+    
+    let mut colors = vec![0.0; vertex_count * 4];
+    // Walk backwards so earlier faces overwrite later ones
+    for (tri, color) in faces.chunks_exact(3).zip(result.face_colors()).rev() {
+        for &i in tri {
+            colors[i as usize * 4..i as usize * 4 + 4].copy_from_slice(&color);
+        }
+    }
+    colors
+}
+
+/// Per-vertex colors after merging vertices by `remap`; a merged vertex
+/// keeps the color of the first vertex mapped onto it
+fn remap_colors(colors: &[f32], remap: &[u32], vertex_count: usize) -> Vec<f32> {
+    let mut remapped = vec![0.0; vertex_count * 4];
+    for (old, &new) in remap.iter().enumerate().rev() {
+        let new = new as usize;
+        remapped[new * 4..new * 4 + 4].copy_from_slice(&colors[old * 4..old * 4 + 4]);
+    }
+    remapped
+}
+
+/// Whether the bounding boxes of two meshes touch; empty meshes touch nothing
+fn bounds_overlap(mesh_a: &Mesh, mesh_b: &Mesh) -> bool {
+    let a = compute_bounding_box(mesh_a);
+    let b = compute_bounding_box(mesh_b);
+    a.min_x <= b.max_x && b.min_x <= a.max_x &&
+    a.min_y <= b.max_y && b.min_y <= a.max_y &&
+    a.min_z <= b.max_z && b.min_z <= a.max_z
+}
+
 // ============ FEATURE OPERATIONS ============
 
+/// Drill a cylindrical hole starting at `position` and running `depth`
+/// along `direction` (default `[0, 0, -1]`, i.e. down into the part)
+///
+/// Fails if the hole misses the geometry entirely.
 #[wasm_bindgen]
 pub fn add_hole(
     geometry_mesh: &Mesh,
@@ -206,34 +550,248 @@ pub fn add_hole(
     position_z: f64,
     diameter: f64,
     depth: f64,
+    direction: Option<Vec<f64>>,
 ) -> Result<Mesh, JsValue> {
-    validate_hole(diameter, depth)
-        .map_err(|e| e.to_js_value())?;
+    let direction = match direction.as_deref() {
+        None => [0.0, 0.0, -1.0],
+        Some(&[x, y, z]) => [x, y, z],
+        Some(other) => {
+            return Err(ValidationError::new(format!(
+                "Hole direction needs 3 components (got {})",
+                other.len()
+            ))
+            .to_js_value())
+        }
+    };
     
-    // Create a cylinder for the hole
-    let radius = diameter / 2.0;
-    let mut hole_cylinder = create_cylinder(radius, depth, Some(32))?;
+    drill_hole(geometry_mesh, [position_x, position_y, position_z], direction, diameter, depth)
+        .map_err(|e| e.to_js_value())
+}
+
+fn drill_hole(
+    mesh: &Mesh,
+    position: [f64; 3],
+    direction: [f64; 3],
+    diameter: f64,
+    depth: f64,
+) -> ValidationResult<Mesh> {
+    validate_hole(diameter, depth)?;
+    let axis = Vec3::from(direction);
+    if !axis.iter().all(|c| c.is_finite()) || axis.norm() == 0.0 {
+        return Err(ValidationError::new("Hole direction must be a non-zero vector"));
+    }
+    let tool = hole_cylinder(Vec3::from(position), axis.normalize(), diameter, depth);
+    let miss = || ValidationError::new("Hole does not intersect the geometry");
+    if !bounds_overlap(mesh, &tool) {
+        return Err(miss());
+    }
     
-    // Translate the hole cylinder to the specified position
-    let hole_mesh = translate_mesh(&hole_cylinder, position_x, position_y, position_z);
+    // Tag the tool so the result shows whether it cut anything: a hole
+    // that removes material leaves its wall behind, and one that swallows
+    // the whole part leaves nothing
+    const TOOL_SOURCE: u32 = 1;
+    let csg_base = CSGMesh::from_buffers(&mesh.vertices, &mesh.faces);
+    let csg_tool = CSGMesh::from_buffers(&tool.vertices, &tool.faces)
+        .with_sources(&vec![TOOL_SOURCE; tool.faces.len() / 3]);
+    let result = csg_subtract(&csg_base, &csg_tool);
+    if !result.triangles.is_empty() && result.triangles.iter().all(|t| t.source != TOOL_SOURCE) {
+        return Err(miss());
+    }
     
-    // Use CSG subtraction to create the hole
-    boolean_subtract(geometry_mesh, &hole_mesh)
+    let (vertices, faces, normals) = result.to_buffers();
+    Ok(Mesh {
+        vertices,
+        faces,
+        normals,
+        material: mesh.material.clone(),
+        source_ids: Vec::new(),
+        colors: Vec::new(),
+    })
+}
+
+/// Cylinder of `diameter` running `depth` from `position` along unit `axis`
+fn hole_cylinder(position: Vec3<f64>, axis: Vec3<f64>, diameter: f64, depth: f64) -> Mesh {
+    // The cylinder is built centred on the Y axis: turn Y onto the hole
+    // axis and move its centre halfway down the hole
+    let rotation = UnitQuaternion::rotation_between(&Vec3::y(), &axis)
+        .unwrap_or_else(|| UnitQuaternion::from_axis_angle(&Vec3::x_axis(), PI));
+    let centre = position + axis * (depth / 2.0);
+    let mut tool = generate_cylinder_mesh(diameter / 2.0, depth, 32);
+    for v in tool.vertices.chunks_exact_mut(3) {
+        let p = rotation * Vec3::new(v[0], v[1], v[2]) + centre;
+        v.copy_from_slice(p.as_slice());
+    }
+    tool
 }
 
+/// Round a convex edge, selected by its `list_edges` index
+///
+/// Fails if the edge is not convex or the radius does not fit (see
+/// `auto_fillet`).
 #[wasm_bindgen]
 pub fn add_fillet(
     geometry_mesh: &Mesh,
     edge_index: u32,
     radius: f64,
 ) -> Result<Mesh, JsValue> {
-    validate_fillet_radius(radius)
-        .map_err(|e| e.to_js_value())?;
+    let outcome = fillet_edges(geometry_mesh, &[edge_index], radius).map_err(|e| e.to_js_value())?;
+    if !outcome.skipped.is_empty() {
+        return Err(ValidationError::new(format!(
+            "Fillet radius {} does not fit edge {}",
+            radius, edge_index
+        ))
+        .to_js_value());
+    }
     
-    // TODO: Implement edge detection and fillet generation
-    // For now, return original mesh with a note in console
-    web_sys::console::log_1(&"Fillet operation: Full implementation pending".into());
-    Ok(geometry_mesh.clone())
+    Ok(outcome.mesh)
+}
+
+/// Mesh with edges rounded, and the requested edges left sharp because the
+/// radius did not fit
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct FilletResult {
+    mesh: Mesh,
+    skipped: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl FilletResult {
+    /// Mesh with every edge that fit rounded
+    #[wasm_bindgen(getter)]
+    pub fn mesh(&self) -> Mesh {
+        self.mesh.clone()
+    }
+
+    /// Indices, as listed by `list_edges`, of the edges left sharp
+    #[wasm_bindgen(getter)]
+    pub fn skipped(&self) -> Vec<u32> {
+        self.skipped.clone()
+    }
+}
+
+/// Round every convex edge listed by `list_edges` with `radius`
+///
+/// An edge is skipped when rounding it would eat more than half of
+/// another feature edge meeting it, since neighbouring fillets would then
+/// overlap; skipped edge indices are returned alongside the mesh. Where
+/// three rounded edges meet, the corner is trimmed by all three fillets
+/// rather than blended into a spherical patch.
+#[wasm_bindgen]
+pub fn auto_fillet(mesh: &Mesh, radius: f64) -> Result<FilletResult, JsValue> {
+    fillet_convex_edges(mesh, radius).map_err(|e| e.to_js_value())
+}
+
+fn fillet_convex_edges(mesh: &Mesh, radius: f64) -> ValidationResult<FilletResult> {
+    let convex: Vec<u32> = feature_edges(mesh)
+        .into_iter()
+        .filter(|edge| edge.convexity == EdgeConvexity::Convex)
+        .map(|edge| edge.index)
+        .collect();
+    fillet_edges(mesh, &convex, radius)
+}
+
+/// Round the given convex edges by subtracting, for each, the sliver
+/// between the edge and a cylinder of `radius` tangent to both faces
+///
+/// Cutters are built from the input geometry and extended past the edge
+/// ends, so edges meeting at a corner all trim it.
+fn fillet_edges(mesh: &Mesh, edge_indices: &[u32], radius: f64) -> ValidationResult<FilletResult> {
+    validate_fillet_radius(radius)?;
+    
+    let edges = feature_edge_faces(mesh);
+    let mut cutters = Vec::new();
+    let mut skipped = Vec::new();
+    for &index in edge_indices {
+        let (edge, normals) = edges.get(index as usize).ok_or_else(|| {
+            ValidationError::new(format!(
+                "Edge {} does not exist (mesh has {} edges)",
+                index,
+                edges.len()
+            ))
+        })?;
+        let (Some([n1, n2]), EdgeConvexity::Convex) = (normals, edge.convexity) else {
+            return Err(ValidationError::new(format!("Edge {} is not convex", index)));
+        };
+        
+        // Distance from the edge to where the fillet meets each face
+        let setback = radius * (n1.angle(n2) / 2.0).tan();
+        let fits = edges.iter().filter(|(other, _)| other.index != index).all(|(other, _)| {
+            let touches = [other.start, other.end]
+                .iter()
+                .any(|p| *p == edge.start || *p == edge.end);
+            !touches || setback <= other.length / 2.0
+        });
+        if fits {
+            cutters.push(fillet_cutter(edge, *n1, *n2, radius)?);
+        } else {
+            skipped.push(index);
+        }
+    }
+    
+    let base = CSGMesh::from_buffers(&mesh.vertices, &mesh.faces);
+    let tools: Vec<CSGMesh> = cutters
+        .iter()
+        .map(|cutter| CSGMesh::from_buffers(&cutter.vertices, &cutter.faces))
+        .collect();
+    let (vertices, faces, normals) = csg_subtract_all(&base, &tools).to_buffers();
+    
+    Ok(FilletResult {
+        mesh: Mesh {
+            vertices,
+            faces,
+            normals,
+            material: mesh.material.clone(),
+            source_ids: Vec::new(),
+            colors: Vec::new(),
+        },
+        skipped,
+    })
+}
+
+/// Prism whose cross-section is bounded by the edge and the fillet arc
+///
+/// The arc is nudged a hair towards the edge, so it crosses both faces
+/// between arc points instead of grazing them at a vertex, which would
+/// leave the inside tests of the subtraction ambiguous along the faces.
+fn fillet_cutter(edge: &EdgeInfo, n1: Vec3<f64>, n2: Vec3<f64>, radius: f64) -> ValidationResult<Mesh> {
+    let start = Vec3::from(edge.start);
+    let along = (Vec3::from(edge.end) - start) / edge.length;
+    
+    // Cross-section frame: x along n1, y in the n1/n2 plane, z along the
+    // edge (right-handed, as `extrude_sketch` expects)
+    let u = n1;
+    let v = along.cross(&n1);
+    let to_2d = |d: Vec3<f64>| [d.dot(&u), d.dot(&v)];
+    
+    let bisector = (n1 + n2).normalize();
+    let centre = -(n1 + n2) * (radius / (1.0 + n1.dot(&n2))) + bisector * (radius * 1e-3);
+    let sweep = {
+        let [x, y] = to_2d(n2);
+        y.atan2(x)
+    };
+    let steps = ((sweep.abs() / (2.0 * PI) * 32.0).ceil() as usize).max(1);
+    
+    // The edge corner, pushed out of the solid
+    let [cx, cy] = to_2d(centre);
+    let mut outline = vec![to_2d(bisector * (radius * 0.1))];
+    for k in 0..=steps {
+        let angle = sweep * k as f64 / steps as f64;
+        outline.push([cx + radius * angle.cos(), cy + radius * angle.sin()]);
+    }
+    
+    let extension = radius;
+    let mut cutter = extrude_sketch(&sketch::Sketch {
+        outer: outline,
+        holes: Vec::new(),
+        distance: edge.length + 2.0 * extension,
+    })?;
+    let origin = start - along * extension;
+    for p in cutter.vertices.chunks_exact_mut(3) {
+        let q = origin + u * p[0] + v * p[1] + along * p[2];
+        p.copy_from_slice(q.as_slice());
+    }
+    Ok(cutter)
 }
 
 #[wasm_bindgen]
@@ -251,469 +809,3951 @@ pub fn add_chamfer(
     Ok(geometry_mesh.clone())
 }
 
-// ============ EXPORT FUNCTIONS ============
+// ============ EDGE SELECTION ============
+
+/// Edges whose faces meet at less than this angle (degrees) are not listed
+const FEATURE_EDGE_ANGLE_DEG: f64 = 1.0;
 
+/// How the faces on either side of an edge meet
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeConvexity {
+    /// Faces fold away from each other (an outside corner)
+    Convex,
+    /// Faces fold towards each other (an inside corner)
+    Concave,
+    /// Only one face uses the edge
+    Boundary,
+    /// More than two faces share the edge
+    NonManifold,
+}
+
+/// A selectable edge, as passed to `add_fillet` / `add_chamfer`
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct EdgeInfo {
+    pub index: u32,
+    pub start: [f64; 3],
+    pub end: [f64; 3],
+    pub length: f64,
+    pub convexity: EdgeConvexity,
+}
+
+/// List the feature edges of a mesh for selection by index
+///
+/// Coincident vertices are welded first, and edges between coplanar
+/// triangles (such as the diagonals of a box face) are skipped, so a box
+/// reports its 12 edges. Indices follow the order edges first appear in
+/// the face list and are stable for a given mesh.
 #[wasm_bindgen]
-pub fn export_stl(mesh: &Mesh, filename: &str) -> Result<String, JsValue> {
-    let mut stl_content = format!("solid {}\n", filename);
+pub fn list_edges(mesh: &Mesh) -> JsValue {
+    serde_wasm_bindgen::to_value(&feature_edges(mesh)).unwrap()
+}
+
+fn feature_edges(mesh: &Mesh) -> Vec<EdgeInfo> {
+    feature_edge_faces(mesh).into_iter().map(|(edge, _)| edge).collect()
+}
+
+/// Feature edges with the unit normals of their two faces, when they have
+/// exactly two
+fn feature_edge_faces(mesh: &Mesh) -> Vec<(EdgeInfo, Option<[Vec3<f64>; 2]>)> {
+    let vertex_count = mesh.vertices.len() / 3;
+    let (vertices, remap) = weld_vertices(mesh);
+    let point = |i: u32| {
+        let i = i as usize * 3;
+        Vec3::new(vertices[i], vertices[i + 1], vertices[i + 2])
+    };
     
-    for i in (0..mesh.faces.len()).step_by(3) {
-        let idx_a = mesh.faces[i] as usize;
-        let idx_b = mesh.faces[i + 1] as usize;
-        let idx_c = mesh.faces[i + 2] as usize;
-        
-        let v0 = [mesh.vertices[idx_a * 3], mesh.vertices[idx_a * 3 + 1], mesh.vertices[idx_a * 3 + 2]];
-        let v1 = [mesh.vertices[idx_b * 3], mesh.vertices[idx_b * 3 + 1], mesh.vertices[idx_b * 3 + 2]];
-        let v2 = [mesh.vertices[idx_c * 3], mesh.vertices[idx_c * 3 + 1], mesh.vertices[idx_c * 3 + 2]];
-        
-        // Calculate normal
-        let e1 = [v1[0] - v0[0], v1[1] - v0[1], v1[2] - v0[2]];
-        let e2 = [v2[0] - v0[0], v2[1] - v0[1], v2[2] - v0[2]];
-        let nx = e1[1] * e2[2] - e1[2] * e2[1];
-        let ny = e1[2] * e2[0] - e1[0] * e2[2];
-        let nz = e1[0] * e2[1] - e1[1] * e2[0];
-        let len = (nx * nx + ny * ny + nz * nz).sqrt();
-        
-        let (nx, ny, nz) = if len > 0.0 {
-            (nx / len, ny / len, nz / len)
-        } else {
-            (0.0, 0.0, 1.0)
+    let mut triangles: Vec<[u32; 3]> = Vec::new();
+    let mut normals: Vec<Vec3<f64>> = Vec::new();
+    for tri in mesh.faces.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        let t = [remap[tri[0] as usize], remap[tri[1] as usize], remap[tri[2] as usize]];
+        let n = (point(t[1]) - point(t[0])).cross(&(point(t[2]) - point(t[0])));
+        if n.norm() > 0.0 {
+            triangles.push(t);
+            normals.push(n.normalize());
+        }
+    }
+    
+    // Triangles on each undirected edge, in first-seen edge order
+    let mut order: Vec<(u32, u32)> = Vec::new();
+    let mut edge_triangles: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            let users = edge_triangles.entry((a.min(b), a.max(b))).or_default();
+            if users.is_empty() {
+                order.push((a.min(b), a.max(b)));
+            }
+            users.push(i);
+        }
+    }
+    
+    let cos_feature = FEATURE_EDGE_ANGLE_DEG.to_radians().cos();
+    let mut edges = Vec::new();
+    for (a, b) in order {
+        let users = &edge_triangles[&(a, b)];
+        let mut faces = None;
+        let convexity = match users.as_slice() {
+            [_] => EdgeConvexity::Boundary,
+            &[t1, t2] => {
+                faces = Some([normals[t1], normals[t2]]);
+                if normals[t1].dot(&normals[t2]) >= cos_feature {
+                    continue;
+                }
+                // Convex when the neighbour's far corner lies behind the first face
+                let far = triangles[t2].iter().copied().find(|&v| v != a && v != b).unwrap_or(a);
+                if normals[t1].dot(&(point(far) - point(a))) < 0.0 {
+                    EdgeConvexity::Convex
+                } else {
+                    EdgeConvexity::Concave
+                }
+            }
+            _ => EdgeConvexity::NonManifold,
         };
         
-        stl_content.push_str(&format!("  facet normal {} {} {}\n", nx, ny, nz));
-        stl_content.push_str("    outer loop\n");
-        stl_content.push_str(&format!("      vertex {} {} {}\n", v0[0], v0[1], v0[2]));
-        stl_content.push_str(&format!("      vertex {} {} {}\n", v1[0], v1[1], v1[2]));
-        stl_content.push_str(&format!("      vertex {} {} {}\n", v2[0], v2[1], v2[2]));
-        stl_content.push_str("    endloop\n");
-        stl_content.push_str("  endfacet\n");
+        let (start, end) = (point(a), point(b));
+        let info = EdgeInfo {
+            index: edges.len() as u32,
+            start: [start.x, start.y, start.z],
+            end: [end.x, end.y, end.z],
+            length: (end - start).norm(),
+            convexity,
+        };
+        edges.push((info, faces));
     }
-    
-    stl_content.push_str("endsolid\n");
-    Ok(stl_content)
+    edges
 }
 
-#[wasm_bindgen]
-pub fn export_obj(mesh: &Mesh, filename: &str) -> Result<String, JsValue> {
-    let mut obj_content = format!("# OBJ file exported from Cadmium-Core\n");
-    obj_content.push_str(&format!("# Filename: {}\n\n", filename));
+/// Open edges of a mesh, stitched into polylines for highlighting holes
+///
+/// Coincident vertices are welded first, so only edges used by exactly one
+/// triangle count. Each loop follows the winding of its triangles and is
+/// closed implicitly (the first point is not repeated). Where a boundary
+/// does not close, as at a non-manifold vertex, the open chain is returned
+/// as it stands.
+pub fn boundary_loops(mesh: &Mesh) -> Vec<Vec<[f64; 3]>> {
+    let vertex_count = mesh.vertices.len() / 3;
+    let (vertices, remap) = weld_vertices(mesh);
     
-    // Write vertices
-    for i in (0..mesh.vertices.len()).step_by(3) {
-        obj_content.push_str(&format!("v {} {} {}\n", 
-            mesh.vertices[i], 
-            mesh.vertices[i + 1], 
-            mesh.vertices[i + 2]
-        ));
+    let mut uses: HashMap<(u32, u32), usize> = HashMap::new();
+    let mut directed: Vec<(u32, u32)> = Vec::new();
+    for tri in mesh.faces.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        let t = [remap[tri[0] as usize], remap[tri[1] as usize], remap[tri[2] as usize]];
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            if a != b {
+                *uses.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+                directed.push((a, b));
+            }
+        }
     }
     
-    obj_content.push_str("\n");
-    
-    // Write faces (OBJ uses 1-based indexing)
-    for i in (0..mesh.faces.len()).step_by(3) {
-        obj_content.push_str(&format!("f {} {} {}\n", 
-            mesh.faces[i] + 1, 
-            mesh.faces[i + 1] + 1, 
-            mesh.faces[i + 2] + 1
-        ));
+    let mut next: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (a, b) in directed {
+        if uses[&(a.min(b), a.max(b))] == 1 {
+            next.entry(a).or_default().push(b);
+        }
     }
     
-    Ok(obj_content)
+    let mut starts: Vec<u32> = next.keys().copied().collect();
+    starts.sort_unstable();
+    let mut loops = Vec::new();
+    for start in starts {
+        let mut indices = Vec::new();
+        let mut current = start;
+        while let Some(following) = next.get_mut(&current).and_then(|out| out.pop()) {
+            indices.push(current);
+            current = following;
+            if current == start {
+                break;
+            }
+        }
+        if current != start {
+            indices.push(current);
+        }
+        if indices.len() > 1 {
+            loops.push(
+                indices
+                    .iter()
+                    .map(|&i| {
+                        let i = i as usize * 3;
+                        [vertices[i], vertices[i + 1], vertices[i + 2]]
+                    })
+                    .collect(),
+            );
+        }
+    }
+    loops
 }
 
-// ============ MESH UTILITIES ============
+/// `boundary_loops` for JS, as an array of `[x, y, z]` point arrays
+#[wasm_bindgen(js_name = boundary_loops)]
+pub fn boundary_loops_js(mesh: &Mesh) -> JsValue {
+    serde_wasm_bindgen::to_value(&boundary_loops(mesh)).unwrap()
+}
+
+// ============ SNAPPING ============
+
+/// Kind of point a cursor snapped to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapKind {
+    Vertex,
+    EdgeMidpoint,
+    FaceCentroid,
+}
+
+/// Snap target picked near where a cursor ray meets the mesh
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SnapResult {
+    pub kind: SnapKind,
+    pub position: [f64; 3],
+    /// Where the ray first hits the surface
+    pub hit: [f64; 3],
+    /// Index of the triangle the ray hit
+    pub triangle: usize,
+    /// Distance from the hit to the snap target
+    pub distance: f64,
+}
 
-fn translate_mesh(mesh: &Mesh, tx: f64, ty: f64, tz: f64) -> Mesh {
-    let mut vertices = mesh.vertices.clone();
+/// Snap a cursor ray to the nearest vertex, edge midpoint or face centroid
+///
+/// The ray is cast against both sides of every triangle. Targets are the
+/// ends and midpoints of the feature edges listed by `list_edges`, and the
+/// centroid of the planar face that was hit (coplanar triangles grown from
+/// the hit triangle). The nearest target within `snap_radius` of the hit
+/// wins, vertices first on ties. Returns `None` on a miss or when nothing
+/// is close enough.
+pub fn snap_query(
+    mesh: &Mesh,
+    ray_origin: [f64; 3],
+    ray_dir: [f64; 3],
+    snap_radius: f64,
+) -> Option<SnapResult> {
+    let origin = Vec3::new(ray_origin[0], ray_origin[1], ray_origin[2]);
+    let dir = Vec3::new(ray_dir[0], ray_dir[1], ray_dir[2]).try_normalize(0.0)?;
+    let (triangle, t) = raycast(mesh, origin, dir)?;
+    let hit = origin + dir * t;
     
-    for i in (0..vertices.len()).step_by(3) {
-        vertices[i] += tx;
-        vertices[i + 1] += ty;
-        vertices[i + 2] += tz;
+    let mut candidates: Vec<(SnapKind, Vec3<f64>)> = Vec::new();
+    let edges = feature_edges(mesh);
+    for edge in &edges {
+        for end in [edge.start, edge.end] {
+            candidates.push((SnapKind::Vertex, Vec3::new(end[0], end[1], end[2])));
+        }
+    }
+    for edge in &edges {
+        let mid = [0, 1, 2].map(|k| (edge.start[k] + edge.end[k]) / 2.0);
+        candidates.push((SnapKind::EdgeMidpoint, Vec3::new(mid[0], mid[1], mid[2])));
+    }
+    if let Some(centroid) = planar_face_centroid(mesh, triangle) {
+        candidates.push((SnapKind::FaceCentroid, centroid));
     }
     
-    Mesh {
-        vertices,
-        faces: mesh.faces.clone(),
-        normals: mesh.normals.clone(),
-        material: mesh.material.clone(),
+    let mut best: Option<(SnapKind, Vec3<f64>, f64)> = None;
+    for (kind, position) in candidates {
+        let distance = (position - hit).norm();
+        if distance <= snap_radius && best.is_none_or(|(_, _, d)| distance < d) {
+            best = Some((kind, position, distance));
+        }
     }
+    
+    best.map(|(kind, position, distance)| SnapResult {
+        kind,
+        position: [position.x, position.y, position.z],
+        hit: [hit.x, hit.y, hit.z],
+        triangle,
+        distance,
+    })
 }
 
-#[wasm_bindgen]
-pub fn compute_bounding_box(mesh: &Mesh) -> BoundingBox {
-    let mut min_x = f64::INFINITY;
-    let mut min_y = f64::INFINITY;
-    let mut min_z = f64::INFINITY;
-    let mut max_x = f64::NEG_INFINITY;
-    let mut max_y = f64::NEG_INFINITY;
-    let mut max_z = f64::NEG_INFINITY;
+/// `snap_query` with the ray coming from JS as `[x, y, z]` arrays;
+/// resolves to `null` when nothing is snapped
+#[wasm_bindgen(js_name = snap_query)]
+pub fn snap_query_js(
+    mesh: &Mesh,
+    ray_origin: Vec<f64>,
+    ray_dir: Vec<f64>,
+    snap_radius: f64,
+) -> Result<JsValue, JsValue> {
+    let origin = validate_point(&ray_origin, "Ray origin").map_err(|e| e.to_js_value())?;
+    let dir = validate_direction(&ray_dir).map_err(|e| e.to_js_value())?;
+    if !snap_radius.is_finite() || snap_radius < 0.0 {
+        return Err(ValidationError::new(format!(
+            "Snap radius must be non-negative (got {})",
+            snap_radius
+        ))
+        .to_js_value());
+    }
+    
+    Ok(serde_wasm_bindgen::to_value(&snap_query(mesh, origin, dir, snap_radius)).unwrap())
+}
 
-    for i in (0..mesh.vertices.len()).step_by(3) {
-        min_x = min_x.min(mesh.vertices[i]);
-        max_x = max_x.max(mesh.vertices[i]);
-        min_y = min_y.min(mesh.vertices[i + 1]);
-        max_y = max_y.max(mesh.vertices[i + 1]);
-        min_z = min_z.min(mesh.vertices[i + 2]);
-        max_z = max_z.max(mesh.vertices[i + 2]);
+/// Nearest triangle a unit-direction ray hits, with the distance along it
+fn raycast(mesh: &Mesh, origin: Vec3<f64>, dir: Vec3<f64>) -> Option<(usize, f64)> {
+    let vertex_count = mesh.vertices.len() / 3;
+    let point = |i: u32| {
+        let i = i as usize * 3;
+        Vec3::new(mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2])
+    };
+    
+    let mut nearest: Option<(usize, f64)> = None;
+    for (index, tri) in mesh.faces.chunks_exact(3).enumerate() {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        // Möller-Trumbore, accepting hits from either side
+        let (a, b, c) = (point(tri[0]), point(tri[1]), point(tri[2]));
+        let (e1, e2) = (b - a, c - a);
+        let p = dir.cross(&e2);
+        let det = e1.dot(&p);
+        if det.abs() < 1e-12 {
+            continue;
+        }
+        let s = origin - a;
+        let u = s.dot(&p) / det;
+        let q = s.cross(&e1);
+        let v = dir.dot(&q) / det;
+        let t = e2.dot(&q) / det;
+        if u < 0.0 || v < 0.0 || u + v > 1.0 || t <= 1e-9 {
+            continue;
+        }
+        if nearest.is_none_or(|(_, best)| t < best) {
+            nearest = Some((index, t));
+        }
     }
+    nearest
+}
 
-    BoundingBox {
-        min_x,
-        min_y,
-        min_z,
-        max_x,
-        max_y,
-        max_z,
+/// Area-weighted centroid of the triangles coplanar with and connected to
+/// `triangle`
+fn planar_face_centroid(mesh: &Mesh, triangle: usize) -> Option<Vec3<f64>> {
+    let vertex_count = mesh.vertices.len() / 3;
+    let (vertices, remap) = weld_vertices(mesh);
+    let point = |i: u32| {
+        let i = i as usize * 3;
+        Vec3::new(vertices[i], vertices[i + 1], vertices[i + 2])
+    };
+    
+    let triangles: Vec<Option<[u32; 3]>> = mesh
+        .faces
+        .chunks_exact(3)
+        .map(|tri| {
+            (!tri.iter().any(|&i| i as usize >= vertex_count))
+                .then(|| [remap[tri[0] as usize], remap[tri[1] as usize], remap[tri[2] as usize]])
+        })
+        .collect();
+    let area_vector = |t: [u32; 3]| (point(t[1]) - point(t[0])).cross(&(point(t[2]) - point(t[0])));
+    
+    let mut edge_triangles: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        let Some(t) = t else { continue };
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            edge_triangles.entry((a.min(b), a.max(b))).or_default().push(i);
+        }
     }
+    
+    let seed = area_vector((*triangles.get(triangle)?)?).try_normalize(0.0)?;
+    let cos_feature = FEATURE_EDGE_ANGLE_DEG.to_radians().cos();
+    let mut visited = vec![false; triangles.len()];
+    let mut stack = vec![triangle];
+    visited[triangle] = true;
+    let (mut area, mut weighted) = (0.0, Vec3::zeros());
+    while let Some(i) = stack.pop() {
+        let t = triangles[i].unwrap();
+        let doubled = area_vector(t).norm();
+        area += doubled;
+        weighted += (point(t[0]) + point(t[1]) + point(t[2])) * (doubled / 3.0);
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            for &j in &edge_triangles[&(a.min(b), a.max(b))] {
+                let coplanar = triangles[j]
+                    .and_then(|u| area_vector(u).try_normalize(0.0))
+                    .is_some_and(|n| n.dot(&seed) >= cos_feature);
+                if !visited[j] && coplanar {
+                    visited[j] = true;
+                    stack.push(j);
+                }
+            }
+        }
+    }
+    (area > 0.0).then(|| weighted / area)
 }
 
+// ============ PLANAR CUT ============
+
+/// Signed distances closer than this to the cutting plane count as on it
+const PLANE_EPSILON: f64 = 1e-9;
+
+/// The two halves of a mesh cut by a plane
 #[wasm_bindgen]
-pub fn compute_mesh_hash(mesh: &Mesh) -> String {
-    use sha2::{Sha256, Digest};
+#[derive(Clone, Debug)]
+pub struct CutResult {
+    above: Mesh,
+    below: Mesh,
+}
 
-    let mut hasher = Sha256::new();
-    for &v in &mesh.vertices {
-        hasher.update(v.to_le_bytes());
+#[wasm_bindgen]
+impl CutResult {
+    /// Half on the side the plane normal points to
+    #[wasm_bindgen(getter)]
+    pub fn above(&self) -> Mesh {
+        self.above.clone()
     }
-    for &f in &mesh.faces {
-        hasher.update(f.to_le_bytes());
+
+    /// Half on the opposite side of the plane normal
+    #[wasm_bindgen(getter)]
+    pub fn below(&self) -> Mesh {
+        self.below.clone()
     }
+}
 
-    format!("{:x}", hasher.finalize())
+#[wasm_bindgen]
+pub fn cut_with_plane(
+    mesh: &Mesh,
+    point_x: f64,
+    point_y: f64,
+    point_z: f64,
+    normal_x: f64,
+    normal_y: f64,
+    normal_z: f64,
+) -> Result<CutResult, JsValue> {
+    let (above, below) = cut_mesh_with_plane(
+        mesh,
+        [point_x, point_y, point_z],
+        [normal_x, normal_y, normal_z],
+    )
+    .map_err(|e| e.to_js_value())?;
+    
+    Ok(CutResult { above, below })
 }
 
-// ============ ASSET VALIDATION ============
+/// Split a closed mesh in two along a plane and cap both cut faces
+///
+/// Straddling triangles are clipped at shared edge intersections, so a
+/// watertight input gives watertight halves. Each cross-section loop is
+/// capped separately; for sections with holes the inner cap is wound
+/// opposite to the outer one, which keeps volumes correct.
+fn cut_mesh_with_plane(
+    mesh: &Mesh,
+    plane_point: [f64; 3],
+    plane_normal: [f64; 3],
+) -> ValidationResult<(Mesh, Mesh)> {
+    let normal = Vec3::from(plane_normal);
+    if !normal.iter().all(|c| c.is_finite()) || normal.norm() == 0.0 {
+        return Err(ValidationError::new("Plane normal must be a non-zero vector"));
+    }
+    let normal = normal.normalize();
+    let origin = Vec3::from(plane_point);
+    
+    let vertex_count = mesh.vertices.len() / 3;
+    let mut points: Vec<Vec3<f64>> = mesh
+        .vertices
+        .chunks_exact(3)
+        .map(|v| Vec3::new(v[0], v[1], v[2]))
+        .collect();
+    let mut distances: Vec<f64> = points
+        .iter()
+        .map(|p| {
+            let d = (p - origin).dot(&normal);
+            if d.abs() < PLANE_EPSILON { 0.0 } else { d }
+        })
+        .collect();
+    
+    // Intersection points are shared by both triangles of an edge
+    let mut crossings: HashMap<(u32, u32), u32> = HashMap::new();
+    let mut above_faces = Vec::new();
+    let mut below_faces = Vec::new();
+    
+    for tri in mesh.faces.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        
+        for (side, faces) in [(1.0, &mut above_faces), (-1.0, &mut below_faces)] {
+            // Sutherland-Hodgman against one half-space
+            let mut polygon = Vec::with_capacity(4);
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                let (da, db) = (distances[a as usize], distances[b as usize]);
+                if da * side >= 0.0 {
+                    polygon.push(a);
+                }
+                if da * db < 0.0 {
+                    let key = (a.min(b), a.max(b));
+                    let index = *crossings.entry(key).or_insert_with(|| {
+                        let (p, q) = (points[key.0 as usize], points[key.1 as usize]);
+                        let (dp, dq) = (distances[key.0 as usize], distances[key.1 as usize]);
+                        points.push(p + (q - p) * (dp / (dp - dq)));
+                        distances.push(0.0);
+                        (points.len() - 1) as u32
+                    });
+                    polygon.push(index);
+                }
+            }
+            
+            // Triangles lying in the plane are replaced by the caps
+            if polygon.len() < 3 || polygon.iter().all(|&i| distances[i as usize] == 0.0) {
+                continue;
+            }
+            for i in 1..polygon.len() - 1 {
+                faces.extend_from_slice(&[polygon[0], polygon[i], polygon[i + 1]]);
+            }
+        }
+    }
+    
+    let above = build_cut_half(&points, above_faces, &normal, mesh.material.clone());
+    let below = build_cut_half(&points, below_faces, &normal, mesh.material.clone());
+    Ok((above, below))
+}
+
+/// Cap the open cross-section of one half and compact its vertices
+fn build_cut_half(
+    points: &[Vec3<f64>],
+    mut faces: Vec<u32>,
+    plane_normal: &Vec3<f64>,
+    material: Option<Material>,
+) -> Mesh {
+    // Boundary edges run one way around the section; caps run the other way
+    let directed: std::collections::HashSet<(u32, u32)> = faces
+        .chunks_exact(3)
+        .flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])])
+        .collect();
+    let mut next: HashMap<u32, u32> = HashMap::new();
+    for &(a, b) in &directed {
+        if !directed.contains(&(b, a)) {
+            next.insert(b, a);
+        }
+    }
+    
+    // In-plane basis for triangulating the section loops
+    let helper = if plane_normal.x.abs() < 0.9 { Vec3::x() } else { Vec3::y() };
+    let u = plane_normal.cross(&helper).normalize();
+    let v = plane_normal.cross(&u);
+    
+    let mut starts: Vec<u32> = next.keys().copied().collect();
+    starts.sort_unstable();
+    for start in starts {
+        let mut loop_indices = Vec::new();
+        let mut current = start;
+        while let Some(following) = next.remove(&current) {
+            loop_indices.push(current);
+            current = following;
+        }
+        if loop_indices.len() < 3 {
+            continue;
+        }
+        
+        let projected: Vec<[f64; 2]> = loop_indices
+            .iter()
+            .map(|&i| [points[i as usize].dot(&u), points[i as usize].dot(&v)])
+            .collect();
+        for [a, b, c] in ear_clip(&projected) {
+            faces.extend_from_slice(&[loop_indices[a], loop_indices[b], loop_indices[c]]);
+        }
+    }
+    
+    // Keep only the vertices this half uses
+    let mut remap: HashMap<u32, u32> = HashMap::new();
+    let mut vertices = Vec::new();
+    for index in faces.iter_mut() {
+        *index = *remap.entry(*index).or_insert_with(|| {
+            let p = points[*index as usize];
+            vertices.extend_from_slice(&[p.x, p.y, p.z]);
+            (vertices.len() / 3 - 1) as u32
+        });
+    }
+    
+    let mut normals = vec![0.0; vertices.len()];
+    compute_normals(&vertices, &faces, &mut normals);
+    
+    Mesh { vertices, faces, normals, material, source_ids: Vec::new(), colors: Vec::new() }
+}
+
+/// Ear-clipping triangulation of a simple polygon, keeping its winding
+///
+/// Holes may be spliced in through zero-width bridges (see `sketch`).
+fn ear_clip(polygon: &[[f64; 2]]) -> Vec<[usize; 3]> {
+    let cross = |o: [f64; 2], a: [f64; 2], b: [f64; 2]| {
+        (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+    };
+    
+    // Positive for counter-clockwise loops
+    let orientation = (0..polygon.len())
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum::<f64>()
+        .signum();
+    
+    let mut remaining: Vec<usize> = (0..polygon.len()).collect();
+    let mut triangles = Vec::new();
+    
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]);
+            let (pa, pb, pc) = (polygon[a], polygon[b], polygon[c]);
+            if cross(pa, pb, pc) * orientation <= 0.0 {
+                return false; // Reflex or collinear corner
+            }
+            // Points coinciding with a corner are bridge duplicates, not blockers
+            remaining.iter().all(|&j| {
+                let p = polygon[j];
+                j == a || j == b || j == c || p == pa || p == pb || p == pc || {
+                    cross(pa, pb, p) * orientation < 0.0
+                        || cross(pb, pc, p) * orientation < 0.0
+                        || cross(pc, pa, p) * orientation < 0.0
+                }
+            })
+        });
+        
+        // Numerically degenerate loops: fall back to a fan over what is left
+        let Some(i) = ear else { break };
+        triangles.push([remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]]);
+        remaining.remove(i);
+    }
+    
+    for i in 1..remaining.len().saturating_sub(1) {
+        triangles.push([remaining[0], remaining[i], remaining[i + 1]]);
+    }
+    triangles
+}
+
+// ============ SKETCH EXTRUSION ============
 
+/// Extrude a closed 2D sketch in the XY plane along +Z
+///
+/// `sketch_json` is `{"outer": [[x, y], ...], "holes": [[[x, y], ...], ...],
+/// "distance": d}`; holes are optional and loops may use either winding.
 #[wasm_bindgen]
-pub fn validate_asset(asset_data: &[u8]) -> Result<JsValue, JsValue> {
-    if asset_data.len() < 4 {
-        return Err(JsValue::from_str("Invalid file: too small"));
+pub fn sketch_extrude(sketch_json: &str) -> Result<Mesh, JsValue> {
+    parse_sketch(sketch_json)
+        .and_then(|sketch| extrude_sketch(&sketch))
+        .map_err(|e| e.to_js_value())
+}
+
+// ============ SELF-INTERSECTION ============
+
+/// Report pairs of triangles that cross each other, as `[[i, j], ...]`
+///
+/// Run before boolean operations or export: the centroid classification
+/// used by the CSG functions gives wrong results on self-intersecting
+/// input. An empty list means no crossings were found.
+#[wasm_bindgen]
+pub fn detect_self_intersections(mesh: &Mesh) -> JsValue {
+    serde_wasm_bindgen::to_value(&find_self_intersections(mesh)).unwrap()
+}
+
+/// Curves where the surfaces of two meshes cross, as `[[[x, y, z], ...], ...]`
+///
+/// Closed loops end with their first point repeated. Unlike
+/// `boolean_intersect` this yields the curves themselves, for fitting
+/// and inspection.
+#[wasm_bindgen(js_name = intersection_curve)]
+pub fn intersection_curve_js(mesh_a: &Mesh, mesh_b: &Mesh) -> JsValue {
+    serde_wasm_bindgen::to_value(&intersection_curve(mesh_a, mesh_b)).unwrap()
+}
+
+// ============ SLICING ============
+
+/// Section contours at height `z`, as `[[[x, y], ...], ...]`
+///
+/// Loops are closed implicitly; pass them to `export_dxf` for 2D
+/// fabrication.
+#[wasm_bindgen]
+pub fn slice_mesh(mesh: &Mesh, z: f64) -> JsValue {
+    serde_wasm_bindgen::to_value(&slice_at_height(mesh, z)).unwrap()
+}
+
+/// Area, centroid and second moments of the section cut by a plane
+#[wasm_bindgen]
+pub fn mesh_section_properties(
+    mesh: &Mesh,
+    point_x: f64,
+    point_y: f64,
+    point_z: f64,
+    normal_x: f64,
+    normal_y: f64,
+    normal_z: f64,
+) -> Result<JsValue, JsValue> {
+    let props = section_properties(
+        mesh,
+        [point_x, point_y, point_z],
+        [normal_x, normal_y, normal_z],
+    )
+    .map_err(|e| e.to_js_value())?;
+    
+    Ok(serde_wasm_bindgen::to_value(&props).unwrap())
+}
+
+// ============ EXPORT FUNCTIONS ============
+
+/// With `embed_hash`, the `solid` line also carries `hash=<compute_mesh_hash>`
+/// so the file can be traced back to the geometry it came from
+///
+/// STL stores every facet's corners separately, so a re-imported file is
+/// an unindexed triangle soup; use `export_obj` to keep shared vertices.
+/// Run `prepare_for_print` first to get a welded, watertight mesh.
+///
+/// With `emit_zero_normals`, every facet is written as `facet normal 0 0 0`
+/// so slicers derive normals from the winding instead of trusting ours.
+#[wasm_bindgen]
+pub fn export_stl(
+    mesh: &Mesh,
+    filename: &str,
+    require_watertight: Option<bool>,
+    embed_hash: Option<bool>,
+    emit_zero_normals: Option<bool>,
+) -> Result<String, JsValue> {
+    write_stl(
+        mesh,
+        filename,
+        require_watertight.unwrap_or(false),
+        embed_hash.unwrap_or(false),
+        emit_zero_normals.unwrap_or(false),
+    )
+    .map_err(|e| e.to_js_value())
+}
+
+fn write_stl(
+    mesh: &Mesh,
+    filename: &str,
+    require_watertight: bool,
+    embed_hash: bool,
+    emit_zero_normals: bool,
+) -> ValidationResult<String> {
+    // Open meshes print poorly; refuse them up front when asked to
+    if require_watertight {
+        validate_watertight(&mesh.vertices, &mesh.faces)?;
+    }
+    
+    // ASCII STL has no comments; readers take the rest of this line as the name
+    let mut stl_content = if embed_hash {
+        format!("solid {} hash={}\n", filename, compute_mesh_hash(mesh))
+    } else {
+        format!("solid {}\n", filename)
+    };
+    
+    for i in (0..mesh.faces.len()).step_by(3) {
+        let idx_a = mesh.faces[i] as usize;
+        let idx_b = mesh.faces[i + 1] as usize;
+        let idx_c = mesh.faces[i + 2] as usize;
+        
+        let v0 = [mesh.vertices[idx_a * 3], mesh.vertices[idx_a * 3 + 1], mesh.vertices[idx_a * 3 + 2]];
+        let v1 = [mesh.vertices[idx_b * 3], mesh.vertices[idx_b * 3 + 1], mesh.vertices[idx_b * 3 + 2]];
+        let v2 = [mesh.vertices[idx_c * 3], mesh.vertices[idx_c * 3 + 1], mesh.vertices[idx_c * 3 + 2]];
+        
+        // Same normal `compute_normals` stores; degenerate facets get a zero
+        // normal, which readers take as "derive it from the winding"
+        let normal = if emit_zero_normals {
+            Vec3::zeros()
+        } else {
+            area_normal(&[v0, v1, v2].map(Vec3::from))
+                .try_normalize(0.0)
+                .unwrap_or_else(Vec3::zeros)
+        };
+        let (nx, ny, nz) = (normal.x, normal.y, normal.z);
+        
+        stl_content.push_str(&format!("  facet normal {} {} {}\n", nx, ny, nz));
+        stl_content.push_str("    outer loop\n");
+        stl_content.push_str(&format!("      vertex {} {} {}\n", v0[0], v0[1], v0[2]));
+        stl_content.push_str(&format!("      vertex {} {} {}\n", v1[0], v1[1], v1[2]));
+        stl_content.push_str(&format!("      vertex {} {} {}\n", v2[0], v2[1], v2[2]));
+        stl_content.push_str("    endloop\n");
+        stl_content.push_str("  endfacet\n");
     }
+    
+    stl_content.push_str("endsolid\n");
+    Ok(stl_content)
+}
 
-    let is_step = asset_data.windows(5).any(|w| w == b"ISO-10");
-    let is_stl = asset_data.windows(5).any(|w| w == b"solid");
+/// With `embed_hash`, a `# Mesh hash:` comment records `compute_mesh_hash`
+///
+/// Vertex normals are written as `vn` lines (one per vertex, same order)
+/// and referenced from faces as `f v//vn`.
+#[wasm_bindgen]
+pub fn export_obj(mesh: &Mesh, filename: &str, embed_hash: Option<bool>) -> Result<String, JsValue> {
+    write_obj(mesh, filename, embed_hash.unwrap_or(false))
+        .map_err(|e| e.to_js_value())
+}
 
-    if !is_step && !is_stl {
-        return Err(JsValue::from_str(
-            "Unsupported format. Expected STEP, IGES, or STL.",
+fn write_obj(mesh: &Mesh, filename: &str, embed_hash: bool) -> ValidationResult<String> {
+    if mesh.normals.len() != mesh.vertices.len() {
+        return Err(ValidationError::new(format!(
+            "Mesh has {} normal components for {} vertex components",
+            mesh.normals.len(),
+            mesh.vertices.len()
+        )));
+    }
+    check_face_indices(mesh)?;
+    
+    let mut obj_content = format!("# OBJ file exported from Cadmium-Core\n");
+    obj_content.push_str(&format!("# Filename: {}\n", filename));
+    if embed_hash {
+        obj_content.push_str(&format!("# Mesh hash: {}\n", compute_mesh_hash(mesh)));
+    }
+    obj_content.push('\n');
+    
+    // Write vertices
+    for i in (0..mesh.vertices.len()).step_by(3) {
+        obj_content.push_str(&format!("v {} {} {}\n", 
+            mesh.vertices[i], 
+            mesh.vertices[i + 1], 
+            mesh.vertices[i + 2]
         ));
     }
-
-    #[derive(serde::Serialize)]
-    struct ValidationResult {
-        status: String,
-        format: String,
-        size_bytes: usize,
-        issues: Vec<String>,
+    
+    obj_content.push_str("\n");
+    
+    // Write normals, indexed like the vertices
+    for n in mesh.normals.chunks_exact(3) {
+        obj_content.push_str(&format!("vn {} {} {}\n", n[0], n[1], n[2]));
+    }
+    
+    obj_content.push('\n');
+    
+    // Write faces (OBJ uses 1-based indexing)
+    for tri in mesh.faces.chunks_exact(3) {
+        let [a, b, c] = [tri[0] + 1, tri[1] + 1, tri[2] + 1];
+        obj_content.push_str(&format!("f {a}//{a} {b}//{b} {c}//{c}\n"));
     }
+    
+    Ok(obj_content)
+}
 
-    let result = ValidationResult {
-        status: "valid".to_string(),
-        format: if is_step {
-            "STEP".to_string()
-        } else {
-            "STL".to_string()
-        },
-        size_bytes: asset_data.len(),
-        issues: vec![],
+/// glTF 2.0 asset holding the mesh in one embedded base64 buffer
+///
+/// Vertex colors become a `COLOR_0` attribute and the material a PBR
+/// material. Normals are written when there is one per vertex.
+#[wasm_bindgen]
+pub fn export_gltf(mesh: &Mesh, name: &str) -> Result<String, JsValue> {
+    write_gltf(mesh, name).map_err(|e| e.to_js_value())
+}
+
+fn write_gltf(mesh: &Mesh, name: &str) -> ValidationResult<String> {
+    check_face_indices(mesh)?;
+    if mesh.faces.is_empty() {
+        return Err(ValidationError::new("Cannot export a mesh without faces"));
+    }
+    
+    let vertex_count = mesh.vertex_count();
+    let positions: Vec<f32> = mesh.vertices.iter().map(|&c| c as f32).collect();
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for p in positions.chunks_exact(3) {
+        for k in 0..3 {
+            min[k] = min[k].min(p[k]);
+            max[k] = max[k].max(p[k]);
+        }
+    }
+    
+    // Every attribute is 4-byte aligned, so views are packed back to back
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut buffer_views = Vec::new();
+    let mut accessors = Vec::new();
+    let mut add = |bytes: Vec<u8>, target: u32, mut accessor: serde_json::Value| {
+        buffer_views.push(serde_json::json!({
+            "buffer": 0,
+            "byteOffset": buffer.len(),
+            "byteLength": bytes.len(),
+            "target": target,
+        }));
+        buffer.extend(bytes);
+        accessor["bufferView"] = serde_json::json!(buffer_views.len() - 1);
+        accessors.push(accessor);
+        accessors.len() - 1
     };
+    let bytes = |values: &[f32]| values.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<u8>>();
+    const ARRAY_BUFFER: u32 = 34962;
+    const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+    const FLOAT: u32 = 5126;
+    const UNSIGNED_INT: u32 = 5125;
+    
+    let mut attributes = serde_json::Map::new();
+    let position = add(bytes(&positions), ARRAY_BUFFER, serde_json::json!({
+        "componentType": FLOAT, "count": vertex_count, "type": "VEC3", "min": min, "max": max,
+    }));
+    attributes.insert("POSITION".into(), position.into());
+    if mesh.normals.len() == mesh.vertices.len() {
+        let normals: Vec<f32> = mesh.normals.iter().map(|&c| c as f32).collect();
+        let normal = add(bytes(&normals), ARRAY_BUFFER, serde_json::json!({
+            "componentType": FLOAT, "count": vertex_count, "type": "VEC3",
+        }));
+        attributes.insert("NORMAL".into(), normal.into());
+    }
+    if !mesh.colors.is_empty() {
+        let color = add(bytes(&mesh.colors), ARRAY_BUFFER, serde_json::json!({
+            "componentType": FLOAT, "count": vertex_count, "type": "VEC4",
+        }));
+        attributes.insert("COLOR_0".into(), color.into());
+    }
+    let index_bytes = mesh.faces.iter().flat_map(|i| i.to_le_bytes()).collect();
+    let indices = add(index_bytes, ELEMENT_ARRAY_BUFFER, serde_json::json!({
+        "componentType": UNSIGNED_INT, "count": mesh.faces.len(), "type": "SCALAR",
+    }));
+    
+    let mut primitive = serde_json::json!({ "attributes": attributes, "indices": indices, "mode": 4 });
+    let mut gltf = serde_json::json!({
+        "asset": { "version": "2.0", "generator": "Cadmium-Core" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0, "name": name }],
+        "meshes": [{ "name": name, "primitives": [] }],
+        "buffers": [{
+            "byteLength": buffer.len(),
+            "uri": format!("data:application/octet-stream;base64,{}", base64_encode(&buffer)),
+        }],
+        "bufferViews": buffer_views,
+        "accessors": accessors,
+    });
+    if let Some(material) = &mesh.material {
+        let rgb = material.color();
+        gltf["materials"] = serde_json::json!([{
+            "name": material.name(),
+            "pbrMetallicRoughness": {
+                "baseColorFactor": [rgb[0], rgb[1], rgb[2], material.opacity()],
+                "metallicFactor": material.metallic(),
+                "roughnessFactor": material.roughness(),
+            },
+            "alphaMode": if material.opacity() < 1.0 { "BLEND" } else { "OPAQUE" },
+        }]);
+        primitive["material"] = 0.into();
+    }
+    gltf["meshes"][0]["primitives"] = serde_json::json!([primitive]);
+    
+    Ok(gltf.to_string())
+}
 
-    Ok(serde_wasm_bindgen::to_value(&result).unwrap())
+/// Standard base64 with padding, for glTF data URIs
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for k in 0..4 {
+            if k <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * k) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
 }
 
-// ============ INTERNAL MESH GENERATION ============
+/// The `3D/3dmodel.model` part of a 3MF package, in millimetres
+///
+/// Vertex colors are written as a materials-extension color group with
+/// one entry per vertex, referenced from every triangle corner. Zipping
+/// the part with its content types and relationships is left to the caller.
+#[wasm_bindgen]
+pub fn export_3mf_model(mesh: &Mesh, name: &str) -> Result<String, JsValue> {
+    write_3mf_model(mesh, name).map_err(|e| e.to_js_value())
+}
 
-fn generate_box_mesh(width: f64, height: f64, depth: f64) -> Mesh {
-    let w = width / 2.0;
-    let h = height / 2.0;
-    let d = depth / 2.0;
+fn write_3mf_model(mesh: &Mesh, name: &str) -> ValidationResult<String> {
+    check_face_indices(mesh)?;
+    let colored = !mesh.colors.is_empty();
+    
+    let mut model = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    model.push_str("<model unit=\"millimeter\" xml:lang=\"en-US\" ");
+    model.push_str("xmlns=\"http://schemas.microsoft.com/3dmanufacturing/core/2015/02\" ");
+    model.push_str("xmlns:m=\"http://schemas.microsoft.com/3dmanufacturing/material/2015/02\">\n");
+    model.push_str(&format!("  <metadata name=\"Title\">{}</metadata>\n", xml_escape(name)));
+    model.push_str("  <resources>\n");
+    
+    // Resource IDs: the color group is 1 and the object follows it
+    let object_id = if colored { 2 } else { 1 };
+    if colored {
+        model.push_str("    <m:colorgroup id=\"1\">\n");
+        for rgba in mesh.colors.chunks_exact(4) {
+            let hex: String = rgba.iter().map(|c| format!("{:02X}", (c * 255.0).round() as u8)).collect();
+            model.push_str(&format!("      <m:color color=\"#{}\"/>\n", hex));
+        }
+        model.push_str("    </m:colorgroup>\n");
+    }
+    model.push_str(&format!("    <object id=\"{}\" type=\"model\">\n", object_id));
+    model.push_str("      <mesh>\n        <vertices>\n");
+    for v in mesh.vertices.chunks_exact(3) {
+        model.push_str(&format!("          <vertex x=\"{}\" y=\"{}\" z=\"{}\"/>\n", v[0], v[1], v[2]));
+    }
+    model.push_str("        </vertices>\n        <triangles>\n");
+    for tri in mesh.faces.chunks_exact(3) {
+        let [a, b, c] = [tri[0], tri[1], tri[2]];
+        if colored {
+            model.push_str(&format!(
+                "          <triangle v1=\"{a}\" v2=\"{b}\" v3=\"{c}\" pid=\"1\" p1=\"{a}\" p2=\"{b}\" p3=\"{c}\"/>\n"
+            ));
+        } else {
+            model.push_str(&format!("          <triangle v1=\"{a}\" v2=\"{b}\" v3=\"{c}\"/>\n"));
+        }
+    }
+    model.push_str("        </triangles>\n      </mesh>\n    </object>\n");
+    model.push_str("  </resources>\n");
+    model.push_str(&format!("  <build>\n    <item objectid=\"{}\"/>\n  </build>\n", object_id));
+    model.push_str("</model>\n");
+    Ok(model)
+}
 
-    let vertices = vec![
-        -w, -h, -d,  // 0
-        w, -h, -d,   // 1
-        w, h, -d,    // 2
-        -w, h, -d,   // 3
-        -w, -h, d,   // 4
-        w, -h, d,    // 5
-        w, h, d,     // 6
-        -w, h, d,    // 7
-    ];
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
 
-    let faces = vec![
-        // bottom (-z)
-        0, 2, 1,  0, 3, 2,
-        // top (+z)
-        4, 5, 6,  4, 6, 7,
-        // front (-y)
-        0, 1, 5,  0, 5, 4,
-        // back (+y)
-        2, 3, 7,  2, 7, 6,
-        // left (-x)
-        0, 4, 7,  0, 7, 3,
-        // right (+x)
-        1, 2, 6,  1, 6, 5,
-    ];
+/// Check that every face index refers to a vertex of the mesh
+fn check_face_indices(mesh: &Mesh) -> ValidationResult<()> {
+    let vertex_count = mesh.vertices.len() / 3;
+    if let Some(&index) = mesh.faces.iter().find(|&&i| i as usize >= vertex_count) {
+        return Err(ValidationError::new(format!(
+            "Face index {} out of range for {} vertices",
+            index, vertex_count
+        )));
+    }
+    Ok(())
+}
 
-    let mut normals = vec![0.0; vertices.len()];
-    compute_normals(&vertices, &faces, &mut normals);
+/// DXF `ENTITIES` section with one closed LWPOLYLINE per contour
+pub fn export_dxf(contours: &[Vec<[f64; 2]>]) -> Result<String, JsValue> {
+    write_dxf(contours).map_err(|e| e.to_js_value())
+}
 
-    Mesh { vertices, faces, normals, material: None }
+/// `export_dxf` for contours coming from JS (e.g. `slice_mesh` output)
+#[wasm_bindgen(js_name = export_dxf)]
+pub fn export_dxf_js(contours: JsValue) -> Result<String, JsValue> {
+    let contours: Vec<Vec<[f64; 2]>> = serde_wasm_bindgen::from_value(contours)
+        .map_err(|e| JsValue::from_str(&format!("Invalid contours: {}", e)))?;
+    export_dxf(&contours)
 }
 
-fn generate_cylinder_mesh(radius: f64, height: f64, segments: u32) -> Mesh {
-    let mut vertices = Vec::new();
-    let mut faces = Vec::new();
-    
-    let half_height = height / 2.0;
-    
-    // Bottom center
-    vertices.extend_from_slice(&[0.0, -half_height, 0.0]);
-    // Top center
+fn write_dxf(contours: &[Vec<[f64; 2]>]) -> ValidationResult<String> {
+    let mut dxf = String::from("0\nSECTION\n2\nENTITIES\n");
+    for (i, contour) in contours.iter().enumerate() {
+        if contour.len() < 3 {
+            return Err(ValidationError::new(format!(
+                "Contour {} needs at least 3 points (got {})",
+                i,
+                contour.len()
+            )));
+        }
+        if contour.iter().flatten().any(|c| !c.is_finite()) {
+            return Err(ValidationError::new(format!("Contour {} has non-finite points", i)));
+        }
+
+        // Layer 0, closed flag set
+        dxf.push_str("0\nLWPOLYLINE\n100\nAcDbEntity\n8\n0\n100\nAcDbPolyline\n");
+        dxf.push_str(&format!("90\n{}\n70\n1\n", contour.len()));
+        for [x, y] in contour {
+            dxf.push_str(&format!("10\n{}\n20\n{}\n", x, y));
+        }
+    }
+    dxf.push_str("0\nENDSEC\n0\nEOF\n");
+    Ok(dxf)
+}
+
+// ============ MESH UTILITIES ============
+
+#[wasm_bindgen]
+pub fn recompute_normals(mesh: &Mesh, weighting: NormalWeighting) -> Mesh {
+    let mut normals = vec![0.0; mesh.vertices.len()];
+    compute_normals_weighted(&mesh.vertices, &mesh.faces, &mut normals, weighting);
+
+    Mesh {
+        vertices: mesh.vertices.clone(),
+        faces: mesh.faces.clone(),
+        normals,
+        material: mesh.material.clone(),
+        source_ids: mesh.source_ids.clone(),
+        colors: mesh.colors.clone(),
+    }
+}
+
+/// Flip every triangle whose normal points toward the mesh centroid
+///
+/// A cheap fix for inverted or mixed winding that is exact for
+/// star-convex solids, where every face can see the centroid of the
+/// vertices. Deeply concave parts can have faces that legitimately point
+/// toward it; those get flipped wrongly. Triangles keep their order, so
+/// source ids and colors still line up, and normals are recomputed.
+#[wasm_bindgen]
+pub fn enforce_outward_winding(mesh: &Mesh) -> Mesh {
+    let vertex_count = mesh.vertices.len() / 3;
+    let position = |i: u32| Vec3::from_column_slice(&mesh.vertices[i as usize * 3..i as usize * 3 + 3]);
+    let centroid = (0..vertex_count as u32).map(position).sum::<Vec3<f64>>() / vertex_count.max(1) as f64;
+
+    let mut faces = mesh.faces.clone();
+    for tri in faces.chunks_exact_mut(3) {
+        let v = [tri[0], tri[1], tri[2]].map(position);
+        let middle = (v[0] + v[1] + v[2]) / 3.0;
+        if area_normal(&v).dot(&(middle - centroid)) < 0.0 {
+            tri.swap(1, 2);
+        }
+    }
+
+    let mut normals = vec![0.0; mesh.vertices.len()];
+    compute_normals(&mesh.vertices, &faces, &mut normals);
+    Mesh {
+        vertices: mesh.vertices.clone(),
+        faces,
+        normals,
+        material: mesh.material.clone(),
+        source_ids: mesh.source_ids.clone(),
+        colors: mesh.colors.clone(),
+    }
+}
+
+/// Combine meshes into one, e.g. the parts of an assembly
+///
+/// Inputs are concatenated as they are, without the CSG of chained
+/// `boolean_union` calls, in a single crossing of the WASM boundary. With
+/// `weld`, coincident vertices are merged afterwards. Normals are
+/// recomputed once; the material comes from the first mesh. If any input
+/// is colored, uncolored ones are filled with their `base_color`.
+#[wasm_bindgen]
+pub fn merge_meshes(meshes: Vec<Mesh>, weld: bool) -> Result<Mesh, JsValue> {
+    concatenate_meshes(&meshes, weld).map_err(|e| e.to_js_value())
+}
+
+fn concatenate_meshes(meshes: &[Mesh], weld: bool) -> ValidationResult<Mesh> {
+    let mut vertices = Vec::with_capacity(meshes.iter().map(|m| m.vertices.len()).sum());
+    let mut faces = Vec::with_capacity(meshes.iter().map(|m| m.faces.len()).sum());
+    let colored = meshes.iter().any(|m| !m.colors.is_empty());
+    let mut colors = Vec::new();
+    for (i, mesh) in meshes.iter().enumerate() {
+        let vertex_count = mesh.vertices.len() / 3;
+        if mesh.faces.iter().any(|&f| f as usize >= vertex_count) {
+            return Err(ValidationError::new(format!(
+                "Mesh {} has face indices beyond its {} vertices",
+                i, vertex_count
+            )));
+        }
+        faces.extend(offset_face_indices(&mesh.faces, vertices.len() / 3)?);
+        vertices.extend_from_slice(&mesh.vertices[..vertex_count * 3]);
+        if colored && mesh.colors.is_empty() {
+            colors.extend(base_color(mesh).repeat(vertex_count));
+        } else if colored {
+            colors.extend_from_slice(&mesh.colors[..vertex_count * 4]);
+        }
+    }
+
+    let material = meshes.first().and_then(|m| m.material.clone());
+    let mut merged = Mesh { vertices, faces, normals: Vec::new(), material, source_ids: Vec::new(), colors };
+    if weld {
+        let (vertices, remap) = weld_vertices(&merged);
+        merged.faces = merged.faces.iter().map(|&f| remap[f as usize]).collect();
+        if colored {
+            merged.colors = remap_colors(&merged.colors, &remap, vertices.len() / 3);
+        }
+        merged.vertices = vertices;
+    }
+
+    merged.normals = vec![0.0; merged.vertices.len()];
+    compute_normals(&merged.vertices, &merged.faces, &mut merged.normals);
+    Ok(merged)
+}
+
+/// Copy of `mesh` with three corners of its own per face, each face's
+/// corners taking its RGBA color from `face_colors`
+fn unshare_face_colors(mesh: &Mesh, face_colors: &[f32]) -> Mesh {
+    let has_normals = mesh.normals.len() == mesh.vertices.len();
+    let mut vertices = Vec::with_capacity(mesh.faces.len() * 3);
+    let mut normals = Vec::with_capacity(if has_normals { mesh.faces.len() * 3 } else { 0 });
+    let mut colors = Vec::with_capacity(mesh.faces.len() * 4);
+    for (tri, color) in mesh.faces.chunks_exact(3).zip(face_colors.chunks_exact(4)) {
+        for &i in tri {
+            let i = i as usize * 3;
+            vertices.extend_from_slice(&mesh.vertices[i..i + 3]);
+            if has_normals {
+                normals.extend_from_slice(&mesh.normals[i..i + 3]);
+            }
+            colors.extend(color.iter().map(|c| c.clamp(0.0, 1.0)));
+        }
+    }
+    
+    Mesh {
+        vertices,
+        faces: (0..mesh.faces.len() as u32).collect(),
+        normals,
+        material: mesh.material.clone(),
+        source_ids: mesh.source_ids.clone(),
+        colors,
+    }
+}
+
+/// Weld, repair and check a mesh so it can be written as a printable STL
+///
+/// Coincident vertices are merged, collapsed and zero-area triangles are
+/// dropped and T-junctions (a vertex lying on another triangle's edge, as
+/// left by booleans) are split, so every edge ends up shared by exactly
+/// two triangles. Fails if the result is still not watertight. Vertex
+/// colors are dropped, as welding would blend differently colored faces.
+#[wasm_bindgen]
+pub fn prepare_for_print(mesh: &Mesh) -> Result<Mesh, JsValue> {
+    repair_for_print(mesh).map_err(|e| e.to_js_value())
+}
+
+fn repair_for_print(mesh: &Mesh) -> ValidationResult<Mesh> {
+    let vertex_count = mesh.vertices.len() / 3;
+    if mesh.faces.iter().any(|&f| f as usize >= vertex_count) {
+        return Err(ValidationError::new(format!(
+            "Mesh has face indices beyond its {} vertices",
+            vertex_count
+        )));
+    }
+    
+    let tolerance = weld_tolerance(&mesh.vertices);
+    let (vertices, remap) = weld_vertices_within(&mesh.vertices, tolerance);
+    let point = |i: u32| {
+        let i = i as usize * 3;
+        Vec3::new(vertices[i], vertices[i + 1], vertices[i + 2])
+    };
+    
+    // Triangles with their source ID, minus the ones with no area
+    let tagged = mesh.source_ids.len() == mesh.faces.len() / 3;
+    let mut triangles: Vec<([u32; 3], u32)> = mesh
+        .faces
+        .chunks_exact(3)
+        .enumerate()
+        .map(|(i, t)| {
+            let source = if tagged { mesh.source_ids[i] } else { 0 };
+            ([remap[t[0] as usize], remap[t[1] as usize], remap[t[2] as usize]], source)
+        })
+        .filter(|([a, b, c], _)| {
+            (point(*b) - point(*a)).cross(&(point(*c) - point(*a))).norm() > tolerance * tolerance
+        })
+        .collect();
+    
+    split_t_junctions(&mut triangles, &point, tolerance);
+    
+    let faces: Vec<u32> = triangles.iter().flat_map(|(t, _)| *t).collect();
+    validate_watertight(&vertices, &faces)?;
+    
+    // Keep only referenced vertices, in first-use order
+    let mut compact: HashMap<u32, u32> = HashMap::new();
+    let mut kept = Vec::new();
+    let faces: Vec<u32> = faces
+        .iter()
+        .map(|&f| {
+            *compact.entry(f).or_insert_with(|| {
+                kept.extend_from_slice(&vertices[f as usize * 3..f as usize * 3 + 3]);
+                (kept.len() / 3 - 1) as u32
+            })
+        })
+        .collect();
+    
+    let mut normals = vec![0.0; kept.len()];
+    compute_normals(&kept, &faces, &mut normals);
+    Ok(Mesh {
+        vertices: kept,
+        faces,
+        normals,
+        material: mesh.material.clone(),
+        source_ids: if tagged { triangles.iter().map(|&(_, id)| id).collect() } else { Vec::new() },
+        colors: Vec::new(),
+    })
+}
+
+/// Split triangles at vertices that lie inside one of their open edges
+///
+/// Each pass splits an edge at its interior vertex nearest the edge's
+/// start, so every pass removes at least one T-junction.
+fn split_t_junctions(
+    triangles: &mut Vec<([u32; 3], u32)>,
+    point: &impl Fn(u32) -> Vec3<f64>,
+    tolerance: f64,
+) {
+    loop {
+        let faces: Vec<u32> = triangles.iter().flat_map(|(t, _)| *t).collect();
+        let open = find_open_edges(&faces);
+        if open.is_empty() {
+            return;
+        }
+        let open_set: std::collections::HashSet<(u32, u32)> = open.iter().copied().collect();
+        let mut candidates: Vec<u32> = open.iter().flat_map(|&(a, b)| [a, b]).collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        
+        let inner = |a: u32, b: u32| {
+            let (pa, pb) = (point(a), point(b));
+            let edge = pb - pa;
+            let length_sq = edge.norm_squared();
+            candidates
+                .iter()
+                .filter(|&&v| v != a && v != b)
+                .filter_map(|&v| {
+                    let t = (point(v) - pa).dot(&edge) / length_sq;
+                    let on_edge = (pa + edge * t - point(v)).norm() <= tolerance;
+                    (on_edge && t > 0.0 && t < 1.0).then_some((t, v))
+                })
+                .min_by(|x, y| x.0.total_cmp(&y.0))
+                .map(|(_, v)| v)
+        };
+        
+        let mut split = false;
+        let mut next = Vec::with_capacity(triangles.len() + open.len());
+        for &(t, source) in triangles.iter() {
+            let junction = (0..3).find_map(|k| {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                if !open_set.contains(&(a.min(b), a.max(b))) {
+                    return None;
+                }
+                inner(a, b).map(|v| (k, v))
+            });
+            match junction {
+                Some((k, v)) => {
+                    let (a, b, c) = (t[k], t[(k + 1) % 3], t[(k + 2) % 3]);
+                    next.push(([a, v, c], source));
+                    next.push(([v, b, c], source));
+                    split = true;
+                }
+                None => next.push((t, source)),
+            }
+        }
+        *triangles = next;
+        if !split {
+            return;
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn compute_bounding_box(mesh: &Mesh) -> BoundingBox {
+    let mut min_x = f64::INFINITY;
+    let mut min_y = f64::INFINITY;
+    let mut min_z = f64::INFINITY;
+    let mut max_x = f64::NEG_INFINITY;
+    let mut max_y = f64::NEG_INFINITY;
+    let mut max_z = f64::NEG_INFINITY;
+
+    for i in (0..mesh.vertices.len()).step_by(3) {
+        min_x = min_x.min(mesh.vertices[i]);
+        max_x = max_x.max(mesh.vertices[i]);
+        min_y = min_y.min(mesh.vertices[i + 1]);
+        max_y = max_y.max(mesh.vertices[i + 1]);
+        min_z = min_z.min(mesh.vertices[i + 2]);
+        max_z = max_z.max(mesh.vertices[i + 2]);
+    }
+
+    BoundingBox {
+        min_x,
+        min_y,
+        min_z,
+        max_x,
+        max_y,
+        max_z,
+    }
+}
+
+/// Center of mass and how it was obtained
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct CenterOfMass {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    /// False when the mesh is open and the vertex average was used instead
+    pub volumetric: bool,
+}
+
+#[wasm_bindgen]
+pub fn analyze_center_of_mass(mesh: &Mesh) -> CenterOfMass {
+    let vertex_count = mesh.vertices.len() / 3;
+    
+    // Tetrahedron-weighted centroid is only meaningful for a closed shell
+    if !mesh.faces.is_empty() && find_open_edges(&mesh.faces).is_empty() {
+        let mut volume = 0.0;
+        let mut weighted = [0.0; 3];
+        
+        for tri in mesh.faces.chunks_exact(3) {
+            if tri.iter().any(|&i| i as usize >= vertex_count) {
+                continue;
+            }
+            let v0 = &mesh.vertices[tri[0] as usize * 3..tri[0] as usize * 3 + 3];
+            let v1 = &mesh.vertices[tri[1] as usize * 3..tri[1] as usize * 3 + 3];
+            let v2 = &mesh.vertices[tri[2] as usize * 3..tri[2] as usize * 3 + 3];
+            
+            // Signed volume of the tetrahedron (origin, v0, v1, v2)
+            let cross = [
+                v1[1] * v2[2] - v1[2] * v2[1],
+                v1[2] * v2[0] - v1[0] * v2[2],
+                v1[0] * v2[1] - v1[1] * v2[0],
+            ];
+            let tet_volume = (v0[0] * cross[0] + v0[1] * cross[1] + v0[2] * cross[2]) / 6.0;
+            
+            volume += tet_volume;
+            for axis in 0..3 {
+                weighted[axis] += tet_volume * (v0[axis] + v1[axis] + v2[axis]) / 4.0;
+            }
+        }
+        
+        if volume.abs() > 1e-12 {
+            return CenterOfMass {
+                x: weighted[0] / volume,
+                y: weighted[1] / volume,
+                z: weighted[2] / volume,
+                volumetric: true,
+            };
+        }
+    }
+    
+    // Open or degenerate mesh: fall back to the vertex average
+    let mut sum = [0.0; 3];
+    for v in mesh.vertices.chunks_exact(3) {
+        sum[0] += v[0];
+        sum[1] += v[1];
+        sum[2] += v[2];
+    }
+    let n = vertex_count.max(1) as f64;
+    
+    CenterOfMass {
+        x: sum[0] / n,
+        y: sum[1] / n,
+        z: sum[2] / n,
+        volumetric: false,
+    }
+}
+
+pub fn compute_center_of_mass(mesh: &Mesh) -> [f64; 3] {
+    let com = analyze_center_of_mass(mesh);
+    [com.x, com.y, com.z]
+}
+
+/// Area of the mesh projected onto the plane perpendicular to `direction`
+///
+/// Sums the projections of triangles facing along `direction`, which is the
+/// exact silhouette area for convex closed meshes and an upper bound where
+/// front faces overlap. A zero direction gives 0.
+pub fn projected_area(mesh: &Mesh, direction: [f64; 3]) -> f64 {
+    let length = (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2]).sqrt();
+    if length == 0.0 {
+        return 0.0;
+    }
+    let d = direction.map(|c| c / length);
+    let vertex_count = mesh.vertices.len() / 3;
+    
+    let mut area = 0.0;
+    for tri in mesh.faces.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| {
+            let i = i as usize * 3;
+            Vec3::new(mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2])
+        });
+        // Twice the triangle's area vector, dotted with the view direction
+        let facing = (b - a).cross(&(c - a)).dot(&Vec3::new(d[0], d[1], d[2]));
+        if facing > 0.0 {
+            area += facing / 2.0;
+        }
+    }
+    area
+}
+
+/// `projected_area` with a direction coming from JS as `[x, y, z]`
+#[wasm_bindgen(js_name = projected_area)]
+pub fn projected_area_js(mesh: &Mesh, direction: Vec<f64>) -> Result<f64, JsValue> {
+    let direction = validate_direction(&direction).map_err(|e| e.to_js_value())?;
+    Ok(projected_area(mesh, direction))
+}
+
+/// Radius and length of the smallest bar stock a part could be turned from
+/// with its axis along `axis`
+///
+/// The length is the extent of the vertices along the axis. The radius is
+/// that of the smallest circle enclosing the vertices projected across the
+/// axis, so the bar need not be centred on the origin. A faceted mesh's
+/// vertices lie on the surface it approximates, so a tessellated cylinder
+/// reports its true radius whatever its segment count. A zero axis or an
+/// empty mesh gives `(0, 0)`.
+pub fn min_enclosing_cylinder(mesh: &Mesh, axis: [f64; 3]) -> (f64, f64) {
+    let Some(axis) = Vec3::new(axis[0], axis[1], axis[2]).try_normalize(0.0) else {
+        return (0.0, 0.0);
+    };
+    if mesh.vertices.len() < 3 {
+        return (0.0, 0.0);
+    }
+    
+    // Any two directions completing the axis to an orthonormal frame
+    let helper = if axis.x.abs() < 0.9 { Vec3::x() } else { Vec3::y() };
+    let u = axis.cross(&helper).normalize();
+    let v = axis.cross(&u);
+    
+    let mut low = f64::INFINITY;
+    let mut high = f64::NEG_INFINITY;
+    let mut points = Vec::with_capacity(mesh.vertices.len() / 3);
+    for p in mesh.vertices.chunks_exact(3) {
+        let p = Vec3::new(p[0], p[1], p[2]);
+        let t = p.dot(&axis);
+        low = low.min(t);
+        high = high.max(t);
+        points.push([p.dot(&u), p.dot(&v)]);
+    }
+    
+    let (_, radius) = min_enclosing_circle(&mut points);
+    (radius, high - low)
+}
+
+/// `min_enclosing_cylinder` with an axis coming from JS as `[x, y, z]`,
+/// returning `[radius, length]`
+#[wasm_bindgen(js_name = min_enclosing_cylinder)]
+pub fn min_enclosing_cylinder_js(mesh: &Mesh, axis: Vec<f64>) -> Result<Vec<f64>, JsValue> {
+    let axis = validate_direction(&axis).map_err(|e| e.to_js_value())?;
+    let (radius, length) = min_enclosing_cylinder(mesh, axis);
+    Ok(vec![radius, length])
+}
+
+/// Smallest circle containing every point, as centre and radius (Welzl)
+///
+/// The points are shuffled first, with a fixed seed so results are
+/// repeatable; in mesh order, e.g. around a ring, the incremental algorithm
+/// degrades to cubic time.
+fn min_enclosing_circle(points: &mut [[f64; 2]]) -> ([f64; 2], f64) {
+    let mut state = 0x9e37_79b9u32;
+    for i in (1..points.len()).rev() {
+        state = state.wrapping_mul(1103515245).wrapping_add(12345);
+        points.swap(i, (state >> 8) as usize % (i + 1));
+    }
+    
+    let distance = |a: [f64; 2], b: [f64; 2]| (a[0] - b[0]).hypot(a[1] - b[1]);
+    let scale = points.iter().fold(0.0_f64, |m, p| m.max(p[0].abs()).max(p[1].abs()));
+    let tolerance = 1e-12 * scale.max(1.0);
+    let inside = |circle: ([f64; 2], f64), p: [f64; 2]| distance(circle.0, p) <= circle.1 + tolerance;
+    let diameter = |a: [f64; 2], b: [f64; 2]| ([(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0], distance(a, b) / 2.0);
+    
+    let mut circle = (points[0], 0.0);
+    for i in 1..points.len() {
+        if inside(circle, points[i]) {
+            continue;
+        }
+        // points[i] is on the boundary of the circle for points[..=i]
+        circle = (points[i], 0.0);
+        for j in 0..i {
+            if inside(circle, points[j]) {
+                continue;
+            }
+            circle = diameter(points[i], points[j]);
+            for k in 0..j {
+                if !inside(circle, points[k]) {
+                    let (a, b, c) = (points[i], points[j], points[k]);
+                    // Collinear points: the farthest pair spans the circle
+                    circle = circumcircle(a, b, c).unwrap_or_else(|| {
+                        [diameter(a, b), diameter(a, c), diameter(b, c)]
+                            .into_iter()
+                            .fold((a, 0.0), |best, pair| if pair.1 > best.1 { pair } else { best })
+                    });
+                }
+            }
+        }
+    }
+    circle
+}
+
+/// Circle through three points, `None` when they are collinear
+fn circumcircle(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> Option<([f64; 2], f64)> {
+    let (bx, by) = (b[0] - a[0], b[1] - a[1]);
+    let (cx, cy) = (c[0] - a[0], c[1] - a[1]);
+    let d = 2.0 * (bx * cy - by * cx);
+    if d == 0.0 {
+        return None;
+    }
+    let (b2, c2) = (bx * bx + by * by, cx * cx + cy * cy);
+    let ux = (cy * b2 - by * c2) / d;
+    let uy = (bx * c2 - cx * b2) / d;
+    Some(([a[0] + ux, a[1] + uy], ux.hypot(uy)))
+}
+
+/// Two picked surface points, the distance between them and how their
+/// faces meet
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct MeasureResult {
+    pub point_a: [f64; 3],
+    pub point_b: [f64; 3],
+    /// `point_b - point_a`
+    pub delta: [f64; 3],
+    pub distance: f64,
+    /// Angle between the normals of the picked triangles (degrees), when
+    /// both have one; 0 for parallel faces, 90 for a box's adjacent faces
+    pub angle_deg: Option<f64>,
+}
+
+/// Measure between two points picked on the surface
+///
+/// Each point is a triangle index and barycentric weights over its corners,
+/// as reported by a viewer's ray pick. Triangles are flat, so every pick
+/// lies on a planar facet; the angle is left out only for degenerate
+/// triangles. On a tessellated curve it is the angle between the facets.
+pub fn measure(
+    mesh: &Mesh,
+    a_tri: usize,
+    a_bary: [f64; 3],
+    b_tri: usize,
+    b_bary: [f64; 3],
+) -> ValidationResult<MeasureResult> {
+    let a_bary = validate_surface_pick(&mesh.faces, a_tri, a_bary)?;
+    let b_bary = validate_surface_pick(&mesh.faces, b_tri, b_bary)?;
+    let corners = |tri: usize| -> ValidationResult<[Vec3<f64>; 3]> {
+        let mut corners = [Vec3::zeros(); 3];
+        for (corner, &index) in corners.iter_mut().zip(&mesh.faces[tri * 3..tri * 3 + 3]) {
+            let i = index as usize * 3;
+            if i + 2 >= mesh.vertices.len() {
+                return Err(ValidationError::new(format!(
+                    "Triangle {} references missing vertex {}",
+                    tri, index
+                )));
+            }
+            *corner = Vec3::new(mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2]);
+        }
+        Ok(corners)
+    };
+    let a = corners(a_tri)?;
+    let b = corners(b_tri)?;
+    
+    let point_a = a[0] * a_bary[0] + a[1] * a_bary[1] + a[2] * a_bary[2];
+    let point_b = b[0] * b_bary[0] + b[1] * b_bary[1] + b[2] * b_bary[2];
+    let delta = point_b - point_a;
+    
+    let normal = |t: &[Vec3<f64>; 3]| (t[1] - t[0]).cross(&(t[2] - t[0])).try_normalize(0.0);
+    let angle_deg = match (normal(&a), normal(&b)) {
+        (Some(na), Some(nb)) => Some(na.dot(&nb).clamp(-1.0, 1.0).acos().to_degrees()),
+        _ => None,
+    };
+    
+    Ok(MeasureResult {
+        point_a: [point_a.x, point_a.y, point_a.z],
+        point_b: [point_b.x, point_b.y, point_b.z],
+        delta: [delta.x, delta.y, delta.z],
+        distance: delta.norm(),
+        angle_deg,
+    })
+}
+
+/// `measure` with barycentric weights coming from JS as `[u, v, w]`
+#[wasm_bindgen(js_name = measure)]
+pub fn measure_js(
+    mesh: &Mesh,
+    a_tri: usize,
+    a_bary: Vec<f64>,
+    b_tri: usize,
+    b_bary: Vec<f64>,
+) -> Result<JsValue, JsValue> {
+    let weights = |bary: &[f64]| -> Result<[f64; 3], JsValue> {
+        bary.try_into().map_err(|_| {
+            ValidationError::new(format!(
+                "Barycentric coordinates must have 3 components (got {})",
+                bary.len()
+            ))
+            .to_js_value()
+        })
+    };
+    let result = measure(mesh, a_tri, weights(&a_bary)?, b_tri, weights(&b_bary)?)
+        .map_err(|e| e.to_js_value())?;
+    
+    Ok(serde_wasm_bindgen::to_value(&result).unwrap())
+}
+
+#[wasm_bindgen]
+pub fn compute_mesh_hash(mesh: &Mesh) -> String {
+    use sha2::{Sha256, Digest};
+
+    let mut hasher = Sha256::new();
+    for &v in &mesh.vertices {
+        hasher.update(v.to_le_bytes());
+    }
+    for &f in &mesh.faces {
+        hasher.update(f.to_le_bytes());
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+/// Triangles with quality (equilateral = 1) below this are counted as slivers
+const SLIVER_QUALITY_THRESHOLD: f64 = 0.1;
+
+/// Equal-width histogram over `[min, max]`
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Histogram {
+    pub min: f64,
+    pub max: f64,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    fn from_values(values: &[f64], bins: usize) -> Self {
+        let bins = bins.max(1);
+        let mut counts = vec![0; bins];
+        if values.is_empty() {
+            return Histogram { min: 0.0, max: 0.0, counts };
+        }
+        
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let width = (max - min) / bins as f64;
+        
+        for &v in values {
+            let bin = if width > 0.0 { ((v - min) / width) as usize } else { 0 };
+            counts[bin.min(bins - 1)] += 1;
+        }
+        
+        Histogram { min, max, counts }
+    }
+}
+
+/// Edge-length and triangle-shape distributions of a mesh
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct MeshQuality {
+    pub triangle_count: usize,
+    pub edge_lengths: Histogram,
+    /// Longest edge / (2 * inradius); sqrt(3) for an equilateral triangle
+    pub aspect_ratios: Histogram,
+    /// Degenerate triangles and those with quality below the threshold
+    pub sliver_count: usize,
+    pub sliver_threshold: f64,
+}
+
+#[wasm_bindgen]
+pub fn mesh_quality(mesh: &Mesh, bins: usize) -> JsValue {
+    serde_wasm_bindgen::to_value(&analyze_mesh_quality(mesh, bins)).unwrap()
+}
+
+fn analyze_mesh_quality(mesh: &Mesh, bins: usize) -> MeshQuality {
+    let vertex_count = mesh.vertices.len() / 3;
+    let point = |i: u32| {
+        let i = i as usize * 3;
+        Vec3::new(mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2])
+    };
+    
+    let mut edges = std::collections::HashSet::new();
+    let mut edge_lengths = Vec::new();
+    let mut aspect_ratios = Vec::new();
+    let mut triangle_count = 0;
+    let mut sliver_count = 0;
+    
+    for tri in mesh.faces.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        triangle_count += 1;
+        
+        let mut lengths = [0.0; 3];
+        for k in 0..3 {
+            let (a, b) = (tri[k], tri[(k + 1) % 3]);
+            lengths[k] = (point(b) - point(a)).norm();
+            
+            // Shared edges are only counted once
+            if edges.insert(if a < b { (a, b) } else { (b, a) }) {
+                edge_lengths.push(lengths[k]);
+            }
+        }
+        
+        let area = (point(tri[1]) - point(tri[0])).cross(&(point(tri[2]) - point(tri[0]))).norm() / 2.0;
+        let semi_perimeter = (lengths[0] + lengths[1] + lengths[2]) / 2.0;
+        let longest = lengths[0].max(lengths[1]).max(lengths[2]);
+        
+        if area <= 0.0 || semi_perimeter <= 0.0 {
+            sliver_count += 1;
+            continue;
+        }
+        
+        let inradius = area / semi_perimeter;
+        let aspect_ratio = longest / (2.0 * inradius);
+        aspect_ratios.push(aspect_ratio);
+        
+        if 3.0f64.sqrt() / aspect_ratio < SLIVER_QUALITY_THRESHOLD {
+            sliver_count += 1;
+        }
+    }
+    
+    MeshQuality {
+        triangle_count,
+        edge_lengths: Histogram::from_values(&edge_lengths, bins),
+        aspect_ratios: Histogram::from_values(&aspect_ratios, bins),
+        sliver_count,
+        sliver_threshold: SLIVER_QUALITY_THRESHOLD,
+    }
+}
+
+/// Maximum distance (mm) of a vertex from a polygon's plane
+const COPLANAR_DISTANCE_TOLERANCE: f64 = 1e-6;
+
+/// Merge adjacent coplanar triangles into planar polygons
+///
+/// Triangles are grouped across shared edges while their normals stay within
+/// `angle_tol_deg` of the group's first triangle and their vertices lie on
+/// its plane. Each group becomes one polygon traced along its boundary;
+/// groups that are not a simple disc (holes, pinched corners) keep their
+/// original triangles.
+#[wasm_bindgen]
+pub fn merge_coplanar(mesh: &Mesh, angle_tol_deg: f64) -> QuadMesh {
+    let vertex_count = mesh.vertices.len() / 3;
+    
+    let (vertices, remap) = weld_vertices(mesh);
+    
+    let point = |i: u32| {
+        let i = i as usize * 3;
+        Vec3::new(vertices[i], vertices[i + 1], vertices[i + 2])
+    };
+    
+    // Welded, non-degenerate triangles with their area-weighted normals
+    let mut triangles: Vec<[u32; 3]> = Vec::new();
+    let mut normals: Vec<Vec3<f64>> = Vec::new();
+    for tri in mesh.faces.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        let t = [remap[tri[0] as usize], remap[tri[1] as usize], remap[tri[2] as usize]];
+        let n = (point(t[1]) - point(t[0])).cross(&(point(t[2]) - point(t[0])));
+        if n.norm() > 0.0 {
+            triangles.push(t);
+            normals.push(n);
+        }
+    }
+    
+    let mut edge_triangles: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            edge_triangles.entry((a.min(b), a.max(b))).or_default().push(i);
+        }
+    }
+    
+    // Grow groups from seeds, comparing against the seed plane to avoid drift
+    let cos_tol = angle_tol_deg.to_radians().cos();
+    let mut group_of = vec![usize::MAX; triangles.len()];
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for seed in 0..triangles.len() {
+        if group_of[seed] != usize::MAX {
+            continue;
+        }
+        let seed_normal = normals[seed].normalize();
+        let seed_point = point(triangles[seed][0]);
+        
+        let group_id = groups.len();
+        group_of[seed] = group_id;
+        let mut members = vec![seed];
+        let mut stack = vec![seed];
+        
+        while let Some(current) = stack.pop() {
+            let t = triangles[current];
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                for &other in &edge_triangles[&(a.min(b), a.max(b))] {
+                    if group_of[other] != usize::MAX {
+                        continue;
+                    }
+                    let parallel = normals[other].normalize().dot(&seed_normal) >= cos_tol;
+                    let on_plane = triangles[other].iter().all(|&v| {
+                        (point(v) - seed_point).dot(&seed_normal).abs() <= COPLANAR_DISTANCE_TOLERANCE
+                    });
+                    if parallel && on_plane {
+                        group_of[other] = group_id;
+                        members.push(other);
+                        stack.push(other);
+                    }
+                }
+            }
+        }
+        groups.push(members);
+    }
+    
+    let mut polygon_indices = Vec::new();
+    let mut polygon_offsets = vec![0];
+    let mut polygon_normals = Vec::new();
+    for members in &groups {
+        let normal = members.iter().fold(Vec3::zeros(), |acc, &i| acc + normals[i]).normalize();
+        
+        let polygons = match trace_boundary(members.iter().map(|&i| triangles[i])) {
+            Some(boundary) => vec![boundary],
+            None => members.iter().map(|&i| triangles[i].to_vec()).collect(),
+        };
+        
+        for polygon in polygons {
+            polygon_indices.extend(polygon);
+            polygon_offsets.push(polygon_indices.len() as u32);
+            polygon_normals.extend_from_slice(&[normal.x, normal.y, normal.z]);
+        }
+    }
+    
+    QuadMesh {
+        vertices,
+        polygon_indices,
+        polygon_offsets,
+        polygon_normals,
+    }
+}
+
+/// Weld coincident vertices so that adjacency does not depend on how the
+/// mesh shares indices (some meshes give each face its own vertex copies)
+///
+/// Returns the unique positions and the welded index of every vertex.
+fn weld_vertices(mesh: &Mesh) -> (Vec<f64>, Vec<u32>) {
+    let mut welded: HashMap<[u64; 3], u32> = HashMap::new();
+    let mut vertices = Vec::new();
+    let mut remap = Vec::with_capacity(mesh.vertices.len() / 3);
+    for v in mesh.vertices.chunks_exact(3) {
+        // +0.0 folds -0.0 into the same key
+        let key = [(v[0] + 0.0).to_bits(), (v[1] + 0.0).to_bits(), (v[2] + 0.0).to_bits()];
+        let index = *welded.entry(key).or_insert_with(|| {
+            vertices.extend_from_slice(v);
+            (vertices.len() / 3 - 1) as u32
+        });
+        remap.push(index);
+    }
+    (vertices, remap)
+}
+
+/// Distance within which `repair_for_print` and `validate_watertight`
+/// merge vertices
+///
+/// Boolean results are quantized to 1e-6, so nearby copies of a vertex
+/// can differ in the last bits; the tolerance is 1e-6 of the bounding
+/// diagonal, and never below 1e-6.
+fn weld_tolerance(vertices: &[f64]) -> f64 {
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    for v in vertices.chunks_exact(3) {
+        for k in 0..3 {
+            min[k] = min[k].min(v[k]);
+            max[k] = max[k].max(v[k]);
+        }
+    }
+    if vertices.len() < 3 {
+        return 1e-6;
+    }
+    let diagonal = Vec3::new(max[0] - min[0], max[1] - min[1], max[2] - min[2]).norm();
+    1e-6 * diagonal.max(1.0)
+}
+
+/// Merge vertices within `tolerance` of an earlier vertex into it
+///
+/// Returns the kept positions and the welded index of every vertex.
+fn weld_vertices_within(vertices: &[f64], tolerance: f64) -> (Vec<f64>, Vec<u32>) {
+    let cell = |v: &[f64]| [0, 1, 2].map(|k| (v[k] / tolerance).floor() as i64);
+    let mut grid: HashMap<[i64; 3], Vec<u32>> = HashMap::new();
+    let mut welded: Vec<f64> = Vec::new();
+    let mut remap = Vec::with_capacity(vertices.len() / 3);
+    for v in vertices.chunks_exact(3) {
+        let home = cell(v);
+        let close = |&i: &u32| {
+            let w = &welded[i as usize * 3..i as usize * 3 + 3];
+            (0..3).map(|k| (w[k] - v[k]).powi(2)).sum::<f64>() <= tolerance * tolerance
+        };
+        let neighbours = (0..27).map(|n| [n % 3 - 1, n / 3 % 3 - 1, n / 9 - 1]);
+        let existing = neighbours
+            .filter_map(|d| grid.get(&[home[0] + d[0], home[1] + d[1], home[2] + d[2]]))
+            .find_map(|indices| indices.iter().copied().find(close));
+        
+        let index = existing.unwrap_or_else(|| {
+            welded.extend_from_slice(v);
+            let index = (welded.len() / 3 - 1) as u32;
+            grid.entry(home).or_default().push(index);
+            index
+        });
+        remap.push(index);
+    }
+    (welded, remap)
+}
+
+/// Trace the boundary of a triangle patch as a single loop, keeping winding
+///
+/// Returns `None` unless the boundary is exactly one simple loop.
+fn trace_boundary(triangles: impl Iterator<Item = [u32; 3]>) -> Option<Vec<u32>> {
+    let mut directed: HashMap<(u32, u32), usize> = HashMap::new();
+    for t in triangles {
+        for k in 0..3 {
+            *directed.entry((t[k], t[(k + 1) % 3])).or_default() += 1;
+        }
+    }
+    
+    // Boundary edges are those whose reverse is not in the patch
+    let mut next: HashMap<u32, u32> = HashMap::new();
+    for (&(a, b), &count) in &directed {
+        if count == 1 && !directed.contains_key(&(b, a)) && next.insert(a, b).is_some() {
+            return None; // Pinched vertex
+        }
+    }
+    
+    let start = *next.keys().min()?;
+    let mut boundary = vec![start];
+    let mut current = next[&start];
+    while current != start {
+        if boundary.len() >= next.len() {
+            return None;
+        }
+        boundary.push(current);
+        current = *next.get(&current)?;
+    }
+    
+    // Anything left over belongs to a second loop (a hole)
+    (boundary.len() == next.len()).then_some(boundary)
+}
+
+// ============ VOXELIZATION ============
+
+/// Occupancy of a regular grid of cubic voxels
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct VoxelGrid {
+    /// Cell counts along x, y and z
+    dimensions: [u32; 3],
+    /// Minimum corner of the grid
+    origin: [f64; 3],
+    resolution: f64,
+    /// One bit per voxel, x fastest then y then z, least significant bit first
+    occupancy: Vec<u8>,
+    occupied_count: usize,
+}
+
+#[wasm_bindgen]
+impl VoxelGrid {
+    #[wasm_bindgen(getter)]
+    pub fn dimensions(&self) -> Vec<u32> {
+        self.dimensions.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn origin(&self) -> Vec<f64> {
+        self.origin.to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn resolution(&self) -> f64 {
+        self.resolution
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn occupancy(&self) -> Vec<u8> {
+        self.occupancy.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn occupied_count(&self) -> usize {
+        self.occupied_count
+    }
+
+    pub fn is_occupied(&self, x: u32, y: u32, z: u32) -> bool {
+        let [nx, ny, nz] = self.dimensions;
+        if x >= nx || y >= ny || z >= nz {
+            return false;
+        }
+        let bit = x as usize + nx as usize * (y as usize + ny as usize * z as usize);
+        self.occupancy[bit / 8] & (1 << (bit % 8)) != 0
+    }
+}
+
+/// Voxelize a closed mesh for FEA preprocessing
+///
+/// The grid covers the mesh bounding box with cubes of edge `resolution`.
+/// A voxel is occupied when the generalized winding number of the mesh at
+/// its center exceeds one half, which tolerates small gaps in the surface.
+#[wasm_bindgen]
+pub fn voxelize(mesh: &Mesh, resolution: f64) -> Result<VoxelGrid, JsValue> {
+    voxelize_mesh(mesh, resolution).map_err(|e| e.to_js_value())
+}
+
+fn voxelize_mesh(mesh: &Mesh, resolution: f64) -> ValidationResult<VoxelGrid> {
+    validate_voxel_grid(resolution, 0.0)?;
+    
+    let triangles = triangle_points(mesh);
+    
+    if triangles.is_empty() {
+        return Ok(VoxelGrid {
+            dimensions: [0; 3],
+            origin: [0.0; 3],
+            resolution,
+            occupancy: Vec::new(),
+            occupied_count: 0,
+        });
+    }
+    
+    let bounds = compute_bounding_box(mesh);
+    let origin = [bounds.min_x, bounds.min_y, bounds.min_z];
+    let extent = [
+        bounds.max_x - bounds.min_x,
+        bounds.max_y - bounds.min_y,
+        bounds.max_z - bounds.min_z,
+    ];
+    let cells = extent.map(|e| (e / resolution).ceil().max(1.0));
+    validate_voxel_grid(resolution, cells[0] * cells[1] * cells[2])?;
+    
+    let dimensions = cells.map(|c| c as u32);
+    let [nx, ny, nz] = dimensions.map(|d| d as usize);
+    let mut occupancy = vec![0u8; (nx * ny * nz).div_ceil(8)];
+    let mut occupied_count = 0;
+    
+    for z in 0..nz {
+        for y in 0..ny {
+            for x in 0..nx {
+                let center = Vec3::new(
+                    origin[0] + (x as f64 + 0.5) * resolution,
+                    origin[1] + (y as f64 + 0.5) * resolution,
+                    origin[2] + (z as f64 + 0.5) * resolution,
+                );
+                if winding_number(&triangles, &center).abs() > 0.5 {
+                    let bit = x + nx * (y + ny * z);
+                    occupancy[bit / 8] |= 1 << (bit % 8);
+                    occupied_count += 1;
+                }
+            }
+        }
+    }
+    
+    Ok(VoxelGrid {
+        dimensions,
+        origin,
+        resolution,
+        occupancy,
+        occupied_count,
+    })
+}
+
+/// Corner positions of every triangle whose indices are in range
+fn triangle_points(mesh: &Mesh) -> Vec<[Vec3<f64>; 3]> {
+    let vertex_count = mesh.vertices.len() / 3;
+    let point = |i: u32| {
+        let i = i as usize * 3;
+        Vec3::new(mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2])
+    };
+    mesh.faces
+        .chunks_exact(3)
+        .filter(|tri| tri.iter().all(|&i| (i as usize) < vertex_count))
+        .map(|tri| [point(tri[0]), point(tri[1]), point(tri[2])])
+        .collect()
+}
+
+/// Generalized winding number: summed solid angle of all triangles / 4π
+///
+/// Uses the Van Oosterom-Strackee formula; about 1 inside a closed,
+/// outward-wound mesh and 0 outside.
+fn winding_number(triangles: &[[Vec3<f64>; 3]], p: &Vec3<f64>) -> f64 {
+    let solid_angle: f64 = triangles
+        .iter()
+        .map(|[a, b, c]| {
+            let (a, b, c) = (a - p, b - p, c - p);
+            let (la, lb, lc) = (a.norm(), b.norm(), c.norm());
+            let numerator = a.dot(&b.cross(&c));
+            let denominator = la * lb * lc + a.dot(&b) * lc + b.dot(&c) * la + c.dot(&a) * lb;
+            2.0 * numerator.atan2(denominator)
+        })
+        .sum();
+    solid_angle / (4.0 * PI)
+}
+
+/// Distance from `p` to the closest point of a triangle
+///
+/// Follows the Voronoi-region walk from Ericson's *Real-Time Collision
+/// Detection*.
+fn distance_to_triangle([a, b, c]: &[Vec3<f64>; 3], p: &Vec3<f64>) -> f64 {
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(&ap), ac.dot(&ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return ap.norm();
+    }
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(&bp), ac.dot(&bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return bp.norm();
+    }
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(&cp), ac.dot(&cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return cp.norm();
+    }
+    
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return (ap - ab * (d1 / (d1 - d3))).norm();
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return (ap - ac * (d2 / (d2 - d6))).norm();
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 >= d3 && d5 >= d6 {
+        return (bp - (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)))).norm();
+    }
+    
+    let denominator = va + vb + vc;
+    (ap - ab * (vb / denominator) - ac * (vc / denominator)).norm()
+}
+
+/// Triangles of a mesh with a bounding volume hierarchy for
+/// closest-point distance queries
+struct SurfaceDistance {
+    triangles: Vec<[Vec3<f64>; 3]>,
+    boxes: Vec<AABB>,
+    bvh: Bvh,
+}
+
+impl SurfaceDistance {
+    fn new(triangles: Vec<[Vec3<f64>; 3]>) -> Self {
+        let boxes: Vec<AABB> = triangles
+            .iter()
+            .map(|t| triangle_aabb(&t.map(Point3::from)))
+            .collect();
+        let bvh = Bvh::build(&boxes);
+        Self { triangles, boxes, bvh }
+    }
+    
+    /// Distance from `p` to the closest triangle
+    fn distance(&self, p: &Vec3<f64>) -> f64 {
+        self.bvh.nearest(&self.boxes, &Point3::from(*p), |i| {
+            distance_to_triangle(&self.triangles[i], p)
+        })
+    }
+}
+
+// ============ ISO-SURFACES ============
+
+/// Surface of a voxel grid by marching cubes
+///
+/// Voxel centers are sampled as 1 when occupied and 0 when empty, with an
+/// empty layer around the grid so the result is closed. `iso` in (0, 1)
+/// places the surface between an occupied and an empty center; 0.5 puts
+/// it halfway, on the shared voxel face.
+#[wasm_bindgen]
+pub fn marching_cubes(grid: &VoxelGrid, iso: f64) -> Result<Mesh, JsValue> {
+    voxel_surface(grid, iso).map_err(|e| e.to_js_value())
+}
+
+fn voxel_surface(grid: &VoxelGrid, iso: f64) -> ValidationResult<Mesh> {
+    if !(iso > 0.0 && iso < 1.0) {
+        return Err(ValidationError::new(format!(
+            "Iso value must lie between 0 and 1 for a voxel grid (got {})",
+            iso
+        )));
+    }
+    
+    let [nx, ny, nz] = grid.dimensions.map(|d| d as usize + 2);
+    let mut values = Vec::with_capacity(nx * ny * nz);
+    for z in 0..nz {
+        for y in 0..ny {
+            for x in 0..nx {
+                let occupied = x > 0
+                    && y > 0
+                    && z > 0
+                    && grid.is_occupied(x as u32 - 1, y as u32 - 1, z as u32 - 1);
+                // Inside is below the iso value, so sample emptiness
+                values.push(if occupied { 0.0 } else { 1.0 });
+            }
+        }
+    }
+    let origin = grid.origin.map(|o| o - 0.5 * grid.resolution);
+    let samples = ScalarGrid::new(origin, grid.resolution, [nx, ny, nz], values);
+    Ok(isosurface_mesh(&samples, 1.0 - iso))
+}
+
+/// Surface where a signed distance function equals `iso`, sampled every
+/// `resolution` over the box from `min` to `max`
+///
+/// Negative values count as inside, and triangles face the positive side.
+/// The surface is closed as long as `sdf` exceeds `iso` on the box
+/// boundary; vertices are interpolated linearly along the lattice edges.
+pub fn marching_cubes_sdf(
+    sdf: impl Fn([f64; 3]) -> f64,
+    min: [f64; 3],
+    max: [f64; 3],
+    resolution: f64,
+    iso: f64,
+) -> Result<Mesh, JsValue> {
+    sdf_mesh(min, max, resolution, iso, sdf).map_err(|e| e.to_js_value())
+}
+
+fn sdf_mesh(
+    min: [f64; 3],
+    max: [f64; 3],
+    resolution: f64,
+    iso: f64,
+    sdf: impl Fn([f64; 3]) -> f64,
+) -> ValidationResult<Mesh> {
+    validate_voxel_grid(resolution, 0.0)?;
+    if (0..3).any(|k| !(min[k].is_finite() && max[k].is_finite() && min[k] <= max[k])) {
+        return Err(ValidationError::new(format!(
+            "Sampling box must be finite with min <= max (got {:?} to {:?})",
+            min, max
+        )));
+    }
+    
+    let samples = [0, 1, 2].map(|k| ((max[k] - min[k]) / resolution).ceil() + 1.0);
+    validate_voxel_grid(resolution, samples[0] * samples[1] * samples[2])?;
+    let grid = ScalarGrid::sample(min, resolution, samples.map(|s| s as usize), sdf);
+    Ok(isosurface_mesh(&grid, iso))
+}
+
+fn isosurface_mesh(grid: &ScalarGrid, iso: f64) -> Mesh {
+    let (vertices, faces) = isosurface::triangulate(grid, iso);
+    let mut normals = vec![0.0; vertices.len()];
+    compute_normals(&vertices, &faces, &mut normals);
+    Mesh::new(vertices, faces, normals)
+}
+
+// ============ ROUNDING ============
+
+/// Lattice cells per radius when `round_all` re-meshes
+const ROUND_CELLS_PER_RADIUS: f64 = 4.0;
+
+/// Round every convex edge and corner at once by growing the solid by
+/// `radius` in all directions
+///
+/// Approximates the Minkowski sum with a sphere: the signed distance to
+/// the mesh is sampled on a lattice of spacing `radius / 4`, and the
+/// surface `radius` outside the mesh is extracted as in
+/// `marching_cubes_sdf`. Faces
+/// move out by `radius`, convex edges become cylinders and corners
+/// spheres, while concave edges stay sharp. The result is faceted at the
+/// lattice spacing and, like `voxelize`, expects a closed mesh.
+#[wasm_bindgen]
+pub fn round_all(mesh: &Mesh, radius: f64) -> Result<Mesh, JsValue> {
+    offset_mesh(mesh, radius).map_err(|e| e.to_js_value())
+}
+
+fn offset_mesh(mesh: &Mesh, radius: f64) -> ValidationResult<Mesh> {
+    validate_fillet_radius(radius)?;
+    
+    let surface = SurfaceDistance::new(triangle_points(mesh));
+    if surface.triangles.is_empty() {
+        return Err(ValidationError::new("Cannot round an empty mesh"));
+    }
+    
+    // Half a cell off the offset faces of an axis-aligned mesh keeps the
+    // samples off the surface, and a spare cell keeps the border outside
+    let spacing = radius / ROUND_CELLS_PER_RADIUS;
+    let margin = radius + 1.5 * spacing;
+    let bounds = compute_bounding_box(mesh);
+    let min = [bounds.min_x - margin, bounds.min_y - margin, bounds.min_z - margin];
+    let max = [bounds.max_x + margin, bounds.max_y + margin, bounds.max_z + margin];
+    
+    let mut rounded = sdf_mesh(min, max, spacing, 0.0, |p| {
+        let p = Vec3::from(p);
+        let distance = surface.distance(&p);
+        // Within `radius` of the surface the sample is inside the offset
+        // whichever side it is on, so the winding number can be skipped
+        if distance > radius && winding_number(&surface.triangles, &p).abs() > 0.5 {
+            -distance - radius
+        } else {
+            distance - radius
+        }
+    })?;
+    rounded.material = mesh.material.clone();
+    Ok(rounded)
+}
+
+// ============ RESIN PRINTING ============
+
+/// Lattice cells per wall thickness when `hollow_for_resin` meshes the cavity
+const HOLLOW_CELLS_PER_WALL: f64 = 2.0;
+
+/// Hollow a closed mesh for resin printing, leaving walls `wall` thick,
+/// and drill `count` drain holes of `drain_diameter` up through its floor
+///
+/// The cavity is everything more than `wall` inside the surface, meshed by
+/// marching cubes at a spacing of `wall / 2`, so its edges are faceted.
+/// Holes run straight up (+Z) from below the mesh into the lowest part of
+/// the cavity, spread apart and kept clear of the cavity walls. The result
+/// goes through `prepare_for_print`, so it is watertight: the holes join
+/// the inner and outer surfaces into one.
+#[wasm_bindgen]
+pub fn hollow_for_resin(
+    mesh: &Mesh,
+    wall: f64,
+    drain_diameter: f64,
+    count: u32,
+) -> Result<Mesh, JsValue> {
+    hollow_with_drains(mesh, wall, drain_diameter, count).map_err(|e| e.to_js_value())
+}
+
+fn hollow_with_drains(
+    mesh: &Mesh,
+    wall: f64,
+    drain_diameter: f64,
+    count: u32,
+) -> ValidationResult<Mesh> {
+    validate_dimension(wall, "wall thickness")?;
+    validate_hole(drain_diameter, wall)?;
+    if count == 0 {
+        return Err(ValidationError::new(
+            "At least one drain hole is needed to empty the cavity",
+        ));
+    }
+    
+    let surface = SurfaceDistance::new(triangle_points(mesh));
+    if surface.triangles.is_empty() {
+        return Err(ValidationError::new("Cannot hollow an empty mesh"));
+    }
+    
+    // Half a cell outside the bounds keeps flat walls between samples
+    let spacing = wall / HOLLOW_CELLS_PER_WALL;
+    let bounds = compute_bounding_box(mesh);
+    let half = spacing / 2.0;
+    let min = [bounds.min_x - half, bounds.min_y - half, bounds.min_z - half];
+    let max = [bounds.max_x + half, bounds.max_y + half, bounds.max_z + half];
+    let cavity = sdf_mesh(min, max, spacing, 0.0, |p| {
+        let p = Vec3::from(p);
+        let distance = surface.distance(&p);
+        // Samples more than a cell short of `wall` deep are wall material
+        // on either side and never border the cavity, so only the rest
+        // need the winding number
+        if distance > wall - spacing && winding_number(&surface.triangles, &p).abs() > 0.5 {
+            wall - distance
+        } else {
+            wall + distance
+        }
+    })?;
+    if cavity.faces.is_empty() {
+        return Err(ValidationError::new(format!(
+            "Wall thickness {}mm leaves no cavity",
+            wall
+        )));
+    }
+    
+    let mut tools = vec![CSGMesh::from_buffers(&cavity.vertices, &cavity.faces)];
+    for top in drain_positions(&cavity, drain_diameter / 2.0, spacing, count)? {
+        // From below the mesh to a cell into the cavity
+        let start = Vec3::new(top[0], top[1], bounds.min_z - spacing);
+        let depth = top[2] + spacing - start.z;
+        let hole = hole_cylinder(start, Vec3::z(), drain_diameter, depth);
+        tools.push(CSGMesh::from_buffers(&hole.vertices, &hole.faces));
+    }
+    
+    let base = CSGMesh::from_buffers(&mesh.vertices, &mesh.faces);
+    let (vertices, faces, normals) = csg_subtract_all(&base, &tools).to_buffers();
+    let mut hollow = repair_for_print(&Mesh::new(vertices, faces, normals))?;
+    hollow.material = mesh.material.clone();
+    Ok(hollow)
+}
+
+/// Points on the cavity floor for `count` drain holes of `radius`
+///
+/// Candidates are cavity vertices within a cell of its lowest point that
+/// lie, horizontally, at least `radius` plus a cell from every vertex up to
+/// a wall higher, so each hole opens into the floor rather than a wall.
+/// The first hole goes nearest the candidates' centroid and each later one
+/// as far from those chosen as possible, at least a diameter plus a cell.
+fn drain_positions(
+    cavity: &Mesh,
+    radius: f64,
+    spacing: f64,
+    count: u32,
+) -> ValidationResult<Vec<[f64; 3]>> {
+    let points: Vec<[f64; 3]> = cavity
+        .vertices
+        .chunks_exact(3)
+        .map(|v| [v[0], v[1], v[2]])
+        .collect();
+    let floor = points.iter().map(|p| p[2]).fold(f64::INFINITY, f64::min);
+    let horizontal = |a: &[f64; 3], b: &[f64; 3]| (a[0] - b[0]).hypot(a[1] - b[1]);
+    
+    let (low, rest): (Vec<[f64; 3]>, Vec<[f64; 3]>) =
+        points.iter().partition(|p| p[2] <= floor + spacing);
+    let walls: Vec<&[f64; 3]> = rest
+        .iter()
+        .filter(|p| p[2] <= floor + spacing * (HOLLOW_CELLS_PER_WALL + 1.0))
+        .collect();
+    let candidates: Vec<[f64; 3]> = low
+        .into_iter()
+        .filter(|p| walls.iter().all(|w| horizontal(p, w) >= radius + spacing))
+        .collect();
+    if candidates.is_empty() {
+        return Err(ValidationError::new(format!(
+            "Drain holes of {}mm diameter do not fit the cavity floor",
+            2.0 * radius
+        )));
+    }
+    
+    let centroid = candidates.iter().fold([0.0; 3], |sum, p| {
+        [0, 1, 2].map(|k| sum[k] + p[k] / candidates.len() as f64)
+    });
+    let first = candidates
+        .iter()
+        .min_by(|a, b| horizontal(a, &centroid).total_cmp(&horizontal(b, &centroid)))
+        .copied()
+        .unwrap();
+    
+    let mut chosen = vec![first];
+    while chosen.len() < count as usize {
+        let clearance = |p: &[f64; 3]| {
+            chosen
+                .iter()
+                .map(|c| horizontal(p, c))
+                .fold(f64::INFINITY, f64::min)
+        };
+        let (next, gap) = candidates
+            .iter()
+            .map(|p| (*p, clearance(p)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        if gap < 2.0 * radius + spacing {
+            return Err(ValidationError::new(format!(
+                "Only {} drain holes of {}mm diameter fit the cavity floor",
+                chosen.len(),
+                2.0 * radius
+            )));
+        }
+        chosen.push(next);
+    }
+    Ok(chosen)
+}
+
+// ============ ASSET VALIDATION ============
+
+#[wasm_bindgen]
+pub fn validate_asset(asset_data: &[u8]) -> Result<JsValue, JsValue> {
+    if asset_data.len() < 4 {
+        return Err(JsValue::from_str("Invalid file: too small"));
+    }
+
+    let is_step = asset_data.windows(5).any(|w| w == b"ISO-10");
+    let is_stl = asset_data.windows(5).any(|w| w == b"solid");
+
+    if !is_step && !is_stl {
+        return Err(JsValue::from_str(
+            "Unsupported format. Expected STEP, IGES, or STL.",
+        ));
+    }
+
+    #[derive(serde::Serialize)]
+    struct ValidationResult {
+        status: String,
+        format: String,
+        size_bytes: usize,
+        issues: Vec<String>,
+    }
+
+    let result = ValidationResult {
+        status: "valid".to_string(),
+        format: if is_step {
+            "STEP".to_string()
+        } else {
+            "STL".to_string()
+        },
+        size_bytes: asset_data.len(),
+        issues: vec![],
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&result).unwrap())
+}
+
+// ============ INTERNAL MESH GENERATION ============
+
+fn generate_box_mesh(width: f64, height: f64, depth: f64) -> Mesh {
+    let w = width / 2.0;
+    let h = height / 2.0;
+    let d = depth / 2.0;
+
+    let vertices = vec![
+        -w, -h, -d,  // 0
+        w, -h, -d,   // 1
+        w, h, -d,    // 2
+        -w, h, -d,   // 3
+        -w, -h, d,   // 4
+        w, -h, d,    // 5
+        w, h, d,     // 6
+        -w, h, d,    // 7
+    ];
+
+    let faces = vec![
+        // bottom (-z)
+        0, 2, 1,  0, 3, 2,
+        // top (+z)
+        4, 5, 6,  4, 6, 7,
+        // front (-y)
+        0, 1, 5,  0, 5, 4,
+        // back (+y)
+        2, 3, 7,  2, 7, 6,
+        // left (-x)
+        0, 4, 7,  0, 7, 3,
+        // right (+x)
+        1, 2, 6,  1, 6, 5,
+    ];
+
+    let mut normals = vec![0.0; vertices.len()];
+    compute_normals(&vertices, &faces, &mut normals);
+
+    Mesh { vertices, faces, normals, material: None, source_ids: Vec::new(), colors: Vec::new() }
+}
+
+fn generate_cylinder_mesh(radius: f64, height: f64, segments: u32) -> Mesh {
+    generate_frustum_mesh(radius, radius, height, segments)
+}
+
+fn generate_frustum_mesh(bottom_radius: f64, top_radius: f64, height: f64, segments: u32) -> Mesh {
+    let mut vertices = Vec::new();
+    let mut faces = Vec::new();
+    
+    let half_height = height / 2.0;
+    
+    // Bottom center
+    vertices.extend_from_slice(&[0.0, -half_height, 0.0]);
+    // Top center
     vertices.extend_from_slice(&[0.0, half_height, 0.0]);
     
-    // Generate ring vertices
-    for i in 0..=segments {
-        let angle = (i as f64 / segments as f64) * 2.0 * PI;
-        let x = radius * angle.cos();
-        let z = radius * angle.sin();
+    // Generate ring vertices
+    for i in 0..=segments {
+        let angle = (i as f64 / segments as f64) * 2.0 * PI;
+        let (cos, sin) = (angle.cos(), angle.sin());
+        
+        // Bottom ring
+        vertices.extend_from_slice(&[bottom_radius * cos, -half_height, bottom_radius * sin]);
+        // Top ring
+        vertices.extend_from_slice(&[top_radius * cos, half_height, top_radius * sin]);
+    }
+    
+    // Bottom cap
+    for i in 0..segments {
+        let base = 2 + i * 2;
+        faces.extend_from_slice(&[0, base, base + 2]);
+    }
+    
+    // Top cap
+    for i in 0..segments {
+        let base = 2 + i * 2;
+        faces.extend_from_slice(&[1, base + 3, base + 1]);
+    }
+    
+    // Sides
+    for i in 0..segments {
+        let base = 2 + i * 2;
+        // Triangle 1
+        faces.extend_from_slice(&[base, base + 1, base + 2]);
+        // Triangle 2
+        faces.extend_from_slice(&[base + 1, base + 3, base + 2]);
+    }
+    
+    let mut normals = vec![0.0; vertices.len()];
+    compute_normals(&vertices, &faces, &mut normals);
+    
+    Mesh { vertices, faces, normals, material: None, source_ids: Vec::new(), colors: Vec::new() }
+}
+
+fn generate_sphere_mesh(radius: f64, segments_lat: u32, segments_lon: u32) -> Mesh {
+    let mut vertices = Vec::new();
+    let mut faces = Vec::new();
+    
+    // Generate vertices
+    for lat in 0..=segments_lat {
+        let theta = (lat as f64 / segments_lat as f64) * PI;
+        let sin_theta = theta.sin();
+        let cos_theta = theta.cos();
+        
+        for lon in 0..=segments_lon {
+            let phi = (lon as f64 / segments_lon as f64) * 2.0 * PI;
+            let sin_phi = phi.sin();
+            let cos_phi = phi.cos();
+            
+            let x = radius * sin_theta * cos_phi;
+            let y = radius * cos_theta;
+            let z = radius * sin_theta * sin_phi;
+            
+            vertices.extend_from_slice(&[x, y, z]);
+        }
+    }
+    
+    // Generate faces
+    for lat in 0..segments_lat {
+        for lon in 0..segments_lon {
+            let current = lat * (segments_lon + 1) + lon;
+            let next = current + segments_lon + 1;
+            
+            // Triangle 1
+            faces.extend_from_slice(&[current, next, current + 1]);
+            // Triangle 2
+            faces.extend_from_slice(&[current + 1, next, next + 1]);
+        }
+    }
+    
+    let mut normals = vec![0.0; vertices.len()];
+    compute_normals(&vertices, &faces, &mut normals);
+    
+    Mesh { vertices, faces, normals, material: None, source_ids: Vec::new(), colors: Vec::new() }
+}
+
+fn generate_cone_mesh(radius: f64, height: f64, segments: u32) -> Mesh {
+    let mut vertices = Vec::new();
+    let mut faces = Vec::new();
+    
+    // Apex
+    vertices.extend_from_slice(&[0.0, height / 2.0, 0.0]);
+    // Base center
+    vertices.extend_from_slice(&[0.0, -height / 2.0, 0.0]);
+    
+    // Base ring
+    for i in 0..=segments {
+        let angle = (i as f64 / segments as f64) * 2.0 * PI;
+        let x = radius * angle.cos();
+        let z = radius * angle.sin();
+        vertices.extend_from_slice(&[x, -height / 2.0, z]);
+    }
+    
+    // Base cap
+    for i in 0..segments {
+        faces.extend_from_slice(&[1, 2 + i, 2 + i + 1]);
+    }
+    
+    // Sides
+    for i in 0..segments {
+        faces.extend_from_slice(&[0, 2 + i + 1, 2 + i]);
+    }
+    
+    let mut normals = vec![0.0; vertices.len()];
+    compute_normals(&vertices, &faces, &mut normals);
+    
+    Mesh { vertices, faces, normals, material: None, source_ids: Vec::new(), colors: Vec::new() }
+}
+
+fn generate_torus_mesh(major_radius: f64, minor_radius: f64, segments_major: u32, segments_minor: u32) -> Mesh {
+    let mut vertices = Vec::new();
+    let mut faces = Vec::new();
+    
+    // Generate vertices
+    for i in 0..=segments_major {
+        let u = (i as f64 / segments_major as f64) * 2.0 * PI;
+        let cos_u = u.cos();
+        let sin_u = u.sin();
         
-        // Bottom ring
-        vertices.extend_from_slice(&[x, -half_height, z]);
-        // Top ring
-        vertices.extend_from_slice(&[x, half_height, z]);
+        for j in 0..=segments_minor {
+            let v = (j as f64 / segments_minor as f64) * 2.0 * PI;
+            let cos_v = v.cos();
+            let sin_v = v.sin();
+            
+            let x = (major_radius + minor_radius * cos_v) * cos_u;
+            let y = minor_radius * sin_v;
+            let z = (major_radius + minor_radius * cos_v) * sin_u;
+            
+            vertices.extend_from_slice(&[x, y, z]);
+        }
+    }
+    
+    // Generate faces
+    for i in 0..segments_major {
+        for j in 0..segments_minor {
+            let current = i * (segments_minor + 1) + j;
+            let next = current + segments_minor + 1;
+            
+            // Triangle 1
+            faces.extend_from_slice(&[current, next, current + 1]);
+            // Triangle 2
+            faces.extend_from_slice(&[current + 1, next, next + 1]);
+        }
+    }
+    
+    let mut normals = vec![0.0; vertices.len()];
+    compute_normals(&vertices, &faces, &mut normals);
+    
+    Mesh { vertices, faces, normals, material: None, source_ids: Vec::new(), colors: Vec::new() }
+}
+
+/// Cross product of a triangle's edges: its normal scaled by twice its
+/// area, zero when the triangle is degenerate
+fn area_normal(v: &[Vec3<f64>; 3]) -> Vec3<f64> {
+    (v[1] - v[0]).cross(&(v[2] - v[0]))
+}
+
+fn compute_normals(vertices: &[f64], faces: &[u32], normals: &mut [f64]) {
+    compute_normals_weighted(vertices, faces, normals, NormalWeighting::Area);
+}
+
+fn compute_normals_weighted(
+    vertices: &[f64],
+    faces: &[u32],
+    normals: &mut [f64],
+    weighting: NormalWeighting,
+) {
+    // Initialize normals to zero
+    for n in normals.iter_mut() {
+        *n = 0.0;
+    }
+    
+    // Accumulate face normals
+    for tri in faces.chunks_exact(3) {
+        let idx = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+        let v = idx.map(|i| Vec3::new(vertices[i * 3], vertices[i * 3 + 1], vertices[i * 3 + 2]));
+        
+        let face_normal = area_normal(&v);
+        let area_x2 = face_normal.norm();
+        if area_x2 == 0.0 {
+            continue; // Degenerate triangles carry no direction
+        }
+        
+        for corner in 0..3 {
+            let weighted = match weighting {
+                NormalWeighting::Area => face_normal,
+                NormalWeighting::Uniform => face_normal / area_x2,
+                NormalWeighting::Angle => {
+                    let e1 = v[(corner + 1) % 3] - v[corner];
+                    let e2 = v[(corner + 2) % 3] - v[corner];
+                    face_normal / area_x2 * e1.angle(&e2)
+                }
+            };
+            
+            let i = idx[corner];
+            normals[i * 3] += weighted.x;
+            normals[i * 3 + 1] += weighted.y;
+            normals[i * 3 + 2] += weighted.z;
+        }
+    }
+    
+    // Normalize
+    for i in (0..normals.len()).step_by(3) {
+        let len = (normals[i] * normals[i] + 
+                   normals[i + 1] * normals[i + 1] + 
+                   normals[i + 2] * normals[i + 2]).sqrt();
+        
+        if len > 0.0 {
+            normals[i] /= len;
+            normals[i + 1] /= len;
+            normals[i + 2] /= len;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_box() {
+        let mesh = create_box(100.0, 50.0, 25.0).unwrap();
+        assert_eq!(mesh.vertex_count(), 8);
+        assert_eq!(mesh.face_count(), 12);
+    }
+
+    #[test]
+    fn test_create_cylinder() {
+        let mesh = create_cylinder(50.0, 100.0, Some(16)).unwrap();
+        assert!(mesh.vertex_count() > 0);
+        assert!(mesh.face_count() > 0);
+    }
+
+    #[test]
+    fn test_create_sphere() {
+        let mesh = create_sphere(50.0, Some(16), Some(16)).unwrap();
+        assert!(mesh.vertex_count() > 0);
+        assert!(mesh.face_count() > 0);
+    }
+
+    #[test]
+    fn test_frustum_volume() {
+        let (big, small, height, segments) = (30.0, 10.0, 40.0, 64);
+        let frustum = create_frustum(big, small, height, Some(segments)).unwrap();
+        
+        // Polygonal rings hold (n/2)·sin(2π/n)/π of the circle's area
+        let n = segments as f64;
+        let polygon = n / 2.0 * (2.0 * PI / n).sin() / PI;
+        let expected = PI * height / 3.0 * (big * big + big * small + small * small);
+        assert!((signed_volume(&frustum) - polygon * expected).abs() < 1e-6);
+        
+        // Degenerate radii reproduce the cone and the cylinder
+        let cone = create_frustum(big, 0.0, height, Some(segments)).unwrap();
+        assert_eq!(cone.vertices, create_cone(big, height, Some(segments)).unwrap().vertices);
+        let cylinder = create_frustum(big, big, height, Some(segments)).unwrap();
+        assert_eq!(cylinder.vertices, create_cylinder(big, height, Some(segments)).unwrap().vertices);
+        
+        assert!(validate_frustum(big, -1.0, height, 32).is_err());
+    }
+    
+    #[test]
+    fn test_zero_segments_clamp_to_minimum() {
+        let min = Some(MIN_SEGMENTS);
+        let pairs = [
+            (create_cylinder(5.0, 10.0, Some(0)), create_cylinder(5.0, 10.0, min)),
+            (create_sphere(5.0, Some(0), Some(0)), create_sphere(5.0, min, min)),
+            (create_cone(5.0, 10.0, Some(0)), create_cone(5.0, 10.0, min)),
+            (create_frustum(5.0, 2.0, 10.0, Some(0)), create_frustum(5.0, 2.0, 10.0, min)),
+            (create_torus(10.0, 2.0, Some(0), Some(0)), create_torus(10.0, 2.0, min, min)),
+        ];
+        
+        for (zero, minimum) in pairs {
+            let (zero, minimum) = (zero.unwrap(), minimum.unwrap());
+            assert_eq!(zero.faces, minimum.faces);
+            assert!(zero.vertices.iter().all(|v| v.is_finite()));
+            assert!(signed_volume(&zero).abs() > 1.0);
+        }
+        
+        // Counts at or above the minimum are used as given
+        assert_ne!(
+            create_cylinder(5.0, 10.0, min).unwrap().faces,
+            create_cylinder(5.0, 10.0, Some(MIN_SEGMENTS + 1)).unwrap().faces
+        );
+        
+        // Only the upper bound is an error
+        assert!(validate_cylinder(5.0, 10.0, 1001).is_err());
+    }
+    
+    #[test]
+    fn test_export_stl() {
+        let mesh = create_box(100.0, 50.0, 25.0).unwrap();
+        let stl = export_stl(&mesh, "test", None, None, None).unwrap();
+        assert!(stl.contains("solid test"));
+        assert!(stl.contains("facet normal"));
+        assert!(stl.contains("endsolid"));
+    }
+
+    #[test]
+    fn test_stl_facet_normals_match_stored_normals() {
+        // Unshare the corners so every vertex normal is one facet's normal
+        let indexed = create_box(100.0, 50.0, 25.0).unwrap();
+        let vertices: Vec<f64> = indexed
+            .faces
+            .iter()
+            .flat_map(|&i| indexed.vertices[i as usize * 3..i as usize * 3 + 3].to_vec())
+            .collect();
+        let faces: Vec<u32> = (0..indexed.faces.len() as u32).collect();
+        let mut normals = vec![0.0; vertices.len()];
+        compute_normals(&vertices, &faces, &mut normals);
+        let flat = Mesh::new(vertices, faces, normals);
+
+        let stl = write_stl(&flat, "box", false, false, false).unwrap();
+        let facet_normals: Vec<Vec<f64>> = stl
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("facet normal "))
+            .map(|rest| rest.split(' ').map(|c| c.parse().unwrap()).collect())
+            .collect();
+        assert_eq!(facet_normals.len(), flat.face_count());
+        for (facet, normal) in facet_normals.iter().zip(flat.normals.chunks_exact(9)) {
+            assert_eq!(facet.as_slice(), &normal[..3]);
+        }
+
+        // A degenerate facet has no direction to report
+        let sliver = Mesh::new(vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 2.0, 0.0, 0.0], vec![0, 1, 2], Vec::new());
+        let stl = write_stl(&sliver, "sliver", false, false, false).unwrap();
+        assert!(stl.contains("facet normal 0 0 0\n"));
+    }
+
+    #[test]
+    fn test_stl_zero_normals_option() {
+        let mesh = create_box(100.0, 50.0, 25.0).unwrap();
+        let stl = export_stl(&mesh, "box", None, None, Some(true)).unwrap();
+        let normals: Vec<&str> = stl.lines().filter(|line| line.contains("facet normal")).collect();
+        assert_eq!(normals.len(), mesh.face_count());
+        assert!(normals.iter().all(|line| line.trim() == "facet normal 0 0 0"));
+
+        // Geometry is written exactly as without the option
+        let computed = export_stl(&mesh, "box", None, None, None).unwrap();
+        let vertex_lines = |stl: &str| stl.lines().filter(|l| l.contains("vertex")).map(String::from).collect::<Vec<_>>();
+        assert_eq!(vertex_lines(&stl), vertex_lines(&computed));
+        assert_ne!(stl, computed);
+    }
+
+    #[test]
+    fn test_export_obj_normals() {
+        let mesh = create_box(100.0, 50.0, 25.0).unwrap();
+        let obj = write_obj(&mesh, "part", false).unwrap();
+        
+        let vn = obj.lines().filter(|line| line.starts_with("vn ")).count();
+        assert_eq!(vn, mesh.vertex_count());
+        
+        let faces: Vec<&str> = obj.lines().filter(|line| line.starts_with("f ")).collect();
+        assert_eq!(faces.len(), mesh.faces.len() / 3);
+        for (face, tri) in faces.iter().zip(mesh.faces.chunks_exact(3)) {
+            let refs: Vec<&str> = face[2..].split(' ').collect();
+            for (reference, &index) in refs.iter().zip(tri) {
+                assert_eq!(*reference, format!("{0}//{0}", index + 1));
+            }
+        }
+        
+        let mut broken = mesh.clone();
+        broken.normals.truncate(3);
+        assert!(write_obj(&broken, "part", false).is_err());
+    }
+    
+    #[test]
+    fn test_export_embeds_mesh_hash() {
+        let mesh = create_box(100.0, 50.0, 25.0).unwrap();
+        let hash = compute_mesh_hash(&mesh);
+        
+        let obj = export_obj(&mesh, "part", Some(true)).unwrap();
+        let stamped = obj
+            .lines()
+            .find_map(|line| line.strip_prefix("# Mesh hash: "))
+            .unwrap();
+        assert_eq!(stamped, hash);
+        
+        let stl = export_stl(&mesh, "part", None, Some(true), None).unwrap();
+        assert!(stl.starts_with(&format!("solid part hash={}\n", hash)));
+        
+        // Off by default
+        assert!(!export_obj(&mesh, "part", None).unwrap().contains(&hash));
+        assert!(!export_stl(&mesh, "part", None, None, None).unwrap().contains(&hash));
+    }
+
+    #[test]
+    fn test_deterministic_mesh() {
+        let mesh1 = create_box(100.0, 50.0, 25.0).unwrap();
+        let mesh2 = create_box(100.0, 50.0, 25.0).unwrap();
+
+        let hash1 = compute_mesh_hash(&mesh1);
+        let hash2 = compute_mesh_hash(&mesh2);
+
+        assert_eq!(hash1, hash2, "Mesh generation must be deterministic");
+    }
+
+    #[test]
+    fn test_export_stl_require_watertight() {
+        let closed = create_box(100.0, 50.0, 25.0).unwrap();
+        assert!(write_stl(&closed, "box", true, false, false).is_ok());
+
+        // Drop the last triangle to open up the shell
+        let mut faces = closed.faces();
+        faces.truncate(faces.len() - 3);
+        let open = Mesh::new(closed.vertices(), faces, closed.normals());
+
+        let err = write_stl(&open, "open", true, false, false).unwrap_err();
+        assert!(err.message.contains("3 open boundary edges"));
+        assert!(!err.message.contains("non-manifold"));
+        
+        // Per-face vertex copies weld back into a closed shell
+        let unshared: Vec<u32> = (0..closed.faces.len() as u32).collect();
+        let vertices: Vec<f64> = closed
+            .faces
+            .iter()
+            .flat_map(|&i| closed.vertices[i as usize * 3..i as usize * 3 + 3].to_vec())
+            .collect();
+        assert!(validate_watertight(&vertices, &unshared).is_ok());
+        
+        // A fin through the box adds a third triangle to two edges and
+        // leaves its third edge open; both kinds are listed
+        let mut fin = closed.faces();
+        fin.extend_from_slice(&[0, 1, 6]);
+        let err = validate_watertight(&closed.vertices, &fin).unwrap_err();
+        assert!(err.message.contains("1 open boundary edges: 0-6"));
+        assert!(err.message.contains("2 non-manifold edges: 0-1, 1-6"));
+
+        // Permissive by default
+        assert!(write_stl(&open, "open", false, false, false).is_ok());
+    }
+
+    #[test]
+    fn test_center_of_mass() {
+        let mesh = create_box(100.0, 50.0, 25.0).unwrap();
+        let com = compute_center_of_mass(&mesh);
+        for c in com {
+            assert!(c.abs() < 1e-9);
+        }
+        assert!(analyze_center_of_mass(&mesh).volumetric);
+
+        let moved = translate_mesh(&mesh, 10.0, -20.0, 5.0);
+        let com = compute_center_of_mass(&moved);
+        assert!((com[0] - 10.0).abs() < 1e-9);
+        assert!((com[1] + 20.0).abs() < 1e-9);
+        assert!((com[2] - 5.0).abs() < 1e-9);
+
+        // Open meshes fall back to the vertex average
+        let mut faces = mesh.faces();
+        faces.truncate(faces.len() - 3);
+        let open = Mesh::new(mesh.vertices(), faces, mesh.normals());
+        assert!(!analyze_center_of_mass(&open).volumetric);
+    }
+
+    #[test]
+    fn test_projected_area() {
+        let cube = create_box(1.0, 1.0, 1.0).unwrap();
+        
+        // Straight along a face normal only that face is seen
+        for direction in [[1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, 2.0]] {
+            assert!((projected_area(&cube, direction) - 1.0).abs() < 1e-9);
+        }
+        
+        // Along the body diagonal three faces are seen, a regular hexagon
+        let diagonal = projected_area(&cube, [1.0, 1.0, 1.0]);
+        assert!((diagonal - 3.0_f64.sqrt()).abs() < 1e-9);
+        assert!(diagonal > 1.0);
+        
+        assert_eq!(projected_area(&cube, [0.0; 3]), 0.0);
+        assert!(validate_direction(&[0.0, 0.0]).is_err());
+        assert!(validate_direction(&[0.0, 0.0, 0.0]).is_err());
+    }
+    
+    #[test]
+    fn test_min_enclosing_cylinder() {
+        for segments in [6, 17, 64, 200] {
+            let bar = create_cylinder(5.0, 12.0, Some(segments)).unwrap();
+            let (radius, length) = min_enclosing_cylinder(&bar, [0.0, 1.0, 0.0]);
+            assert!((radius - 5.0).abs() < 1e-9, "{} segments: {}", segments, radius);
+            assert!((length - 12.0).abs() < 1e-9);
+            
+            // Off-centre, with an unnormalized axis pointing the other way
+            let moved = translate_mesh(&bar, 30.0, -4.0, 7.0);
+            let (radius, length) = min_enclosing_cylinder(&moved, [0.0, -3.0, 0.0]);
+            assert!((radius - 5.0).abs() < 1e-9);
+            assert!((length - 12.0).abs() < 1e-9);
+        }
+        
+        // Across the bar the stock must cover its length as a diameter
+        let bar = create_cylinder(5.0, 12.0, Some(64)).unwrap();
+        let (radius, length) = min_enclosing_cylinder(&bar, [1.0, 0.0, 0.0]);
+        assert!((radius - (6.0_f64.hypot(5.0))).abs() < 0.05);
+        assert!((length - 10.0).abs() < 1e-9);
+        
+        assert_eq!(min_enclosing_cylinder(&bar, [0.0; 3]), (0.0, 0.0));
+    }
+    
+    #[test]
+    fn test_gear_pitch_diameter() {
+        use crate::gear::gear_profile;
+        
+        let (module, teeth) = (2.0, 20);
+        let gear = create_gear(module, teeth, 5.0, 20.0).unwrap();
+        assert!(find_open_edges(&gear.faces).is_empty());
+        let tip = gear
+            .vertices
+            .chunks_exact(3)
+            .map(|v| v[0].hypot(v[1]))
+            .fold(0.0, f64::max);
+        assert!((tip - module * (teeth as f64 + 2.0) / 2.0).abs() < 1e-9);
+        
+        // The outline crosses the pitch circle twice per tooth, and at a
+        // pitch diameter of module * teeth tooth and gap are equally wide
+        let pitch = module * teeth as f64 / 2.0;
+        let profile = gear_profile(module, teeth, 20.0);
+        let mut crossings = Vec::new();
+        for (i, p) in profile.iter().enumerate() {
+            let q = profile[(i + 1) % profile.len()];
+            let (dp, dq) = (p[0].hypot(p[1]) - pitch, q[0].hypot(q[1]) - pitch);
+            if (dp < 0.0) != (dq < 0.0) {
+                let s = dp / (dp - dq);
+                let x = p[0] + (q[0] - p[0]) * s;
+                let y = p[1] + (q[1] - p[1]) * s;
+                crossings.push(y.atan2(x).rem_euclid(2.0 * PI));
+            }
+        }
+        assert_eq!(crossings.len(), 2 * teeth as usize);
+        crossings.sort_by(f64::total_cmp);
+        // Crossings are read off sampled flanks, so allow for chord error
+        let half_pitch = PI / teeth as f64;
+        for pair in crossings.windows(2) {
+            assert!((pair[1] - pair[0] - half_pitch).abs() < 1e-2 * half_pitch);
+        }
+        
+        assert!(validate_gear(module, MIN_GEAR_TEETH - 1, 5.0, 20.0).is_err());
+        assert!(validate_gear(module, teeth, 5.0, 45.0).is_err());
+    }
+    
+    #[test]
+    fn test_snap_to_box_corner() {
+        let cube = create_box(2.0, 2.0, 2.0).unwrap();
+        
+        // Straight down onto the top face, just inside the (1, 1, 1) corner
+        let snap = snap_query(&cube, [0.95, 0.9, 5.0], [0.0, 0.0, -1.0], 0.2).unwrap();
+        assert_eq!(snap.kind, SnapKind::Vertex);
+        assert_eq!(snap.position, [1.0, 1.0, 1.0]);
+        assert!((snap.hit[2] - 1.0).abs() < 1e-12);
+        
+        // Near the middle of an edge and of the face
+        let edge = snap_query(&cube, [0.0, 0.95, 5.0], [0.0, 0.0, -1.0], 0.2).unwrap();
+        assert_eq!(edge.kind, SnapKind::EdgeMidpoint);
+        assert_eq!(edge.position, [0.0, 1.0, 1.0]);
+        let centre = snap_query(&cube, [0.1, -0.05, 5.0], [0.0, 0.0, -1.0], 0.2).unwrap();
+        assert_eq!(centre.kind, SnapKind::FaceCentroid);
+        assert!((0..3).all(|k| (centre.position[k] - [0.0, 0.0, 1.0][k]).abs() < 1e-12));
+        
+        // Nothing in range, and a ray that misses
+        assert!(snap_query(&cube, [0.5, 0.5, 5.0], [0.0, 0.0, -1.0], 0.2).is_none());
+        assert!(snap_query(&cube, [5.0, 5.0, 5.0], [0.0, 0.0, -1.0], 10.0).is_none());
+    }
+    
+    #[test]
+    fn test_interleaved_buffer() {
+        let sphere = generate_sphere_mesh(1.0, 8, 12);
+        let buffer = sphere.to_interleaved_buffer();
+        assert_eq!(buffer.len(), sphere.vertex_count() * 6);
+        for (i, vertex) in buffer.chunks_exact(6).enumerate() {
+            for k in 0..3 {
+                assert_eq!(vertex[k], sphere.vertices[i * 3 + k] as f32);
+                assert_eq!(vertex[3 + k], sphere.normals[i * 3 + k] as f32);
+            }
+        }
+        
+        let bare = Mesh::new(vec![0.0, 1.0, 2.0], vec![], vec![]);
+        assert_eq!(bare.to_interleaved_buffer(), vec![0.0, 1.0, 2.0, 0.0, 0.0, 0.0]);
+    }
+    
+    #[test]
+    fn test_measure_box_diagonal() {
+        let block = create_box(2.0, 3.0, 6.0).unwrap();
+        
+        // Corner 0 of the bottom triangle 0 to corner 6 of the top triangle 2
+        let result = measure(&block, 0, [1.0, 0.0, 0.0], 2, [0.0, 0.0, 1.0]).unwrap();
+        assert_eq!(result.point_a, [-1.0, -1.5, -3.0]);
+        assert_eq!(result.point_b, [1.0, 1.5, 3.0]);
+        assert!((result.distance - 7.0).abs() < 1e-9);
+        assert!((result.angle_deg.unwrap() - 180.0).abs() < 1e-6);
+        
+        // Bottom to front face, with weights that need normalizing
+        let centre = [1.0; 3];
+        let side = measure(&block, 0, centre, 4, centre).unwrap();
+        assert!((side.angle_deg.unwrap() - 90.0).abs() < 1e-6);
+        let same = measure(&block, 0, centre, 0, centre).unwrap();
+        assert_eq!(same.distance, 0.0);
+        assert!(same.angle_deg.unwrap().abs() < 1e-6);
+        
+        assert!(measure(&block, 12, centre, 0, centre).is_err());
+        assert!(measure(&block, 0, [-1.0, 1.0, 1.0], 0, centre).is_err());
+    }
+    
+    #[test]
+    fn test_normal_weighting() {
+        let sphere = generate_sphere_mesh(1.0, 16, 16);
+        let area = recompute_normals(&sphere, NormalWeighting::Area);
+        let angle = recompute_normals(&sphere, NormalWeighting::Angle);
+
+        // Orientation-independent angle between a normal and the radial direction
+        let radial_error = |mesh: &Mesh, i: usize| {
+            let v = Vec3::new(mesh.vertices[i * 3], mesh.vertices[i * 3 + 1], mesh.vertices[i * 3 + 2]);
+            let n = Vec3::new(mesh.normals[i * 3], mesh.normals[i * 3 + 1], mesh.normals[i * 3 + 2]);
+            n.dot(&v.normalize()).abs().min(1.0).acos()
+        };
+
+        // First ring below the pole (excluding the UV seam), where the
+        // adjacent triangles are thin and uneven
+        for i in 18..33 {
+            let area_error = radial_error(&area, i);
+            let angle_error = radial_error(&angle, i);
+            assert!((area_error - angle_error).abs() > 1e-3);
+            assert!(angle_error < area_error);
+        }
+
+        assert_eq!(recompute_normals(&sphere, NormalWeighting::Area).normals, sphere.normals);
+    }
+
+    #[test]
+    fn test_enforce_outward_winding() {
+        let mut scrambled = create_box(100.0, 50.0, 25.0).unwrap();
+        // Flip a pseudo-random half of the triangles
+        let mut state = 12345u32;
+        for tri in scrambled.faces.chunks_exact_mut(3) {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            if (state >> 16) & 1 == 1 {
+                tri.swap(0, 1);
+            }
+        }
+        assert_ne!(scrambled.faces, create_box(100.0, 50.0, 25.0).unwrap().faces);
+
+        let fixed = enforce_outward_winding(&scrambled);
+        let vertex = |i: u32| Vec3::from_column_slice(&fixed.vertices[i as usize * 3..i as usize * 3 + 3]);
+        let centroid = (0..fixed.vertex_count() as u32).map(vertex).sum::<Vec3<f64>>() / fixed.vertex_count() as f64;
+        for tri in fixed.faces.chunks_exact(3) {
+            let v = [tri[0], tri[1], tri[2]].map(vertex);
+            let middle = (v[0] + v[1] + v[2]) / 3.0;
+            assert!(area_normal(&v).dot(&(middle - centroid)) > 0.0);
+        }
+        assert!((signed_volume(&fixed) - 100.0 * 50.0 * 25.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mesh_quality() {
+        let mesh = create_box(10.0, 10.0, 10.0).unwrap();
+        let quality = analyze_mesh_quality(&mesh, 8);
+
+        assert_eq!(quality.triangle_count, 12);
+        assert_eq!(quality.sliver_count, 0);
+        assert_eq!(quality.aspect_ratios.counts.iter().sum::<usize>(), 12);
+        assert_eq!(quality.edge_lengths.counts.len(), 8);
+
+        // Right isosceles halves of a square: 1 + sqrt(2)
+        assert!((quality.aspect_ratios.max - (1.0 + 2.0f64.sqrt())).abs() < 1e-9);
+        assert!((quality.edge_lengths.min - 10.0).abs() < 1e-9);
+        assert!((quality.edge_lengths.max - 200.0f64.sqrt()).abs() < 1e-9);
+
+        // A needle triangle is a sliver
+        let needle = Mesh::new(vec![0.0, 0.0, 0.0, 100.0, 0.0, 0.0, 50.0, 0.1, 0.0], vec![0, 1, 2], vec![0.0; 9]);
+        assert_eq!(analyze_mesh_quality(&needle, 4).sliver_count, 1);
+    }
+
+    #[test]
+    fn test_merge_coplanar_box() {
+        let mesh = create_box(10.0, 20.0, 30.0).unwrap();
+        assert_eq!(mesh.face_count(), 12);
+
+        let merged = merge_coplanar(&mesh, 1.0);
+        assert_eq!(merged.polygon_count(), 6);
+        assert_eq!(merged.vertex_count(), 8);
+
+        for i in 0..merged.polygon_count() {
+            let start = merged.polygon_offsets[i] as usize;
+            let polygon = &merged.polygon_indices[start..merged.polygon_offsets[i + 1] as usize];
+            assert_eq!(polygon.len(), 4);
+
+            // Polygon normal points away from the box center
+            let n = &merged.polygon_normals[i * 3..i * 3 + 3];
+            let v = polygon[0] as usize * 3;
+            let dot = n[0] * merged.vertices[v] + n[1] * merged.vertices[v + 1] + n[2] * merged.vertices[v + 2];
+            assert!(dot > 0.0);
+        }
+
+        // A sphere has no coplanar neighbours at a tight tolerance
+        let sphere = create_sphere(1.0, Some(8), Some(8)).unwrap();
+        let merged = merge_coplanar(&sphere, 0.1);
+        assert!(merged.polygon_count() > 50);
+    }
+
+    #[test]
+    fn test_list_box_edges() {
+        let mesh = create_box(2.0, 3.0, 4.0).unwrap();
+        let edges = feature_edges(&mesh);
+        assert_eq!(edges.len(), 12);
+        
+        let mut lengths: Vec<f64> = edges.iter().map(|e| e.length).collect();
+        lengths.sort_by(f64::total_cmp);
+        for (i, expected) in [2.0, 3.0, 4.0].iter().enumerate() {
+            assert!(lengths[i * 4..i * 4 + 4].iter().all(|l| (l - expected).abs() < 1e-12));
+        }
+        
+        assert!(edges.iter().enumerate().all(|(i, e)| e.index as usize == i));
+        assert!(edges.iter().all(|e| e.convexity == EdgeConvexity::Convex));
+        
+        // A lone triangle is all boundary
+        let triangle = Mesh {
+            vertices: vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+            faces: vec![0, 1, 2],
+            normals: vec![0.0; 9],
+            material: None,
+            source_ids: Vec::new(),
+            colors: Vec::new(),
+        };
+        let edges = feature_edges(&triangle);
+        assert_eq!(edges.len(), 3);
+        assert!(edges.iter().all(|e| e.convexity == EdgeConvexity::Boundary));
+        
+        // A second face folding up towards the first face's normal
+        let valley = Mesh {
+            vertices: vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, -1.0, 1.0],
+            faces: vec![0, 1, 2, 1, 0, 3],
+            normals: vec![0.0; 12],
+            material: None,
+            source_ids: Vec::new(),
+            colors: Vec::new(),
+        };
+        let edges = feature_edges(&valley);
+        assert_eq!(edges.len(), 5);
+        assert_eq!(edges[0].convexity, EdgeConvexity::Concave);
     }
+
+    fn translate_mesh(mesh: &Mesh, tx: f64, ty: f64, tz: f64) -> Mesh {
+        let mut vertices = mesh.vertices.clone();
     
-    // Bottom cap
-    for i in 0..segments {
-        let base = 2 + i * 2;
-        faces.extend_from_slice(&[0, base, base + 2]);
-    }
+        for i in (0..vertices.len()).step_by(3) {
+            vertices[i] += tx;
+            vertices[i + 1] += ty;
+            vertices[i + 2] += tz;
+        }
     
-    // Top cap
-    for i in 0..segments {
-        let base = 2 + i * 2;
-        faces.extend_from_slice(&[1, base + 3, base + 1]);
+        Mesh {
+            vertices,
+            faces: mesh.faces.clone(),
+            normals: mesh.normals.clone(),
+            material: mesh.material.clone(),
+            source_ids: mesh.source_ids.clone(),
+            colors: mesh.colors.clone(),
+        }
     }
-    
-    // Sides
-    for i in 0..segments {
-        let base = 2 + i * 2;
-        // Triangle 1
-        faces.extend_from_slice(&[base, base + 1, base + 2]);
-        // Triangle 2
-        faces.extend_from_slice(&[base + 1, base + 3, base + 2]);
+
+    fn signed_volume(mesh: &Mesh) -> f64 {
+        mesh.faces
+            .chunks_exact(3)
+            .map(|t| {
+                let p = |i: u32| Vec3::new(mesh.vertices[i as usize * 3], mesh.vertices[i as usize * 3 + 1], mesh.vertices[i as usize * 3 + 2]);
+                p(t[0]).dot(&p(t[1]).cross(&p(t[2]))) / 6.0
+            })
+            .sum()
     }
-    
-    let mut normals = vec![0.0; vertices.len()];
-    compute_normals(&vertices, &faces, &mut normals);
-    
-    Mesh { vertices, faces, normals, material: None }
-}
 
-fn generate_sphere_mesh(radius: f64, segments_lat: u32, segments_lon: u32) -> Mesh {
-    let mut vertices = Vec::new();
-    let mut faces = Vec::new();
-    
-    // Generate vertices
-    for lat in 0..=segments_lat {
-        let theta = (lat as f64 / segments_lat as f64) * PI;
-        let sin_theta = theta.sin();
-        let cos_theta = theta.cos();
+    #[test]
+    fn test_boundary_loops_of_open_box() {
+        let mut mesh = create_box(2.0, 3.0, 4.0).unwrap();
+        assert!(boundary_loops(&mesh).is_empty());
         
-        for lon in 0..=segments_lon {
-            let phi = (lon as f64 / segments_lon as f64) * 2.0 * PI;
-            let sin_phi = phi.sin();
-            let cos_phi = phi.cos();
-            
-            let x = radius * sin_theta * cos_phi;
-            let y = radius * cos_theta;
-            let z = radius * sin_theta * sin_phi;
-            
-            vertices.extend_from_slice(&[x, y, z]);
-        }
+        // Drop the top face
+        mesh.faces.drain(6..12);
+        let loops = boundary_loops(&mesh);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 4);
+        assert!(loops[0].iter().all(|p| p[2] == 2.0 && p[0].abs() == 1.0 && p[1].abs() == 1.5));
+        
+        let mut corners = loops[0].clone();
+        corners.sort_by(|p, q| p.partial_cmp(q).unwrap());
+        corners.dedup();
+        assert_eq!(corners.len(), 4);
     }
     
-    // Generate faces
-    for lat in 0..segments_lat {
-        for lon in 0..segments_lon {
-            let current = lat * (segments_lon + 1) + lon;
-            let next = current + segments_lon + 1;
-            
-            // Triangle 1
-            faces.extend_from_slice(&[current, next, current + 1]);
-            // Triangle 2
-            faces.extend_from_slice(&[current + 1, next, next + 1]);
+    #[test]
+    fn test_cut_box_through_center() {
+        let mesh = create_box(10.0, 20.0, 30.0).unwrap();
+        let (above, below) = cut_mesh_with_plane(&mesh, [0.0; 3], [1.0, 0.0, 0.0]).unwrap();
+
+        for half in [&above, &below] {
+            assert!(find_open_edges(&half.faces).is_empty());
+            assert!((signed_volume(half) - 3000.0).abs() < 1e-9);
         }
+        assert!((compute_center_of_mass(&above)[0] - 2.5).abs() < 1e-9);
+        assert!((compute_center_of_mass(&below)[0] + 2.5).abs() < 1e-9);
+
+        // An oblique cut through vertices and edges still closes both halves
+        let (above, below) = cut_mesh_with_plane(&mesh, [1.0, 2.0, 3.0], [1.0, 1.0, 1.0]).unwrap();
+        assert!(find_open_edges(&above.faces).is_empty());
+        assert!(find_open_edges(&below.faces).is_empty());
+        assert!((signed_volume(&above) + signed_volume(&below) - 6000.0).abs() < 1e-6);
+
+        assert!(cut_mesh_with_plane(&mesh, [0.0; 3], [0.0; 3]).is_err());
     }
-    
-    let mut normals = vec![0.0; vertices.len()];
-    compute_normals(&vertices, &faces, &mut normals);
-    
-    Mesh { vertices, faces, normals, material: None }
-}
 
-fn generate_cone_mesh(radius: f64, height: f64, segments: u32) -> Mesh {
-    let mut vertices = Vec::new();
-    let mut faces = Vec::new();
-    
-    // Apex
-    vertices.extend_from_slice(&[0.0, height / 2.0, 0.0]);
-    // Base center
-    vertices.extend_from_slice(&[0.0, -height / 2.0, 0.0]);
-    
-    // Base ring
-    for i in 0..=segments {
-        let angle = (i as f64 / segments as f64) * 2.0 * PI;
-        let x = radius * angle.cos();
-        let z = radius * angle.sin();
-        vertices.extend_from_slice(&[x, -height / 2.0, z]);
+    #[test]
+    fn test_boolean_xor_volume() {
+        let a = create_box(10.0, 10.0, 10.0).unwrap();
+        let b = translate_mesh(&a, 5.0, 0.0, 0.0);
+
+        let xor = boolean_xor(&a, &b).unwrap();
+        let union = boolean_union(&a, &b).unwrap();
+        let overlap = boolean_intersect(&a, &b).unwrap();
+        assert!(signed_volume(&overlap) > 0.0);
+        assert!((signed_volume(&xor) - (signed_volume(&union) - signed_volume(&overlap))).abs() < 1e-6);
+
+        // Without an overlap XOR is the union of both boxes
+        let far = translate_mesh(&a, 20.0, 0.0, 0.0);
+        let xor = boolean_xor(&a, &far).unwrap();
+        assert_eq!(xor.face_count(), 24);
+        assert!((signed_volume(&xor) - 2000.0).abs() < 1e-6);
     }
-    
-    // Base cap
-    for i in 0..segments {
-        faces.extend_from_slice(&[1, 2 + i, 2 + i + 1]);
+
+    #[test]
+    fn test_voxelize_box() {
+        let mesh = create_box(10.0, 6.0, 4.0).unwrap();
+        let resolution = 0.7;
+        let grid = voxelize_mesh(&mesh, resolution).unwrap();
+
+        assert_eq!(grid.dimensions, [15, 9, 6]);
+        assert_eq!(grid.origin, [-5.0, -3.0, -2.0]);
+        assert_eq!(grid.occupancy.len(), (15 * 9 * 6usize).div_ceil(8));
+
+        // Boundary voxels make the count approximate
+        let expected = 240.0 / resolution.powi(3);
+        assert!((grid.occupied_count as f64 - expected).abs() < 0.1 * expected);
+
+        assert!(grid.is_occupied(0, 0, 0));
+        assert!(!grid.is_occupied(14, 0, 0)); // Center at x = 5.15, past the box
+        assert!(!grid.is_occupied(15, 0, 0));
+
+        assert!(voxelize_mesh(&mesh, 0.0).is_err());
+        assert!(voxelize_mesh(&mesh, 1e-4).is_err());
     }
-    
-    // Sides
-    for i in 0..segments {
-        faces.extend_from_slice(&[0, 2 + i + 1, 2 + i]);
+
+    #[test]
+    fn test_sketch_extrude_frame() {
+        // 10 x 10 square with a clockwise 4 x 4 hole, extruded by 3
+        let json = r#"{
+            "outer": [[-5, -5], [5, -5], [5, 5], [-5, 5], [-5, -5]],
+            "holes": [[[-2, -2], [-2, 2], [2, 2], [2, -2]]],
+            "distance": 3
+        }"#;
+        let mesh = extrude_sketch(&parse_sketch(json).unwrap()).unwrap();
+
+        assert!(find_open_edges(&mesh.faces).is_empty());
+        assert!((signed_volume(&mesh) - (100.0 - 16.0) * 3.0).abs() < 1e-9);
+        let bounds = compute_bounding_box(&mesh);
+        assert_eq!((bounds.min_z, bounds.max_z), (0.0, 3.0));
+
+        assert!(parse_sketch("{\"outer\": []}").is_err());
+        let open = parse_sketch(r#"{"outer": [[0, 0], [1, 0]], "distance": 1}"#).unwrap();
+        assert!(extrude_sketch(&open).is_err());
+        let flat = parse_sketch(r#"{"outer": [[0, 0], [1, 0], [0, 1]], "distance": 0}"#).unwrap();
+        assert!(extrude_sketch(&flat).is_err());
     }
-    
-    let mut normals = vec![0.0; vertices.len()];
-    compute_normals(&vertices, &faces, &mut normals);
-    
-    Mesh { vertices, faces, normals, material: None }
-}
+    #[test]
+    fn test_find_self_intersections() {
+        assert!(find_self_intersections(&create_box(10.0, 10.0, 10.0).unwrap()).is_empty());
 
-fn generate_torus_mesh(major_radius: f64, minor_radius: f64, segments_major: u32, segments_minor: u32) -> Mesh {
-    let mut vertices = Vec::new();
-    let mut faces = Vec::new();
-    
-    // Generate vertices
-    for i in 0..=segments_major {
-        let u = (i as f64 / segments_major as f64) * 2.0 * PI;
-        let cos_u = u.cos();
-        let sin_u = u.sin();
-        
-        for j in 0..=segments_minor {
-            let v = (j as f64 / segments_minor as f64) * 2.0 * PI;
-            let cos_v = v.cos();
-            let sin_v = v.sin();
+        // Two tetrahedra, the second's apex pushed through the first's base
+        let vertices = vec![
+            0.0, 0.0, 0.0, 4.0, 0.0, 0.0, 0.0, 4.0, 0.0, 0.0, 0.0, 4.0,
+            1.0, 1.0, -3.0, 5.0, 1.0, -3.0, 1.0, 5.0, -3.0, 1.0, 1.0, 1.0,
+        ];
+        let tetrahedron = [0, 2, 1, 0, 1, 3, 1, 2, 3, 2, 0, 3];
+        let faces: Vec<u32> = tetrahedron
+            .iter()
+            .copied()
+            .chain(tetrahedron.iter().map(|i| i + 4))
+            .collect();
+        let normals = vec![0.0; vertices.len()];
+        let mesh = Mesh { vertices, faces, normals, material: None, source_ids: Vec::new(), colors: Vec::new() };
+
+        let pairs = find_self_intersections(&mesh);
+        assert!(!pairs.is_empty());
+        assert!(pairs.iter().all(|&(i, j)| i < 4 && j >= 4));
+        assert!(pairs.contains(&(0, 5))); // First base against a side of the second
+    }
+
+    #[test]
+    fn test_intersection_curve_of_boxes() {
+        // Overlap is [-2, 5] x [-1, 5] x [0, 5]; the surfaces cross along
+        // the six edges of that block joining a face of one box to a face
+        // of the other, split into 13 segments by the face diagonals
+        let a = create_box(10.0, 10.0, 10.0).unwrap();
+        let b = translate_mesh(&a, 3.0, 4.0, 5.0);
+
+        let curves = intersection_curve(&a, &b);
+        assert_eq!(curves.len(), 1);
+        let curve = &curves[0];
+        assert_eq!(curve.len(), 14);
+        assert_eq!(curve.first(), curve.last());
+
+        let length: f64 = curve
+            .windows(2)
+            .map(|w| (0..3).map(|k| (w[1][k] - w[0][k]).powi(2)).sum::<f64>().sqrt())
+            .sum();
+        assert!((length - 2.0 * (7.0 + 6.0 + 5.0)).abs() < 1e-9);
+        for p in curve {
+            // Both boxes have half-size 5, so the point is on both surfaces
+            let half = |centre: [f64; 3]| (0..3).map(|k| (p[k] - centre[k]).abs()).fold(0.0, f64::max);
+            assert!((half([0.0; 3]) - 5.0).abs() < 1e-9);
+            assert!((half([3.0, 4.0, 5.0]) - 5.0).abs() < 1e-9);
+        }
+
+        let apart = translate_mesh(&a, 20.0, 0.0, 0.0);
+        assert!(intersection_curve(&a, &apart).is_empty());
+    }
+
+    #[test]
+    fn test_merge_meshes() {
+        let cube = create_box(10.0, 10.0, 10.0).unwrap();
+        let boxes: Vec<Mesh> =
+            (0..3).map(|i| translate_mesh(&cube, 10.0 * i as f64, 0.0, 0.0)).collect();
+
+        let merged = concatenate_meshes(&boxes, false).unwrap();
+        assert_eq!(merged.vertices.len(), boxes.iter().map(|m| m.vertices.len()).sum::<usize>());
+        assert_eq!(merged.faces.len(), boxes.iter().map(|m| m.faces.len()).sum::<usize>());
+        assert_eq!(merged.normals.len(), merged.vertices.len());
+        let volume: f64 = boxes.iter().map(signed_volume).sum();
+        assert!((signed_volume(&merged) - volume).abs() < 1e-9);
+
+        // Touching boxes share the corners of their common faces
+        let welded = concatenate_meshes(&boxes, true).unwrap();
+        assert_eq!(welded.vertices.len(), (8 * 3 - 4 * 2) * 3);
+        assert_eq!(welded.faces.len(), merged.faces.len());
+
+        assert!(concatenate_meshes(&[], true).unwrap().faces.is_empty());
+    }
+
+    #[test]
+    fn test_prepare_for_print_after_union() {
+        let a = create_box(10.0, 10.0, 10.0).unwrap();
+        let tools = [
+            translate_mesh(&a, 5.0, 3.0, 2.0),
+            create_cylinder(5.0, 20.0, Some(24)).unwrap(),
+        ];
+        for tool in &tools {
+            let union = boolean_union(&a, tool).unwrap();
+            assert!(validate_watertight(&union.vertices, &union.faces).is_err());
             
-            let x = (major_radius + minor_radius * cos_v) * cos_u;
-            let y = minor_radius * sin_v;
-            let z = (major_radius + minor_radius * cos_v) * sin_u;
+            let printable = repair_for_print(&union).unwrap();
+            assert!(validate_watertight(&printable.vertices, &printable.faces).is_ok());
+            assert!((signed_volume(&printable) - signed_volume(&union)).abs() < 1e-6);
+            assert_eq!(printable.source_ids.len(), printable.face_count());
             
-            vertices.extend_from_slice(&[x, y, z]);
+            // Welded: every vertex is used and no two share a position
+            let (welded, _) = weld_vertices(&printable);
+            assert_eq!(welded.len(), printable.vertices.len());
+            let mut used = printable.faces.clone();
+            used.sort_unstable();
+            used.dedup();
+            assert_eq!(used.len(), printable.vertex_count());
         }
+        
+        // Holes are reported rather than papered over
+        let mut open = a.clone();
+        open.faces.truncate(open.faces.len() - 3);
+        assert!(repair_for_print(&open).is_err());
     }
     
-    // Generate faces
-    for i in 0..segments_major {
-        for j in 0..segments_minor {
-            let current = i * (segments_minor + 1) + j;
-            let next = current + segments_minor + 1;
-            
-            // Triangle 1
-            faces.extend_from_slice(&[current, next, current + 1]);
-            // Triangle 2
-            faces.extend_from_slice(&[current + 1, next, next + 1]);
+    #[test]
+    fn test_auto_fillet_box() {
+        let (size, radius) = (20.0, 2.0);
+        let cube = create_box(size, size, size).unwrap();
+        let edges = feature_edges(&cube);
+        assert_eq!(edges.len(), 12);
+        
+        // One edge loses the sliver between its corner and the fillet arc
+        let sliver = (1.0 - PI / 4.0) * radius * radius;
+        let single = fillet_edges(&cube, &[0], radius).unwrap().mesh;
+        let removed = signed_volume(&cube) - signed_volume(&single);
+        assert!((removed - sliver * size).abs() < 0.03 * sliver * size);
+        
+        let outcome = auto_fillet(&cube, radius).unwrap();
+        assert!(outcome.skipped().is_empty());
+        let rounded = outcome.mesh();
+        assert!(rounded.faces.len() > single.faces.len());
+        assert!(single.faces.len() > cube.faces.len());
+        
+        // Slivers of edges meeting at a corner overlap within a radius of it
+        let removed = signed_volume(&cube) - signed_volume(&rounded);
+        assert!(removed > 12.0 * sliver * (size - 2.0 * radius));
+        assert!(removed < 12.0 * sliver * size * 1.03);
+        
+        // No vertex is left on or near an original edge
+        for edge in &edges {
+            let (a, b) = (Vec3::from(edge.start), Vec3::from(edge.end));
+            for v in rounded.vertices.chunks_exact(3) {
+                let p = Vec3::new(v[0], v[1], v[2]);
+                let t = ((p - a).dot(&(b - a)) / (b - a).norm_squared()).clamp(0.0, 1.0);
+                assert!((p - (a + (b - a) * t)).norm() > 0.3 * radius);
+            }
         }
+        
+        // Too large to fit: every edge is skipped and the box is unchanged
+        let oversized = auto_fillet(&cube, 11.0).unwrap();
+        assert_eq!(oversized.skipped(), (0..12).collect::<Vec<u32>>());
+        assert!((signed_volume(&oversized.mesh()) - signed_volume(&cube)).abs() < 1e-9);
+        assert!(fillet_edges(&cube, &[12], radius).is_err());
     }
-    
-    let mut normals = vec![0.0; vertices.len()];
-    compute_normals(&vertices, &faces, &mut normals);
-    
-    Mesh { vertices, faces, normals, material: None }
-}
 
-fn compute_normals(vertices: &[f64], faces: &[u32], normals: &mut [f64]) {
-    // Initialize normals to zero
-    for n in normals.iter_mut() {
-        *n = 0.0;
+    #[test]
+    fn test_face_index_offset_overflow() {
+        let faces = [0, 1, 2, 2, 1, 3];
+        assert_eq!(offset_face_indices(&faces, 10).unwrap(), vec![10, 11, 12, 12, 11, 13]);
+
+        // The last index lands exactly on u32::MAX
+        let last = u32::MAX as usize - 3;
+        assert_eq!(offset_face_indices(&faces, last).unwrap()[5], u32::MAX);
+
+        // One more vertex would wrap around to index 0
+        assert!(offset_face_indices(&faces, last + 1).is_err());
+        assert!(offset_face_indices(&faces, u32::MAX as usize).is_err());
+        assert!(validate_index_space(u64::from(u32::MAX) + 1).is_ok());
+        assert!(validate_index_space(u64::from(u32::MAX) + 2).is_err());
     }
-    
-    // Accumulate face normals
-    for i in (0..faces.len()).step_by(3) {
-        let idx_a = faces[i] as usize;
-        let idx_b = faces[i + 1] as usize;
-        let idx_c = faces[i + 2] as usize;
-        
-        let v0 = [vertices[idx_a * 3], vertices[idx_a * 3 + 1], vertices[idx_a * 3 + 2]];
-        let v1 = [vertices[idx_b * 3], vertices[idx_b * 3 + 1], vertices[idx_b * 3 + 2]];
-        let v2 = [vertices[idx_c * 3], vertices[idx_c * 3 + 1], vertices[idx_c * 3 + 2]];
+
+    #[test]
+    fn test_slice_box_to_dxf() {
+        let mesh = create_box(20.0, 10.0, 6.0).unwrap();
+        let contours = slice_at_height(&mesh, 1.0);
+        assert_eq!(contours.len(), 1);
+
+        let contour = &contours[0];
+        assert_eq!(contour.len(), 4);
+        for corner in [[-10.0, -5.0], [10.0, -5.0], [10.0, 5.0], [-10.0, 5.0]] {
+            assert!(contour.contains(&corner), "missing corner {:?}", corner);
+        }
+        let area: f64 = (0..4)
+            .map(|i| {
+                let (a, b) = (contour[i], contour[(i + 1) % 4]);
+                a[0] * b[1] - b[0] * a[1]
+            })
+            .sum::<f64>()
+            / 2.0;
+        assert!((area - 200.0).abs() < 1e-9); // Counter-clockwise outer loop
+
+        let dxf = export_dxf(&contours).unwrap();
+        assert_eq!(dxf.matches("LWPOLYLINE").count(), 1);
+        assert!(dxf.contains("90\n4\n70\n1\n"));
+        for [x, y] in contour {
+            assert!(dxf.contains(&format!("10\n{}\n20\n{}\n", x, y)));
+        }
+        assert!(dxf.ends_with("0\nENDSEC\n0\nEOF\n"));
+
+        assert!(write_dxf(&[vec![[0.0, 0.0], [1.0, 0.0]]]).is_err());
+    }
+    #[test]
+    fn test_section_properties_of_bar() {
+        let (b, h) = (20.0, 40.0);
+        let bar = create_box(b, h, 200.0).unwrap();
         
-        let e1 = [v1[0] - v0[0], v1[1] - v0[1], v1[2] - v0[2]];
-        let e2 = [v2[0] - v0[0], v2[1] - v0[1], v2[2] - v0[2]];
+        // Square cut across the bar's length: u = X, v = Y
+        let props = section_properties(&bar, [0.0, 0.0, 30.0], [0.0, 0.0, 1.0]).unwrap();
+        assert!((props.area - b * h).abs() < 1e-9);
+        assert!(props.centroid.iter().zip([0.0, 0.0, 30.0]).all(|(c, e)| (c - e).abs() < 1e-9));
+        assert_eq!(props.u_axis, [1.0, 0.0, 0.0]);
+        assert!((props.ixx - b * h.powi(3) / 12.0).abs() < 1e-6);
+        assert!((props.iyy - h * b.powi(3) / 12.0).abs() < 1e-6);
+        assert!(props.ixy.abs() < 1e-6);
         
-        let nx = e1[1] * e2[2] - e1[2] * e2[1];
-        let ny = e1[2] * e2[0] - e1[0] * e2[2];
-        let nz = e1[0] * e2[1] - e1[1] * e2[0];
+        // Moments are about the centroid wherever the plane point lies
+        let offset = section_properties(&bar, [5.0, -7.0, 30.0], [0.0, 0.0, -1.0]).unwrap();
+        assert!((offset.area - b * h).abs() < 1e-9);
+        assert!((offset.ixx + offset.iyy - props.ixx - props.iyy).abs() < 1e-6);
         
-        for &idx in &[idx_a, idx_b, idx_c] {
-            normals[idx * 3] += nx;
-            normals[idx * 3 + 1] += ny;
-            normals[idx * 3 + 2] += nz;
-        }
+        let miss = section_properties(&bar, [0.0, 0.0, 500.0], [0.0, 0.0, 1.0]).unwrap();
+        assert_eq!(miss.area, 0.0);
+        assert!(section_properties(&bar, [0.0; 3], [0.0; 3]).is_err());
     }
     
-    // Normalize
-    for i in (0..normals.len()).step_by(3) {
-        let len = (normals[i] * normals[i] + 
-                   normals[i + 1] * normals[i + 1] + 
-                   normals[i + 2] * normals[i + 2]).sqrt();
-        
-        if len > 0.0 {
-            normals[i] /= len;
-            normals[i + 1] /= len;
-            normals[i + 2] /= len;
+    #[test]
+    fn test_add_hole_through_box() {
+        let mesh = create_box(20.0, 20.0, 20.0).unwrap();
+        let radius: f64 = 3.0;
+
+        // Down from above the top face and out through the bottom
+        let drilled = drill_hole(&mesh, [2.0, -1.0, 15.0], [0.0, 0.0, -1.0], 2.0 * radius, 30.0)
+            .unwrap();
+        let section = 16.0 * radius * radius * (2.0 * PI / 32.0).sin(); // 32-gon area
+        assert!((signed_volume(&drilled) - (8000.0 - section * 20.0)).abs() < 1e-6);
+        let bounds = compute_bounding_box(&drilled);
+        assert_eq!((bounds.min_z, bounds.max_z), (-10.0, 10.0));
+
+        // A blind hole along +X only removes the part inside the box
+        let blind = drill_hole(&mesh, [-15.0, 0.0, 0.0], [1.0, 0.0, 0.0], 2.0 * radius, 10.0)
+            .unwrap();
+        assert!((signed_volume(&blind) - (8000.0 - section * 5.0)).abs() < 1e-6);
+
+        assert!(drill_hole(&mesh, [50.0, 0.0, 15.0], [0.0, 0.0, -1.0], 6.0, 30.0).is_err());
+        // Bounds overlap at the corner, but the hole passes beside it
+        assert!(drill_hole(&mesh, [12.5, 12.5, 15.0], [0.0, 0.0, -1.0], 6.0, 30.0).is_err());
+        assert!(drill_hole(&mesh, [0.0; 3], [0.0; 3], 6.0, 30.0).is_err());
+    }
+    #[test]
+    fn test_boolean_fast_reject() {
+        let a = create_box(10.0, 10.0, 10.0).unwrap();
+        let far = translate_mesh(&a, 100.0, 0.0, 0.0);
+        let before = csg::split_count();
+
+        let overlap = boolean_intersect(&a, &far).unwrap();
+        assert_eq!((overlap.vertex_count(), overlap.face_count()), (0, 0));
+        let difference = boolean_subtract(&a, &far).unwrap();
+        assert_eq!(difference.vertices, a.vertices);
+        assert_eq!(difference.faces, a.faces);
+        assert_eq!(csg::split_count(), before);
+
+        // Overlapping inputs still go through the CSG backend
+        boolean_intersect(&a, &translate_mesh(&a, 5.0, 0.0, 0.0)).unwrap();
+        assert!(csg::split_count() > before);
+    }
+
+    #[test]
+    fn test_point_inside_on_face_diagonal() {
+        // Points with y == z send a +X ray through the diagonal shared by
+        // the two triangles of the box's +X face, which used to count twice
+        let cube = create_box(10.0, 10.0, 10.0).unwrap();
+        let csg_cube = CSGMesh::from_buffers(&cube.vertices, &cube.faces);
+        for p in [[0.0, 0.0, 0.0], [1.0, 2.0, 2.0], [-4.0, -3.0, -3.0]] {
+            assert!(csg_cube.is_point_inside(&Point3::new(p[0], p[1], p[2])));
         }
+        assert!(!csg_cube.is_point_inside(&Point3::new(6.0, 2.0, 2.0)));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_boolean_source_ids() {
+        let a = create_box(10.0, 10.0, 10.0).unwrap();
+        let b = translate_mesh(&a, 5.0, 5.0, 5.0);
+        let union = boolean_union(&a, &b).unwrap();
+        let ids = union.source_ids();
+        assert_eq!(ids.len(), union.face_count());
+
+        // Every face lies on the surface of the box its ID names
+        let on_surface = |face: &[u32], centre: [f64; 3]| {
+            face.iter().all(|&v| {
+                let p = &union.vertices[v as usize * 3..v as usize * 3 + 3];
+                let offsets: Vec<f64> = (0..3).map(|k| (p[k] - centre[k]).abs()).collect();
+                offsets.iter().all(|&d| d <= 5.0 + 1e-9)
+                    && offsets.iter().any(|&d| (d - 5.0).abs() <= 1e-9)
+            })
+        };
+        for (face, &id) in union.faces.chunks_exact(3).zip(&ids) {
+            let centre = match id {
+                0 => [0.0; 3],
+                1 => [5.0; 3],
+                other => panic!("unexpected source id {}", other),
+            };
+            assert!(on_surface(face, centre));
+        }
+        assert!(ids.contains(&0) && ids.contains(&1));
 
+        // Tagged inputs keep their IDs; untagged ones are numbered after them
+        let mut tagged = a.clone();
+        tagged.set_source_id(7);
+        let ids = boolean_subtract(&tagged, &b).unwrap().source_ids();
+        assert!(ids.contains(&7) && ids.contains(&8));
+        assert!(ids.iter().all(|&id| id == 7 || id == 8));
+    }
     #[test]
-    fn test_create_box() {
-        let mesh = create_box(100.0, 50.0, 25.0);
-        assert_eq!(mesh.vertex_count(), 8);
-        assert_eq!(mesh.face_count(), 12);
+    fn test_gltf_exports_face_colors() {
+        let mut mesh = create_box(10.0, 10.0, 10.0).unwrap();
+        // One color per side, shared by its two triangles
+        let colors: Vec<f32> = (0..12).flat_map(|i| [(i / 2) as f32 / 5.0, 0.5, 1.0, 1.0]).collect();
+        mesh.set_face_colors(colors).unwrap();
+        assert_eq!(mesh.vertex_count(), 36);
+        assert_eq!(mesh.colors().len(), 36 * 4);
+
+        let gltf: serde_json::Value = serde_json::from_str(&write_gltf(&mesh, "box").unwrap()).unwrap();
+        let attributes = &gltf["meshes"][0]["primitives"][0]["attributes"];
+        let color = &gltf["accessors"][attributes["COLOR_0"].as_u64().unwrap() as usize];
+        assert_eq!(color["count"], 36);
+        assert_eq!(color["type"], "VEC4");
+        let view = &gltf["bufferViews"][color["bufferView"].as_u64().unwrap() as usize];
+        assert_eq!(view["byteLength"], 36 * 16);
+
+        // The data URI holds the whole buffer
+        let length = gltf["buffers"][0]["byteLength"].as_u64().unwrap() as usize;
+        let uri = gltf["buffers"][0]["uri"].as_str().unwrap();
+        assert_eq!(uri.split(',').nth(1).unwrap().len(), length.div_ceil(3) * 4);
+        assert_eq!(base64_encode(b"Man"), "TWFu");
+        assert_eq!(base64_encode(b"Ma"), "TWE=");
+
+        let model = write_3mf_model(&mesh, "box").unwrap();
+        assert_eq!(model.matches("<m:color ").count(), 36);
+        assert_eq!(model.matches("pid=\"1\"").count(), 12);
+        assert!(!write_3mf_model(&create_box(1.0, 1.0, 1.0).unwrap(), "plain").unwrap().contains("colorgroup"));
     }
 
     #[test]
-    fn test_create_cylinder() {
-        let mesh = create_cylinder(50.0, 100.0, Some(16));
-        assert!(mesh.vertex_count() > 0);
-        assert!(mesh.face_count() > 0);
+    fn test_colors_survive_union_and_merge() {
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let mut a = create_box(10.0, 10.0, 10.0).unwrap();
+        a.set_colors(red.repeat(a.vertex_count())).unwrap();
+        let b = translate_mesh(&create_box(10.0, 10.0, 10.0).unwrap(), 5.0, 5.0, 5.0);
+
+        // The uncolored operand is filled with opaque white
+        let union = boolean_union(&a, &b).unwrap();
+        assert_eq!(union.colors().len(), union.vertex_count() * 4);
+        let colors: Vec<&[f32]> = union.colors.chunks_exact(4).collect();
+        assert!(colors.iter().all(|&c| c == red || c == [1.0; 4]));
+        assert!(colors.contains(&&red[..]) && colors.contains(&&[1.0; 4][..]));
+
+        let merged = concatenate_meshes(&[a, b], true).unwrap();
+        assert_eq!(merged.colors.len(), merged.vertex_count() * 4);
+        assert_eq!(merged.colors[..4], red);
+        assert_eq!(merged.colors[merged.colors.len() - 4..], [1.0; 4]);
+
+        // Uncolored booleans stay uncolored
+        let plain = create_box(10.0, 10.0, 10.0).unwrap();
+        assert!(boolean_union(&plain, &translate_mesh(&plain, 5.0, 5.0, 5.0)).unwrap().colors().is_empty());
     }
 
     #[test]
-    fn test_create_sphere() {
-        let mesh = create_sphere(50.0, Some(16), Some(16));
-        assert!(mesh.vertex_count() > 0);
-        assert!(mesh.face_count() > 0);
+    fn test_round_all_box() {
+        let rounded = round_all(&create_box(4.0, 4.0, 4.0).unwrap(), 1.0).unwrap();
+        assert!(validate_watertight(&rounded.vertices, &rounded.faces).is_ok());
+
+        // Every side moves out by the radius
+        let bounds = compute_bounding_box(&rounded);
+        for (min, max) in [
+            (bounds.min_x, bounds.max_x),
+            (bounds.min_y, bounds.max_y),
+            (bounds.min_z, bounds.max_z),
+        ] {
+            assert!((min + 3.0).abs() < 1e-6 && (max - 3.0).abs() < 1e-6);
+        }
+
+        // No edge is anywhere near the box's 90 degree creases
+        let triangles = triangle_points(&rounded);
+        let mut edge_normals: HashMap<(u32, u32), Vec<Vec3<f64>>> = HashMap::new();
+        for (tri, points) in rounded.faces.chunks_exact(3).zip(&triangles) {
+            let normal = area_normal(points);
+            if normal.norm() < 1e-9 {
+                continue;
+            }
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                edge_normals.entry((a.min(b), a.max(b))).or_default().push(normal.normalize());
+            }
+        }
+        let sharpest = edge_normals
+            .values()
+            .filter(|normals| normals.len() == 2)
+            .map(|normals| normals[0].angle(&normals[1]).to_degrees())
+            .fold(0.0, f64::max);
+        assert!(sharpest < 30.0, "sharpest edge is {} degrees", sharpest);
+
+        assert!(offset_mesh(&create_box(4.0, 4.0, 4.0).unwrap(), 0.0).is_err());
     }
 
     #[test]
-    fn test_export_stl() {
-        let mesh = create_box(100.0, 50.0, 25.0);
-        let stl = export_stl(&mesh, "test").unwrap();
-        assert!(stl.contains("solid test"));
-        assert!(stl.contains("facet normal"));
-        assert!(stl.contains("endsolid"));
+    fn test_marching_cubes_sphere() {
+        let radius = 5.0;
+        let resolution = 0.5;
+        let sphere = sdf_mesh([-6.0; 3], [6.0; 3], resolution, 0.0, |p| {
+            (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt() - radius
+        })
+        .unwrap();
+        assert!(sphere.face_count() > 0);
+        assert!(validate_watertight(&sphere.vertices, &sphere.faces).is_ok());
+        for v in sphere.vertices.chunks_exact(3) {
+            let r = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+            assert!((r - radius).abs() < resolution, "vertex at radius {}", r);
+        }
+        // Triangles face away from the center
+        assert!(signed_volume(&sphere) > 0.0);
+
+        // A voxelized box comes back closed, with faces between centers
+        let grid = voxelize_mesh(&create_box(4.0, 4.0, 4.0).unwrap(), 1.0).unwrap();
+        let surface = voxel_surface(&grid, 0.5).unwrap();
+        assert!(validate_watertight(&surface.vertices, &surface.faces).is_ok());
+        let bounds = compute_bounding_box(&surface);
+        assert!((bounds.min_x + 2.0).abs() < 1e-9 && (bounds.max_z - 2.0).abs() < 1e-9);
+        assert!(voxel_surface(&grid, 1.0).is_err());
     }
 
     #[test]
-    fn test_deterministic_mesh() {
-        let mesh1 = create_box(100.0, 50.0, 25.0);
-        let mesh2 = create_box(100.0, 50.0, 25.0);
+    fn test_surface_distance_matches_brute_force() {
+        let sphere = create_sphere(5.0, Some(12), Some(16)).unwrap();
+        let triangles = triangle_points(&sphere);
+        let surface = SurfaceDistance::new(triangles.clone());
+        for i in 0..6 {
+            for j in 0..6 {
+                for k in 0..6 {
+                    let p = Vec3::new(i as f64, j as f64, k as f64) * 1.7 - Vec3::repeat(4.1);
+                    let expected = triangles
+                        .iter()
+                        .map(|t| distance_to_triangle(t, &p))
+                        .fold(f64::INFINITY, f64::min);
+                    assert!((surface.distance(&p) - expected).abs() < 1e-12);
+                }
+            }
+        }
+        assert_eq!(SurfaceDistance::new(Vec::new()).distance(&Vec3::zeros()), f64::INFINITY);
+    }
 
-        let hash1 = compute_mesh_hash(&mesh1);
-        let hash2 = compute_mesh_hash(&mesh2);
+    #[test]
+    fn test_hollow_for_resin() {
+        let wall = 2.0;
+        let radius = 1.0;
+        let cube = create_box(20.0, 20.0, 20.0).unwrap();
+        let hollow = hollow_with_drains(&cube, wall, 2.0 * radius, 2).unwrap();
 
-        assert_eq!(hash1, hash2, "Mesh generation must be deterministic");
+        // The drain holes join the cavity to the outside, so the result is
+        // one closed surface with no openings left over
+        assert!(validate_watertight(&hollow.vertices, &hollow.faces).is_ok());
+        assert!(boundary_loops(&hollow).is_empty());
+
+        // Holes come up through the bottom face: its vertices include the
+        // rims of two separate circles
+        let rims: Vec<[f64; 3]> = hollow
+            .vertices
+            .chunks_exact(3)
+            .filter(|v| (v[2] + 10.0).abs() < 1e-6 && v[0].abs().max(v[1].abs()) < 10.0 - 1e-6)
+            .map(|v| [v[0], v[1], v[2]])
+            .collect();
+        assert!(rims.len() >= 2 * 32);
+        let farthest = rims
+            .iter()
+            .flat_map(|a| rims.iter().map(move |b| (a[0] - b[0]).hypot(a[1] - b[1])))
+            .fold(0.0, f64::max);
+        assert!(farthest > 4.0 * radius);
+
+        // Away from the holes the inner surface sits `wall` inside the box
+        let on_outside = |v: &[f64]| v.iter().any(|c| (c.abs() - 10.0).abs() < 1e-6);
+        let in_hole = |v: &[f64]| {
+            v[2] < -10.0 + wall + 1e-6
+                && rims.iter().any(|r| (v[0] - r[0]).hypot(v[1] - r[1]) <= 2.0 * radius)
+        };
+        let inner: Vec<&[f64]> = hollow
+            .vertices
+            .chunks_exact(3)
+            .filter(|v| !on_outside(v) && !in_hole(v))
+            .collect();
+        assert!(!inner.is_empty());
+        for k in 0..3 {
+            let extent = inner.iter().map(|v| v[k].abs()).fold(0.0, f64::max);
+            assert!((extent - (10.0 - wall)).abs() < 1e-6, "inner extent {} along axis {}", extent, k);
+        }
+        assert!(signed_volume(&hollow) < 20.0f64.powi(3) - 15.0f64.powi(3));
+
+        assert!(hollow_with_drains(&cube, 12.0, 2.0, 1).is_err());
+        assert!(hollow_with_drains(&cube, wall, 2.0, 0).is_err());
     }
 }
@@ -0,0 +1,175 @@
+// Sketch extrusion: closed 2D loops in the XY plane swept along +Z
+// Holes are bridged into the outer loop so a single ear-clipping pass
+// triangulates the caps.
+
+use serde::Deserialize;
+
+use crate::validation::*;
+use crate::{compute_normals, ear_clip, Mesh};
+
+/// A closed profile with optional holes, as authored in the sketcher
+#[derive(Clone, Debug, Deserialize)]
+pub struct Sketch {
+    /// Outer boundary, in either winding; the closing point may be repeated
+    pub outer: Vec<[f64; 2]>,
+    /// Loops cut out of the profile, each inside the outer boundary
+    #[serde(default)]
+    pub holes: Vec<Vec<[f64; 2]>>,
+    /// Extrusion distance along +Z
+    pub distance: f64,
+}
+
+pub fn parse_sketch(sketch_json: &str) -> ValidationResult<Sketch> {
+    serde_json::from_str(sketch_json)
+        .map_err(|e| ValidationError::new(format!("Invalid sketch JSON: {}", e)))
+}
+
+pub fn extrude_sketch(sketch: &Sketch) -> ValidationResult<Mesh> {
+    validate_dimension(sketch.distance, "extrusion distance")?;
+
+    // Outer loop counter-clockwise, holes clockwise, all in one point list
+    let mut points = Vec::new();
+    let mut loops = Vec::new();
+    for (i, loop_points) in std::iter::once(&sketch.outer).chain(&sketch.holes).enumerate() {
+        let mut loop_points = close_loop(loop_points, i)?;
+        if (signed_area(&loop_points) > 0.0) != (i == 0) {
+            loop_points.reverse();
+        }
+        let start = points.len();
+        points.extend(loop_points);
+        loops.push((start..points.len()).collect::<Vec<usize>>());
+    }
+
+    let polygon = bridge_holes(&points, &loops);
+    let projected: Vec<[f64; 2]> = polygon.iter().map(|&i| points[i]).collect();
+    let cap: Vec<[usize; 3]> = ear_clip(&projected)
+        .into_iter()
+        .map(|[a, b, c]| [polygon[a], polygon[b], polygon[c]])
+        .collect();
+
+    // Bottom ring at z = 0, top ring at z = distance
+    let n = points.len() as u32;
+    let mut vertices = Vec::with_capacity(points.len() * 6);
+    for z in [0.0, sketch.distance] {
+        for p in &points {
+            vertices.extend_from_slice(&[p[0], p[1], z]);
+        }
+    }
+
+    let mut faces = Vec::new();
+    for [a, b, c] in cap {
+        let (a, b, c) = (a as u32, b as u32, c as u32);
+        faces.extend_from_slice(&[a, c, b]);
+        faces.extend_from_slice(&[a + n, b + n, c + n]);
+    }
+    for loop_indices in &loops {
+        for k in 0..loop_indices.len() {
+            let p = loop_indices[k] as u32;
+            let q = loop_indices[(k + 1) % loop_indices.len()] as u32;
+            faces.extend_from_slice(&[p, q, q + n, p, q + n, p + n]);
+        }
+    }
+
+    let mut normals = vec![0.0; vertices.len()];
+    compute_normals(&vertices, &faces, &mut normals);
+
+    Ok(Mesh { vertices, faces, normals, material: None, source_ids: Vec::new(), colors: Vec::new() })
+}
+
+/// Drop a repeated closing point and reject loops without area
+fn close_loop(points: &[[f64; 2]], index: usize) -> ValidationResult<Vec<[f64; 2]>> {
+    let mut points = points.to_vec();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let name = if index == 0 { "Outer loop".to_string() } else { format!("Hole {}", index - 1) };
+    if points.len() < 3 {
+        return Err(ValidationError::new(format!(
+            "{} needs at least 3 points (got {})",
+            name,
+            points.len()
+        )));
+    }
+    if signed_area(&points).abs() <= f64::EPSILON {
+        return Err(ValidationError::new(format!("{} encloses no area", name)));
+    }
+    Ok(points)
+}
+
+/// Positive for counter-clockwise loops
+fn signed_area(points: &[[f64; 2]]) -> f64 {
+    (0..points.len())
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Splice every hole into the outer loop through a zero-width bridge
+///
+/// Holes are taken rightmost first; each is joined from its rightmost
+/// vertex to the nearest polygon vertex whose connecting segment crosses
+/// no edge. The result lists point indices, with bridge ends repeated.
+fn bridge_holes(points: &[[f64; 2]], loops: &[Vec<usize>]) -> Vec<usize> {
+    let rightmost = |hole: &Vec<usize>| {
+        (0..hole.len())
+            .max_by(|&a, &b| points[hole[a]][0].total_cmp(&points[hole[b]][0]))
+            .unwrap_or(0)
+    };
+
+    let mut holes: Vec<&Vec<usize>> = loops[1..].iter().collect();
+    holes.sort_by(|a, b| points[b[rightmost(b)]][0].total_cmp(&points[a[rightmost(a)]][0]));
+
+    let mut polygon = loops[0].clone();
+    for (h, hole) in holes.iter().enumerate() {
+        let start = rightmost(hole);
+        let m = points[hole[start]];
+
+        // Every edge the bridge must not cross: the polygon so far and all
+        // holes not yet merged (including this one)
+        let mut edges: Vec<(usize, usize)> = (0..polygon.len())
+            .map(|k| (polygon[k], polygon[(k + 1) % polygon.len()]))
+            .collect();
+        for other in &holes[h..] {
+            edges.extend((0..other.len()).map(|k| (other[k], other[(k + 1) % other.len()])));
+        }
+
+        let visible = |k: &usize| {
+            let p = points[polygon[*k]];
+            edges.iter().all(|&(a, b)| !segments_cross(m, p, points[a], points[b]))
+        };
+        let distance = |k: &usize| {
+            let p = points[polygon[*k]];
+            (p[0] - m[0]).powi(2) + (p[1] - m[1]).powi(2)
+        };
+        let Some(k) = (0..polygon.len())
+            .filter(visible)
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        else {
+            continue;
+        };
+
+        let mut spliced = Vec::with_capacity(hole.len() + 2);
+        spliced.extend_from_slice(&hole[start..]);
+        spliced.extend_from_slice(&hole[..=start]);
+        spliced.push(polygon[k]);
+        polygon.splice(k + 1..k + 1, spliced);
+    }
+    polygon
+}
+
+/// Whether two segments cross at a point interior to both
+fn segments_cross(p1: [f64; 2], p2: [f64; 2], q1: [f64; 2], q2: [f64; 2]) -> bool {
+    if p1 == q1 || p1 == q2 || p2 == q1 || p2 == q2 {
+        return false;
+    }
+    let cross = |o: [f64; 2], a: [f64; 2], b: [f64; 2]| {
+        (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+    };
+    let (d1, d2) = (cross(q1, q2, p1), cross(q1, q2, p2));
+    let (d3, d4) = (cross(p1, p2, q1), cross(p1, p2, q2));
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
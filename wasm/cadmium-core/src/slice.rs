@@ -0,0 +1,195 @@
+// Planar slicing: closed contours where a horizontal plane crosses a mesh
+// Each straddling triangle contributes one segment; segments are chained
+// through the mesh edges they cut, so a closed mesh gives closed loops.
+// Section properties integrate those loops for an arbitrary cutting plane.
+
+use std::collections::HashMap;
+
+use nalgebra::Vector3 as Vec3;
+
+use crate::validation::{ValidationError, ValidationResult};
+use crate::{weld_vertices, Mesh};
+
+/// Sine of the turn below which a contour point is dropped as collinear
+const COLLINEAR_EPSILON: f64 = 1e-9;
+
+/// Contours of the section at height `z`, as XY loops without a repeated
+/// closing point
+///
+/// Vertices lying exactly on the plane count as above it, so a slice
+/// through a vertex or a horizontal face still yields simple loops. For
+/// an outward-facing mesh outer contours run counter-clockwise and holes
+/// clockwise, seen from +Z. Points where a loop runs straight on (such as
+/// cuts through a face diagonal) are dropped. Loops are ordered by their
+/// first cut edge.
+pub fn slice_at_height(mesh: &Mesh, z: f64) -> Vec<Vec<[f64; 2]>> {
+    let (vertices, remap) = weld_vertices(mesh);
+    let vertex_count = remap.len();
+    let point = |i: u32| {
+        let i = i as usize * 3;
+        [vertices[i], vertices[i + 1], vertices[i + 2]]
+    };
+
+    // Each triangle links the cut edge it enters by to the one it leaves by
+    let mut next: HashMap<(u32, u32), (u32, u32)> = HashMap::new();
+    let mut crossings: HashMap<(u32, u32), [f64; 2]> = HashMap::new();
+    for tri in mesh.faces.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        let ids = [remap[tri[0] as usize], remap[tri[1] as usize], remap[tri[2] as usize]];
+        let above = ids.map(|i| point(i)[2] >= z);
+
+        let mut entry = None;
+        let mut exit = None;
+        for k in 0..3 {
+            let (a, b) = (ids[k], ids[(k + 1) % 3]);
+            if above[k] == above[(k + 1) % 3] {
+                continue;
+            }
+            let key = (a.min(b), a.max(b));
+            crossings.entry(key).or_insert_with(|| {
+                let (p, q) = (point(key.0), point(key.1));
+                let t = (z - p[2]) / (q[2] - p[2]);
+                [p[0] + (q[0] - p[0]) * t, p[1] + (q[1] - p[1]) * t]
+            });
+            // Walking the triangle counter-clockwise, the section runs
+            // from the edge going down to the edge going up
+            if above[k] {
+                entry = Some(key);
+            } else {
+                exit = Some(key);
+            }
+        }
+        if let (Some(entry), Some(exit)) = (entry, exit) {
+            next.insert(entry, exit);
+        }
+    }
+
+    let mut starts: Vec<(u32, u32)> = next.keys().copied().collect();
+    starts.sort_unstable();
+    let mut contours = Vec::new();
+    for start in starts {
+        let mut contour: Vec<[f64; 2]> = Vec::new();
+        let mut current = start;
+        while let Some(following) = next.remove(&current) {
+            let p = crossings[&current];
+            // Cuts through an on-plane vertex repeat that vertex
+            if contour.last() != Some(&p) {
+                contour.push(p);
+            }
+            current = following;
+        }
+        if contour.len() > 1 && contour.first() == contour.last() {
+            contour.pop();
+        }
+        let contour = drop_collinear(contour);
+        if contour.len() >= 3 {
+            contours.push(contour);
+        }
+    }
+    contours
+}
+
+/// Remove points lying on the straight line through their neighbours
+fn drop_collinear(mut contour: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+    let mut i = 0;
+    while contour.len() > 3 && i < contour.len() {
+        let n = contour.len();
+        let (a, b, c) = (contour[(i + n - 1) % n], contour[i], contour[(i + 1) % n]);
+        let (ab, bc) = ([b[0] - a[0], b[1] - a[1]], [c[0] - b[0], c[1] - b[1]]);
+        let cross = ab[0] * bc[1] - ab[1] * bc[0];
+        let dot = ab[0] * bc[0] + ab[1] * bc[1];
+        let limit = COLLINEAR_EPSILON * ab[0].hypot(ab[1]) * bc[0].hypot(bc[1]);
+        if dot > 0.0 && cross.abs() <= limit {
+            contour.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    contour
+}
+
+/// Area properties of a planar cross-section
+///
+/// In-plane coordinates are measured along `u_axis` and `v_axis`, which
+/// with the plane normal form a right-handed frame. Second moments are
+/// taken about axes through the centroid: `ixx` about the `u` axis
+/// (integral of v²), `iyy` about the `v` axis (integral of u²).
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SectionProps {
+    pub area: f64,
+    pub centroid: [f64; 3],
+    pub u_axis: [f64; 3],
+    pub v_axis: [f64; 3],
+    pub ixx: f64,
+    pub iyy: f64,
+    pub ixy: f64,
+}
+
+/// Section properties where the plane through `plane_point` with normal
+/// `plane_normal` cuts a closed mesh
+///
+/// Holes subtract from the section because their contours wind the other
+/// way. A plane that misses the mesh gives zero area, reported at
+/// `plane_point`.
+pub fn section_properties(
+    mesh: &Mesh,
+    plane_point: [f64; 3],
+    plane_normal: [f64; 3],
+) -> ValidationResult<SectionProps> {
+    let normal = Vec3::from(plane_normal);
+    if !normal.iter().all(|c| c.is_finite()) || normal.norm() == 0.0 {
+        return Err(ValidationError::new("Plane normal must be a non-zero vector"));
+    }
+    let n = normal.normalize();
+    // Prefer world X for u so axis-aligned sections keep world axes
+    let helper = if n.x.abs() < 0.9 { Vec3::x() } else { Vec3::y() };
+    let u = (helper - n * helper.dot(&n)).normalize();
+    let v = n.cross(&u);
+    let origin = Vec3::from(plane_point);
+
+    // Express the mesh in the plane frame and slice it at height 0
+    let local = Mesh {
+        vertices: mesh
+            .vertices
+            .chunks_exact(3)
+            .flat_map(|p| {
+                let d = Vec3::new(p[0], p[1], p[2]) - origin;
+                [d.dot(&u), d.dot(&v), d.dot(&n)]
+            })
+            .collect(),
+        faces: mesh.faces.clone(),
+        normals: Vec::new(),
+        material: None,
+        source_ids: Vec::new(),
+        colors: Vec::new(),
+    };
+
+    let (mut area, mut first, mut second) = (0.0, [0.0; 2], [0.0; 3]);
+    for contour in slice_at_height(&local, 0.0) {
+        for (i, &[x0, y0]) in contour.iter().enumerate() {
+            let [x1, y1] = contour[(i + 1) % contour.len()];
+            let c = x0 * y1 - x1 * y0;
+            area += c / 2.0;
+            first[0] += (x0 + x1) * c / 6.0;
+            first[1] += (y0 + y1) * c / 6.0;
+            second[0] += (y0 * y0 + y0 * y1 + y1 * y1) * c / 12.0;
+            second[1] += (x0 * x0 + x0 * x1 + x1 * x1) * c / 12.0;
+            second[2] += (x0 * y1 + 2.0 * x0 * y0 + 2.0 * x1 * y1 + x1 * y0) * c / 24.0;
+        }
+    }
+
+    let (cu, cv) = if area == 0.0 { (0.0, 0.0) } else { (first[0] / area, first[1] / area) };
+    let centroid = origin + u * cu + v * cv;
+    Ok(SectionProps {
+        area,
+        centroid: [centroid.x, centroid.y, centroid.z],
+        u_axis: [u.x, u.y, u.z],
+        v_axis: [v.x, v.y, v.z],
+        // Parallel-axis shift from the plane origin to the centroid
+        ixx: second[0] - area * cv * cv,
+        iyy: second[1] - area * cu * cu,
+        ixy: second[2] - area * cu * cv,
+    })
+}
@@ -5,7 +5,9 @@ use wasm_bindgen::JsValue;
 const MIN_DIMENSION: f64 = 0.01;  // 0.01mm minimum
 const MAX_DIMENSION: f64 = 10000.0; // 10m maximum
 const EPSILON: f64 = 1e-10;
+const MAX_VOXEL_COUNT: usize = 16_000_000; // 2MB occupancy bitset
 
+#[derive(Debug)]
 pub struct ValidationError {
     pub message: String,
 }
@@ -53,14 +55,18 @@ pub fn validate_radius(radius: f64, name: &str) -> ValidationResult<()> {
     validate_dimension(radius, name)
 }
 
-pub fn validate_segments(segments: u32, min: u32) -> ValidationResult<()> {
-    if segments < min {
-        return Err(ValidationError::new(format!(
-            "Segments must be at least {} (got {})",
-            min, segments
-        )));
-    }
-    
+/// Fewest segments in any count of a generated primitive: around a
+/// circle, between the poles of a sphere, or around a torus ring or tube.
+/// Smaller counts, including 0, are clamped up to it; the geometry kernel
+/// uses the same minimum and policy
+pub const MIN_SEGMENTS: u32 = 3;
+
+/// Raise a requested segment count to `MIN_SEGMENTS`
+pub fn clamp_segments(segments: u32) -> u32 {
+    segments.max(MIN_SEGMENTS)
+}
+
+pub fn validate_segments(segments: u32) -> ValidationResult<()> {
     if segments > 1000 {
         return Err(ValidationError::new(format!(
             "Segments must not exceed 1000 (got {})",
@@ -81,21 +87,21 @@ pub fn validate_box_dimensions(width: f64, height: f64, depth: f64) -> Validatio
 pub fn validate_cylinder(radius: f64, height: f64, segments: u32) -> ValidationResult<()> {
     validate_radius(radius, "radius")?;
     validate_dimension(height, "height")?;
-    validate_segments(segments, 3)?;
+    validate_segments(segments)?;
     Ok(())
 }
 
 pub fn validate_sphere(radius: f64, segments_lat: u32, segments_lon: u32) -> ValidationResult<()> {
     validate_radius(radius, "radius")?;
-    validate_segments(segments_lat, 3)?;
-    validate_segments(segments_lon, 3)?;
+    validate_segments(segments_lat)?;
+    validate_segments(segments_lon)?;
     Ok(())
 }
 
 pub fn validate_cone(radius: f64, height: f64, segments: u32) -> ValidationResult<()> {
     validate_radius(radius, "radius")?;
     validate_dimension(height, "height")?;
-    validate_segments(segments, 3)?;
+    validate_segments(segments)?;
     
     // Cone-specific: radius should be reasonable relative to height
     if radius > height * 2.0 {
@@ -108,11 +114,47 @@ pub fn validate_cone(radius: f64, height: f64, segments: u32) -> ValidationResul
     Ok(())
 }
 
+pub fn validate_frustum(bottom_radius: f64, top_radius: f64, height: f64, segments: u32) -> ValidationResult<()> {
+    validate_radius(bottom_radius, "bottom radius")?;
+    // A zero top radius closes the frustum into a cone
+    if top_radius != 0.0 {
+        validate_radius(top_radius, "top radius")?;
+    }
+    validate_dimension(height, "height")?;
+    validate_segments(segments)?;
+    Ok(())
+}
+
+/// Fewest teeth a generated spur gear may have
+pub const MIN_GEAR_TEETH: u32 = 6;
+
+pub fn validate_gear(module: f64, teeth: u32, thickness: f64, pressure_angle_deg: f64) -> ValidationResult<()> {
+    validate_dimension(module, "module")?;
+    validate_dimension(thickness, "thickness")?;
+    
+    if !(MIN_GEAR_TEETH..=500).contains(&teeth) {
+        return Err(ValidationError::new(format!(
+            "Gear must have between {} and 500 teeth (got {})",
+            MIN_GEAR_TEETH, teeth
+        )));
+    }
+    
+    // Steeper angles give pointed teeth on small gears
+    if !(10.0..=30.0).contains(&pressure_angle_deg) {
+        return Err(ValidationError::new(format!(
+            "Pressure angle must be between 10 and 30 degrees (got {})",
+            pressure_angle_deg
+        )));
+    }
+    
+    Ok(())
+}
+
 pub fn validate_torus(major_radius: f64, minor_radius: f64, segments_major: u32, segments_minor: u32) -> ValidationResult<()> {
     validate_radius(major_radius, "major radius")?;
     validate_radius(minor_radius, "minor radius")?;
-    validate_segments(segments_major, 3)?;
-    validate_segments(segments_minor, 3)?;
+    validate_segments(segments_major)?;
+    validate_segments(segments_minor)?;
     
     if minor_radius >= major_radius {
         return Err(ValidationError::new(format!(
@@ -163,3 +205,218 @@ pub fn validate_chamfer_distance(distance: f64) -> ValidationResult<()> {
     
     Ok(())
 }
+
+pub fn validate_voxel_grid(resolution: f64, cell_count: f64) -> ValidationResult<()> {
+    if !resolution.is_finite() || resolution <= EPSILON {
+        return Err(ValidationError::new(format!(
+            "Voxel resolution must be positive (got {})",
+            resolution
+        )));
+    }
+    
+    if cell_count > MAX_VOXEL_COUNT as f64 {
+        return Err(ValidationError::new(format!(
+            "Voxel grid must not exceed {} cells (got {}); use a coarser resolution",
+            MAX_VOXEL_COUNT, cell_count
+        )));
+    }
+    
+    Ok(())
+}
+
+pub fn validate_direction(direction: &[f64]) -> ValidationResult<[f64; 3]> {
+    let direction: [f64; 3] = direction.try_into().map_err(|_| {
+        ValidationError::new(format!(
+            "Direction must have 3 components (got {})",
+            direction.len()
+        ))
+    })?;
+    
+    let length = (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2]).sqrt();
+    if !length.is_finite() || length <= EPSILON {
+        return Err(ValidationError::new(format!(
+            "Direction must be a finite non-zero vector (got {:?})",
+            direction
+        )));
+    }
+    
+    Ok(direction)
+}
+
+pub fn validate_point(point: &[f64], name: &str) -> ValidationResult<[f64; 3]> {
+    let point: [f64; 3] = point.try_into().map_err(|_| {
+        ValidationError::new(format!(
+            "{} must have 3 components (got {})",
+            name,
+            point.len()
+        ))
+    })?;
+    
+    if point.iter().any(|c| !c.is_finite()) {
+        return Err(ValidationError::new(format!(
+            "{} must be finite (got {:?})",
+            name, point
+        )));
+    }
+    
+    Ok(point)
+}
+
+/// Check that `vertex_count` vertices can all be addressed by `u32` face indices
+pub fn validate_index_space(vertex_count: u64) -> ValidationResult<()> {
+    if vertex_count > u64::from(u32::MAX) + 1 {
+        return Err(ValidationError::new(format!(
+            "Merged mesh would have {} vertices, more than 32-bit face indices can address",
+            vertex_count
+        )));
+    }
+    
+    Ok(())
+}
+
+/// Check RGBA colors for `count` vertices or faces (`per` names which)
+pub fn validate_colors(colors: &[f32], count: usize, per: &str) -> ValidationResult<()> {
+    if colors.len() != count * 4 {
+        return Err(ValidationError::new(format!(
+            "Expected {} color components (4 per {}), got {}",
+            count * 4,
+            per,
+            colors.len()
+        )));
+    }
+    
+    if colors.iter().any(|c| !c.is_finite()) {
+        return Err(ValidationError::new("Colors must be finite"));
+    }
+    
+    Ok(())
+}
+
+/// Check a triangle pick and normalize its barycentric coordinates to sum to 1
+pub fn validate_surface_pick(
+    faces: &[u32],
+    triangle: usize,
+    bary: [f64; 3],
+) -> ValidationResult<[f64; 3]> {
+    let triangle_count = faces.len() / 3;
+    if triangle >= triangle_count {
+        return Err(ValidationError::new(format!(
+            "Triangle {} is out of range (mesh has {} triangles)",
+            triangle, triangle_count
+        )));
+    }
+    
+    let sum: f64 = bary.iter().sum();
+    if bary.iter().any(|w| !w.is_finite() || *w < -EPSILON) || sum <= EPSILON {
+        return Err(ValidationError::new(format!(
+            "Barycentric coordinates must be non-negative with a positive sum (got {:?})",
+            bary
+        )));
+    }
+    
+    Ok(bary.map(|w| w.max(0.0) / sum))
+}
+
+/// Number of triangles using each edge, keyed as (low, high) index pairs
+fn edge_use_counts(faces: &[u32]) -> std::collections::HashMap<(u32, u32), usize> {
+    let mut edge_counts: std::collections::HashMap<(u32, u32), usize> = std::collections::HashMap::new();
+    
+    for tri in faces.chunks_exact(3) {
+        for k in 0..3 {
+            let a = tri[k];
+            let b = tri[(k + 1) % 3];
+            let key = if a < b { (a, b) } else { (b, a) };
+            *edge_counts.entry(key).or_insert(0) += 1;
+        }
+    }
+    
+    edge_counts
+}
+
+/// Sorted edges whose use count passes `keep`
+fn edges_where(
+    edge_counts: &std::collections::HashMap<(u32, u32), usize>,
+    keep: impl Fn(usize) -> bool,
+) -> Vec<(u32, u32)> {
+    let mut edges: Vec<(u32, u32)> = edge_counts
+        .iter()
+        .filter(|(_, &count)| keep(count))
+        .map(|(&edge, _)| edge)
+        .collect();
+    edges.sort_unstable();
+    edges
+}
+
+/// Collect edges used by exactly one triangle, as sorted (low, high) index pairs
+pub fn find_open_edges(faces: &[u32]) -> Vec<(u32, u32)> {
+    edges_where(&edge_use_counts(faces), |count| count == 1)
+}
+
+/// Check that a mesh is a closed 2-manifold shell: every edge is shared by
+/// exactly two triangles
+///
+/// Vertices are first welded by position with the same tolerance as
+/// `repair_for_print`, so meshes that give each face its own vertex copies
+/// still count as closed, and triangles that weld down to a line or a
+/// point are ignored. The error lists open boundary edges (one triangle)
+/// and non-manifold edges (three or more) separately, by the index of the
+/// first vertex at each welded position.
+pub fn validate_watertight(vertices: &[f64], faces: &[u32]) -> ValidationResult<()> {
+    const MAX_LISTED: usize = 10;
+    
+    let (_, remap) = crate::weld_vertices_within(vertices, crate::weld_tolerance(vertices));
+    let vertex_count = remap.len();
+    if let Some(&index) = faces.iter().find(|&&i| i as usize >= vertex_count) {
+        return Err(ValidationError::new(format!(
+            "Face index {} is out of range (mesh has {} vertices)",
+            index, vertex_count
+        )));
+    }
+    
+    let welded: Vec<u32> = faces
+        .chunks_exact(3)
+        .map(|t| [remap[t[0] as usize], remap[t[1] as usize], remap[t[2] as usize]])
+        .filter(|[a, b, c]| a != b && b != c && c != a)
+        .flatten()
+        .collect();
+    let edge_counts = edge_use_counts(&welded);
+    let open = edges_where(&edge_counts, |count| count == 1);
+    let non_manifold = edges_where(&edge_counts, |count| count > 2);
+    if open.is_empty() && non_manifold.is_empty() {
+        return Ok(());
+    }
+    
+    // Report each welded vertex by its first original index
+    let mut first = vec![u32::MAX; vertex_count];
+    for (i, &w) in remap.iter().enumerate().rev() {
+        first[w as usize] = i as u32;
+    }
+    let describe = |edges: &[(u32, u32)], kind: &str| {
+        let listed: Vec<String> = edges
+            .iter()
+            .take(MAX_LISTED)
+            .map(|&(a, b)| {
+                let (a, b) = (first[a as usize], first[b as usize]);
+                format!("{}-{}", a.min(b), a.max(b))
+            })
+            .collect();
+        let remainder = if edges.len() > MAX_LISTED {
+            format!(" (and {} more)", edges.len() - MAX_LISTED)
+        } else {
+            String::new()
+        };
+        format!("{} {} edges: {}{}", edges.len(), kind, listed.join(", "), remainder)
+    };
+    
+    let mut problems = Vec::new();
+    if !open.is_empty() {
+        problems.push(describe(&open, "open boundary"));
+    }
+    if !non_manifold.is_empty() {
+        problems.push(describe(&non_manifold, "non-manifold"));
+    }
+    Err(ValidationError::new(format!(
+        "Mesh is not watertight: {}",
+        problems.join("; ")
+    )))
+}
@@ -3,23 +3,53 @@
 //! Combines parsing, evaluation, and optimization into a single
 //! compilation pipeline.
 
+use crate::compiler::metrics::{now_ms, Stopwatch};
 use crate::compiler::{CsgEvaluator, IntentParser};
+use crate::config::KernelConfig;
 use crate::errors::{KernelError, KernelResult};
 use crate::geometry::analysis::bounding_box::compute_bounding_box;
+use crate::geometry::operations::{
+    precise_boolean_operation, solids_from_precision_mesh, BooleanOperation,
+};
+use crate::geometry::topology::step::mesh_to_step_with_config;
 use crate::hashing;
 use crate::types::{
-    CompileResult, CompileStatus, GeometryIR, ManufacturabilityReport, PreviewMesh,
+    CompileMetrics, CompileResult, CompileStatus, GeometryIR, Intent, ManufacturabilityReport,
+    OperationType, PrecisionMesh, PreviewMesh,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Format version written by [`CsgCompiler::snapshot`]
+pub const SNAPSHOT_VERSION: u32 = 1;
+
 /// High-level CSG compiler
 ///
 /// Orchestrates the full compilation pipeline from Intent IR to geometry.
 pub struct CsgCompiler {
     parser: IntentParser,
     evaluator: CsgEvaluator,
+    config: KernelConfig,
+    tessellation_quality: f32,
+    /// Wall-clock limit for evaluation, in milliseconds
+    time_budget_ms: Option<u64>,
+    /// Intent hash and tessellation quality of the cached result
     cached_hash: Option<String>,
     cached_result: Option<CompileResult>,
+    /// Intent the cached result was compiled from
+    cached_intent: Option<GeometryIR>,
+}
+
+/// Persisted session of a [`CsgCompiler`]
+#[derive(Serialize, Deserialize)]
+struct CompilerSnapshot {
+    version: u32,
+    config: KernelConfig,
+    subdivisions: u32,
+    tessellation_quality: f32,
+    time_budget_ms: Option<u64>,
+    cached_hash: Option<String>,
+    intent: Option<GeometryIR>,
 }
 
 impl CsgCompiler {
@@ -27,8 +57,12 @@ impl CsgCompiler {
         CsgCompiler {
             parser: IntentParser::new(),
             evaluator: CsgEvaluator::new(),
+            config: KernelConfig::default(),
+            tessellation_quality: 1.0,
+            time_budget_ms: None,
             cached_hash: None,
             cached_result: None,
+            cached_intent: None,
         }
     }
 
@@ -36,61 +70,271 @@ impl CsgCompiler {
         CsgCompiler {
             parser: IntentParser::new(),
             evaluator: CsgEvaluator::with_subdivisions(subdivisions),
+            config: KernelConfig::default(),
+            tessellation_quality: 1.0,
+            time_budget_ms: None,
             cached_hash: None,
             cached_result: None,
+            cached_intent: None,
         }
     }
 
+    pub fn with_config(config: KernelConfig) -> KernelResult<Self> {
+        config.validate()?;
+        let mut compiler = CsgCompiler {
+            config,
+            ..Self::new()
+        };
+        compiler.evaluator.set_max_chord_error(config.max_chord_error);
+        compiler.evaluator.set_linear_tolerance(config.linear_tolerance);
+        Ok(compiler)
+    }
+
+    /// Units and tolerances used for compilation
+    pub fn config(&self) -> &KernelConfig {
+        &self.config
+    }
+
+    /// Replace the kernel configuration
+    ///
+    /// Cached results were produced under the old tolerances, so the cache
+    /// is cleared.
+    pub fn set_config(&mut self, config: KernelConfig) -> KernelResult<()> {
+        config.validate()?;
+        self.config = config;
+        self.evaluator.set_max_chord_error(config.max_chord_error);
+        self.evaluator.set_linear_tolerance(config.linear_tolerance);
+        self.clear_cache();
+        Ok(())
+    }
+
     /// Compile Intent IR to geometry with caching
+    ///
+    /// With a time budget set, intents are evaluated one at a time and a
+    /// compile that runs past the budget stops after the current one,
+    /// returning a [`CompileStatus::Partial`] result instead of a mesh.
     pub fn compile(&mut self, ir: &GeometryIR) -> KernelResult<CompileResult> {
+        // Reject empty intents before hashing or touching the cache
+        if ir.operations.is_empty() {
+            return Err(KernelError::empty_intent());
+        }
+
         // Compute hash
         let intent_hash = hashing::hash_intent(ir);
+        let cache_key = format!("{}@{}", intent_hash, self.tessellation_quality);
 
         // Check cache
+        let mut restored = false;
         if let Some(cached_hash) = &self.cached_hash {
-            if cached_hash == &intent_hash {
+            if cached_hash == &cache_key {
                 if let Some(result) = &self.cached_result {
+                    let metrics = CompileMetrics {
+                        cache_hits: 1,
+                        total_triangles: result.mesh.as_ref().map_or(0, |m| m.triangle_count()),
+                        ..CompileMetrics::default()
+                    };
                     return Ok(CompileResult {
                         status: CompileStatus::Cached,
+                        metrics: Some(metrics),
                         ..result.clone()
                     });
                 }
+                // Restored from a snapshot: the session already holds this
+                // geometry, it just has not been rebuilt yet
+                restored = self.cached_intent.is_some();
             }
         }
 
+        let mut stopwatch = Stopwatch::start();
+        let counts_before = self.evaluator.counts();
+
         // Validate intent structure
         let csg_tree = self.parser.parse(ir)?;
 
         // Validate tree structure
         csg_tree.validate()?;
+        let csg_build_ms = stopwatch.lap();
 
-        // Evaluate to mesh
-        let mesh = self.evaluator.evaluate(&csg_tree)?;
+        // Evaluate to mesh, or as much of it as the budget allows
+        let mesh = match self.time_budget_ms {
+            Some(budget) => {
+                let deadline = now_ms() + budget as f64;
+                match self.evaluate_until(ir, deadline)? {
+                    Budgeted::Finished(mesh) => mesh,
+                    Budgeted::OutOfTime { meshes, remaining } => {
+                        let counts = self.evaluator.counts().since(counts_before);
+                        let metrics = CompileMetrics {
+                            nodes_evaluated: counts.nodes_evaluated,
+                            cache_hits: counts.cache_hits,
+                            cache_misses: counts.cache_misses,
+                            total_triangles: meshes.values().map(|m| m.triangle_count()).sum(),
+                            csg_build_ms,
+                            evaluate_ms: stopwatch.lap(),
+                            ..CompileMetrics::default()
+                        };
+                        return Ok(CompileResult {
+                            status: CompileStatus::Partial,
+                            intent_hash,
+                            mesh: None,
+                            step: None,
+                            topology: None,
+                            mfg_report: None,
+                            error: None,
+                            solids: Vec::new(),
+                            partial_meshes: meshes
+                                .into_iter()
+                                .map(|(id, mesh)| (id, mesh.to_preview()))
+                                .collect(),
+                            remaining_nodes: remaining,
+                            metrics: Some(metrics),
+                            warnings: Vec::new(),
+                        });
+                    }
+                }
+            }
+            None => self.evaluator.evaluate(&csg_tree)?,
+        };
+        let evaluate_ms = stopwatch.lap();
+        let counts = self.evaluator.counts().since(counts_before);
 
-        // Validate mesh output
+        // Validate mesh output, measuring solids before the f32 downcast
         mesh.is_valid()?;
+        let solids = solids_from_precision_mesh(&mesh);
+        let mesh = mesh.to_preview();
 
         // Check manufacturability constraints
         let mfg_report = self.check_manufacturability(&mesh, ir)?;
 
+        // Export STEP in the configured unit; a mesh the exporter rejects
+        // still compiles, with a warning instead of a STEP file
+        let mut warnings = Vec::new();
+        let step = match mesh_to_step_with_config(&mesh, &self.config) {
+            Ok(step) => Some(step),
+            Err(e) => {
+                warnings.push(format!("STEP export failed: {}", e));
+                None
+            }
+        };
+
+        let metrics = CompileMetrics {
+            nodes_evaluated: counts.nodes_evaluated,
+            cache_hits: counts.cache_hits,
+            cache_misses: counts.cache_misses,
+            total_triangles: mesh.triangle_count(),
+            csg_build_ms,
+            evaluate_ms,
+            mesh_ms: stopwatch.lap(),
+            ..CompileMetrics::default()
+        };
+
         // Build result
         let result = CompileResult {
-            status: CompileStatus::Compiled,
-            intent_hash: intent_hash.clone(),
+            status: if restored {
+                CompileStatus::Cached
+            } else {
+                CompileStatus::Compiled
+            },
+            intent_hash,
+            solids,
             mesh: Some(mesh),
-            step: None,     // TODO: Implement STEP export
+            step,
             topology: None, // TODO: Implement B-rep extraction
             mfg_report: Some(mfg_report),
             error: None,
+            partial_meshes: HashMap::new(),
+            remaining_nodes: Vec::new(),
+            metrics: Some(metrics),
+            warnings,
         };
 
         // Update cache
-        self.cached_hash = Some(intent_hash);
+        self.cached_hash = Some(cache_key);
         self.cached_result = Some(result.clone());
+        self.cached_intent = Some(ir.clone());
 
         Ok(result)
     }
 
+    /// Evaluate the parsed intents one at a time, primitives first as the
+    /// parser orders them, until `deadline` (see [`now_ms`]) passes
+    ///
+    /// Operations combine the finished meshes of their inputs exactly as
+    /// the CSG tree would, so a compile that finishes in time produces the
+    /// same mesh either way.
+    fn evaluate_until(&mut self, ir: &GeometryIR, deadline: f64) -> KernelResult<Budgeted> {
+        let (primitives, operations): (Vec<&Intent>, Vec<&Intent>) = ir
+            .operations
+            .iter()
+            .partition(|intent| matches!(intent, Intent::Primitive(_)));
+        let order: Vec<&Intent> = primitives.into_iter().chain(operations).collect();
+        let intent_id = |intent: &Intent| match intent {
+            Intent::Primitive(primitive) => primitive.id.clone(),
+            Intent::Operation(operation) => operation.id.clone(),
+        };
+
+        let mut meshes: HashMap<String, PrecisionMesh> = HashMap::new();
+        let tolerance = self.config.linear_tolerance;
+        for (index, intent) in order.iter().enumerate() {
+            let mesh = match intent {
+                Intent::Primitive(primitive) => {
+                    let node = self.parser.node(&primitive.id).ok_or_else(|| {
+                        KernelError::internal(format!("Failed to find primitive {}", primitive.id))
+                    })?;
+                    self.evaluator.evaluate(node)?
+                }
+                Intent::Operation(operation) => {
+                    let input = |id: &str| {
+                        meshes.get(id).ok_or_else(|| {
+                            KernelError::internal(format!(
+                                "Input '{}' of operation '{}' was not evaluated",
+                                id, operation.id
+                            ))
+                        })
+                    };
+                    let target = input(&operation.target)?;
+                    let operand = || input(operation.operand.as_deref().unwrap_or_default());
+                    self.evaluator.counts.nodes_evaluated += 1;
+                    match operation.type_ {
+                        OperationType::Union => precise_boolean_operation(
+                            target,
+                            operand()?,
+                            BooleanOperation::Union,
+                            tolerance,
+                        )?,
+                        OperationType::Subtract => precise_boolean_operation(
+                            target,
+                            operand()?,
+                            BooleanOperation::Subtract,
+                            tolerance,
+                        )?,
+                        OperationType::Intersect => precise_boolean_operation(
+                            target,
+                            operand()?,
+                            BooleanOperation::Intersect,
+                            tolerance,
+                        )?,
+                        // Passed through, as the parser does
+                        OperationType::Fillet | OperationType::Hole | OperationType::Chamfer => {
+                            target.clone()
+                        }
+                    }
+                }
+            };
+            meshes.insert(intent_id(intent), mesh);
+
+            if index + 1 < order.len() && now_ms() >= deadline {
+                let remaining = order[index + 1..].iter().map(|i| intent_id(i)).collect();
+                return Ok(Budgeted::OutOfTime { meshes, remaining });
+            }
+        }
+
+        let last = ir.operations.last().map(intent_id).unwrap_or_default();
+        meshes
+            .remove(&last)
+            .map(Budgeted::Finished)
+            .ok_or_else(|| KernelError::internal(format!("Failed to find result {}", last)))
+    }
+
     /// Validate intent without full compilation
     pub fn validate(&mut self, ir: &GeometryIR) -> KernelResult<()> {
         let csg_tree = self.parser.parse(ir)?;
@@ -129,21 +373,111 @@ impl CsgCompiler {
         }
 
         // Validate constraints
-        let report = crate::geometry::constraints::validate_constraints(mesh, &constraints);
+        let report = crate::geometry::constraints::validate_constraints_with_config(
+            mesh,
+            &constraints,
+            &self.config,
+        );
 
         Ok(report)
     }
 
+    /// Wall-clock limit for evaluation, in milliseconds, if any
+    pub fn time_budget_ms(&self) -> Option<u64> {
+        self.time_budget_ms
+    }
+
+    /// Limit how long [`compile`](Self::compile) may spend evaluating
+    ///
+    /// `None` removes the limit. In the browser, time is read from
+    /// `performance.now()`.
+    pub fn set_time_budget_ms(&mut self, budget_ms: Option<u64>) {
+        self.time_budget_ms = budget_ms;
+    }
+
     /// Set subdivision level for mesh generation
     pub fn set_subdivisions(&mut self, subdivisions: u32) {
         self.evaluator.set_subdivisions(subdivisions);
         self.clear_cache();
     }
 
+    /// Fraction of the full triangle count that curved primitives keep
+    pub fn tessellation_quality(&self) -> f32 {
+        self.tessellation_quality
+    }
+
+    /// Coarsen every primitive for previews of large assemblies
+    ///
+    /// `quality` is in `(0, 1]`; 1 is full resolution and 0.25 keeps about
+    /// a quarter of the triangles (see
+    /// [`CsgEvaluator::set_tessellation_quality`]). Compiled results are
+    /// cached per quality.
+    pub fn set_tessellation_quality(&mut self, quality: f32) -> KernelResult<()> {
+        if !(quality > 0.0 && quality <= 1.0) {
+            return Err(KernelError::invalid_parameter(
+                "tessellation_quality",
+                format!("must be in (0, 1], got {}", quality),
+            ));
+        }
+        self.tessellation_quality = quality;
+        self.evaluator.set_tessellation_quality(quality);
+        Ok(())
+    }
+
+    /// Serialize the session to JSON for crash recovery or sharing
+    ///
+    /// Records the configuration, tessellation settings and time budget,
+    /// plus the cache key and the intent it was compiled from, but no
+    /// geometry; see [`restore`](Self::restore).
+    pub fn snapshot(&self) -> KernelResult<String> {
+        let snapshot = CompilerSnapshot {
+            version: SNAPSHOT_VERSION,
+            config: self.config,
+            subdivisions: self.evaluator.subdivisions(),
+            tessellation_quality: self.tessellation_quality,
+            time_budget_ms: self.time_budget_ms,
+            cached_hash: self.cached_hash.clone(),
+            intent: self.cached_intent.clone(),
+        };
+        serde_json::to_string(&snapshot).map_err(|e| KernelError::internal(e.to_string()))
+    }
+
+    /// Replace the session with one saved by [`snapshot`](Self::snapshot)
+    ///
+    /// Nothing is compiled here. The first compile of the snapshot's intent
+    /// rebuilds its geometry and reports it as cached, as it would have been
+    /// in the saved session. An invalid snapshot leaves the session as it
+    /// was.
+    pub fn restore(&mut self, snapshot: &str) -> KernelResult<()> {
+        let snapshot: CompilerSnapshot =
+            serde_json::from_str(snapshot).map_err(|e| KernelError::invalid_json(e.to_string()))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(KernelError::invalid_json(format!(
+                "Unsupported snapshot version {}",
+                snapshot.version
+            )));
+        }
+        if snapshot.cached_hash.is_some() != snapshot.intent.is_some() {
+            return Err(KernelError::invalid_json(
+                "Snapshot cache key and intent must be saved together",
+            ));
+        }
+
+        let mut compiler = CsgCompiler::with_config(snapshot.config)?;
+        compiler.set_subdivisions(snapshot.subdivisions);
+        compiler.set_tessellation_quality(snapshot.tessellation_quality)?;
+        compiler.time_budget_ms = snapshot.time_budget_ms;
+        compiler.cached_hash = snapshot.cached_hash;
+        compiler.cached_intent = snapshot.intent;
+        *self = compiler;
+        Ok(())
+    }
+
     /// Clear cache
     pub fn clear_cache(&mut self) {
         self.cached_hash = None;
         self.cached_result = None;
+        self.cached_intent = None;
         self.evaluator.clear_cache();
     }
 
@@ -167,10 +501,20 @@ pub struct CacheStats {
     pub size: usize,
 }
 
+/// Outcome of evaluating within a time budget
+enum Budgeted {
+    Finished(PrecisionMesh),
+    /// Meshes finished so far by intent ID, and the intents left over
+    OutOfTime {
+        meshes: HashMap<String, PrecisionMesh>,
+        remaining: Vec<String>,
+    },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{Intent, OperationIntent, OperationType, PrimitiveIntent, PrimitiveType};
+    use crate::types::{Intent, OperationIntent, OperationType, PrimitiveIntent, PrimitiveType, Transform};
 
     fn create_test_box_intent(id: &str) -> PrimitiveIntent {
         PrimitiveIntent {
@@ -244,6 +588,140 @@ mod tests {
         assert!(result.mesh.is_some());
     }
 
+    #[test]
+    fn test_compile_keeps_precision_far_from_origin() {
+        // f32 steps by 0.0625 near 1e6, so these widths only survive in f64
+        let far_box = |id: &str, x: f64| {
+            Intent::Primitive(PrimitiveIntent {
+                id: id.to_string(),
+                type_: PrimitiveType::Box,
+                parameters: vec![
+                    ("width".to_string(), 0.1),
+                    ("height".to_string(), 0.2),
+                    ("depth".to_string(), 0.3),
+                ]
+                .into_iter()
+                .collect(),
+                transform: Some(Transform {
+                    position: Some([x, 0.0, 0.0]),
+                    rotation: None,
+                    scale: None,
+                }),
+                timestamp: 0.0,
+            })
+        };
+        let ir = GeometryIR {
+            part: "far".to_string(),
+            operations: vec![
+                far_box("left", 1e6),
+                far_box("right", 1e6 + 0.1),
+                Intent::Operation(OperationIntent {
+                    id: "union1".to_string(),
+                    type_: OperationType::Union,
+                    target: "left".to_string(),
+                    operand: Some("right".to_string()),
+                    parameters: HashMap::new(),
+                    timestamp: 0.0,
+                }),
+            ],
+            constraints: vec![],
+        };
+
+        let result = CsgCompiler::new().compile(&ir).unwrap();
+        assert_eq!(result.status, CompileStatus::Compiled);
+
+        let volume: f64 = result.solids.iter().map(|solid| solid.volume).sum();
+        assert!((volume - 2.0 * 0.1 * 0.2 * 0.3).abs() < 1e-9, "volume {}", volume);
+
+        // The boxes meet without a gap or an overlap
+        let mut spans: Vec<[f64; 2]> = result
+            .solids
+            .iter()
+            .map(|solid| [solid.bounding_box.min[0], solid.bounding_box.max[0]])
+            .collect();
+        spans.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        assert!((spans[0][0] - (1e6 - 0.05)).abs() < 1e-9);
+        assert!((spans.last().unwrap()[1] - (1e6 + 0.15)).abs() < 1e-9);
+        for pair in spans.windows(2) {
+            assert!((pair[1][0] - pair[0][1]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_compile_in_meters() {
+        use crate::config::LengthUnit;
+
+        let cuboid = |id: &str, size: [f64; 3]| {
+            Intent::Primitive(PrimitiveIntent {
+                id: id.to_string(),
+                type_: PrimitiveType::Box,
+                parameters: ["width", "height", "depth"]
+                    .iter()
+                    .zip(size)
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect(),
+                transform: None,
+                timestamp: 0.0,
+            })
+        };
+        // A 20 mm cube with a 10 mm square hole through it
+        let ir = GeometryIR {
+            part: "bracket".to_string(),
+            operations: vec![
+                cuboid("block", [0.02, 0.02, 0.02]),
+                cuboid("slot", [0.01, 0.03, 0.01]),
+                Intent::Operation(OperationIntent {
+                    id: "cut".to_string(),
+                    type_: OperationType::Subtract,
+                    target: "block".to_string(),
+                    operand: Some("slot".to_string()),
+                    parameters: HashMap::new(),
+                    timestamp: 0.0,
+                }),
+            ],
+            constraints: vec![],
+        };
+        let small_part = |result: &CompileResult| {
+            let report = result.mfg_report.as_ref().unwrap();
+            report.warnings.iter().any(|w| w.message.contains("very small"))
+        };
+
+        let mut meters = CsgCompiler::with_config(KernelConfig::for_unit(LengthUnit::Meter)).unwrap();
+        let result = meters.compile(&ir).unwrap();
+        assert_eq!(result.status, CompileStatus::Compiled);
+        let volume: f64 = result.solids.iter().map(|solid| solid.volume).sum();
+        assert!((volume - 6e-6).abs() < 1e-12, "volume {}", volume);
+        assert!(!small_part(&result));
+
+        // Read as millimeters, the same numbers are a speck
+        let millimeters = CsgCompiler::new().compile(&ir).unwrap();
+        assert!(small_part(&millimeters));
+    }
+
+    #[test]
+    fn test_compile_exports_step() {
+        use crate::config::LengthUnit;
+
+        let ir = GeometryIR {
+            part: "test_part".to_string(),
+            operations: vec![Intent::Primitive(create_test_box_intent("box1"))],
+            constraints: vec![],
+        };
+        let faces = |step: &str| step.matches("=ADVANCED_FACE(").count();
+
+        let result = CsgCompiler::new().compile(&ir).unwrap();
+        let step = result.step.unwrap().content;
+        assert_eq!(faces(&step), 6);
+        assert!(step.contains("SI_UNIT(.MILLI.,.METRE.)"));
+        assert!(result.warnings.is_empty());
+
+        let mut compiler =
+            CsgCompiler::with_config(KernelConfig::for_unit(LengthUnit::Meter)).unwrap();
+        let step = compiler.compile(&ir).unwrap().step.unwrap().content;
+        assert_eq!(faces(&step), 6);
+        assert!(step.contains("SI_UNIT($,.METRE.)"));
+    }
+
     #[test]
     fn test_compile_caching() {
         let mut compiler = CsgCompiler::new();
@@ -261,11 +739,123 @@ mod tests {
         assert_eq!(result1.status, CompileStatus::Compiled);
         assert_eq!(result2.status, CompileStatus::Cached);
         assert_eq!(result1.intent_hash, result2.intent_hash);
+
+        let (metrics1, metrics2) = (result1.metrics.unwrap(), result2.metrics.unwrap());
+        assert_eq!((metrics1.nodes_evaluated, metrics1.cache_misses), (1, 1));
+        assert_eq!((metrics2.nodes_evaluated, metrics2.cache_hits), (0, 1));
+        assert_eq!(metrics1.total_triangles, metrics2.total_triangles);
+    }
+
+    #[test]
+    fn test_snapshot_restores_session() {
+        use crate::config::LengthUnit;
+
+        let ir = GeometryIR {
+            part: "ball".to_string(),
+            operations: vec![
+                Intent::Primitive(create_test_box_intent("box1")),
+                Intent::Primitive(PrimitiveIntent {
+                    id: "sphere1".to_string(),
+                    type_: PrimitiveType::Sphere,
+                    parameters: vec![("radius".to_string(), 6.0)].into_iter().collect(),
+                    transform: None,
+                    timestamp: 0.0,
+                }),
+                Intent::Operation(OperationIntent {
+                    id: "union1".to_string(),
+                    type_: OperationType::Union,
+                    target: "box1".to_string(),
+                    operand: Some("sphere1".to_string()),
+                    parameters: HashMap::new(),
+                    timestamp: 0.0,
+                }),
+            ],
+            constraints: vec![],
+        };
+        let mut compiler =
+            CsgCompiler::with_config(KernelConfig::for_unit(LengthUnit::Centimeter)).unwrap();
+        compiler.set_subdivisions(24);
+        compiler.set_tessellation_quality(0.5).unwrap();
+        compiler.set_time_budget_ms(Some(60_000));
+        let compiled = compiler.compile(&ir).unwrap();
+        let snapshot = compiler.snapshot().unwrap();
+
+        let mut restored = CsgCompiler::new();
+        restored.restore(&snapshot).unwrap();
+        assert_eq!(*restored.config(), *compiler.config());
+        assert_eq!(restored.tessellation_quality(), 0.5);
+        assert_eq!(restored.time_budget_ms(), Some(60_000));
+
+        let result = restored.compile(&ir).unwrap();
+        assert_eq!(result.status, CompileStatus::Cached);
+        assert_eq!(result.intent_hash, compiled.intent_hash);
+        assert_eq!(
+            result.mesh.unwrap().vertices,
+            compiled.mesh.unwrap().vertices
+        );
+        assert_eq!(restored.compile(&ir).unwrap().status, CompileStatus::Cached);
+
+        // A different intent still compiles afresh
+        let other = GeometryIR {
+            operations: vec![Intent::Primitive(create_test_box_intent("box2"))],
+            ..ir
+        };
+        restored.restore(&snapshot).unwrap();
+        assert_eq!(
+            restored.compile(&other).unwrap().status,
+            CompileStatus::Compiled
+        );
+
+        // Bad snapshots leave the session alone
+        assert!(restored.restore("{\"version\": 99}").is_err());
+        assert!(restored.restore("not json").is_err());
+        let coarse = snapshot.replace(
+            "\"tessellation_quality\":0.5",
+            "\"tessellation_quality\":2.0",
+        );
+        assert_ne!(coarse, snapshot);
+        assert!(restored.restore(&coarse).is_err());
+        assert_eq!(restored.tessellation_quality(), 0.5);
+        assert_eq!(restored.compile(&other).unwrap().status, CompileStatus::Cached);
+    }
+
+    #[test]
+    fn test_tessellation_quality_scales_triangles() {
+        let mut compiler = CsgCompiler::with_subdivisions(32);
+        let ir = GeometryIR {
+            part: "ball".to_string(),
+            operations: vec![Intent::Primitive(PrimitiveIntent {
+                id: "sphere1".to_string(),
+                type_: PrimitiveType::Sphere,
+                parameters: vec![("radius".to_string(), 10.0)].into_iter().collect(),
+                transform: None,
+                timestamp: 0.0,
+            })],
+            constraints: vec![],
+        };
+        let triangles = |result: &CompileResult| result.mesh.as_ref().unwrap().indices.len() / 3;
+
+        let full = compiler.compile(&ir).unwrap();
+        compiler.set_tessellation_quality(0.25).unwrap();
+        let coarse = compiler.compile(&ir).unwrap();
+
+        // The cache is keyed by quality as well as by intent
+        assert_eq!(coarse.status, CompileStatus::Compiled);
+        let ratio = triangles(&coarse) as f64 / triangles(&full) as f64;
+        assert!((0.2..=0.3).contains(&ratio), "ratio {}", ratio);
+
+        compiler.set_tessellation_quality(1.0).unwrap();
+        assert_eq!(triangles(&compiler.compile(&ir).unwrap()), triangles(&full));
+
+        assert!(compiler.set_tessellation_quality(0.0).is_err());
+        assert!(compiler.set_tessellation_quality(1.5).is_err());
+        assert!(compiler.set_tessellation_quality(f32::NAN).is_err());
+        assert_eq!(compiler.tessellation_quality(), 1.0);
     }
 
     #[test]
     fn test_validate() {
-        let compiler = CsgCompiler::new();
+        let mut compiler = CsgCompiler::new();
 
         let ir = GeometryIR {
             part: "test_part".to_string(),
@@ -278,7 +868,7 @@ mod tests {
 
     #[test]
     fn test_validate_missing_parameter() {
-        let compiler = CsgCompiler::new();
+        let mut compiler = CsgCompiler::new();
 
         let ir = GeometryIR {
             part: "test_part".to_string(),
@@ -294,4 +884,152 @@ mod tests {
 
         assert!(compiler.validate(&ir).is_err());
     }
+
+    #[test]
+    fn test_compile_reports_disjoint_solids() {
+        // A 20x2x2 bar with a 2 wide slot through its middle. Boolean
+        // subtraction does not split triangles, so the cut bar is modeled
+        // directly as its two remaining halves.
+        let half = |id: &str, x: f64| {
+            Intent::Primitive(PrimitiveIntent {
+                id: id.to_string(),
+                type_: PrimitiveType::Box,
+                parameters: vec![
+                    ("width".to_string(), 9.0),
+                    ("height".to_string(), 2.0),
+                    ("depth".to_string(), 2.0),
+                ]
+                .into_iter()
+                .collect(),
+                transform: Some(Transform {
+                    position: Some([x, 0.0, 0.0]),
+                    rotation: None,
+                    scale: None,
+                }),
+                timestamp: 0.0,
+            })
+        };
+
+        let ir = GeometryIR {
+            part: "cut_bar".to_string(),
+            operations: vec![
+                half("left", -5.5),
+                half("right", 5.5),
+                Intent::Operation(OperationIntent {
+                    id: "union1".to_string(),
+                    type_: OperationType::Union,
+                    target: "left".to_string(),
+                    operand: Some("right".to_string()),
+                    parameters: HashMap::new(),
+                    timestamp: 0.0,
+                }),
+            ],
+            constraints: vec![],
+        };
+
+        let result = CsgCompiler::new().compile(&ir).unwrap();
+        assert_eq!(result.solids.len(), 2);
+
+        let bar_volume = 20.0 * 2.0 * 2.0;
+        let cut_volume = 2.0 * 2.0 * 2.0;
+        let total: f64 = result.solids.iter().map(|s| s.volume).sum();
+        assert!((total - (bar_volume - cut_volume)).abs() < 1e-4);
+
+        assert!(result.solids[0].bounding_box.max[0] <= -1.0 + 1e-6);
+        assert!(result.solids[1].bounding_box.min[0] >= 1.0 - 1e-6);
+    }
+
+    #[test]
+    fn test_time_budget_returns_partial_result() {
+        // A long chain of sphere unions, far more than a millisecond of work
+        let count = 100;
+        let mut operations: Vec<Intent> = (0..count)
+            .map(|i| {
+                Intent::Primitive(PrimitiveIntent {
+                    id: format!("sphere{}", i),
+                    type_: PrimitiveType::Sphere,
+                    parameters: vec![("radius".to_string(), 1.0)].into_iter().collect(),
+                    transform: Some(Transform {
+                        position: Some([i as f64 * 1.5, 0.0, 0.0]),
+                        rotation: None,
+                        scale: None,
+                    }),
+                    timestamp: 0.0,
+                })
+            })
+            .collect();
+        operations.extend((1..count).map(|i| {
+            let target = if i == 1 {
+                "sphere0".to_string()
+            } else {
+                format!("union{}", i - 1)
+            };
+            Intent::Operation(OperationIntent {
+                id: format!("union{}", i),
+                type_: OperationType::Union,
+                target,
+                operand: Some(format!("sphere{}", i)),
+                parameters: HashMap::new(),
+                timestamp: 0.0,
+            })
+        }));
+        let ir = GeometryIR {
+            part: "chain".to_string(),
+            operations,
+            constraints: vec![],
+        };
+
+        let mut compiler = CsgCompiler::with_subdivisions(32);
+        compiler.set_time_budget_ms(Some(1));
+        let result = compiler.compile(&ir).unwrap();
+        assert_eq!(result.status, CompileStatus::Partial);
+        assert!(result.mesh.is_none());
+        assert!(!result.partial_meshes.is_empty());
+        assert!(!result.remaining_nodes.is_empty());
+        assert_eq!(
+            result.partial_meshes.len() + result.remaining_nodes.len(),
+            ir.operations.len()
+        );
+        let last = format!("union{}", count - 1);
+        assert!(result.remaining_nodes.contains(&last));
+
+        // Partial results are not cached
+        let again = compiler.compile(&ir).unwrap();
+        assert_eq!(again.status, CompileStatus::Partial);
+    }
+
+    #[test]
+    fn test_time_budget_matches_unbudgeted_compile() {
+        let ir = GeometryIR {
+            part: "test_part".to_string(),
+            operations: vec![
+                Intent::Primitive(create_test_box_intent("box1")),
+                Intent::Primitive(PrimitiveIntent {
+                    transform: Some(Transform {
+                        position: Some([5.0, 0.0, 0.0]),
+                        rotation: None,
+                        scale: None,
+                    }),
+                    ..create_test_box_intent("box2")
+                }),
+                Intent::Operation(OperationIntent {
+                    id: "cut".to_string(),
+                    type_: OperationType::Subtract,
+                    target: "box1".to_string(),
+                    operand: Some("box2".to_string()),
+                    parameters: HashMap::new(),
+                    timestamp: 0.0,
+                }),
+            ],
+            constraints: vec![],
+        };
+
+        let expected = CsgCompiler::new().compile(&ir).unwrap();
+        let mut compiler = CsgCompiler::new();
+        compiler.set_time_budget_ms(Some(60_000));
+        let result = compiler.compile(&ir).unwrap();
+        assert_eq!(result.status, CompileStatus::Compiled);
+        assert!(result.remaining_nodes.is_empty());
+        assert_eq!(result.mesh.unwrap().vertices, expected.mesh.unwrap().vertices);
+    }
 }
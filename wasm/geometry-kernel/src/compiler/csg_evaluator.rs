@@ -4,20 +4,31 @@
 //! memoization and optimization.
 
 use crate::compiler::csg_tree::CsgNode;
-use crate::types::PreviewMesh;
+use crate::config::KernelConfig;
+use crate::types::PrecisionMesh;
 use crate::geometry::{Primitive, create_primitive};
+use crate::geometry::operations::{precise_boolean_operation, BooleanOperation};
 use crate::errors::{KernelError, KernelResult};
 use std::collections::HashMap;
 
 /// CSG tree evaluator
 ///
-/// Evaluates CSG trees to produce preview meshes with memoization
-/// for performance.
+/// Evaluates CSG trees to f64 meshes with memoization for performance.
+/// Callers downcast the result to a preview mesh once, so parts far from
+/// the origin keep their precision through every boolean.
 pub struct CsgEvaluator {
     /// Cache of evaluated results
-    pub(crate) cache: HashMap<String, PreviewMesh>,
+    pub(crate) cache: HashMap<String, PrecisionMesh>,
     /// Subdivision level for mesh generation
     subdivisions: u32,
+    /// Chord error for curved primitives; overrides `subdivisions` when set
+    max_chord_error: Option<f64>,
+    /// Fraction of the full triangle count to keep, in `(0, 1]`
+    quality: f32,
+    /// Distance (in model units) below which booleans merge points
+    linear_tolerance: f64,
+    /// Work done since the evaluator was created
+    pub(crate) counts: EvaluationCounts,
 }
 
 impl CsgEvaluator {
@@ -25,6 +36,10 @@ impl CsgEvaluator {
         CsgEvaluator {
             cache: HashMap::new(),
             subdivisions: 16, // Default subdivisions
+            max_chord_error: None,
+            quality: 1.0,
+            linear_tolerance: KernelConfig::default().linear_tolerance,
+            counts: EvaluationCounts::default(),
         }
     }
 
@@ -32,17 +47,24 @@ impl CsgEvaluator {
         CsgEvaluator {
             cache: HashMap::new(),
             subdivisions: subdivisions.max(4).min(64),
+            max_chord_error: None,
+            quality: 1.0,
+            linear_tolerance: KernelConfig::default().linear_tolerance,
+            counts: EvaluationCounts::default(),
         }
     }
 
     /// Evaluate a CSG node to produce a mesh
-    pub fn evaluate(&mut self, node: &CsgNode) -> KernelResult<PreviewMesh> {
+    pub fn evaluate(&mut self, node: &CsgNode) -> KernelResult<PrecisionMesh> {
         // Check cache
         if let Some(id) = node.get_id() {
             if let Some(mesh) = self.cache.get(id) {
+                self.counts.cache_hits += 1;
                 return Ok(mesh.clone());
             }
+            self.counts.cache_misses += 1;
         }
+        self.counts.nodes_evaluated += 1;
 
         let result = match node {
             CsgNode::Primitive {
@@ -51,8 +73,14 @@ impl CsgEvaluator {
                 params,
                 transform,
             } => {
-                let primitive = create_primitive(type_.clone(), params)?;
-                let mut mesh = primitive.to_mesh(self.subdivisions);
+                let mut primitive = create_primitive(type_.clone(), params)?;
+                if let Some(transform) = transform {
+                    primitive.apply_transform(transform);
+                }
+                let mesh = match self.max_chord_error {
+                    Some(error) => primitive.to_adaptive_precision_mesh(error / self.quality as f64)?,
+                    None => primitive.to_precision_mesh(self.scaled_subdivisions()),
+                };
 
                 // Cache primitive result
                 self.cache.insert(id.clone(), mesh.clone());
@@ -63,43 +91,123 @@ impl CsgEvaluator {
                 let left_mesh = self.evaluate(left)?;
                 let right_mesh = self.evaluate(right)?;
 
-                crate::geometry::operations::boolean_operation(
+                precise_boolean_operation(
                     &left_mesh,
                     &right_mesh,
-                    crate::geometry::operations::BooleanOperation::Union,
+                    BooleanOperation::Union,
+                    self.linear_tolerance,
                 )
             }
             CsgNode::Subtract { target, tool } => {
                 let target_mesh = self.evaluate(target)?;
                 let tool_mesh = self.evaluate(tool)?;
 
-                crate::geometry::operations::boolean_operation(
+                precise_boolean_operation(
                     &target_mesh,
                     &tool_mesh,
-                    crate::geometry::operations::BooleanOperation::Subtract,
+                    BooleanOperation::Subtract,
+                    self.linear_tolerance,
                 )
             }
             CsgNode::Intersect { left, right } => {
                 let left_mesh = self.evaluate(left)?;
                 let right_mesh = self.evaluate(right)?;
 
-                crate::geometry::operations::boolean_operation(
+                precise_boolean_operation(
                     &left_mesh,
                     &right_mesh,
-                    crate::geometry::operations::BooleanOperation::Intersect,
+                    BooleanOperation::Intersect,
+                    self.linear_tolerance,
                 )
             }
+            CsgNode::UnionN(operands) => self.evaluate_balanced(operands, BooleanOperation::Union),
+            CsgNode::IntersectN(operands) => {
+                self.evaluate_balanced(operands, BooleanOperation::Intersect)
+            }
         };
 
         result
     }
 
+    /// Evaluate every operand, then combine neighbours pairwise until one
+    /// mesh is left
+    ///
+    /// N operands take ceil(log2 N) rounds of booleans rather than the N - 1
+    /// levels of a chain of binary nodes, so intermediate meshes stay small.
+    fn evaluate_balanced(
+        &mut self,
+        operands: &[CsgNode],
+        operation: BooleanOperation,
+    ) -> KernelResult<PrecisionMesh> {
+        let mut meshes = operands
+            .iter()
+            .map(|operand| self.evaluate(operand))
+            .collect::<KernelResult<Vec<_>>>()?;
+
+        while meshes.len() > 1 {
+            let mut combined = Vec::with_capacity(meshes.len().div_ceil(2));
+            let mut pending = meshes.into_iter();
+            while let Some(first) = pending.next() {
+                match pending.next() {
+                    Some(second) => combined.push(precise_boolean_operation(
+                        &first,
+                        &second,
+                        operation,
+                        self.linear_tolerance,
+                    )?),
+                    None => combined.push(first),
+                }
+            }
+            meshes = combined;
+        }
+
+        meshes
+            .pop()
+            .ok_or_else(|| KernelError::csg_error("Boolean node has no operands"))
+    }
+
+    /// Subdivision level for curved primitives, before quality scaling
+    pub fn subdivisions(&self) -> u32 {
+        self.subdivisions
+    }
+
     /// Set subdivision level for mesh generation
     pub fn set_subdivisions(&mut self, subdivisions: u32) {
         self.subdivisions = subdivisions.max(4).min(64);
         self.cache.clear(); // Clear cache when subdivisions change
     }
 
+    /// Mesh curved primitives to a chord error instead of fixed subdivisions
+    pub fn set_max_chord_error(&mut self, max_chord_error: Option<f64>) {
+        self.max_chord_error = max_chord_error;
+        self.cache.clear();
+    }
+
+    /// Distance (in model units) below which booleans merge points
+    ///
+    /// Only booleans evaluated afterwards use it, so cached primitives
+    /// stay valid.
+    pub fn set_linear_tolerance(&mut self, linear_tolerance: f64) {
+        self.linear_tolerance = linear_tolerance;
+    }
+
+    /// Trade fidelity for speed on every primitive
+    ///
+    /// Curved surfaces are subdivided in two directions, so subdivision
+    /// counts are scaled by the square root of `quality` (down to 4) and
+    /// a chord error target is divided by it; either way a curved
+    /// primitive keeps roughly `quality` of its triangles. The caller
+    /// checks the range.
+    pub fn set_tessellation_quality(&mut self, quality: f32) {
+        self.quality = quality;
+        self.cache.clear();
+    }
+
+    fn scaled_subdivisions(&self) -> u32 {
+        let scaled = self.subdivisions as f64 * (self.quality as f64).sqrt();
+        (scaled.round() as u32).max(4)
+    }
+
     /// Clear evaluation cache
     pub fn clear_cache(&mut self) {
         self.cache.clear();
@@ -109,9 +217,14 @@ impl CsgEvaluator {
     pub fn cache_stats(&self) -> CacheStats {
         CacheStats {
             size: self.cache.len(),
-            hits: 0, // Would need counter for accurate stats
+            hits: self.counts.cache_hits,
         }
     }
+
+    /// Nodes evaluated and cache lookups since the evaluator was created
+    pub fn counts(&self) -> EvaluationCounts {
+        self.counts
+    }
 }
 
 impl Default for CsgEvaluator {
@@ -127,11 +240,36 @@ pub struct CacheStats {
     pub hits: usize,
 }
 
+/// Running totals of evaluator work
+///
+/// Only primitives are cached, so only they count as hits or misses;
+/// every node not served from the cache counts as evaluated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvaluationCounts {
+    pub nodes_evaluated: usize,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+}
+
+impl EvaluationCounts {
+    /// Work done between `earlier` and these counts
+    pub fn since(&self, earlier: EvaluationCounts) -> EvaluationCounts {
+        EvaluationCounts {
+            nodes_evaluated: self.nodes_evaluated - earlier.nodes_evaluated,
+            cache_hits: self.cache_hits - earlier.cache_hits,
+            cache_misses: self.cache_misses - earlier.cache_misses,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::compiler::csg_tree::{primitive_node, union_node};
-    use crate::types::PrimitiveType;
+    use crate::compiler::csg_tree::{
+        intersect_all_node, intersect_node, primitive_node, union_all_node, union_node,
+    };
+    use crate::geometry::analysis::MassPropertiesAnalyzer;
+    use crate::types::{PrimitiveType, Transform};
 
     #[test]
     fn test_evaluate_primitive() {
@@ -219,4 +357,82 @@ mod tests {
         assert_eq!(mesh1.vertices.len(), mesh2.vertices.len());
         assert_eq!(mesh1.indices.len(), mesh2.indices.len());
     }
+
+    /// Box primitive with the given size, centered at `x` on the X axis
+    fn box_at(id: &str, size: [f64; 3], x: f64) -> CsgNode {
+        primitive_node(
+            id.to_string(),
+            PrimitiveType::Box,
+            ["width", "height", "depth"]
+                .iter()
+                .zip(size)
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+            Some(Transform {
+                position: Some([x, 0.0, 0.0]),
+                rotation: None,
+                scale: None,
+            }),
+        )
+    }
+
+    fn volume(node: &CsgNode) -> f64 {
+        let mesh = CsgEvaluator::new().evaluate(node).unwrap();
+        MassPropertiesAnalyzer::new().analyze_mesh(&mesh.to_preview()).unwrap().volume
+    }
+
+    #[test]
+    fn test_union_n_matches_nested_unions() {
+        // Cubes of side 1 to 4, spaced apart along X
+        let cubes = || {
+            vec![
+                box_at("a", [1.0; 3], 0.0),
+                box_at("b", [2.0; 3], 10.0),
+                box_at("c", [3.0; 3], 20.0),
+                box_at("d", [4.0; 3], 30.0),
+            ]
+        };
+
+        let nested = cubes()
+            .into_iter()
+            .reduce(union_node)
+            .unwrap();
+        let flat = union_all_node(cubes());
+        assert_eq!(nested.depth(), 3);
+        assert_eq!(flat.depth(), 1);
+        assert_eq!(flat.collect_primitive_ids(), nested.collect_primitive_ids());
+
+        let nested_volume = volume(&nested);
+        assert!((nested_volume - 100.0).abs() < 1e-9);
+        assert!((volume(&flat) - nested_volume).abs() < 1e-9);
+
+        assert!(union_all_node(Vec::new()).validate().is_err());
+        assert!(CsgEvaluator::new().evaluate(&union_all_node(Vec::new())).is_err());
+    }
+
+    #[test]
+    fn test_intersect_n_matches_nested_intersections() {
+        // Staggered along X, and each taller than the last so no faces
+        // are coplanar: the common part is x in [0, 2] of the first box
+        let boxes = || {
+            vec![
+                box_at("a", [4.0, 4.0, 4.0], 0.0),
+                box_at("b", [4.0, 5.0, 5.0], 1.0),
+                box_at("c", [4.0, 6.0, 6.0], 2.0),
+            ]
+        };
+
+        let nested = boxes()
+            .into_iter()
+            .reduce(intersect_node)
+            .unwrap();
+        let flat = intersect_all_node(boxes());
+        assert_eq!(flat.depth(), 1);
+
+        let nested_volume = volume(&nested);
+        assert!((nested_volume - 32.0).abs() < 1e-4);
+        assert!((volume(&flat) - nested_volume).abs() < 1e-4);
+
+        assert!(CsgEvaluator::new().evaluate(&intersect_all_node(Vec::new())).is_err());
+    }
 }
@@ -33,6 +33,10 @@ pub enum CsgNode {
         left: Box<CsgNode>,
         right: Box<CsgNode>,
     },
+    /// Union of any number of shapes, combined pairwise in balanced rounds
+    UnionN(Vec<CsgNode>),
+    /// Intersection of any number of shapes, combined like `UnionN`
+    IntersectN(Vec<CsgNode>),
 }
 
 impl CsgNode {
@@ -65,9 +69,9 @@ impl CsgNode {
     /// Get operation type as string
     pub fn get_operation_type(&self) -> Option<&'static str> {
         match self {
-            CsgNode::Union { .. } => Some("union"),
+            CsgNode::Union { .. } | CsgNode::UnionN(_) => Some("union"),
             CsgNode::Subtract { .. } => Some("subtract"),
-            CsgNode::Intersect { .. } => Some("intersect"),
+            CsgNode::Intersect { .. } | CsgNode::IntersectN(_) => Some("intersect"),
             CsgNode::Primitive { .. } => None,
         }
     }
@@ -81,6 +85,9 @@ impl CsgNode {
             | CsgNode::Intersect { left, right } => {
                 left.node_count() + right.node_count() + 1
             }
+            CsgNode::UnionN(operands) | CsgNode::IntersectN(operands) => {
+                operands.iter().map(CsgNode::node_count).sum::<usize>() + 1
+            }
         }
     }
 
@@ -93,6 +100,9 @@ impl CsgNode {
             | CsgNode::Intersect { left, right } => {
                 left.depth().max(right.depth()) + 1
             }
+            CsgNode::UnionN(operands) | CsgNode::IntersectN(operands) => {
+                operands.iter().map(CsgNode::depth).max().unwrap_or(0) + 1
+            }
         }
     }
 
@@ -112,6 +122,11 @@ impl CsgNode {
                 left.collect_primitive_ids_recursive(ids);
                 right.collect_primitive_ids_recursive(ids);
             }
+            CsgNode::UnionN(operands) | CsgNode::IntersectN(operands) => {
+                for operand in operands {
+                    operand.collect_primitive_ids_recursive(ids);
+                }
+            }
         }
     }
 
@@ -161,6 +176,9 @@ impl CsgNode {
                 right.check_circular_references(visited)?;
                 Ok(())
             }
+            CsgNode::UnionN(operands) | CsgNode::IntersectN(operands) => operands
+                .iter()
+                .try_for_each(|operand| operand.check_circular_references(visited)),
         }
     }
 
@@ -175,6 +193,15 @@ impl CsgNode {
                 left.validate_primitives()?;
                 right.validate_primitives()
             }
+            CsgNode::UnionN(operands) | CsgNode::IntersectN(operands) => {
+                if operands.is_empty() {
+                    return Err(KernelError::csg_error(format!(
+                        "{} node has no operands",
+                        self.get_operation_type().unwrap_or("boolean")
+                    )));
+                }
+                operands.iter().try_for_each(CsgNode::validate_primitives)
+            }
         }
     }
 }
@@ -218,6 +245,16 @@ pub fn intersect_node(left: CsgNode, right: CsgNode) -> CsgNode {
     }
 }
 
+/// Create a union node over any number of operands
+pub fn union_all_node(operands: Vec<CsgNode>) -> CsgNode {
+    CsgNode::UnionN(operands)
+}
+
+/// Create an intersect node over any number of operands
+pub fn intersect_all_node(operands: Vec<CsgNode>) -> CsgNode {
+    CsgNode::IntersectN(operands)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,130 @@
+//! Exploded views of assemblies.
+//!
+//! Each part (a solid marked by a part analysis node, see
+//! [`crate::geometry::ir::bom`]) is pushed away from the assembly centre
+//! along the line from that centre to the part's own centre. Geometry is
+//! left untouched; the result is one transform per part for the viewer or
+//! an animation to apply.
+
+use crate::compiler::rebuild::{evaluate_nodes, mesh_bounds};
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::constants;
+use crate::geometry::ir::bom::part_instances;
+use crate::geometry::ir::node::Transform;
+use crate::geometry::{IRGraph, NodeId};
+use std::collections::HashMap;
+
+/// Per-part transforms for an exploded view of an assembly graph
+///
+/// A part moves by `factor` times its offset from the assembly centre, so
+/// `factor = 0` gives identity transforms and `factor = 1` doubles every
+/// part's distance from the centre. Part centres are bounding-box centres
+/// and the assembly centre is their mean.
+pub fn explode(graph: &IRGraph, factor: f64) -> KernelResult<HashMap<NodeId, Transform>> {
+    if !factor.is_finite() || factor < 0.0 {
+        return Err(KernelError::invalid_parameter("factor", factor.to_string()));
+    }
+
+    let parts = part_instances(graph)?;
+    if parts.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let order = graph.evaluation_order()?;
+    let meshes = evaluate_nodes(graph, &order, constants::DEFAULT_SUBDIVISIONS)?.results;
+    let mut centres = Vec::with_capacity(parts.len());
+    for (solid, _) in &parts {
+        let mesh = meshes.get(*solid).ok_or_else(|| {
+            KernelError::invalid_graph(format!("Part {} produced no geometry", solid.as_str()))
+        })?;
+        centres.push(mesh_bounds(mesh).center());
+    }
+
+    let count = centres.len() as f64;
+    let assembly = [0, 1, 2].map(|k| centres.iter().map(|c| c[k]).sum::<f64>() / count);
+
+    Ok(parts
+        .into_iter()
+        .zip(centres)
+        .map(|((solid, _), centre)| {
+            let transform = Transform {
+                translation: [0, 1, 2].map(|k| (centre[k] - assembly[k]) * factor),
+                ..Transform::default()
+            };
+            (solid.clone(), transform)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::ir::node::{NodeMetadata, NodeSource};
+    use crate::geometry::ir::PART_ANALYSIS;
+    use crate::geometry::{IRNode, NodeContent, NodeType};
+
+    fn part(graph: &mut IRGraph, name: &str, position: [f64; 3]) -> NodeId {
+        let solid = IRNode::with_user_id(
+            name,
+            NodeType::Primitive,
+            NodeContent::Primitive {
+                primitive_type: "box".to_string(),
+                parameters: [("width", 2.0), ("height", 2.0), ("depth", 2.0)]
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect(),
+                transform: Some(Transform {
+                    translation: position,
+                    ..Transform::default()
+                }),
+            },
+            vec![],
+            NodeMetadata::new(Some(name.to_string()), NodeSource::User),
+        )
+        .unwrap();
+        let id = solid.id.clone();
+        graph.add_node(solid).unwrap();
+
+        let marker = IRNode::new(
+            NodeType::Analysis,
+            NodeContent::Analysis {
+                analysis_type: PART_ANALYSIS.to_string(),
+                target_node: id.clone(),
+                parameters: [("part_number".to_string(), serde_json::json!(name))]
+                    .into_iter()
+                    .collect(),
+            },
+            vec![id.clone()],
+            NodeMetadata::new(None, NodeSource::User),
+        )
+        .unwrap();
+        graph.add_node(marker).unwrap();
+        id
+    }
+
+    #[test]
+    fn test_explode_moves_parts_outward() {
+        let mut graph = IRGraph::new();
+        let left = part(&mut graph, "left", [-5.0, 0.0, 0.0]);
+        let right = part(&mut graph, "right", [5.0, 0.0, 0.0]);
+        let top = part(&mut graph, "top", [0.0, 6.0, 0.0]);
+
+        let identity = explode(&graph, 0.0).unwrap();
+        assert_eq!(identity.len(), 3);
+        for transform in identity.values() {
+            assert_eq!(transform.translation, [0.0; 3]);
+            assert_eq!(transform.rotation, Transform::default().rotation);
+            assert_eq!(transform.scale, Transform::default().scale);
+        }
+
+        // Assembly centre is (0, 2, 0)
+        let exploded = explode(&graph, 1.0).unwrap();
+        let moved = |id: &NodeId| exploded[id].translation;
+        assert!((moved(&left)[0] + 5.0).abs() < 1e-6 && (moved(&left)[1] + 2.0).abs() < 1e-6);
+        assert!((moved(&right)[0] - 5.0).abs() < 1e-6);
+        assert!((moved(&top)[1] - 4.0).abs() < 1e-6);
+
+        assert!(explode(&graph, f64::NAN).is_err());
+        assert!(explode(&IRGraph::new(), 1.0).unwrap().is_empty());
+    }
+}
@@ -3,8 +3,13 @@
 //! Converts the serialized intent from TypeScript into a CSG tree
 //! structure that can be evaluated to produce geometry.
 
-use crate::compiler::csg_tree::{CsgNode, primitive_node, union_node, subtract_node, intersect_node};
+use crate::compiler::csg_tree::{
+    CsgNode, primitive_node, union_node, subtract_node, intersect_node, union_all_node,
+    intersect_all_node,
+};
+use crate::compiler::rebuild::to_ir_transform;
 use crate::errors::{KernelError, KernelResult};
+use crate::geometry::ir::{IRGraph, IRNode, NodeContent, NodeId, NodeMetadata, NodeSource, NodeType};
 use crate::types::{GeometryIR, Intent, PrimitiveIntent, OperationIntent, PrimitiveType, OperationType, Transform};
 
 /// Parser for converting Intent IR to CSG tree
@@ -25,7 +30,7 @@ impl IntentParser {
         self.node_map.clear();
 
         if ir.operations.is_empty() {
-            return Err(KernelError::invalid_intent("No operations in intent"));
+            return Err(KernelError::empty_intent());
         }
 
         // First pass: create all primitive nodes
@@ -43,9 +48,7 @@ impl IntentParser {
         }
 
         // Find the final result (last operation or primitive)
-        let last_op = ir.operations.last().ok_or_else(|| {
-            KernelError::invalid_intent("No operations in intent")
-        })?;
+        let last_op = ir.operations.last().ok_or_else(KernelError::empty_intent)?;
 
         match last_op {
             Intent::Primitive(primitive) => {
@@ -63,6 +66,11 @@ impl IntentParser {
         }
     }
 
+    /// CSG node built for an intent ID by the last [`parse`](Self::parse)
+    pub fn node(&self, id: &str) -> Option<&CsgNode> {
+        self.node_map.get(id)
+    }
+
     /// Parse a primitive intent
     fn parse_primitive(&mut self, primitive: &PrimitiveIntent) -> KernelResult<()> {
         // Convert transform
@@ -114,7 +122,7 @@ impl IntentParser {
                     ))
                 })?;
 
-                let node = union_node(target.clone(), operand.clone());
+                let node = extend_chain(target, operand, OperationType::Union);
                 self.node_map.insert(operation.id.clone(), node);
             }
             OperationType::Subtract => {
@@ -152,7 +160,7 @@ impl IntentParser {
                     ))
                 })?;
 
-                let node = intersect_node(target.clone(), operand.clone());
+                let node = extend_chain(target, operand, OperationType::Intersect);
                 self.node_map.insert(operation.id.clone(), node);
             }
             OperationType::Fillet | OperationType::Hole | OperationType::Chamfer => {
@@ -168,6 +176,112 @@ impl IntentParser {
     }
 }
 
+/// Union or intersect `operand` onto `target`
+///
+/// When the target is already the same operation, the operand joins its
+/// operand list instead of nesting another level, so a chain of intents
+/// builds one `UnionN` or `IntersectN` that evaluates as a balanced tree.
+fn extend_chain(target: &CsgNode, operand: &CsgNode, operation: OperationType) -> CsgNode {
+    let extended = |operands: Vec<&CsgNode>| {
+        operands
+            .into_iter()
+            .chain([operand])
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    match (operation, target) {
+        (OperationType::Union, CsgNode::UnionN(operands)) => {
+            union_all_node(extended(operands.iter().collect()))
+        }
+        (OperationType::Union, CsgNode::Union { left, right }) => {
+            union_all_node(extended(vec![left, right]))
+        }
+        (OperationType::Union, _) => union_node(target.clone(), operand.clone()),
+        (OperationType::Intersect, CsgNode::IntersectN(operands)) => {
+            intersect_all_node(extended(operands.iter().collect()))
+        }
+        (OperationType::Intersect, CsgNode::Intersect { left, right }) => {
+            intersect_all_node(extended(vec![left, right]))
+        }
+        _ => intersect_node(target.clone(), operand.clone()),
+    }
+}
+
+/// Map intent IR onto an IR graph, one node per intent with its user ID
+///
+/// Primitives become primitive nodes, unions, subtractions and
+/// intersections boolean nodes, and fillets, holes and chamfers feature
+/// nodes on their target. Like [`IntentParser::parse`], primitives are
+/// added first, so operations may reference any of them.
+pub fn intent_graph(ir: &GeometryIR) -> KernelResult<IRGraph> {
+    let mut graph = IRGraph::new();
+    let metadata = |id: &str| NodeMetadata::new(Some(id.to_string()), NodeSource::User);
+
+    for intent in &ir.operations {
+        if let Intent::Primitive(primitive) = intent {
+            graph.add_node(IRNode::with_user_id(
+                &primitive.id,
+                NodeType::Primitive,
+                NodeContent::Primitive {
+                    primitive_type: format!("{:?}", primitive.type_).to_lowercase(),
+                    parameters: primitive.parameters.clone(),
+                    transform: primitive.transform.as_ref().map(to_ir_transform),
+                },
+                Vec::new(),
+                metadata(&primitive.id),
+            )?)?;
+        }
+    }
+
+    for intent in &ir.operations {
+        let Intent::Operation(operation) = intent else {
+            continue;
+        };
+        let target = NodeId::from_user_string(&operation.target);
+        let operation_type = format!("{:?}", operation.type_).to_lowercase();
+
+        let node = match operation.type_ {
+            OperationType::Union | OperationType::Subtract | OperationType::Intersect => {
+                let operand = operation.operand.as_deref().ok_or_else(|| {
+                    KernelError::invalid_intent(format!(
+                        "{} operation '{}' missing operand",
+                        operation_type, operation.id
+                    ))
+                })?;
+                let operand = NodeId::from_user_string(operand);
+                IRNode::with_user_id(
+                    &operation.id,
+                    NodeType::BooleanOp,
+                    NodeContent::BooleanOp {
+                        operation_type,
+                        operand_a: target.clone(),
+                        operand_b: operand.clone(),
+                    },
+                    vec![target, operand],
+                    metadata(&operation.id),
+                )?
+            }
+            OperationType::Fillet | OperationType::Hole | OperationType::Chamfer => {
+                IRNode::with_user_id(
+                    &operation.id,
+                    NodeType::Feature,
+                    NodeContent::Feature {
+                        feature_type: operation_type,
+                        target_node: target.clone(),
+                        parameters: operation.parameters.clone(),
+                    },
+                    vec![target],
+                    metadata(&operation.id),
+                )?
+            }
+        };
+        graph.add_node(node)?;
+    }
+
+    Ok(graph)
+}
+
 impl Default for IntentParser {
     fn default() -> Self {
         Self::new()
@@ -294,6 +408,63 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_chained_operations_flatten() {
+        let cube = |id: &str| {
+            Intent::Primitive(PrimitiveIntent {
+                id: id.to_string(),
+                type_: PrimitiveType::Box,
+                parameters: vec![("width".to_string(), 1.0)].into_iter().collect(),
+                transform: None,
+                timestamp: 0.0,
+            })
+        };
+        let operation = |id: &str, type_: OperationType, target: &str, operand: &str| {
+            Intent::Operation(OperationIntent {
+                id: id.to_string(),
+                type_,
+                target: target.to_string(),
+                operand: Some(operand.to_string()),
+                parameters: HashMap::new(),
+                timestamp: 0.0,
+            })
+        };
+
+        let ir = GeometryIR {
+            part: "test_part".to_string(),
+            operations: vec![
+                cube("a"),
+                cube("b"),
+                cube("c"),
+                cube("d"),
+                operation("u1", OperationType::Union, "a", "b"),
+                operation("u2", OperationType::Union, "u1", "c"),
+                operation("u3", OperationType::Union, "u2", "d"),
+                operation("i1", OperationType::Intersect, "u3", "a"),
+                operation("i2", OperationType::Intersect, "i1", "b"),
+            ],
+            constraints: vec![],
+        };
+
+        let mut parser = IntentParser::new();
+        let tree = parser.parse(&ir).unwrap();
+
+        // The first pair stays binary, later links join the chain
+        assert!(matches!(parser.node("u1"), Some(CsgNode::Union { .. })));
+        match parser.node("u3") {
+            Some(CsgNode::UnionN(operands)) => assert_eq!(operands.len(), 4),
+            other => panic!("expected UnionN, got {:?}", other),
+        }
+        match &tree {
+            CsgNode::IntersectN(operands) => {
+                assert_eq!(operands.len(), 3);
+                assert!(matches!(operands[0], CsgNode::UnionN(_)));
+            }
+            other => panic!("expected IntersectN, got {:?}", other),
+        }
+        assert_eq!(tree.depth(), 2);
+    }
+
     #[test]
     fn test_parse_empty_operations() {
         let mut parser = IntentParser::new();
@@ -0,0 +1,46 @@
+//! Wall-clock timing for compile metrics.
+//!
+//! `std::time::Instant` panics on `wasm32-unknown-unknown`, so in the
+//! browser the host's `performance.now()` is used instead.
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
+}
+
+/// Milliseconds since an arbitrary fixed point
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now_ms() -> f64 {
+    performance_now()
+}
+
+/// Milliseconds since an arbitrary fixed point
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+/// Measures consecutive phases of a compile
+pub(crate) struct Stopwatch {
+    last: f64,
+}
+
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Stopwatch { last: now_ms() }
+    }
+
+    /// Milliseconds since the start or the previous lap
+    pub(crate) fn lap(&mut self) -> f64 {
+        let now = now_ms();
+        let elapsed = now - self.last;
+        self.last = now;
+        elapsed
+    }
+}
@@ -0,0 +1,374 @@
+//! Mirroring whole feature histories.
+//!
+//! A subgraph is copied node by node with every placement reflected, so a
+//! bracket built once can be added again on the other side of an assembly
+//! and edited independently of the original.
+
+use crate::compiler::rebuild::vector_param;
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::analysis::Plane;
+use crate::geometry::ir::node::{ContentHash, NodeSource, Transform};
+use crate::geometry::math::vec3::{dot, normalize, scale, sub};
+use crate::geometry::{IRGraph, IRNode, NodeContent, NodeId};
+use std::collections::HashMap;
+
+/// Feature parameters holding points, reflected as positions
+const POINT_PARAMETERS: [&str; 1] = ["position"];
+
+/// Feature parameters holding directions, reflected as vectors
+const VECTOR_PARAMETERS: [&str; 1] = ["direction"];
+
+/// Copies of `root` and everything it depends on, reflected across `plane`
+///
+/// Primitive transforms are reflected by changing their rotation only:
+/// every kernel primitive is symmetric about its local YZ plane, so the
+/// mirrored solid keeps a positive scale and outward-facing triangles.
+/// Hole `position` and `direction` parameters are reflected; other
+/// parameters are copied as they are.
+///
+/// Each copy gets an ID derived from the original's and the plane, so
+/// mirroring again gives the same IDs, and references between copies are
+/// rewired to the copies. The nodes come in dependency order, ready to
+/// pass to [`IRGraph::add_node`] one by one.
+pub fn mirror_subgraph(graph: &IRGraph, root: &NodeId, plane: &Plane) -> KernelResult<Vec<IRNode>> {
+    if graph.get_node(root).is_none() {
+        return Err(KernelError::node_not_found(root.as_str()));
+    }
+    let normal = normalize(plane.normal)
+        .ok_or_else(|| KernelError::invalid_parameter("plane", "zero-length normal"))?;
+    let plane = Plane {
+        point: plane.point,
+        normal,
+    };
+
+    let subgraph = graph.subgraph(std::slice::from_ref(root))?;
+    let order = subgraph.evaluation_order()?;
+
+    let ids: HashMap<&NodeId, NodeId> = order
+        .iter()
+        .map(|id| {
+            let node = &subgraph.nodes()[id];
+            let salt = format!(
+                "mirror:{}:{:?}:{:?}",
+                id.as_str(),
+                plane.point,
+                plane.normal
+            );
+            (id, NodeId::new(&node.node_type.to_string(), &salt, &[]))
+        })
+        .collect();
+    let remap = |id: &NodeId| ids.get(id).cloned().unwrap_or_else(|| id.clone());
+
+    order
+        .iter()
+        .map(|id| {
+            let node = &subgraph.nodes()[id];
+            let content = mirror_content(&node.content, &plane, &remap)?;
+            let mut metadata = node.metadata.clone();
+            metadata.source = NodeSource::Derived {
+                from_nodes: vec![id.clone()],
+            };
+
+            Ok(IRNode {
+                id: remap(id),
+                content_hash: ContentHash::from_content(&content)?,
+                node_type: node.node_type.clone(),
+                content,
+                dependencies: node.dependencies.iter().map(&remap).collect(),
+                metadata,
+                suppressed: node.suppressed,
+            })
+        })
+        .collect()
+}
+
+fn mirror_content(
+    content: &NodeContent,
+    plane: &Plane,
+    remap: &impl Fn(&NodeId) -> NodeId,
+) -> KernelResult<NodeContent> {
+    Ok(match content {
+        NodeContent::Primitive {
+            primitive_type,
+            parameters,
+            transform,
+        } => NodeContent::Primitive {
+            primitive_type: primitive_type.clone(),
+            parameters: parameters.clone(),
+            transform: Some(mirror_transform(
+                transform.as_ref().unwrap_or(&Transform::default()),
+                plane,
+            )),
+        },
+        NodeContent::Feature {
+            feature_type,
+            target_node,
+            parameters,
+        } => {
+            let mut parameters = parameters.clone();
+            for name in POINT_PARAMETERS {
+                if let Some(point) = vector_param(&parameters, name)? {
+                    parameters.insert(name.to_string(), serde_json::json!(plane.reflect(point)));
+                }
+            }
+            for name in VECTOR_PARAMETERS {
+                if let Some(vector) = vector_param(&parameters, name)? {
+                    let mirrored = reflect_vector(vector, plane.normal);
+                    parameters.insert(name.to_string(), serde_json::json!(mirrored));
+                }
+            }
+            NodeContent::Feature {
+                feature_type: feature_type.clone(),
+                target_node: remap(target_node),
+                parameters,
+            }
+        }
+        NodeContent::BooleanOp {
+            operation_type,
+            operand_a,
+            operand_b,
+        } => NodeContent::BooleanOp {
+            operation_type: operation_type.clone(),
+            operand_a: remap(operand_a),
+            operand_b: remap(operand_b),
+        },
+        NodeContent::Constraint {
+            constraint_type,
+            affected_nodes,
+            parameters,
+        } => NodeContent::Constraint {
+            constraint_type: constraint_type.clone(),
+            affected_nodes: affected_nodes.iter().map(remap).collect(),
+            parameters: parameters.clone(),
+        },
+        NodeContent::Analysis {
+            analysis_type,
+            target_node,
+            parameters,
+        } => NodeContent::Analysis {
+            analysis_type: analysis_type.clone(),
+            target_node: remap(target_node),
+            parameters: parameters.clone(),
+        },
+    })
+}
+
+/// Reflection of a placement, as a rotation of the (symmetric) primitive
+///
+/// With `H` the reflection and `D` the flip of the local X axis, the
+/// mirrored linear part is `H R S = (H R D) S D`, and `D` maps the
+/// primitive onto itself, so `H R D` is the new rotation.
+fn mirror_transform(transform: &Transform, plane: &Plane) -> Transform {
+    let mut columns = rotation_columns(transform.rotation);
+    columns[0] = scale(columns[0], -1.0);
+    let columns = columns.map(|column| reflect_vector(column, plane.normal));
+
+    Transform {
+        translation: plane.reflect(transform.translation),
+        rotation: quaternion_from_columns(columns),
+        scale: transform.scale,
+    }
+}
+
+fn reflect_vector(v: [f64; 3], normal: [f64; 3]) -> [f64; 3] {
+    sub(v, scale(normal, 2.0 * dot(v, normal)))
+}
+
+/// Columns of the rotation matrix of a unit quaternion `[w, x, y, z]`
+fn rotation_columns([w, x, y, z]: [f64; 4]) -> [[f64; 3]; 3] {
+    [
+        [
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y + w * z),
+            2.0 * (x * z - w * y),
+        ],
+        [
+            2.0 * (x * y - w * z),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z + w * x),
+        ],
+        [
+            2.0 * (x * z + w * y),
+            2.0 * (y * z - w * x),
+            1.0 - 2.0 * (x * x + y * y),
+        ],
+    ]
+}
+
+/// Unit quaternion of a rotation matrix given by columns, with `w >= 0`
+fn quaternion_from_columns(c: [[f64; 3]; 3]) -> [f64; 4] {
+    // m(i, j) is row i, column j
+    let m = |i: usize, j: usize| c[j][i];
+    let trace = m(0, 0) + m(1, 1) + m(2, 2);
+
+    let q = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            s / 4.0,
+            (m(2, 1) - m(1, 2)) / s,
+            (m(0, 2) - m(2, 0)) / s,
+            (m(1, 0) - m(0, 1)) / s,
+        ]
+    } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+        let s = (1.0 + m(0, 0) - m(1, 1) - m(2, 2)).sqrt() * 2.0;
+        [
+            (m(2, 1) - m(1, 2)) / s,
+            s / 4.0,
+            (m(0, 1) + m(1, 0)) / s,
+            (m(0, 2) + m(2, 0)) / s,
+        ]
+    } else if m(1, 1) > m(2, 2) {
+        let s = (1.0 + m(1, 1) - m(0, 0) - m(2, 2)).sqrt() * 2.0;
+        [
+            (m(0, 2) - m(2, 0)) / s,
+            (m(0, 1) + m(1, 0)) / s,
+            s / 4.0,
+            (m(1, 2) + m(2, 1)) / s,
+        ]
+    } else {
+        let s = (1.0 + m(2, 2) - m(0, 0) - m(1, 1)).sqrt() * 2.0;
+        [
+            (m(1, 0) - m(0, 1)) / s,
+            (m(0, 2) + m(2, 0)) / s,
+            (m(1, 2) + m(2, 1)) / s,
+            s / 4.0,
+        ]
+    };
+
+    if q[0] < 0.0 {
+        q.map(|component| -component)
+    } else {
+        q
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::rebuild::{mesh_bounds, rebuild};
+    use crate::geometry::ir::node::NodeMetadata;
+    use crate::geometry::NodeType;
+
+    fn bracket(graph: &mut IRGraph, rotation: [f64; 4]) {
+        let block = IRNode::with_user_id(
+            "block",
+            NodeType::Primitive,
+            NodeContent::Primitive {
+                primitive_type: "box".to_string(),
+                parameters: [("width", 4.0), ("height", 6.0), ("depth", 8.0)]
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect(),
+                transform: Some(Transform {
+                    translation: [10.0, 0.0, 0.0],
+                    rotation,
+                    ..Transform::default()
+                }),
+            },
+            vec![],
+            NodeMetadata::new(Some("block".to_string()), NodeSource::User),
+        )
+        .unwrap();
+        let block_id = block.id.clone();
+
+        let hole = IRNode::with_user_id(
+            "hole",
+            NodeType::Feature,
+            NodeContent::Feature {
+                feature_type: "hole".to_string(),
+                target_node: block_id.clone(),
+                parameters: [
+                    ("diameter", serde_json::json!(1.0)),
+                    ("position", serde_json::json!([11.0, 3.0, 0.0])),
+                    ("direction", serde_json::json!([0.0, -1.0, 0.0])),
+                ]
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+            },
+            vec![block_id],
+            NodeMetadata::new(Some("hole".to_string()), NodeSource::User),
+        )
+        .unwrap();
+
+        graph.add_node(block).unwrap();
+        graph.add_node(hole).unwrap();
+    }
+
+    #[test]
+    fn test_mirror_primitive_and_hole() {
+        let mut graph = IRGraph::new();
+        bracket(&mut graph, [1.0, 0.0, 0.0, 0.0]);
+        let hole_id = NodeId::from_user_string("hole");
+        let plane = Plane {
+            point: [0.0, 0.0, 0.0],
+            normal: [2.0, 0.0, 0.0],
+        };
+
+        let mirrored = mirror_subgraph(&graph, &hole_id, &plane).unwrap();
+        assert_eq!(mirrored.len(), 2);
+        let (block, hole) = (&mirrored[0], &mirrored[1]);
+        assert_eq!(hole.dependencies, vec![block.id.clone()]);
+        assert!(mirrored
+            .iter()
+            .all(|node| graph.get_node(&node.id).is_none()));
+        assert_eq!(
+            mirror_subgraph(&graph, &hole_id, &plane).unwrap()[1].id,
+            hole.id
+        );
+
+        let NodeContent::Feature {
+            target_node,
+            parameters,
+            ..
+        } = &hole.content
+        else {
+            panic!("hole should stay a feature");
+        };
+        assert_eq!(target_node, &block.id);
+        assert_eq!(parameters["position"], serde_json::json!([-11.0, 3.0, 0.0]));
+        assert_eq!(parameters["direction"], serde_json::json!([0.0, -1.0, 0.0]));
+
+        let mirrored_hole = hole.id.clone();
+        for node in mirrored {
+            graph.add_node(node).unwrap();
+        }
+        let original = rebuild(&graph.subgraph(&[hole_id]).unwrap()).unwrap();
+        let copy = rebuild(&graph.subgraph(&[mirrored_hole]).unwrap()).unwrap();
+        let (original, copy) = (
+            mesh_bounds(&original.mesh.unwrap()),
+            mesh_bounds(&copy.mesh.unwrap()),
+        );
+        assert!((copy.min[0] + original.max[0]).abs() < 1e-4);
+        assert!((copy.max[0] + original.min[0]).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_mirror_reverses_rotation() {
+        // An eighth of a turn about Y mirrors across the YZ plane to the
+        // opposite turn
+        let half = std::f64::consts::FRAC_PI_8;
+        let mut graph = IRGraph::new();
+        bracket(&mut graph, [half.cos(), 0.0, half.sin(), 0.0]);
+        let plane = Plane {
+            point: [0.0, 0.0, 0.0],
+            normal: [1.0, 0.0, 0.0],
+        };
+
+        let mirrored = mirror_subgraph(&graph, &NodeId::from_user_string("block"), &plane).unwrap();
+        let NodeContent::Primitive {
+            transform: Some(transform),
+            ..
+        } = &mirrored[0].content
+        else {
+            panic!("block should stay a primitive with a transform");
+        };
+        let expected = [half.cos(), 0.0, -half.sin(), 0.0];
+        for (a, b) in transform.rotation.iter().zip(expected) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert_eq!(transform.translation, [-10.0, 0.0, 0.0]);
+
+        assert!(mirror_subgraph(&graph, &NodeId::from_user_string("missing"), &plane).is_err());
+    }
+}
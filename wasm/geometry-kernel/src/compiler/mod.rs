@@ -7,16 +7,28 @@ pub mod csg_tree;
 pub mod intent_parser;
 pub mod csg_evaluator;
 pub mod csg_compiler;
+pub mod rebuild;
+pub mod explode;
+pub mod mirror;
+pub mod processes;
+pub(crate) mod metrics;
 
 pub use csg_tree::*;
 pub use intent_parser::*;
 pub use csg_evaluator::*;
 pub use csg_compiler::*;
+pub use rebuild::*;
+pub use explode::*;
+pub use mirror::*;
+pub use processes::*;
 
 use crate::types::PreviewMesh;
-use crate::errors::{KernelError, KernelResult};
-use crate::types::{GeometryIR, CompileResult, CompileStatus};
+use crate::errors::KernelResult;
+use crate::types::{GeometryIR, CompileMetrics, CompileResult, CompileStatus};
 use crate::hashing;
+use crate::geometry::ir::{IRValidator, ManufacturingAnalysis};
+use crate::geometry::operations::solids_from_mesh;
+use std::collections::HashMap;
 
 /// Main compiler entry point
 ///
@@ -41,31 +53,59 @@ impl GeometryCompiler {
         if let Some(cached_hash) = &self.cached_hash {
             if cached_hash == &intent_hash {
                 if let Some(result) = &self.cached_result {
+                    let metrics = CompileMetrics {
+                        cache_hits: 1,
+                        total_triangles: result.mesh.as_ref().map_or(0, |m| m.triangle_count()),
+                        ..CompileMetrics::default()
+                    };
                     return Ok(CompileResult {
                         status: CompileStatus::Cached,
+                        metrics: Some(metrics),
                         ..result.clone()
                     });
                 }
             }
         }
 
+        let mut stopwatch = metrics::Stopwatch::start();
+
         // Parse intent to CSG tree
         let mut parser = IntentParser::new();
         let csg_tree = parser.parse(ir)?;
+        let csg_build_ms = stopwatch.lap();
 
         // Evaluate CSG tree to mesh
         let mut evaluator = CsgEvaluator::new();
-        let mesh = evaluator.evaluate(&csg_tree)?;
+        let mesh = evaluator.evaluate(&csg_tree)?.to_preview();
+        let evaluate_ms = stopwatch.lap();
+
+        let solids = solids_from_mesh(&mesh);
+        let counts = evaluator.counts();
+        let metrics = CompileMetrics {
+            nodes_evaluated: counts.nodes_evaluated,
+            cache_hits: counts.cache_hits,
+            cache_misses: counts.cache_misses,
+            total_triangles: mesh.triangle_count(),
+            csg_build_ms,
+            evaluate_ms,
+            mesh_ms: stopwatch.lap(),
+            ..CompileMetrics::default()
+        };
 
         // Compile result
         let result = CompileResult {
             status: CompileStatus::Compiled,
             intent_hash: intent_hash.clone(),
+            solids,
             mesh: Some(mesh),
             step: None, // TODO: Implement STEP export
             topology: None, // TODO: Implement B-rep topology
             mfg_report: None, // TODO: Implement manufacturability validation
             error: None,
+            partial_meshes: HashMap::new(),
+            remaining_nodes: Vec::new(),
+            metrics: Some(metrics),
+            warnings: Vec::new(),
         };
 
         // Update cache
@@ -82,6 +122,18 @@ impl GeometryCompiler {
         Ok(())
     }
 
+    /// Manufacturability report without meshing
+    ///
+    /// The intent is checked as in [`validate`](Self::validate), mapped
+    /// onto an IR graph (see [`intent_graph`]) and run through the
+    /// validator's manufacturing pass. No geometry is evaluated, so this is
+    /// cheap enough to run on every edit.
+    pub fn analyze(&self, ir: &GeometryIR) -> KernelResult<ManufacturingAnalysis> {
+        self.validate(ir)?;
+        let graph = intent_graph(ir)?;
+        IRValidator::new().analyze_manufacturing(&graph)
+    }
+
     /// Clear cache
     pub fn clear_cache(&mut self) {
         self.cached_hash = None;
@@ -94,3 +146,71 @@ impl Default for GeometryCompiler {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::ir::NodeId;
+    use crate::types::{Intent, OperationIntent, OperationType, PrimitiveIntent, PrimitiveType};
+    use std::collections::HashMap;
+
+    fn block(id: &str, width: f64) -> Intent {
+        Intent::Primitive(PrimitiveIntent {
+            id: id.to_string(),
+            type_: PrimitiveType::Box,
+            parameters: [("width", width), ("height", 20.0), ("depth", 10.0)]
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+            transform: None,
+            timestamp: 0.0,
+        })
+    }
+
+    fn operation(id: &str, type_: OperationType, target: &str, operand: Option<&str>) -> Intent {
+        Intent::Operation(OperationIntent {
+            id: id.to_string(),
+            type_,
+            target: target.to_string(),
+            operand: operand.map(str::to_string),
+            parameters: HashMap::new(),
+            timestamp: 0.0,
+        })
+    }
+
+    #[test]
+    fn test_analyze_flags_tiny_feature() {
+        let compiler = GeometryCompiler::new();
+        let clean = GeometryIR {
+            part: "bracket".to_string(),
+            operations: vec![block("body", 40.0)],
+            constraints: vec![],
+        };
+        let clean_report = compiler.analyze(&clean).unwrap();
+        assert!(clean_report.constraint_violations.is_empty());
+
+        // A 0.2 wide pin is below the milling minimum
+        let pinned = GeometryIR {
+            part: "bracket".to_string(),
+            operations: vec![
+                block("body", 40.0),
+                block("pin", 0.2),
+                operation("joined", OperationType::Union, "body", Some("pin")),
+                operation("rounded", OperationType::Fillet, "joined", None),
+            ],
+            constraints: vec![],
+        };
+        let report = compiler.analyze(&pinned).unwrap();
+        assert!(report.manufacturability_score < clean_report.manufacturability_score - 20.0);
+        assert_eq!(report.constraint_violations.len(), 1);
+        let violation = &report.constraint_violations[0];
+        assert_eq!(violation.node_id, NodeId::from_user_string("pin"));
+        assert_eq!(violation.constraint_type, "MinFeatureSize");
+
+        let dangling = GeometryIR {
+            operations: vec![operation("rounded", OperationType::Fillet, "missing", None)],
+            ..pinned
+        };
+        assert!(compiler.analyze(&dangling).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Manufacturing process compatibility of a whole graph.
+//!
+//! Each output solid is classified from its shape and asked for its
+//! compatible processes (see [`Solid::update_compatible_processes`]); the
+//! graph supports only the processes every solid supports, so an assembly
+//! reports what can make all of its parts.
+
+use crate::compiler::rebuild::{evaluate_nodes, output_nodes};
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::analysis::symmetry::surface_principal_axes;
+use crate::geometry::constants;
+use crate::geometry::math::vec3::dot;
+use crate::geometry::operations::solids_from_mesh;
+use crate::geometry::topology::solid::SolidType;
+use crate::geometry::topology::{ShellId, Solid};
+use crate::geometry::{IRGraph, ManufacturingProcess};
+use crate::types::PreviewMesh;
+use std::collections::HashMap;
+
+/// Largest ratio of a solid's thinnest extent to its middle one for it to
+/// count as sheet stock
+pub const SHEET_ASPECT_RATIO: f64 = 0.1;
+
+/// Processes that can make every output solid of a graph
+///
+/// Outputs are chosen as in [`rebuild`](crate::compiler::rebuild) and
+/// split into connected solids. A solid whose thinnest extent along its
+/// principal axes is at most [`SHEET_ASPECT_RATIO`] of its middle extent
+/// is treated as sheet stock, any other as a simple solid. The result keeps
+/// the order of the first solid's processes.
+pub fn compatible_processes(graph: &IRGraph) -> KernelResult<Vec<ManufacturingProcess>> {
+    let order = graph.evaluation_order()?;
+    let nodes = evaluate_nodes(graph, &order, constants::DEFAULT_SUBDIVISIONS)?;
+
+    let solids: Vec<Solid> = output_nodes(graph, &order, &nodes.consumed)
+        .into_iter()
+        .filter_map(|id| nodes.results.get(id).map(|mesh| (id, mesh)))
+        .flat_map(|(id, mesh)| {
+            solids_from_mesh(mesh)
+                .into_iter()
+                .enumerate()
+                .map(move |(index, info)| {
+                    let shell = ShellId::new(format!("{}#{}", id.as_str(), index));
+                    let mut solid = Solid::new(shell, solid_type(&info.mesh));
+                    solid.volume = info.volume;
+                    solid.bounding_box = Some(info.bounding_box);
+                    solid
+                })
+        })
+        .collect();
+
+    let mut solids = solids.into_iter().map(|mut solid| {
+        solid.update_compatible_processes(&HashMap::new());
+        solid.compatible_processes
+    });
+    let mut processes = solids
+        .next()
+        .ok_or_else(|| KernelError::invalid_graph("Graph contains no geometry".to_string()))?;
+    for other in solids {
+        processes.retain(|process| other.contains(process));
+    }
+    Ok(processes)
+}
+
+/// Sheet for thin plates, simple otherwise
+fn solid_type(mesh: &PreviewMesh) -> SolidType {
+    let Some((_, axes)) = surface_principal_axes(mesh) else {
+        return SolidType::Simple;
+    };
+
+    let mut extents = axes.map(|axis| {
+        let projections = mesh
+            .vertices
+            .chunks_exact(3)
+            .map(|v| dot([v[0] as f64, v[1] as f64, v[2] as f64], axis));
+        let (low, high) = projections.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), t| {
+            (lo.min(t), hi.max(t))
+        });
+        high - low
+    });
+    extents.sort_by(f64::total_cmp);
+
+    if extents[0] <= SHEET_ASPECT_RATIO * extents[1] {
+        SolidType::Sheet
+    } else {
+        SolidType::Simple
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::ir::node::{NodeMetadata, NodeSource, Transform};
+    use crate::geometry::{IRNode, NodeContent, NodeType};
+
+    fn block(graph: &mut IRGraph, name: &str, size: [f64; 3], position: [f64; 3]) {
+        let node = IRNode::with_user_id(
+            name,
+            NodeType::Primitive,
+            NodeContent::Primitive {
+                primitive_type: "box".to_string(),
+                parameters: ["width", "height", "depth"]
+                    .into_iter()
+                    .zip(size)
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect(),
+                transform: Some(Transform {
+                    translation: position,
+                    ..Transform::default()
+                }),
+            },
+            vec![],
+            NodeMetadata::new(Some(name.to_string()), NodeSource::User),
+        )
+        .unwrap();
+        graph.add_node(node).unwrap();
+    }
+
+    #[test]
+    fn test_sheet_and_block_share_common_processes() {
+        let mut graph = IRGraph::new();
+        block(&mut graph, "panel", [100.0, 1.5, 60.0], [0.0, 0.0, 0.0]);
+        assert!(compatible_processes(&graph)
+            .unwrap()
+            .contains(&ManufacturingProcess::SheetMetal));
+
+        block(&mut graph, "bracket", [30.0, 30.0, 30.0], [100.0, 0.0, 0.0]);
+        let processes = compatible_processes(&graph).unwrap();
+        assert_eq!(
+            processes,
+            vec![
+                ManufacturingProcess::CNCMilling,
+                ManufacturingProcess::Printing3D
+            ]
+        );
+
+        assert!(compatible_processes(&IRGraph::new()).is_err());
+    }
+}
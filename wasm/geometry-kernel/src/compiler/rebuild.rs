@@ -0,0 +1,884 @@
+//! Parametric rebuild from IR graph history.
+//!
+//! Re-evaluates every node of an `IRGraph` in dependency order, feeding each
+//! feature the result of its target node, so that editing an upstream
+//! parameter propagates through the whole feature history.
+
+use crate::compiler::metrics::Stopwatch;
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::ir::node::Transform as IrTransform;
+use crate::geometry::ir::BlindBottom;
+use crate::geometry::operations::{boolean_operation, solids_from_mesh, BooleanOperation};
+use crate::geometry::primitives::Cylinder;
+use crate::geometry::{
+    apply_transform_to_normal, apply_transform_to_point, compute_face_normal, constants,
+    create_primitive, IRGraph, IRNode, NodeContent, NodeId, Primitive,
+};
+use crate::hashing;
+use crate::types::{
+    BoundingBox, CompileMetrics, CompileResult, CompileStatus, PrecisionMesh, PreviewMesh,
+    PrimitiveType, Transform,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Rebuild the final geometry from a graph's feature history
+///
+/// Nodes are evaluated in topological order. Primitives are tessellated,
+/// features are applied to the mesh of their target node and boolean
+/// operations combine their operands. Constraint and analysis nodes do not
+/// produce geometry and are skipped. The meshes of the graph's marked
+/// outputs (see [`IRGraph::mark_output`]) are unioned into the result; a
+/// graph without marked outputs uses every geometric node that is not
+/// consumed by another node instead.
+///
+/// A suppressed feature passes its target through unchanged; any other
+/// suppressed node produces no geometry. Nodes left without input by a
+/// suppression are skipped too, except that a union keeps its remaining
+/// operand and a subtraction without its tool keeps the base.
+pub fn rebuild(graph: &IRGraph) -> KernelResult<CompileResult> {
+    rebuild_with_subdivisions(graph, constants::DEFAULT_SUBDIVISIONS)
+}
+
+/// Rebuild with an explicit tessellation level
+pub fn rebuild_with_subdivisions(graph: &IRGraph, subdivisions: u32) -> KernelResult<CompileResult> {
+    let mut stopwatch = Stopwatch::start();
+    let order = graph.evaluation_order()?;
+    let csg_build_ms = stopwatch.lap();
+    let NodeMeshes {
+        results,
+        consumed,
+        passed_through,
+    } = evaluate_nodes(graph, &order, subdivisions)?;
+    let evaluate_ms = stopwatch.lap();
+
+    let mut mesh: Option<PreviewMesh> = None;
+    for node_id in output_nodes(graph, &order, &consumed) {
+        if let Some(result) = results.get(node_id) {
+            mesh = Some(match mesh {
+                Some(acc) => boolean_operation(&acc, result, BooleanOperation::Union)?,
+                None => result.clone(),
+            });
+        }
+    }
+
+    let mesh = mesh.ok_or_else(|| KernelError::invalid_graph("Graph contains no geometry".to_string()))?;
+    mesh.is_valid()?;
+    let solids = solids_from_mesh(&mesh);
+
+    // Nothing is cached between rebuilds, so every node is a miss
+    let metrics = CompileMetrics {
+        nodes_evaluated: results.len(),
+        cache_misses: results.len(),
+        total_triangles: mesh.triangle_count(),
+        csg_build_ms,
+        evaluate_ms,
+        mesh_ms: stopwatch.lap(),
+        ..CompileMetrics::default()
+    };
+
+    // Features the preview cannot represent leave the part incomplete
+    let status = if passed_through.is_empty() {
+        CompileStatus::Compiled
+    } else {
+        CompileStatus::Fallback
+    };
+    let warnings = passed_through
+        .iter()
+        .map(|(node_id, feature_type)| {
+            format!(
+                "Skipped {} feature {}: not supported by the preview mesh",
+                feature_type,
+                node_id.as_str()
+            )
+        })
+        .collect();
+
+    Ok(CompileResult {
+        status,
+        intent_hash: history_hash(graph, &order),
+        solids,
+        mesh: Some(mesh),
+        step: None,
+        topology: None,
+        mfg_report: None,
+        error: None,
+        partial_meshes: HashMap::new(),
+        remaining_nodes: Vec::new(),
+        metrics: Some(metrics),
+        warnings,
+    })
+}
+
+/// The graph's marked outputs, or else every node in `order` whose mesh
+/// was not consumed by another node
+pub(crate) fn output_nodes<'a>(
+    graph: &'a IRGraph,
+    order: &'a [NodeId],
+    consumed: &HashSet<NodeId>,
+) -> Vec<&'a NodeId> {
+    if graph.outputs().is_empty() {
+        order.iter().filter(|id| !consumed.contains(*id)).collect()
+    } else {
+        graph.outputs().iter().collect()
+    }
+}
+
+/// Meshes of the nodes that produced geometry, the nodes whose mesh was
+/// consumed as another node's input, and the features (with their type)
+/// that passed their target through because they could not be applied
+pub(crate) struct NodeMeshes {
+    pub results: HashMap<NodeId, PreviewMesh>,
+    pub consumed: HashSet<NodeId>,
+    pub passed_through: Vec<(NodeId, String)>,
+}
+
+/// Evaluate every node in `order`, by the rules described on [`rebuild`]
+pub(crate) fn evaluate_nodes(
+    graph: &IRGraph,
+    order: &[NodeId],
+    subdivisions: u32,
+) -> KernelResult<NodeMeshes> {
+    let mut results: HashMap<NodeId, PreviewMesh> = HashMap::new();
+    let mut consumed: HashSet<NodeId> = HashSet::new();
+    let mut skipped: HashSet<NodeId> = HashSet::new();
+    let mut passed_through: Vec<(NodeId, String)> = Vec::new();
+
+    for node_id in order {
+        let node = graph
+            .get_node(node_id)
+            .ok_or_else(|| KernelError::node_not_found(node_id.as_str()))?;
+
+        if node.suppressed && !matches!(node.content, NodeContent::Feature { .. }) {
+            skipped.insert(node_id.clone());
+            continue;
+        }
+
+        let mesh = match &node.content {
+            NodeContent::Primitive {
+                primitive_type,
+                parameters,
+                transform,
+            } => evaluate_primitive(primitive_type, parameters, transform.as_ref(), subdivisions)?,
+            NodeContent::Feature {
+                feature_type,
+                target_node,
+                parameters,
+            } => {
+                let Some(target) = optional_input(&results, &skipped, node, target_node)? else {
+                    skipped.insert(node_id.clone());
+                    continue;
+                };
+                consumed.insert(target_node.clone());
+                if node.suppressed {
+                    target.clone()
+                } else {
+                    match apply_feature(feature_type, target, parameters, subdivisions)? {
+                        Some(mesh) => mesh,
+                        None => {
+                            passed_through.push((node_id.clone(), feature_type.clone()));
+                            target.clone()
+                        }
+                    }
+                }
+            }
+            NodeContent::BooleanOp {
+                operation_type,
+                operand_a,
+                operand_b,
+            } => {
+                let operation = parse_boolean_operation(operation_type)?;
+                let a = optional_input(&results, &skipped, node, operand_a)?;
+                let b = optional_input(&results, &skipped, node, operand_b)?;
+                consumed.insert(operand_a.clone());
+                consumed.insert(operand_b.clone());
+                match (a, b, operation) {
+                    (Some(a), Some(b), _) => boolean_operation(a, b, operation)?,
+                    (Some(a), None, BooleanOperation::Union | BooleanOperation::Subtract)
+                    | (None, Some(a), BooleanOperation::Union) => a.clone(),
+                    _ => {
+                        skipped.insert(node_id.clone());
+                        continue;
+                    }
+                }
+            }
+            NodeContent::Constraint { .. } | NodeContent::Analysis { .. } => continue,
+        };
+
+        results.insert(node_id.clone(), mesh);
+    }
+
+    Ok(NodeMeshes {
+        results,
+        consumed,
+        passed_through,
+    })
+}
+
+/// Look up the already evaluated mesh for a node input
+fn input_mesh<'a>(
+    results: &'a HashMap<NodeId, PreviewMesh>,
+    node: &IRNode,
+    input: &NodeId,
+) -> KernelResult<&'a PreviewMesh> {
+    results.get(input).ok_or_else(|| {
+        KernelError::invalid_graph(format!(
+            "Node {} references {} which produced no geometry",
+            node.id.as_str(),
+            input.as_str()
+        ))
+    })
+}
+
+/// Input mesh of a node, or `None` if a suppression left it without one
+fn optional_input<'a>(
+    results: &'a HashMap<NodeId, PreviewMesh>,
+    skipped: &HashSet<NodeId>,
+    node: &IRNode,
+    input: &NodeId,
+) -> KernelResult<Option<&'a PreviewMesh>> {
+    if skipped.contains(input) {
+        return Ok(None);
+    }
+    input_mesh(results, node, input).map(Some)
+}
+
+/// Tessellate a primitive node
+fn evaluate_primitive(
+    primitive_type: &str,
+    parameters: &HashMap<String, f64>,
+    transform: Option<&IrTransform>,
+    subdivisions: u32,
+) -> KernelResult<PreviewMesh> {
+    let type_ = match primitive_type {
+        "box" => PrimitiveType::Box,
+        "cylinder" => PrimitiveType::Cylinder,
+        "sphere" => PrimitiveType::Sphere,
+        "cone" => PrimitiveType::Cone,
+        "torus" => PrimitiveType::Torus,
+        other => return Err(KernelError::unknown_primitive(other)),
+    };
+
+    let mut primitive = create_primitive(type_, parameters)?;
+    if let Some(transform) = transform {
+        primitive.apply_transform(&to_euler_transform(transform));
+    }
+
+    Ok(primitive.to_mesh(subdivisions))
+}
+
+/// Apply a feature node to the mesh of its target
+///
+/// Holes are cut with a cylindrical tool. Edge and face treatments that
+/// the preview tessellation cannot represent (fillet, chamfer, draft)
+/// return `None`, so the caller can pass the target through and report
+/// the node instead of claiming the feature was applied.
+fn apply_feature(
+    feature_type: &str,
+    target: &PreviewMesh,
+    parameters: &HashMap<String, serde_json::Value>,
+    subdivisions: u32,
+) -> KernelResult<Option<PreviewMesh>> {
+    match feature_type.to_lowercase().as_str() {
+        "hole" => apply_hole(target, parameters, subdivisions).map(Some),
+        "fillet" | "chamfer" | "draft" => Ok(None),
+        _ => Err(KernelError::unknown_operation(feature_type)),
+    }
+}
+
+/// Cut a cylindrical hole into a mesh
+///
+/// Parameters:
+/// - `diameter` (required)
+/// - `position`: entry point on the surface, defaults to the top center
+/// - `direction`: drilling direction, defaults to `[0, -1, 0]`
+/// - `depth`: defaults to a through hole
+/// - `bottom`: a [`BlindBottom`] for blind holes, defaults to flat; the
+///   depth of a drill-pointed hole is measured to the tip
+fn apply_hole(
+    target: &PreviewMesh,
+    parameters: &HashMap<String, serde_json::Value>,
+    subdivisions: u32,
+) -> KernelResult<PreviewMesh> {
+    let diameter = number_param(parameters, "diameter")?
+        .ok_or_else(|| KernelError::missing_parameter("diameter"))?;
+    if diameter <= 0.0 {
+        return Err(KernelError::invalid_parameter("diameter", diameter.to_string()));
+    }
+
+    let bounds = mesh_bounds(target);
+    let center = bounds.center();
+
+    let position = vector_param(parameters, "position")?
+        .unwrap_or([center[0], bounds.max[1], center[2]]);
+    let direction = vector_param(parameters, "direction")?.unwrap_or([0.0, -1.0, 0.0]);
+
+    let length = (direction[0] * direction[0]
+        + direction[1] * direction[1]
+        + direction[2] * direction[2])
+        .sqrt();
+    if length < constants::EPSILON {
+        return Err(KernelError::invalid_parameter("direction", "zero length"));
+    }
+
+    // Axis of the tool, pointing out of the hole
+    let axis = [
+        -direction[0] / length,
+        -direction[1] / length,
+        -direction[2] / length,
+    ];
+
+    // A through hole spans the target's extent along the axis
+    let size = bounds.size();
+    let extent = axis[0].abs() * size[0] + axis[1].abs() * size[1] + axis[2].abs() * size[2];
+    let blind_depth = match number_param(parameters, "depth")? {
+        Some(depth) if depth > 0.0 => Some(depth),
+        Some(depth) => return Err(KernelError::invalid_parameter("depth", depth.to_string())),
+        None => None,
+    };
+    let bottom = match parameters.get("bottom") {
+        None => BlindBottom::Flat,
+        Some(_) if blind_depth.is_none() => {
+            return Err(KernelError::invalid_parameter(
+                "bottom",
+                "only blind holes have a bottom",
+            ))
+        }
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|_| KernelError::invalid_parameter("bottom", value.to_string()))?,
+    };
+    let depth = blind_depth.unwrap_or(extent);
+
+    // Extend the tool past the entry face, and the exit face of a through
+    // hole, to avoid coplanar cuts
+    let clearance = depth * 0.05;
+    let exit = if blind_depth.is_some() {
+        0.0
+    } else {
+        clearance
+    };
+
+    // Rotate the tool's +Y axis onto the hole axis
+    let rotation = [axis[2].clamp(-1.0, 1.0).asin(), 0.0, (-axis[0]).atan2(axis[1])];
+    let along = |distance: f64| [0, 1, 2].map(|k| position[k] + axis[k] * distance);
+
+    let point_height = bottom.point_height(diameter)?;
+    let tool = if point_height > 0.0 {
+        if point_height >= depth {
+            return Err(KernelError::invalid_parameter(
+                "depth",
+                format!("{} is shorter than the drill point", depth),
+            ));
+        }
+        let transform = Transform {
+            position: Some(along(-depth)),
+            rotation: Some(rotation),
+            scale: None,
+        };
+        let mut tool = drill_point_tool(
+            diameter / 2.0,
+            point_height,
+            depth + clearance,
+            subdivisions,
+        );
+        for (v, n) in tool
+            .vertices
+            .chunks_exact_mut(3)
+            .zip(tool.normals.chunks_exact_mut(3))
+        {
+            v.copy_from_slice(&apply_transform_to_point([v[0], v[1], v[2]], &transform));
+            n.copy_from_slice(&apply_transform_to_normal([n[0], n[1], n[2]], &transform));
+        }
+        tool
+    } else {
+        let tool_length = depth + clearance + exit;
+        let mut tool = Cylinder::new(diameter / 2.0, tool_length);
+        tool.apply_transform(&Transform {
+            position: Some(along(clearance - tool_length / 2.0)),
+            rotation: Some(rotation),
+            scale: None,
+        });
+        tool.to_precision_mesh(subdivisions)
+    };
+
+    boolean_operation(target, &tool.to_preview(), BooleanOperation::Subtract)
+}
+
+/// Drill body along +Y with its tip at the origin: a cone up to
+/// `point_height`, then a cylindrical shank up to `length`
+fn drill_point_tool(
+    radius: f64,
+    point_height: f64,
+    length: f64,
+    subdivisions: u32,
+) -> PrecisionMesh {
+    let segments = subdivisions.max(8);
+    let ring = |y: f64| -> Vec<[f64; 3]> {
+        (0..segments)
+            .map(|i| {
+                let angle = 2.0 * std::f64::consts::PI * i as f64 / segments as f64;
+                [radius * angle.cos(), y, radius * angle.sin()]
+            })
+            .collect()
+    };
+    let (shoulder, top) = (ring(point_height), ring(length));
+    let (tip, top_center) = ([0.0; 3], [0.0, length, 0.0]);
+
+    let mut mesh = PrecisionMesh::new();
+    for i in 0..segments as usize {
+        let j = (i + 1) % segments as usize;
+        let radial = [
+            shoulder[i][0] + shoulder[j][0],
+            0.0,
+            shoulder[i][2] + shoulder[j][2],
+        ];
+        push_facet(&mut mesh, [tip, shoulder[i], shoulder[j]], radial);
+        push_facet(&mut mesh, [shoulder[i], top[i], top[j]], radial);
+        push_facet(&mut mesh, [shoulder[i], top[j], shoulder[j]], radial);
+        push_facet(&mut mesh, [top_center, top[j], top[i]], [0.0, 1.0, 0.0]);
+    }
+    mesh
+}
+
+/// Append a flat-shaded triangle, wound so its normal has a positive
+/// component along `outward`
+fn push_facet(mesh: &mut PrecisionMesh, corners: [[f64; 3]; 3], outward: [f64; 3]) {
+    let [a, mut b, mut c] = corners;
+    let mut normal = compute_face_normal(a, b, c);
+    if normal[0] * outward[0] + normal[1] * outward[1] + normal[2] * outward[2] < 0.0 {
+        std::mem::swap(&mut b, &mut c);
+        normal = normal.map(|n| -n);
+    }
+
+    let base = mesh.vertices.len() as u32 / 3;
+    for corner in [a, b, c] {
+        mesh.vertices.extend_from_slice(&corner);
+        mesh.normals.extend_from_slice(&normal);
+    }
+    mesh.indices.extend_from_slice(&[base, base + 1, base + 2]);
+}
+
+fn parse_boolean_operation(operation_type: &str) -> KernelResult<BooleanOperation> {
+    match operation_type.to_lowercase().as_str() {
+        "union" => Ok(BooleanOperation::Union),
+        "subtract" | "difference" => Ok(BooleanOperation::Subtract),
+        "intersect" | "intersection" => Ok(BooleanOperation::Intersect),
+        _ => Err(KernelError::unknown_operation(operation_type)),
+    }
+}
+
+fn number_param(
+    parameters: &HashMap<String, serde_json::Value>,
+    name: &str,
+) -> KernelResult<Option<f64>> {
+    match parameters.get(name) {
+        None => Ok(None),
+        Some(value) => value
+            .as_f64()
+            .map(Some)
+            .ok_or_else(|| KernelError::invalid_parameter(name, value.to_string())),
+    }
+}
+
+pub(crate) fn vector_param(
+    parameters: &HashMap<String, serde_json::Value>,
+    name: &str,
+) -> KernelResult<Option<[f64; 3]>> {
+    let value = match parameters.get(name) {
+        None => return Ok(None),
+        Some(value) => value,
+    };
+
+    let invalid = || KernelError::invalid_parameter(name, value.to_string());
+    let items = value.as_array().filter(|items| items.len() == 3).ok_or_else(invalid)?;
+
+    let mut vector = [0.0; 3];
+    for (component, item) in vector.iter_mut().zip(items) {
+        *component = item.as_f64().ok_or_else(invalid)?;
+    }
+
+    Ok(Some(vector))
+}
+
+pub(crate) fn mesh_bounds(mesh: &PreviewMesh) -> BoundingBox {
+    mesh.vertices
+        .chunks_exact(3)
+        .fold(BoundingBox::empty(), |acc, v| {
+            let p = [v[0] as f64, v[1] as f64, v[2] as f64];
+            acc.merge(&BoundingBox::new(p, p))
+        })
+}
+
+/// Convert an IR quaternion transform to the Euler form used by primitives
+fn to_euler_transform(transform: &IrTransform) -> Transform {
+    let [w, x, y, z] = transform.rotation;
+
+    let roll = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+    let pitch = (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin();
+    let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+
+    Transform {
+        position: Some(transform.translation),
+        rotation: Some([roll, pitch, yaw]),
+        scale: Some(transform.scale),
+    }
+}
+
+/// Convert an Euler transform from intent IR to the quaternion form of IR nodes
+///
+/// Inverse of [`to_euler_transform`]; missing components are identity.
+pub(crate) fn to_ir_transform(transform: &Transform) -> IrTransform {
+    let [roll, pitch, yaw] = transform.get_rotation().map(|angle| angle / 2.0);
+    let (sr, cr) = roll.sin_cos();
+    let (sp, cp) = pitch.sin_cos();
+    let (sy, cy) = yaw.sin_cos();
+
+    IrTransform {
+        translation: transform.get_position(),
+        rotation: [
+            cr * cp * cy + sr * sp * sy,
+            sr * cp * cy - cr * sp * sy,
+            cr * sp * cy + sr * cp * sy,
+            cr * cp * sy - sr * sp * cy,
+        ],
+        scale: transform.get_scale(),
+    }
+}
+
+/// Hash of the evaluated history, in evaluation order, without suppressed nodes
+fn history_hash(graph: &IRGraph, order: &[NodeId]) -> String {
+    let entries: Vec<(&str, &str)> = order
+        .iter()
+        .filter_map(|id| graph.get_node(id))
+        .filter(|node| !node.suppressed)
+        .map(|node| (node.id.as_str(), node.content_hash.as_hex()))
+        .collect();
+    let outputs: Vec<&str> = graph.outputs().iter().map(|id| id.as_str()).collect();
+
+    hashing::hash_value(&(entries, outputs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::ir::node::{NodeMetadata, NodeSource};
+    use crate::geometry::math::vec3::{cross, length, sub};
+    use crate::geometry::NodeType;
+
+    fn metadata(name: &str) -> NodeMetadata {
+        NodeMetadata::new(Some(name.to_string()), NodeSource::User)
+    }
+
+    fn hole_node(diameter: f64) -> IRNode {
+        let mut parameters = HashMap::new();
+        parameters.insert("diameter".to_string(), serde_json::json!(diameter));
+
+        IRNode::with_user_id(
+            "hole",
+            NodeType::Feature,
+            NodeContent::Feature {
+                feature_type: "hole".to_string(),
+                target_node: NodeId::from_user_string("block"),
+                parameters,
+            },
+            vec![NodeId::from_user_string("block")],
+            metadata("hole"),
+        )
+        .unwrap()
+    }
+
+    fn block_node() -> IRNode {
+        let mut block_params = HashMap::new();
+        block_params.insert("width".to_string(), 10.0);
+        block_params.insert("height".to_string(), 10.0);
+        block_params.insert("depth".to_string(), 10.0);
+
+        IRNode::with_user_id(
+            "block",
+            NodeType::Primitive,
+            NodeContent::Primitive {
+                primitive_type: "box".to_string(),
+                parameters: block_params,
+                transform: None,
+            },
+            vec![],
+            metadata("block"),
+        )
+        .unwrap()
+    }
+
+    fn history_graph(hole_diameter: f64) -> IRGraph {
+        let mut graph = IRGraph::new();
+
+        let mut fillet_params = HashMap::new();
+        fillet_params.insert("radius".to_string(), serde_json::json!(0.5));
+
+        let fillet = IRNode::with_user_id(
+            "fillet",
+            NodeType::Feature,
+            NodeContent::Feature {
+                feature_type: "fillet".to_string(),
+                target_node: NodeId::from_user_string("hole"),
+                parameters: fillet_params,
+            },
+            vec![NodeId::from_user_string("hole")],
+            metadata("fillet"),
+        )
+        .unwrap();
+
+        graph.add_node(block_node()).unwrap();
+        graph.add_node(hole_node(hole_diameter)).unwrap();
+        graph.add_node(fillet).unwrap();
+        graph
+    }
+
+    /// Radii (in the XZ plane) of the hole wall: vertices of sideways
+    /// facing triangles strictly inside the block
+    fn interior_radii(mesh: &PreviewMesh) -> Vec<f64> {
+        let p = |i: u32| {
+            let i = i as usize * 3;
+            [0, 1, 2].map(|k| mesh.vertices[i + k] as f64)
+        };
+        mesh.indices
+            .chunks_exact(3)
+            .map(|t| [p(t[0]), p(t[1]), p(t[2])])
+            .filter(|[a, b, c]| {
+                let normal = cross(sub(*b, *a), sub(*c, *a));
+                normal[1].abs() < 0.5 * length(normal)
+                    && [a, b, c].iter().all(|v| v[0].abs() < 4.9 && v[2].abs() < 4.9)
+            })
+            .flatten()
+            .map(|v| (v[0] * v[0] + v[2] * v[2]).sqrt())
+            .collect()
+    }
+
+    /// Whether a radius lies on the faceted wall of a hole: between the
+    /// chord of a facet and the circle through its corners
+    fn on_wall(r: f64, radius: f64) -> bool {
+        r > radius * 0.99 && r < radius + 1e-4
+    }
+
+    /// Edges not shared by exactly two triangles, matching vertices by
+    /// exact position
+    fn open_edges(mesh: &PreviewMesh) -> usize {
+        let key = |i: u32| {
+            let i = i as usize * 3;
+            [0, 1, 2].map(|k| mesh.vertices[i + k].to_bits())
+        };
+        let mut uses: HashMap<([u32; 3], [u32; 3]), usize> = HashMap::new();
+        for t in mesh.indices.chunks_exact(3) {
+            for k in 0..3 {
+                let (a, b) = (key(t[k]), key(t[(k + 1) % 3]));
+                *uses.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        uses.values().filter(|&&count| count != 2).count()
+    }
+
+    #[test]
+    fn test_rebuild_propagates_hole_diameter() {
+        let mut graph = history_graph(2.0);
+
+        // The fillet cannot be previewed, so it is skipped and reported
+        let first = rebuild(&graph).unwrap();
+        assert_eq!(first.status, CompileStatus::Fallback);
+        assert_eq!(first.warnings.len(), 1);
+        assert!(first.warnings[0].contains("fillet"));
+        let first_mesh = first.mesh.unwrap();
+        let first_radii = interior_radii(&first_mesh);
+        assert!(!first_radii.is_empty());
+        assert!(first_radii.iter().all(|&r| on_wall(r, 1.0)));
+
+        // The hole goes through: the part stays closed and nothing blocks
+        // a ray down its axis
+        assert_eq!(open_edges(&first_mesh), 0);
+        let bvh = crate::geometry::Bvh::build(&first_mesh);
+        assert_eq!(bvh.count_crossings([0.0, 10.0, 0.0], [0.0, -1.0, 0.0]), 0);
+        assert_eq!(bvh.count_crossings([3.0, 10.0, 0.0], [0.0, -1.0, 0.0]), 2);
+
+        graph.update_node(hole_node(4.0)).unwrap();
+
+        let second = rebuild(&graph).unwrap();
+        assert_ne!(first.intent_hash, second.intent_hash);
+        let second_mesh = second.mesh.unwrap();
+        let second_radii = interior_radii(&second_mesh);
+        assert!(!second_radii.is_empty());
+        assert!(second_radii.iter().all(|&r| on_wall(r, 2.0)));
+    }
+
+    #[test]
+    fn test_drill_point_removes_less_than_flat_bottom() {
+        use crate::geometry::primitives::Box as BoxPrimitive;
+
+        let block = BoxPrimitive::new(10.0, 10.0, 10.0).to_mesh(32);
+        let hole = |bottom: Option<serde_json::Value>| {
+            let mut parameters = HashMap::new();
+            parameters.insert("diameter".to_string(), serde_json::json!(2.0));
+            parameters.insert("depth".to_string(), serde_json::json!(5.0));
+            if let Some(bottom) = bottom {
+                parameters.insert("bottom".to_string(), bottom);
+            }
+            apply_hole(&block, &parameters, 32)
+        };
+        let volume = |mesh: &PreviewMesh| -> f64 {
+            let p = |i: u32| {
+                let i = i as usize * 3;
+                [0, 1, 2].map(|k| mesh.vertices[i + k] as f64)
+            };
+            mesh.indices
+                .chunks_exact(3)
+                .map(|t| {
+                    let [a, b, c] = [p(t[0]), p(t[1]), p(t[2])];
+                    (a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+                        + a[2] * (b[0] * c[1] - b[1] * c[0]))
+                        / 6.0
+                })
+                .sum()
+        };
+
+        let flat = hole(None).unwrap();
+        let point = hole(Some(serde_json::json!({ "Conical": { "angle": 118.0 } }))).unwrap();
+
+        // Both cavities share the shank, so they differ by the cylinder
+        // around the point less the point's cone: 2/3 of a prism
+        let point_height = 1.0 / 59f64.to_radians().tan();
+        let polygon_area = 16.0 * (2.0 * std::f64::consts::PI / 32.0).sin();
+        let saved = volume(&point) - volume(&flat);
+        assert!(saved > 0.0);
+        assert!((saved - polygon_area * point_height * 2.0 / 3.0).abs() < 1e-3);
+
+        // The point must fit in the depth, and through holes have no bottom
+        let long_point = serde_json::json!({ "Conical": { "angle": 10.0 } });
+        assert!(hole(Some(long_point)).is_err());
+        let mut through = HashMap::new();
+        through.insert("diameter".to_string(), serde_json::json!(2.0));
+        through.insert("bottom".to_string(), serde_json::json!("Flat"));
+        assert!(apply_hole(&block, &through, 32).is_err());
+    }
+
+    #[test]
+    fn test_rebuild_is_deterministic() {
+        let graph = history_graph(2.0);
+
+        let a = rebuild(&graph).unwrap();
+        let b = rebuild(&graph).unwrap();
+
+        assert_eq!(a.intent_hash, b.intent_hash);
+        assert_eq!(a.mesh.unwrap().vertices, b.mesh.unwrap().vertices);
+    }
+
+    #[test]
+    fn test_euler_transform_roundtrip() {
+        let euler = Transform {
+            position: Some([1.0, -2.0, 3.0]),
+            rotation: Some([0.3, -0.7, 1.2]),
+            scale: None,
+        };
+        let quaternion = to_ir_transform(&euler);
+        let norm: f64 = quaternion.rotation.iter().map(|q| q * q).sum();
+        assert!((norm - 1.0).abs() < 1e-12);
+        assert_eq!(quaternion.scale, [1.0; 3]);
+
+        let back = to_euler_transform(&quaternion);
+        assert_eq!(back.position, euler.position);
+        for (a, b) in back.get_rotation().iter().zip(euler.get_rotation()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_rebuild_reports_metrics() {
+        let mut graph = IRGraph::new();
+        graph.add_node(block_node()).unwrap();
+        graph.add_node(hole_node(2.0)).unwrap();
+
+        let result = rebuild(&graph).unwrap();
+        let metrics = result.metrics.unwrap();
+        assert_eq!(metrics.nodes_evaluated, 2);
+        assert_eq!(metrics.cache_hits, 0);
+        assert!(metrics.total_triangles > 0);
+        assert_eq!(
+            metrics.total_triangles,
+            result.mesh.unwrap().triangle_count()
+        );
+        assert!(metrics.evaluate_ms >= 0.0 && metrics.mesh_ms >= 0.0);
+    }
+
+    #[test]
+    fn test_rebuild_empty_graph() {
+        assert!(rebuild(&IRGraph::new()).is_err());
+    }
+
+    #[test]
+    fn test_marked_output_selects_geometry() {
+        let mut graph = history_graph(2.0);
+        let full = rebuild(&graph).unwrap();
+        assert!(!interior_radii(&full.mesh.unwrap()).is_empty());
+
+        // A second, unrelated leaf
+        let marker = IRNode::with_user_id(
+            "marker",
+            NodeType::Primitive,
+            NodeContent::Primitive {
+                primitive_type: "sphere".to_string(),
+                parameters: [("radius".to_string(), 1.0)].into_iter().collect(),
+                transform: Some(IrTransform {
+                    translation: [50.0, 0.0, 0.0],
+                    ..IrTransform::default()
+                }),
+            },
+            vec![],
+            metadata("marker"),
+        )
+        .unwrap();
+        graph.add_node(marker).unwrap();
+
+        // The intermediate block, before the hole and fillet
+        let block = NodeId::from_user_string("block");
+        graph.mark_output(&block).unwrap();
+        let result = rebuild(&graph).unwrap();
+        let mesh = result.mesh.unwrap();
+        assert!(interior_radii(&mesh).is_empty());
+        let bounds = mesh_bounds(&mesh);
+        assert!((bounds.max[0] - 5.0).abs() < 1e-4);
+        assert_ne!(result.intent_hash, full.intent_hash);
+
+        graph.unmark_output(&block);
+        let unmarked = rebuild(&graph).unwrap();
+        assert!(mesh_bounds(&unmarked.mesh.unwrap()).max[0] > 50.0);
+
+        assert!(graph
+            .mark_output(&NodeId::from_user_string("missing"))
+            .is_err());
+    }
+    #[test]
+    fn test_suppressed_hole_matches_part_without_it() {
+        let mut graph = history_graph(2.0);
+        graph.set_suppressed(&NodeId::from_user_string("hole"), true).unwrap();
+        let suppressed = rebuild(&graph).unwrap();
+
+        // The same block and fillet with no hole in the history at all
+        let mut plain = history_graph(2.0);
+        let mut fillet = plain.get_node(&NodeId::from_user_string("fillet")).unwrap().clone();
+        if let NodeContent::Feature { target_node, .. } = &mut fillet.content {
+            *target_node = NodeId::from_user_string("block");
+        }
+        fillet.dependencies = vec![NodeId::from_user_string("block")];
+        plain.update_node(fillet).unwrap();
+        plain.remove_node(&NodeId::from_user_string("hole")).unwrap();
+        let expected = rebuild(&plain).unwrap();
+
+        let mesh = suppressed.mesh.unwrap();
+        assert_eq!(mesh.vertices, expected.mesh.unwrap().vertices);
+        assert!(interior_radii(&mesh).is_empty());
+
+        // Unsuppressing restores the hole
+        graph.set_suppressed(&NodeId::from_user_string("hole"), false).unwrap();
+        let restored = rebuild(&graph).unwrap();
+        assert_ne!(restored.intent_hash, suppressed.intent_hash);
+        assert!(!interior_radii(&restored.mesh.unwrap()).is_empty());
+
+        // Suppressing the block leaves nothing to build
+        graph.set_suppressed(&NodeId::from_user_string("block"), true).unwrap();
+        assert!(rebuild(&graph).is_err());
+    }
+}
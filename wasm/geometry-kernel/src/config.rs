@@ -0,0 +1,168 @@
+//! Kernel configuration for model units and tolerances.
+//!
+//! Geometric tolerances only make sense relative to the unit a part is
+//! modeled in: a micron is `1e-3` in millimeters but `1e-6` in meters.
+//! `KernelConfig` keeps the unit and the tolerances together so that
+//! compilers and topology builders use epsilons that match the model.
+
+use crate::errors::{KernelError, KernelResult};
+use serde::{Deserialize, Serialize};
+
+/// Physical linear tolerance used for every unit (1 nanometer)
+const LINEAR_TOLERANCE_METERS: f64 = 1e-9;
+
+/// Default angular tolerance in radians
+const ANGULAR_TOLERANCE: f64 = 1e-9;
+
+/// Length unit a model is expressed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthUnit {
+    Millimeter,
+    Centimeter,
+    Meter,
+    Inch,
+}
+
+impl LengthUnit {
+    /// Size of one unit in meters
+    pub fn meters_per_unit(&self) -> f64 {
+        match self {
+            LengthUnit::Millimeter => 1e-3,
+            LengthUnit::Centimeter => 1e-2,
+            LengthUnit::Meter => 1.0,
+            LengthUnit::Inch => 0.0254,
+        }
+    }
+
+    /// Short symbol, for messages
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            LengthUnit::Millimeter => "mm",
+            LengthUnit::Centimeter => "cm",
+            LengthUnit::Meter => "m",
+            LengthUnit::Inch => "in",
+        }
+    }
+
+    /// Parse a unit name or symbol (e.g. "mm", "meter", "in")
+    pub fn parse(name: &str) -> KernelResult<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "mm" | "millimeter" | "millimeters" => Ok(LengthUnit::Millimeter),
+            "cm" | "centimeter" | "centimeters" => Ok(LengthUnit::Centimeter),
+            "m" | "meter" | "meters" => Ok(LengthUnit::Meter),
+            "in" | "inch" | "inches" => Ok(LengthUnit::Inch),
+            _ => Err(KernelError::invalid_parameter("length_unit", name)),
+        }
+    }
+}
+
+/// Triangulation of planar faces that have only a boundary loop
+///
+/// Both policies start from the lowest-index boundary vertex, so a face
+/// always yields the same index array however its edges are listed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FanPolicy {
+    /// Fan from the first boundary vertex; correct only for convex faces
+    #[default]
+    Fan,
+    /// Ear clipping, which also handles non-convex faces
+    EarClip,
+}
+
+/// Units and tolerances shared by the compiler and topology builders
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KernelConfig {
+    /// Unit all model coordinates are expressed in
+    pub length_unit: LengthUnit,
+    /// Distance (in model units) below which points are coincident
+    pub linear_tolerance: f64,
+    /// Angle (in radians) below which directions are parallel
+    pub angular_tolerance: f64,
+    /// How boundary-only planar faces are triangulated
+    #[serde(default)]
+    pub fan_policy: FanPolicy,
+    /// Largest distance (in model units) between a tessellated curved face
+    /// and its surface; `None` keeps uniform subdivisions
+    #[serde(default)]
+    pub max_chord_error: Option<f64>,
+}
+
+impl KernelConfig {
+    /// Configuration for a unit, with the same physical tolerance as the
+    /// millimeter default
+    pub fn for_unit(length_unit: LengthUnit) -> Self {
+        KernelConfig {
+            length_unit,
+            linear_tolerance: LINEAR_TOLERANCE_METERS / length_unit.meters_per_unit(),
+            angular_tolerance: ANGULAR_TOLERANCE,
+            fan_policy: FanPolicy::Fan,
+            max_chord_error: None,
+        }
+    }
+
+    /// Convert a length in millimeters to model units
+    pub fn millimeters_to_model(&self, millimeters: f64) -> f64 {
+        millimeters * 1e-3 / self.length_unit.meters_per_unit()
+    }
+
+    /// Check that tolerances (and the chord error, if set) are positive
+    /// and finite
+    pub fn validate(&self) -> KernelResult<()> {
+        if !self.linear_tolerance.is_finite() || self.linear_tolerance <= 0.0 {
+            return Err(KernelError::invalid_parameter(
+                "linear_tolerance",
+                self.linear_tolerance.to_string(),
+            ));
+        }
+        if !self.angular_tolerance.is_finite() || self.angular_tolerance <= 0.0 {
+            return Err(KernelError::invalid_parameter(
+                "angular_tolerance",
+                self.angular_tolerance.to_string(),
+            ));
+        }
+        if let Some(error) = self.max_chord_error {
+            if !error.is_finite() || error <= 0.0 {
+                return Err(KernelError::invalid_parameter("max_chord_error", error.to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for KernelConfig {
+    fn default() -> Self {
+        Self::for_unit(LengthUnit::Millimeter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tolerance_scales_with_unit() {
+        let mm = KernelConfig::default();
+        let m = KernelConfig::for_unit(LengthUnit::Meter);
+
+        assert_eq!(mm.length_unit, LengthUnit::Millimeter);
+        assert!((mm.linear_tolerance - 1e-6).abs() < 1e-18);
+        assert!((m.linear_tolerance - 1e-9).abs() < 1e-21);
+        assert!((m.millimeters_to_model(250.0) - 0.25).abs() < 1e-12);
+
+        assert_eq!(LengthUnit::parse("M").unwrap(), LengthUnit::Meter);
+        assert!(LengthUnit::parse("furlong").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(KernelConfig::default().validate().is_ok());
+
+        let config = KernelConfig {
+            linear_tolerance: 0.0,
+            ..KernelConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
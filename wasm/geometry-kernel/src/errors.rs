@@ -12,6 +12,8 @@ pub enum ErrorCode {
     InvalidJson,
     #[serde(rename = "INVALID_INTENT")]
     InvalidIntent,
+    #[serde(rename = "EMPTY_INTENT")]
+    EmptyIntent,
     #[serde(rename = "UNKNOWN_PRIMITIVE")]
     UnknownPrimitive,
     #[serde(rename = "UNKNOWN_OPERATION")]
@@ -41,6 +43,7 @@ impl fmt::Display for ErrorCode {
         match self {
             ErrorCode::InvalidJson => write!(f, "INVALID_JSON"),
             ErrorCode::InvalidIntent => write!(f, "INVALID_INTENT"),
+            ErrorCode::EmptyIntent => write!(f, "EMPTY_INTENT"),
             ErrorCode::UnknownPrimitive => write!(f, "UNKNOWN_PRIMITIVE"),
             ErrorCode::UnknownOperation => write!(f, "UNKNOWN_OPERATION"),
             ErrorCode::MissingParameter => write!(f, "MISSING_PARAMETER"),
@@ -151,6 +154,11 @@ impl KernelError {
         KernelError::new(ErrorCode::InvalidIntent, msg)
     }
 
+    pub fn empty_intent() -> Self {
+        KernelError::new(ErrorCode::EmptyIntent, "Intent has no operations")
+            .with_hint("Add at least one primitive before compiling")
+    }
+
     pub fn unknown_primitive(type_: impl Into<String>) -> Self {
         KernelError::new(
             ErrorCode::UnknownPrimitive,
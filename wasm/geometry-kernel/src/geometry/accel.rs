@@ -0,0 +1,679 @@
+//! Bounding volume hierarchy for triangle meshes.
+//!
+//! Ray casting, boolean classification, distance queries and interference
+//! checks all need fast triangle lookups. This module provides a single
+//! acceleration structure that those features share instead of each
+//! building their own.
+
+use crate::geometry::constants;
+use crate::geometry::math::vec3::{add, cross, dot, length_sq, scale, sub};
+use crate::types::{BoundingBox, PrecisionMesh, PreviewMesh};
+
+/// Maximum number of triangles stored in a leaf node
+const LEAF_SIZE: usize = 4;
+
+/// Result of a ray query against a BVH
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// Index of the hit triangle in the source mesh
+    pub triangle: usize,
+    /// Distance along the ray (in units of the direction vector)
+    pub distance: f64,
+    /// Hit point in world coordinates
+    pub point: [f64; 3],
+}
+
+/// Result of a closest-point query against a BVH
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NearestHit {
+    /// Index of the closest triangle in the source mesh
+    pub triangle: usize,
+    /// Euclidean distance from the query point
+    pub distance: f64,
+    /// Closest point on the mesh surface
+    pub point: [f64; 3],
+}
+
+/// Triangle stored in the hierarchy
+#[derive(Debug, Clone)]
+struct BvhTriangle {
+    /// Index of the triangle in the source mesh
+    index: usize,
+    /// Corner positions
+    vertices: [[f64; 3]; 3],
+    /// Bounding box of the triangle
+    bbox: BoundingBox,
+    /// Centroid used for splitting
+    centroid: [f64; 3],
+}
+
+/// Node in the flattened hierarchy
+#[derive(Debug, Clone)]
+struct BvhNode {
+    /// Bounds of everything below this node
+    bbox: BoundingBox,
+    /// Child node indices for interior nodes
+    children: Option<(usize, usize)>,
+    /// Range into the triangle array for leaf nodes
+    start: usize,
+    count: usize,
+}
+
+/// Triangle bounding volume hierarchy
+///
+/// Built once from a `PreviewMesh` and queried many times. The hierarchy
+/// keeps its own copy of triangle positions, so it stays valid after the
+/// source mesh is dropped, but it must be rebuilt when the mesh changes.
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    triangles: Vec<BvhTriangle>,
+}
+
+impl Bvh {
+    /// Build a hierarchy over all triangles of a mesh
+    ///
+    /// Triangles referencing out-of-range vertices are skipped.
+    pub fn build(mesh: &PreviewMesh) -> Self {
+        Self::from_triangles(mesh.indices.chunks_exact(3), mesh.vertex_count(), |i| {
+            vertex_at(mesh, i as usize)
+        })
+    }
+
+    /// Build a hierarchy over the f64 triangles of a precision mesh
+    pub fn build_precise(mesh: &PrecisionMesh) -> Self {
+        let vertex_at = |i: u32| {
+            let i = i as usize * 3;
+            [mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2]]
+        };
+        Self::from_triangles(mesh.indices.chunks_exact(3), mesh.vertex_count(), vertex_at)
+    }
+
+    /// Hierarchy over the triangles of an index buffer, reading corner
+    /// positions through `vertex_at`
+    fn from_triangles<'a>(
+        indices: impl Iterator<Item = &'a [u32]>,
+        vertex_count: usize,
+        vertex_at: impl Fn(u32) -> [f64; 3],
+    ) -> Self {
+        let mut triangles = Vec::new();
+
+        for (index, tri) in indices.enumerate() {
+            if tri.iter().any(|&i| i as usize >= vertex_count) {
+                continue;
+            }
+
+            let vertices = [vertex_at(tri[0]), vertex_at(tri[1]), vertex_at(tri[2])];
+            let bbox = triangle_bbox(&vertices);
+
+            triangles.push(BvhTriangle {
+                index,
+                vertices,
+                bbox,
+                centroid: bbox.center(),
+            });
+        }
+
+        let mut bvh = Bvh {
+            nodes: Vec::new(),
+            triangles,
+        };
+
+        if !bvh.triangles.is_empty() {
+            let count = bvh.triangles.len();
+            bvh.build_node(0, count);
+        }
+
+        bvh
+    }
+
+    /// Number of triangles in the hierarchy
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Check if the hierarchy contains no triangles
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Bounds of the whole mesh
+    pub fn bounds(&self) -> BoundingBox {
+        self.nodes
+            .first()
+            .map(|node| node.bbox)
+            .unwrap_or_else(BoundingBox::empty)
+    }
+
+    /// Find the closest triangle hit by a ray
+    ///
+    /// Only hits in front of the origin (`distance >= 0`) are reported.
+    /// Returns `None` if the ray misses the mesh.
+    pub fn raycast(&self, origin: [f64; 3], direction: [f64; 3]) -> Option<RayHit> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let inv_dir = [1.0 / direction[0], 1.0 / direction[1], 1.0 / direction[2]];
+        let mut best: Option<RayHit> = None;
+        let mut stack = vec![0usize];
+
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            let limit = best.map_or(f64::INFINITY, |hit| hit.distance);
+
+            match ray_box_entry(origin, inv_dir, &node.bbox) {
+                Some(entry) if entry <= limit => {}
+                _ => continue,
+            }
+
+            match node.children {
+                Some((left, right)) => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                None => {
+                    for tri in &self.triangles[node.start..node.start + node.count] {
+                        if let Some(t) = ray_triangle(origin, direction, &tri.vertices) {
+                            if best.is_none_or(|hit| t < hit.distance) {
+                                best = Some(RayHit {
+                                    triangle: tri.index,
+                                    distance: t,
+                                    point: add(origin, scale(direction, t)),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Find the closest point on the mesh surface to a query point
+    ///
+    /// Returns `None` only if the hierarchy is empty.
+    pub fn nearest(&self, point: [f64; 3]) -> Option<NearestHit> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<NearestHit> = None;
+        let mut best_sq = f64::INFINITY;
+        let mut stack = vec![0usize];
+
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if box_distance_sq(point, &node.bbox) > best_sq {
+                continue;
+            }
+
+            match node.children {
+                Some((left, right)) => {
+                    // Visit the nearer child first so pruning kicks in sooner
+                    let dl = box_distance_sq(point, &self.nodes[left].bbox);
+                    let dr = box_distance_sq(point, &self.nodes[right].bbox);
+                    if dl < dr {
+                        stack.push(right);
+                        stack.push(left);
+                    } else {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                }
+                None => {
+                    for tri in &self.triangles[node.start..node.start + node.count] {
+                        let closest = closest_point_on_triangle(point, &tri.vertices);
+                        let d_sq = length_sq(sub(closest, point));
+                        if d_sq < best_sq {
+                            best_sq = d_sq;
+                            best = Some(NearestHit {
+                                triangle: tri.index,
+                                distance: d_sq.sqrt(),
+                                point: closest,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Collect triangles whose bounds overlap a box
+    ///
+    /// This is a conservative broad-phase query: every triangle that touches
+    /// the box is returned, along with some whose bounds overlap but whose
+    /// surface does not. Indices refer to the source mesh and are sorted.
+    pub fn triangles_in_box(&self, bbox: &BoundingBox) -> Vec<usize> {
+        let mut result = Vec::new();
+        if self.nodes.is_empty() || bbox.is_empty() {
+            return result;
+        }
+
+        let mut stack = vec![0usize];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if !node.bbox.intersects(bbox) {
+                continue;
+            }
+
+            match node.children {
+                Some((left, right)) => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                None => {
+                    for tri in &self.triangles[node.start..node.start + node.count] {
+                        if tri.bbox.intersects(bbox) {
+                            result.push(tri.index);
+                        }
+                    }
+                }
+            }
+        }
+
+        result.sort_unstable();
+        result
+    }
+
+    /// Count triangles crossed by a ray
+    pub fn count_crossings(&self, origin: [f64; 3], direction: [f64; 3]) -> usize {
+        if self.nodes.is_empty() {
+            return 0;
+        }
+
+        let inv_dir = [1.0 / direction[0], 1.0 / direction[1], 1.0 / direction[2]];
+        let mut crossings = 0;
+        let mut stack = vec![0usize];
+
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if ray_box_entry(origin, inv_dir, &node.bbox).is_none() {
+                continue;
+            }
+
+            match node.children {
+                Some((left, right)) => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                None => {
+                    crossings += self.triangles[node.start..node.start + node.count]
+                        .iter()
+                        .filter(|tri| ray_triangle(origin, direction, &tri.vertices).is_some())
+                        .count();
+                }
+            }
+        }
+
+        crossings
+    }
+
+    /// Test whether a point lies inside a closed mesh
+    ///
+    /// Uses crossing parity along three skewed rays and takes the majority,
+    /// so a ray grazing an edge or vertex does not flip the result. The
+    /// answer is only meaningful for watertight meshes.
+    pub fn contains_point(&self, point: [f64; 3]) -> bool {
+        if self.nodes.is_empty() || !self.bounds().contains(point) {
+            return false;
+        }
+
+        const DIRECTIONS: [[f64; 3]; 3] = [
+            [0.5773, 0.5774, 0.5775],
+            [-0.6143, 0.3517, 0.7061],
+            [0.2311, -0.8826, 0.4093],
+        ];
+
+        let inside_votes = DIRECTIONS
+            .iter()
+            .filter(|&&direction| self.count_crossings(point, direction) % 2 == 1)
+            .count();
+
+        inside_votes >= 2
+    }
+
+    /// Recursively build the node covering `triangles[start..start + count]`
+    fn build_node(&mut self, start: usize, count: usize) -> usize {
+        let bbox = self.triangles[start..start + count]
+            .iter()
+            .fold(BoundingBox::empty(), |acc, tri| acc.merge(&tri.bbox));
+
+        let node_index = self.nodes.len();
+        self.nodes.push(BvhNode {
+            bbox,
+            children: None,
+            start,
+            count,
+        });
+
+        if count <= LEAF_SIZE {
+            return node_index;
+        }
+
+        // Split at the median centroid along the longest axis
+        let size = bbox.size();
+        let axis = if size[0] >= size[1] && size[0] >= size[2] {
+            0
+        } else if size[1] >= size[2] {
+            1
+        } else {
+            2
+        };
+
+        let mid = count / 2;
+        self.triangles[start..start + count].select_nth_unstable_by(mid, |a, b| {
+            a.centroid[axis]
+                .partial_cmp(&b.centroid[axis])
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.index.cmp(&b.index))
+        });
+
+        let left = self.build_node(start, mid);
+        let right = self.build_node(start + mid, count - mid);
+        self.nodes[node_index].children = Some((left, right));
+
+        node_index
+    }
+}
+
+// Geometric helpers
+
+fn vertex_at(mesh: &PreviewMesh, index: usize) -> [f64; 3] {
+    [
+        mesh.vertices[index * 3] as f64,
+        mesh.vertices[index * 3 + 1] as f64,
+        mesh.vertices[index * 3 + 2] as f64,
+    ]
+}
+
+fn triangle_bbox(vertices: &[[f64; 3]; 3]) -> BoundingBox {
+    let mut min = vertices[0];
+    let mut max = vertices[0];
+    for v in &vertices[1..] {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(v[axis]);
+            max[axis] = max[axis].max(v[axis]);
+        }
+    }
+    BoundingBox::new(min, max)
+}
+
+/// Slab test returning the entry distance of a ray into a box
+fn ray_box_entry(origin: [f64; 3], inv_dir: [f64; 3], bbox: &BoundingBox) -> Option<f64> {
+    let mut t_min = 0.0f64;
+    let mut t_max = f64::INFINITY;
+
+    for axis in 0..3 {
+        if inv_dir[axis].is_infinite() {
+            // Ray parallel to this slab: it must start inside it
+            if origin[axis] < bbox.min[axis] || origin[axis] > bbox.max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let t1 = (bbox.min[axis] - origin[axis]) * inv_dir[axis];
+        let t2 = (bbox.max[axis] - origin[axis]) * inv_dir[axis];
+        t_min = t_min.max(t1.min(t2));
+        t_max = t_max.min(t1.max(t2));
+    }
+
+    if t_min <= t_max {
+        Some(t_min)
+    } else {
+        None
+    }
+}
+
+/// Möller–Trumbore ray/triangle intersection
+fn ray_triangle(origin: [f64; 3], direction: [f64; 3], tri: &[[f64; 3]; 3]) -> Option<f64> {
+    let edge1 = sub(tri[1], tri[0]);
+    let edge2 = sub(tri[2], tri[0]);
+    let p = cross(direction, edge2);
+    let det = dot(edge1, p);
+
+    if det.abs() < constants::EPSILON {
+        return None; // Ray parallel to triangle
+    }
+
+    let inv_det = 1.0 / det;
+    let s = sub(origin, tri[0]);
+    let u = dot(s, p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = cross(s, edge1);
+    let v = dot(direction, q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = dot(edge2, q) * inv_det;
+    if t >= 0.0 {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+/// Squared distance from a point to a box (zero inside)
+fn box_distance_sq(point: [f64; 3], bbox: &BoundingBox) -> f64 {
+    let mut d_sq = 0.0;
+    for (axis, &v) in point.iter().enumerate() {
+        if v < bbox.min[axis] {
+            d_sq += (bbox.min[axis] - v).powi(2);
+        } else if v > bbox.max[axis] {
+            d_sq += (v - bbox.max[axis]).powi(2);
+        }
+    }
+    d_sq
+}
+
+/// Closest point on a triangle to a query point (Ericson, Real-Time Collision Detection)
+fn closest_point_on_triangle(p: [f64; 3], tri: &[[f64; 3]; 3]) -> [f64; 3] {
+    let [a, b, c] = *tri;
+    let ab = sub(b, a);
+    let ac = sub(c, a);
+    let ap = sub(p, a);
+
+    let d1 = dot(ab, ap);
+    let d2 = dot(ac, ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = sub(p, b);
+    let d3 = dot(ab, bp);
+    let d4 = dot(ac, bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let v = d1 / (d1 - d3);
+        return add(a, scale(ab, v));
+    }
+
+    let cp = sub(p, c);
+    let d5 = dot(ab, cp);
+    let d6 = dot(ac, cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let w = d2 / (d2 - d6);
+        return add(a, scale(ac, w));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return add(b, scale(sub(c, b), w));
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    add(a, add(scale(ab, v), scale(ac, w)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::primitives::Sphere;
+    use crate::geometry::Primitive;
+
+    fn test_mesh() -> PreviewMesh {
+        Sphere::new(5.0).to_mesh(48)
+    }
+
+    fn brute_force_raycast(
+        mesh: &PreviewMesh,
+        origin: [f64; 3],
+        direction: [f64; 3],
+    ) -> Option<f64> {
+        mesh.indices
+            .chunks_exact(3)
+            .filter_map(|tri| {
+                let vertices = [
+                    vertex_at(mesh, tri[0] as usize),
+                    vertex_at(mesh, tri[1] as usize),
+                    vertex_at(mesh, tri[2] as usize),
+                ];
+                ray_triangle(origin, direction, &vertices)
+            })
+            .fold(None, |best: Option<f64>, t| {
+                Some(best.map_or(t, |b| b.min(t)))
+            })
+    }
+
+    #[test]
+    fn test_raycast_matches_brute_force() {
+        let mesh = test_mesh();
+        assert!(mesh.triangle_count() > 1000);
+        let bvh = Bvh::build(&mesh);
+        assert_eq!(bvh.triangle_count(), mesh.triangle_count());
+
+        let mut checked_hits = 0;
+        for i in 0..200 {
+            let a = i as f64 * 0.37;
+            let b = i as f64 * 0.11;
+            let origin = [12.0 * a.cos(), 7.0 * b.sin(), 12.0 * a.sin()];
+            let target = [2.0 * b.cos(), 3.0 * a.sin(), 2.0 * b.sin()];
+            let direction = sub(target, origin);
+
+            let expected = brute_force_raycast(&mesh, origin, direction);
+            let actual = bvh.raycast(origin, direction);
+
+            match (expected, actual) {
+                (Some(t), Some(hit)) => {
+                    assert!((t - hit.distance).abs() < 1e-9, "ray {} distance mismatch", i);
+                    checked_hits += 1;
+                }
+                (None, None) => {}
+                _ => panic!("ray {} disagrees with brute force", i),
+            }
+        }
+
+        assert!(checked_hits > 100);
+
+        // Ray pointing away from the mesh
+        assert!(bvh.raycast([20.0, 0.0, 0.0], [1.0, 0.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn test_nearest_matches_brute_force() {
+        let mesh = test_mesh();
+        let bvh = Bvh::build(&mesh);
+
+        for i in 0..50 {
+            let a = i as f64 * 0.53;
+            let point = [8.0 * a.cos(), (i as f64 - 25.0) * 0.3, 8.0 * a.sin()];
+
+            let expected = mesh
+                .indices
+                .chunks_exact(3)
+                .map(|tri| {
+                    let vertices = [
+                        vertex_at(&mesh, tri[0] as usize),
+                        vertex_at(&mesh, tri[1] as usize),
+                        vertex_at(&mesh, tri[2] as usize),
+                    ];
+                    length_sq(sub(closest_point_on_triangle(point, &vertices), point)).sqrt()
+                })
+                .fold(f64::INFINITY, f64::min);
+
+            let hit = bvh.nearest(point).unwrap();
+            assert!((hit.distance - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_triangles_in_box() {
+        let mesh = test_mesh();
+        let bvh = Bvh::build(&mesh);
+        let query = BoundingBox::new([0.0, 0.0, 0.0], [5.0, 5.0, 5.0]);
+
+        let expected: Vec<usize> = mesh
+            .indices
+            .chunks_exact(3)
+            .enumerate()
+            .filter(|(_, tri)| {
+                let vertices = [
+                    vertex_at(&mesh, tri[0] as usize),
+                    vertex_at(&mesh, tri[1] as usize),
+                    vertex_at(&mesh, tri[2] as usize),
+                ];
+                triangle_bbox(&vertices).intersects(&query)
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        assert!(!expected.is_empty());
+        assert_eq!(bvh.triangles_in_box(&query), expected);
+    }
+
+    #[test]
+    fn test_empty_mesh() {
+        let bvh = Bvh::build(&PreviewMesh::new());
+        assert!(bvh.is_empty());
+        assert!(bvh.raycast([0.0; 3], [1.0, 0.0, 0.0]).is_none());
+        assert!(bvh.nearest([0.0; 3]).is_none());
+        assert!(bvh.bounds().is_empty());
+    }
+
+    #[test]
+    fn test_cached_bvh_on_mesh() {
+        let mut mesh = test_mesh();
+        assert!(mesh.bvh().is_none());
+
+        let count = mesh.build_bvh().triangle_count();
+        assert_eq!(count, mesh.triangle_count());
+
+        // Clones share the cached hierarchy
+        let cloned = mesh.clone();
+        assert!(cloned.bvh().is_some());
+
+        mesh.clear_bvh();
+        assert!(mesh.bvh().is_none());
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let bvh = Bvh::build(&test_mesh());
+
+        assert!(bvh.contains_point([0.0, 0.0, 0.0]));
+        assert!(bvh.contains_point([2.0, -3.0, 1.0]));
+        assert!(!bvh.contains_point([4.0, 4.0, 0.0]));
+        assert!(!bvh.contains_point([10.0, 0.0, 0.0]));
+        assert!(!Bvh::build(&PreviewMesh::new()).contains_point([0.0, 0.0, 0.0]));
+    }
+}
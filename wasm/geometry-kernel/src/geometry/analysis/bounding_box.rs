@@ -2,6 +2,7 @@
 //!
 //! Efficient AABB operations for early rejection in CSG operations.
 
+use crate::errors::{KernelError, KernelResult};
 use crate::types::BoundingBox;
 use crate::types::PreviewMesh;
 use crate::geometry::constants;
@@ -34,6 +35,40 @@ pub fn compute_bounding_box(mesh: &PreviewMesh) -> BoundingBox {
     bbox
 }
 
+/// Bounding box of a mesh in its own frame, measured once
+///
+/// Interactive edits move a mesh far more often than they change it, so
+/// the bounds under a new transform come from the 8 cached corners
+/// instead of every vertex. For rotations the result contains the exact
+/// bounds but may be larger, as with [`transform_bbox`].
+#[derive(Debug, Clone, Copy)]
+pub struct LocalBounds {
+    bounds: BoundingBox,
+}
+
+impl LocalBounds {
+    /// Measure a mesh's untransformed bounds
+    pub fn new(mesh: &PreviewMesh) -> Self {
+        LocalBounds {
+            bounds: compute_bounding_box(mesh),
+        }
+    }
+
+    /// Untransformed bounds
+    pub fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    /// Bounds of the mesh after `transform`; see [`transform_bbox`] for
+    /// the errors
+    pub fn transformed_bounds(
+        &self,
+        transform: &crate::types::Transform,
+    ) -> KernelResult<BoundingBox> {
+        transform_bbox(&self.bounds, transform)
+    }
+}
+
 /// Test if point is inside bounding box
 pub fn point_in_bbox(point: [f64; 3], bbox: &BoundingBox) -> bool {
     bbox.contains(point)
@@ -44,17 +79,36 @@ pub fn bboxes_intersect(bbox1: &BoundingBox, bbox2: &BoundingBox) -> bool {
     bbox1.intersects(bbox2)
 }
 
-/// Merge multiple bounding boxes
-pub fn merge_bboxes(bboxes: &[BoundingBox]) -> BoundingBox {
-    if bboxes.is_empty() {
-        return BoundingBox::empty();
+/// Fail on a box with a NaN or infinite coordinate
+///
+/// Empty boxes are marked by finite sentinels (see [`BoundingBox::empty`]),
+/// so a non-finite coordinate always means corrupt geometry upstream.
+fn check_finite(bbox: &BoundingBox) -> KernelResult<()> {
+    if bbox.min.iter().chain(&bbox.max).all(|c| c.is_finite()) {
+        Ok(())
+    } else {
+        Err(KernelError::invalid_parameter(
+            "bounding_box",
+            format!("non-finite bounds {:?} to {:?}", bbox.min, bbox.max),
+        ))
     }
+}
 
-    let mut result = bboxes[0];
-    for bbox in &bboxes[1..] {
-        result = result.merge(bbox);
+/// Merge multiple bounding boxes
+///
+/// Empty boxes contribute nothing, and merging none gives an empty box. A
+/// box with a NaN or infinite coordinate is an error rather than being
+/// merged or skipped, so one corrupt part cannot spread into the bounds of
+/// a whole assembly.
+pub fn merge_bboxes(bboxes: &[BoundingBox]) -> KernelResult<BoundingBox> {
+    let mut result = BoundingBox::empty();
+    for bbox in bboxes {
+        check_finite(bbox)?;
+        if !bbox.is_empty() {
+            result = result.merge(bbox);
+        }
     }
-    result
+    Ok(result)
 }
 
 /// Expand bounding box by epsilon
@@ -108,17 +162,24 @@ pub fn bbox_surface_area(bbox: &BoundingBox) -> f64 {
 }
 
 /// Transform bounding box by transform matrix
-pub fn transform_bbox(bbox: &BoundingBox, transform: &crate::types::Transform) -> BoundingBox {
+///
+/// An empty box stays empty. Non-finite input bounds, or a transform that
+/// takes the corners to NaN or infinity, are errors.
+pub fn transform_bbox(
+    bbox: &BoundingBox,
+    transform: &crate::types::Transform,
+) -> KernelResult<BoundingBox> {
+    check_finite(bbox)?;
+    if bbox.is_empty() {
+        return Ok(BoundingBox::empty());
+    }
+
     let corners = get_bbox_corners(bbox);
     let transformed: Vec<[f64; 3]> = corners
         .iter()
         .map(|c| crate::geometry::apply_transform_to_point(*c, transform))
         .collect();
 
-    if transformed.is_empty() {
-        return BoundingBox::empty();
-    }
-
     let mut result = BoundingBox::new(
         [f64::MAX, f64::MAX, f64::MAX],
         [f64::MIN, f64::MIN, f64::MIN],
@@ -134,18 +195,25 @@ pub fn transform_bbox(bbox: &BoundingBox, transform: &crate::types::Transform) -
         result.max[2] = result.max[2].max(point[2]);
     }
 
-    result
+    // min/max skip NaN, so check the corners rather than the result
+    if transformed.iter().flatten().any(|c| !c.is_finite()) {
+        return Err(KernelError::invalid_parameter(
+            "transform",
+            format!("maps {:?} to {:?} to non-finite bounds", bbox.min, bbox.max),
+        ));
+    }
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::geometry::primitives::{Box as BoxPrimitive, Primitive};
+    use crate::geometry::{primitives::Box as BoxPrimitive, Primitive};
 
     #[test]
     fn test_compute_bounding_box() {
         let mesh = BoxPrimitive::new(1.0, 2.0, 3.0).to_mesh(4);
-        let bbox = compute_bounding_box(&mesh).unwrap();
+        let bbox = compute_bounding_box(&mesh);
 
         assert!(!bbox.is_empty());
 
@@ -176,4 +244,57 @@ mod tests {
         assert!((expanded.min[0] - (-0.1)).abs() < constants::EPSILON);
         assert!((expanded.max[0] - 1.1).abs() < constants::EPSILON);
     }
+
+    #[test]
+    fn test_transformed_bounds_match_recompute() {
+        let mesh = BoxPrimitive::new(1.0, 2.0, 3.0).to_mesh(4);
+        let local = LocalBounds::new(&mesh);
+        let transform = crate::types::Transform {
+            position: Some([5.0, -1.0, 2.0]),
+            rotation: Some([0.3, 0.7, -1.1]),
+            scale: Some([1.0, 2.0, 0.5]),
+        };
+
+        let mut moved = mesh.clone();
+        for v in moved.vertices.chunks_exact_mut(3) {
+            let p = [v[0] as f64, v[1] as f64, v[2] as f64];
+            let p = crate::geometry::apply_transform_to_point(p, &transform);
+            v.copy_from_slice(&p.map(|c| c as f32));
+        }
+        let expected = compute_bounding_box(&moved);
+
+        let bounds = local.transformed_bounds(&transform).unwrap();
+        for k in 0..3 {
+            assert!((bounds.min[k] - expected.min[k]).abs() < 1e-5);
+            assert!((bounds.max[k] - expected.max[k]).abs() < 1e-5);
+        }
+
+        let empty = LocalBounds::new(&PreviewMesh::new());
+        assert!(empty.transformed_bounds(&transform).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_non_finite_bounds_are_rejected() {
+        let unit = BoundingBox::new([0.0; 3], [1.0; 3]);
+        let far = BoundingBox::new([2.0; 3], [3.0; 3]);
+        let merged = merge_bboxes(&[unit, BoundingBox::empty(), far]).unwrap();
+        assert_eq!((merged.min, merged.max), ([0.0; 3], [3.0; 3]));
+        assert!(merge_bboxes(&[]).unwrap().is_empty());
+
+        let infinite = BoundingBox::new([f64::NEG_INFINITY; 3], [f64::INFINITY; 3]);
+        assert!(merge_bboxes(&[unit, infinite]).is_err());
+        let nan = BoundingBox::new([0.0; 3], [f64::NAN, 1.0, 1.0]);
+        assert!(merge_bboxes(&[nan, unit]).is_err());
+
+        let identity = crate::types::Transform::identity();
+        assert!(transform_bbox(&infinite, &identity).is_err());
+        assert!(transform_bbox(&BoundingBox::empty(), &identity)
+            .unwrap()
+            .is_empty());
+        let overflow = crate::types::Transform {
+            scale: Some([f64::MAX; 3]),
+            ..crate::types::Transform::identity()
+        };
+        assert!(transform_bbox(&far, &overflow).is_err());
+    }
 }
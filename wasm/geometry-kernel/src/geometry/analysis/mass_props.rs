@@ -144,6 +144,16 @@ impl MaterialProperties {
         }
     }
 
+    /// Look up a common material by name (e.g. "aluminum", "steel", "abs")
+    pub fn by_name(name: &str) -> KernelResult<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "aluminum" | "aluminium" => Ok(Self::aluminum()),
+            "steel" => Ok(Self::steel()),
+            "abs" | "plastic_abs" | "abs plastic" => Ok(Self::plastic_abs()),
+            _ => Err(KernelError::invalid_parameter("material", name)),
+        }
+    }
+
     pub fn custom(density: f64, name: String) -> Self {
         MaterialProperties {
             density,
@@ -559,6 +569,8 @@ mod tests {
             vertices,
             indices,
             normals,
+            face_map: Vec::new(),
+            bvh: None,
         }
     }
 
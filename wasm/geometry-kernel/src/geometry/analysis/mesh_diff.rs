@@ -0,0 +1,127 @@
+//! Tolerance-aware comparison of triangle meshes.
+//!
+//! Meshes are compared by surface distance rather than by vertex or index
+//! equality, so a re-triangulated copy of the same shape compares equal.
+//! Distances are sampled at the corners, edge midpoints and centroid of
+//! every triangle and measured to the closest point on the other mesh.
+
+use crate::geometry::accel::Bvh;
+use crate::types::PreviewMesh;
+use serde::{Deserialize, Serialize};
+
+/// Surface distance between two meshes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MeshDiff {
+    /// Largest distance from the first mesh to the second
+    pub forward_distance: f64,
+    /// Largest distance from the second mesh to the first
+    pub backward_distance: f64,
+    /// Symmetric Hausdorff distance (the larger of the two directions)
+    pub hausdorff_distance: f64,
+    /// Whether the Hausdorff distance is within the requested tolerance
+    pub within_tolerance: bool,
+}
+
+/// Compare two meshes by approximate Hausdorff distance
+///
+/// Two empty meshes are identical; an empty mesh is infinitely far from a
+/// non-empty one.
+pub fn mesh_diff(a: &PreviewMesh, b: &PreviewMesh, tolerance: f64) -> MeshDiff {
+    let forward_distance = directed_distance(a, b);
+    let backward_distance = directed_distance(b, a);
+    let hausdorff_distance = forward_distance.max(backward_distance);
+
+    MeshDiff {
+        forward_distance,
+        backward_distance,
+        hausdorff_distance,
+        within_tolerance: hausdorff_distance <= tolerance,
+    }
+}
+
+/// Largest distance from samples on `from` to the surface of `to`
+fn directed_distance(from: &PreviewMesh, to: &PreviewMesh) -> f64 {
+    let built;
+    let bvh = match to.bvh() {
+        Some(bvh) => bvh,
+        None => {
+            built = Bvh::build(to);
+            &built
+        }
+    };
+
+    let samples = surface_samples(from);
+    if samples.is_empty() {
+        return if bvh.is_empty() { 0.0 } else { f64::INFINITY };
+    }
+
+    samples
+        .into_iter()
+        .map(|p| bvh.nearest(p).map_or(f64::INFINITY, |hit| hit.distance))
+        .fold(0.0, f64::max)
+}
+
+/// Triangle corners, edge midpoints and centroids
+fn surface_samples(mesh: &PreviewMesh) -> Vec<[f64; 3]> {
+    let vertex = |i: u32| {
+        let i = i as usize * 3;
+        [
+            mesh.vertices[i] as f64,
+            mesh.vertices[i + 1] as f64,
+            mesh.vertices[i + 2] as f64,
+        ]
+    };
+    let mix = |points: &[[f64; 3]]| {
+        let n = points.len() as f64;
+        [0, 1, 2].map(|axis| points.iter().map(|p| p[axis]).sum::<f64>() / n)
+    };
+
+    let mut samples = Vec::with_capacity(mesh.indices.len() / 3 * 7);
+    for tri in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [vertex(tri[0]), vertex(tri[1]), vertex(tri[2])];
+        samples.extend_from_slice(&[a, b, c]);
+        samples.extend_from_slice(&[mix(&[a, b]), mix(&[b, c]), mix(&[c, a])]);
+        samples.push(mix(&[a, b, c]));
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::primitives::Box as BoxPrimitive;
+    use crate::geometry::Primitive;
+
+    #[test]
+    fn test_mesh_diff() {
+        let a = BoxPrimitive::new(2.0, 2.0, 2.0).to_mesh(16);
+        let same = mesh_diff(&a, &a.clone(), 1e-9);
+        assert!(same.within_tolerance);
+        assert!(same.hausdorff_distance < 1e-9);
+
+        // A taller box: its top and bottom faces are 0.5 away
+        let b = BoxPrimitive::new(2.0, 3.0, 2.0).to_mesh(16);
+        let diff = mesh_diff(&a, &b, 0.1);
+        assert!(!diff.within_tolerance);
+        assert!((diff.hausdorff_distance - 0.5).abs() < 1e-6);
+        assert!(mesh_diff(&a, &b, 0.5 + 1e-6).within_tolerance);
+
+        assert!(mesh_diff(&a, &PreviewMesh::new(), 1.0).hausdorff_distance.is_infinite());
+    }
+
+    #[test]
+    fn test_surface_samples_are_bit_identical_across_runs() {
+        let mesh = BoxPrimitive::new(1.7, 2.3, 0.9).to_mesh(16);
+        let bits = |samples: Vec<[f64; 3]>| -> Vec<u64> {
+            samples.iter().flatten().map(|c| c.to_bits()).collect()
+        };
+        assert_eq!(bits(surface_samples(&mesh)), bits(surface_samples(&mesh)));
+
+        let other = BoxPrimitive::new(1.7, 2.4, 0.9).to_mesh(16);
+        let diff = mesh_diff(&mesh, &other, 0.0).hausdorff_distance;
+        assert_eq!(
+            diff.to_bits(),
+            mesh_diff(&mesh, &other, 0.0).hausdorff_distance.to_bits()
+        );
+    }
+}
@@ -7,11 +7,14 @@
 
 pub mod bounding_box;
 pub mod mass_props;
+pub mod mesh_diff;
+pub mod recognize;
+pub mod symmetry;
 
 // Re-export core analysis types
 pub use bounding_box::{
     bbox_surface_area, bbox_volume, bboxes_intersect, compute_bounding_box, expand_bbox,
-    get_bbox_corners, merge_bboxes, point_in_bbox, transform_bbox,
+    get_bbox_corners, merge_bboxes, point_in_bbox, transform_bbox, LocalBounds,
 };
 
 pub use mass_props::{
@@ -19,13 +22,20 @@ pub use mass_props::{
     MaterialProperties,
 };
 
+pub use mesh_diff::{mesh_diff, MeshDiff};
+
+pub use recognize::recognize_primitive;
+
+pub use symmetry::{detect_symmetry_planes, Plane};
+
 use crate::errors::KernelResult;
 use crate::geometry::ir::node::{IRNode, NodeId};
 use crate::types::{BoundingBox, PreviewMesh};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Comprehensive analysis result for a geometric entity
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeometricAnalysis {
     /// Node being analyzed
     pub node_id: NodeId,
@@ -72,24 +82,33 @@ impl GeometricAnalysis {
     }
 }
 
+/// Default number of analyses kept before the least recently used is evicted
+pub const DEFAULT_ANALYSIS_CACHE_ENTRIES: usize = 256;
+
+/// Cached analysis with the tick it was last used at
+struct CachedAnalysis {
+    analysis: GeometricAnalysis,
+    last_used: u64,
+}
+
 /// Analyzer for computing comprehensive geometric analysis
 pub struct GeometricAnalyzer {
     /// Mass properties analyzer
     mass_analyzer: MassPropertiesAnalyzer,
     /// Analysis cache
-    cache: HashMap<NodeId, GeometricAnalysis>,
+    cache: HashMap<NodeId, CachedAnalysis>,
     /// Cache timeout in seconds
     cache_timeout: f64,
+    /// Maximum number of cached analyses
+    max_entries: usize,
+    /// Monotonic counter ordering cache accesses
+    tick: u64,
 }
 
 impl GeometricAnalyzer {
     /// Create a new geometric analyzer
     pub fn new() -> Self {
-        GeometricAnalyzer {
-            mass_analyzer: MassPropertiesAnalyzer::new(),
-            cache: HashMap::new(),
-            cache_timeout: 300.0, // 5 minutes
-        }
+        Self::with_material(MaterialProperties::aluminum())
     }
 
     /// Create analyzer with custom material
@@ -97,7 +116,9 @@ impl GeometricAnalyzer {
         GeometricAnalyzer {
             mass_analyzer: MassPropertiesAnalyzer::with_material(material),
             cache: HashMap::new(),
-            cache_timeout: 300.0,
+            cache_timeout: 300.0, // 5 minutes
+            max_entries: DEFAULT_ANALYSIS_CACHE_ENTRIES,
+            tick: 0,
         }
     }
 
@@ -107,10 +128,13 @@ impl GeometricAnalyzer {
         node: &IRNode,
         mesh: &PreviewMesh,
     ) -> KernelResult<GeometricAnalysis> {
+        self.tick += 1;
+
         // Check cache first
-        if let Some(cached) = self.cache.get(&node.id) {
-            if cached.is_fresh(self.cache_timeout) {
-                return Ok(cached.clone());
+        if let Some(cached) = self.cache.get_mut(&node.id) {
+            if cached.analysis.is_fresh(self.cache_timeout) {
+                cached.last_used = self.tick;
+                return Ok(cached.analysis.clone());
             }
         }
 
@@ -124,11 +148,43 @@ impl GeometricAnalyzer {
         let analysis = GeometricAnalysis::new(node.id.clone(), bbox, mass_props);
 
         // Cache the result
-        self.cache.insert(node.id.clone(), analysis.clone());
+        self.cache.insert(
+            node.id.clone(),
+            CachedAnalysis {
+                analysis: analysis.clone(),
+                last_used: self.tick,
+            },
+        );
+        self.evict_to(self.max_entries);
 
         Ok(analysis)
     }
 
+    /// Drop least recently used analyses until at most `limit` remain
+    fn evict_to(&mut self, limit: usize) {
+        while self.cache.len() > limit {
+            let oldest = self
+                .cache
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(id, _)| id.clone());
+            match oldest {
+                Some(id) => self.cache.remove(&id),
+                None => break,
+            };
+        }
+    }
+
+    /// Drop cached analyses older than the cache timeout
+    ///
+    /// Returns the number of entries removed.
+    pub fn prune_stale(&mut self) -> usize {
+        let before = self.cache.len();
+        let timeout = self.cache_timeout;
+        self.cache.retain(|_, cached| cached.analysis.is_fresh(timeout));
+        before - self.cache.len()
+    }
+
     /// Clear analysis cache
     pub fn clear_cache(&mut self) {
         self.cache.clear();
@@ -139,13 +195,20 @@ impl GeometricAnalyzer {
         self.cache_timeout = timeout_seconds;
     }
 
+    /// Set the maximum number of cached analyses, evicting the least
+    /// recently used ones beyond it
+    pub fn set_max_entries(&mut self, max_entries: usize) {
+        self.max_entries = max_entries;
+        self.evict_to(max_entries);
+    }
+
     /// Get cache statistics
     pub fn cache_stats(&self) -> (usize, usize) {
         let total_entries = self.cache.len();
         let fresh_entries = self
             .cache
             .values()
-            .filter(|analysis| analysis.is_fresh(self.cache_timeout))
+            .filter(|cached| cached.analysis.is_fresh(self.cache_timeout))
             .count();
 
         (total_entries, fresh_entries)
@@ -267,9 +330,13 @@ mod tests {
                 0.0, 0.0, 0.0, // 0
                 1.0, 0.0, 0.0, // 1
                 0.0, 1.0, 0.0, // 2
+                0.0, 0.0, 1.0, // 3
             ],
-            indices: vec![0, 1, 2],
-            normals: vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0],
+            // Closed tetrahedron so mass properties have a volume
+            indices: vec![0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3],
+            normals: vec![0.0; 12],
+            face_map: Vec::new(),
+            bvh: None,
         }
     }
 
@@ -310,6 +377,39 @@ mod tests {
         assert_eq!(total, 0);
     }
 
+    #[test]
+    fn test_cache_eviction_and_pruning() {
+        use crate::geometry::primitives::Box as BoxPrimitive;
+        use crate::geometry::Primitive;
+
+        let mut analyzer = GeometricAnalyzer::new();
+        analyzer.set_max_entries(2);
+        let mesh = BoxPrimitive::new(1.0, 2.0, 3.0).to_mesh(16);
+        let node = |name: &str| {
+            let mut node = create_test_node();
+            node.id = NodeId::from_user_string(name);
+            node
+        };
+        let (a, b, c) = (node("a"), node("b"), node("c"));
+
+        analyzer.analyze_node(&a, &mesh).unwrap();
+        analyzer.analyze_node(&b, &mesh).unwrap();
+        // Touch `a` so `b` becomes the least recently used
+        analyzer.analyze_node(&a, &mesh).unwrap();
+        analyzer.analyze_node(&c, &mesh).unwrap();
+
+        assert_eq!(analyzer.cache_stats(), (2, 2));
+        assert!(analyzer.cache.contains_key(&a.id));
+        assert!(!analyzer.cache.contains_key(&b.id));
+        assert!(analyzer.cache.contains_key(&c.id));
+
+        // Age `a` past the timeout
+        analyzer.cache.get_mut(&a.id).unwrap().analysis.computed_at -= 1000.0;
+        assert_eq!(analyzer.prune_stale(), 1);
+        assert_eq!(analyzer.cache_stats(), (1, 1));
+        assert!(analyzer.cache.contains_key(&c.id));
+    }
+
     #[test]
     fn test_quick_primitive_analysis() {
         let mut params = HashMap::new();
@@ -322,22 +422,15 @@ mod tests {
         assert_eq!(analysis.surface_area, 52.0); // 2*(2*3 + 2*4 + 3*4)
 
         // Check bounding box
-        assert_eq!(analysis.bounding_box.min_x, -1.0);
-        assert_eq!(analysis.bounding_box.max_x, 1.0);
+        assert_eq!(analysis.bounding_box.min[0], -1.0);
+        assert_eq!(analysis.bounding_box.max[0], 1.0);
     }
 
     #[test]
     fn test_analysis_freshness() {
         let mut analysis = GeometricAnalysis::new(
             crate::geometry::ir::node::NodeId::from_user_string("test"),
-            BoundingBox {
-                min_x: 0.0,
-                max_x: 1.0,
-                min_y: 0.0,
-                max_y: 1.0,
-                min_z: 0.0,
-                max_z: 1.0,
-            },
+            BoundingBox::new([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]),
             MassProperties::zero(),
         );
 
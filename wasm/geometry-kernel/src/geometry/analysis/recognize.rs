@@ -0,0 +1,198 @@
+//! Primitive recognition for imported triangle meshes.
+//!
+//! Each candidate primitive is fitted to the mesh and scored by the largest
+//! distance of a sample point from the fitted surface. Samples are the
+//! vertices and the triangle centroids, so a cube whose corners happen to
+//! lie on a sphere is still rejected as a sphere by its flat faces.
+
+use crate::geometry::analysis::symmetry::surface_principal_axes;
+use crate::geometry::math::vec3::{dot, length, scale, sub};
+use crate::types::{PreviewMesh, PrimitiveType};
+use std::collections::HashMap;
+
+/// Fitted primitive and the largest sample distance from its surface
+struct Fit {
+    primitive: PrimitiveType,
+    parameters: HashMap<String, f64>,
+    residual: f64,
+}
+
+/// The primitive the mesh most closely matches, with its parameters
+///
+/// Boxes are fitted to the axis-aligned bounding box and to the principal
+/// axes of the surface; cylinders take each principal axis in turn and
+/// spheres are centred on the surface centroid. The best fit is returned
+/// when every sample lies within `tolerance` of it. Parameters are named
+/// as in the primitives' `from_params` and describe size only, not
+/// placement. Empty meshes and invalid tolerances match nothing.
+pub fn recognize_primitive(
+    mesh: &PreviewMesh,
+    tolerance: f64,
+) -> Option<(PrimitiveType, HashMap<String, f64>)> {
+    if !(tolerance >= 0.0 && tolerance.is_finite()) {
+        return None;
+    }
+    let samples = sample_points(mesh);
+    let (centroid, principal) = surface_principal_axes(mesh)?;
+
+    let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    let mut fits = vec![fit_box(&samples, identity), fit_box(&samples, principal)];
+    fits.extend(principal.map(|axis| fit_cylinder(&samples, centroid, axis)));
+    fits.push(fit_sphere(&samples, centroid));
+
+    let best = fits
+        .into_iter()
+        .filter(|fit| fit.residual <= tolerance)
+        .min_by(|a, b| a.residual.total_cmp(&b.residual))?;
+    Some((best.primitive, best.parameters))
+}
+
+/// Vertices and triangle centroids of the mesh
+fn sample_points(mesh: &PreviewMesh) -> Vec<[f64; 3]> {
+    let vertex_count = mesh.vertex_count();
+    let vertex = |i: u32| {
+        let i = i as usize * 3;
+        [0, 1, 2].map(|k| mesh.vertices[i + k] as f64)
+    };
+
+    let mut samples: Vec<[f64; 3]> = (0..vertex_count as u32).map(vertex).collect();
+    for tri in mesh.indices.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        let [a, b, c] = [vertex(tri[0]), vertex(tri[1]), vertex(tri[2])];
+        samples.push([0, 1, 2].map(|k| (a[k] + b[k] + c[k]) / 3.0));
+    }
+    samples
+}
+
+/// Box spanning the samples along three orthonormal `axes`
+fn fit_box(samples: &[[f64; 3]], axes: [[f64; 3]; 3]) -> Fit {
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    for &p in samples {
+        for k in 0..3 {
+            let t = dot(p, axes[k]);
+            min[k] = min[k].min(t);
+            max[k] = max[k].max(t);
+        }
+    }
+    let half = [0, 1, 2].map(|k| (max[k] - min[k]) / 2.0);
+    let centre = [0, 1, 2].map(|k| (max[k] + min[k]) / 2.0);
+
+    // Signed distance from the box surface in its own frame
+    let residual = samples
+        .iter()
+        .map(|&p| {
+            let q = [0, 1, 2].map(|k| (dot(p, axes[k]) - centre[k]).abs() - half[k]);
+            let outside = length(q.map(|c| c.max(0.0)));
+            let inside = q[0].max(q[1]).max(q[2]).min(0.0);
+            (outside + inside).abs()
+        })
+        .fold(0.0, f64::max);
+
+    let parameters = [("width", half[0]), ("height", half[1]), ("depth", half[2])]
+        .into_iter()
+        .map(|(name, h)| (name.to_string(), 2.0 * h))
+        .collect();
+    Fit {
+        primitive: PrimitiveType::Box,
+        parameters,
+        residual,
+    }
+}
+
+/// Cylinder along `axis` through `centroid`, as wide as the farthest sample
+fn fit_cylinder(samples: &[[f64; 3]], centroid: [f64; 3], axis: [f64; 3]) -> Fit {
+    let (mut low, mut high, mut radius) = (f64::INFINITY, f64::NEG_INFINITY, 0.0_f64);
+    let split = |p: [f64; 3]| {
+        let d = sub(p, centroid);
+        let t = dot(d, axis);
+        (t, length(sub(d, scale(axis, t))))
+    };
+    for &p in samples {
+        let (t, r) = split(p);
+        low = low.min(t);
+        high = high.max(t);
+        radius = radius.max(r);
+    }
+    let half_height = (high - low) / 2.0;
+    let middle = (high + low) / 2.0;
+
+    let residual = samples
+        .iter()
+        .map(|&p| {
+            let (t, r) = split(p);
+            let q = [r - radius, (t - middle).abs() - half_height];
+            let outside = q[0].max(0.0).hypot(q[1].max(0.0));
+            (outside + q[0].max(q[1]).min(0.0)).abs()
+        })
+        .fold(0.0, f64::max);
+
+    let parameters = [("radius", radius), ("height", 2.0 * half_height)]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+    Fit {
+        primitive: PrimitiveType::Cylinder,
+        parameters,
+        residual,
+    }
+}
+
+/// Sphere about `centroid` with the mean sample distance as its radius
+fn fit_sphere(samples: &[[f64; 3]], centroid: [f64; 3]) -> Fit {
+    let distances: Vec<f64> = samples.iter().map(|&p| length(sub(p, centroid))).collect();
+    let radius = distances.iter().sum::<f64>() / distances.len() as f64;
+    let residual = distances
+        .iter()
+        .map(|d| (d - radius).abs())
+        .fold(0.0, f64::max);
+
+    Fit {
+        primitive: PrimitiveType::Sphere,
+        parameters: HashMap::from([("radius".to_string(), radius)]),
+        residual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::primitives::{Box as BoxPrimitive, Cylinder, Sphere};
+    use crate::geometry::Primitive;
+    use crate::types::Transform;
+
+    #[test]
+    fn test_recognize_cylinder() {
+        // Tilted so the axis has to come from the principal axes
+        let mut cylinder = Cylinder::new(5.0, 20.0);
+        cylinder.apply_transform(&Transform {
+            position: Some([1.0, 2.0, 3.0]),
+            rotation: Some([0.3, 0.0, 0.5]),
+            scale: None,
+        });
+        let mesh = cylinder.to_mesh(64);
+
+        let (primitive, parameters) = recognize_primitive(&mesh, 0.05).unwrap();
+        assert_eq!(primitive, PrimitiveType::Cylinder);
+        assert!((parameters["radius"] - 5.0).abs() < 1e-3);
+        assert!((parameters["height"] - 20.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_recognize_box_and_sphere() {
+        let block = BoxPrimitive::new(2.0, 4.0, 6.0).to_mesh(16);
+        let (primitive, parameters) = recognize_primitive(&block, 1e-4).unwrap();
+        assert_eq!(primitive, PrimitiveType::Box);
+        assert!((parameters["depth"] - 6.0).abs() < 1e-4);
+
+        let ball = Sphere::new(3.0).to_mesh(64);
+        let (primitive, parameters) = recognize_primitive(&ball, 0.05).unwrap();
+        assert_eq!(primitive, PrimitiveType::Sphere);
+        assert!((parameters["radius"] - 3.0).abs() < 0.05);
+
+        // Coarse tessellations fall outside a tight tolerance
+        assert!(recognize_primitive(&Sphere::new(3.0).to_mesh(8), 1e-3).is_none());
+    }
+}
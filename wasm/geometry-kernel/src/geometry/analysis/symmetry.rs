@@ -0,0 +1,228 @@
+//! Mirror symmetry detection for triangle meshes.
+//!
+//! Candidate planes pass through the area-weighted surface centroid normal
+//! to each principal axis of the surface, and through the bounding-box
+//! centre normal to each coordinate axis. A candidate is a symmetry plane
+//! when the mesh reflected across it matches the original within
+//! tolerance, as measured by `mesh_diff`.
+
+use crate::geometry::analysis::bounding_box::compute_bounding_box;
+use crate::geometry::analysis::mesh_diff::mesh_diff;
+use crate::geometry::math::vec3::{cross, length, sub};
+use crate::types::PreviewMesh;
+use serde::{Deserialize, Serialize};
+
+/// Normals closer than this (as `1 - |cos|`) are treated as parallel
+const PARALLEL_EPSILON: f64 = 1e-6;
+
+/// Jacobi sweeps used to diagonalize the surface covariance
+const JACOBI_SWEEPS: usize = 32;
+
+/// Infinite plane through `point` with unit `normal`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Plane {
+    pub point: [f64; 3],
+    pub normal: [f64; 3],
+}
+
+impl Plane {
+    /// Signed distance of a point from the plane
+    pub fn signed_distance(&self, p: [f64; 3]) -> f64 {
+        (0..3).map(|k| (p[k] - self.point[k]) * self.normal[k]).sum()
+    }
+
+    /// Mirror image of a point
+    pub fn reflect(&self, p: [f64; 3]) -> [f64; 3] {
+        let d = 2.0 * self.signed_distance(p);
+        [0, 1, 2].map(|k| p[k] - d * self.normal[k])
+    }
+}
+
+/// Planes the mesh is mirror-symmetric about, within `tolerance`
+///
+/// Principal-axis candidates come first, then bounding-box mid-planes not
+/// already covered. Normals are unit length with their largest component
+/// positive. Empty meshes have no symmetry planes.
+pub fn detect_symmetry_planes(mesh: &PreviewMesh, tolerance: f64) -> Vec<Plane> {
+    if mesh.triangle_count() == 0 {
+        return Vec::new();
+    }
+
+    let mut candidates = Vec::with_capacity(6);
+    if let Some((centroid, axes)) = surface_principal_axes(mesh) {
+        candidates.extend(axes.map(|normal| Plane { point: centroid, normal }));
+    }
+    let bbox = compute_bounding_box(mesh);
+    let centre = [0, 1, 2].map(|k| (bbox.min[k] + bbox.max[k]) / 2.0);
+    for axis in 0..3 {
+        let mut normal = [0.0; 3];
+        normal[axis] = 1.0;
+        candidates.push(Plane { point: centre, normal });
+    }
+
+    let mut planes: Vec<Plane> = Vec::new();
+    let mut tested: Vec<Plane> = Vec::new();
+    for candidate in candidates {
+        let candidate = canonical(candidate);
+        let duplicate = tested.iter().any(|seen| {
+            let cos: f64 = (0..3).map(|k| seen.normal[k] * candidate.normal[k]).sum();
+            cos.abs() > 1.0 - PARALLEL_EPSILON
+                && seen.signed_distance(candidate.point).abs() <= tolerance
+        });
+        if duplicate {
+            continue;
+        }
+        tested.push(candidate);
+
+        if mesh_diff(mesh, &reflect_mesh(mesh, &candidate), tolerance).within_tolerance {
+            planes.push(candidate);
+        }
+    }
+    planes
+}
+
+/// Mirror a mesh, reversing winding so faces still point outward
+fn reflect_mesh(mesh: &PreviewMesh, plane: &Plane) -> PreviewMesh {
+    let mut reflected = PreviewMesh::new();
+    for v in mesh.vertices.chunks_exact(3) {
+        let p = plane.reflect([v[0] as f64, v[1] as f64, v[2] as f64]);
+        reflected.vertices.extend(p.map(|c| c as f32));
+    }
+    for n in mesh.normals.chunks_exact(3) {
+        let n = [n[0] as f64, n[1] as f64, n[2] as f64];
+        let d: f64 = 2.0 * (0..3).map(|k| n[k] * plane.normal[k]).sum::<f64>();
+        reflected.normals.extend([0, 1, 2].map(|k| (n[k] - d * plane.normal[k]) as f32));
+    }
+    for tri in mesh.indices.chunks_exact(3) {
+        reflected.indices.extend_from_slice(&[tri[0], tri[2], tri[1]]);
+    }
+    reflected
+}
+
+/// Area-weighted centroid and principal directions of the surface
+pub(crate) fn surface_principal_axes(mesh: &PreviewMesh) -> Option<([f64; 3], [[f64; 3]; 3])> {
+    let vertex_count = mesh.vertex_count();
+    let vertex = |i: u32| {
+        let i = i as usize * 3;
+        [0, 1, 2].map(|k| mesh.vertices[i + k] as f64)
+    };
+
+    let mut samples = Vec::with_capacity(mesh.triangle_count());
+    for tri in mesh.indices.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= vertex_count) {
+            continue;
+        }
+        let [a, b, c] = [vertex(tri[0]), vertex(tri[1]), vertex(tri[2])];
+        let area = length(cross(sub(b, a), sub(c, a))) / 2.0;
+        let centroid = [0, 1, 2].map(|k| (a[k] + b[k] + c[k]) / 3.0);
+        samples.push((area, centroid));
+    }
+
+    let total: f64 = samples.iter().map(|(area, _)| area).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let centroid =
+        [0, 1, 2].map(|k| samples.iter().map(|(area, c)| area * c[k]).sum::<f64>() / total);
+
+    let mut covariance = [[0.0; 3]; 3];
+    for (area, c) in &samples {
+        let d = sub(*c, centroid);
+        for i in 0..3 {
+            for j in 0..3 {
+                covariance[i][j] += area * d[i] * d[j];
+            }
+        }
+    }
+    Some((centroid, symmetric_eigenvectors(covariance)))
+}
+
+/// Eigenvectors of a symmetric 3x3 matrix by cyclic Jacobi rotations
+fn symmetric_eigenvectors(mut a: [[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for _ in 0..JACOBI_SWEEPS {
+        let off = a[0][1].powi(2) + a[0][2].powi(2) + a[1][2].powi(2);
+        if off <= f64::EPSILON * (a[0][0].powi(2) + a[1][1].powi(2) + a[2][2].powi(2)) {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q] == 0.0 {
+                continue;
+            }
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+
+            // A' = Jᵀ A J with the rotation J in the (p, q) plane
+            for row in a.iter_mut() {
+                let (akp, akq) = (row[p], row[q]);
+                row[p] = c * akp - s * akq;
+                row[q] = s * akp + c * akq;
+            }
+            let (row_p, row_q) = (a[p], a[q]);
+            a[p] = [0, 1, 2].map(|k| c * row_p[k] - s * row_q[k]);
+            a[q] = [0, 1, 2].map(|k| s * row_p[k] + c * row_q[k]);
+            for row in v.iter_mut() {
+                let (vp, vq) = (row[p], row[q]);
+                row[p] = c * vp - s * vq;
+                row[q] = s * vp + c * vq;
+            }
+        }
+    }
+    // Columns of v are the eigenvectors
+    [0, 1, 2].map(|col| [v[0][col], v[1][col], v[2][col]])
+}
+
+/// Unit normal with its largest component positive
+fn canonical(plane: Plane) -> Plane {
+    let length = length(plane.normal);
+    let largest = (0..3)
+        .max_by(|&i, &j| plane.normal[i].abs().total_cmp(&plane.normal[j].abs()))
+        .unwrap_or(0);
+    let sign = if plane.normal[largest] < 0.0 { -1.0 } else { 1.0 };
+    Plane {
+        point: plane.point,
+        normal: plane.normal.map(|c| sign * c / length),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::primitives::Box as BoxPrimitive;
+    use crate::geometry::Primitive;
+
+    #[test]
+    fn test_symmetry_planes() {
+        let block = BoxPrimitive::new(2.0, 4.0, 6.0).to_mesh(16);
+        let planes = detect_symmetry_planes(&block, 1e-4);
+        assert_eq!(planes.len(), 3);
+        for axis in 0..3 {
+            assert!(
+                planes.iter().any(|p| p.normal[axis] > 1.0 - 1e-9
+                    && p.signed_distance([0.0; 3]).abs() < 1e-6),
+                "missing mid-plane normal to axis {}",
+                axis
+            );
+        }
+
+        // Right-triangle prism: only the mid-plane across the extrusion
+        let mut wedge = PreviewMesh::new();
+        for z in [0.0, 6.0] {
+            wedge.vertices.extend_from_slice(&[0.0, 0.0, z, 4.0, 0.0, z, 0.0, 2.0, z]);
+        }
+        wedge.indices = vec![
+            0, 2, 1, 3, 4, 5, // Caps
+            0, 1, 4, 0, 4, 3, // y = 0
+            1, 2, 5, 1, 5, 4, // Hypotenuse
+            2, 0, 3, 2, 3, 5, // x = 0
+        ];
+        let planes = detect_symmetry_planes(&wedge, 1e-4);
+        assert_eq!(planes.len(), 1);
+        assert!(planes[0].normal[2] > 1.0 - 1e-9);
+        assert!((planes[0].point[2] - 3.0).abs() < 1e-6);
+
+        assert!(detect_symmetry_planes(&PreviewMesh::new(), 1e-4).is_empty());
+    }
+}
@@ -3,6 +3,7 @@
 //! Validates geometry against manufacturing process constraints
 //! such as minimum wall thickness, tool diameter, and overhang angles.
 
+use crate::config::KernelConfig;
 use crate::errors::{KernelError, KernelResult};
 use crate::geometry::{BoundingBox, PreviewMesh};
 use crate::types::{
@@ -10,10 +11,23 @@ use crate::types::{
 };
 use std::collections::HashMap;
 
-/// Validate mesh against manufacturing constraints
+/// Validate mesh against manufacturing constraints, for a millimeter model
 pub fn validate_constraints(
     mesh: &PreviewMesh,
     constraints: &HashMap<ConstraintType, f64>,
+) -> ManufacturabilityReport {
+    validate_constraints_with_config(mesh, constraints, &KernelConfig::default())
+}
+
+/// Validate mesh against manufacturing constraints
+///
+/// Constraint values are in model units. The built-in feature size and
+/// degenerate triangle checks are scaled to the unit and linear tolerance
+/// of `config`.
+pub fn validate_constraints_with_config(
+    mesh: &PreviewMesh,
+    constraints: &HashMap<ConstraintType, f64>,
+    config: &KernelConfig,
 ) -> ManufacturabilityReport {
     let mut violations = Vec::new();
     let mut warnings = Vec::new();
@@ -40,12 +54,12 @@ pub fn validate_constraints(
     }
 
     // Check for very small features
-    if let Some(violation) = check_feature_size(mesh) {
+    if let Some(violation) = check_feature_size(mesh, config) {
         warnings.push(violation);
     }
 
     // Check mesh integrity
-    if let Some(violation) = check_mesh_integrity(mesh) {
+    if let Some(violation) = check_mesh_integrity(mesh, config) {
         if violation.severity == ViolationSeverity::Error {
             violations.push(violation);
         } else {
@@ -198,11 +212,43 @@ fn check_overhang_angle(mesh: &PreviewMesh, max_overhang_deg: f64) -> Option<Con
     }
 }
 
+/// Find triangles that overhang beyond a support angle
+///
+/// The overhang angle of a downward-facing triangle is measured from the
+/// vertical: walls are 0° and flat ceilings are 90°. Upward-facing and
+/// degenerate triangles never overhang. Returns triangle indices in order.
+pub fn find_overhang_triangles(mesh: &PreviewMesh, max_overhang_deg: f64) -> Vec<usize> {
+    let vertex_count = mesh.vertex_count();
+
+    mesh.indices
+        .chunks_exact(3)
+        .enumerate()
+        .filter(|(_, tri)| tri.iter().all(|&i| (i as usize) < vertex_count))
+        .filter_map(|(index, tri)| {
+            let v = |k: usize| {
+                let i = tri[k] as usize * 3;
+                [
+                    mesh.vertices[i] as f64,
+                    mesh.vertices[i + 1] as f64,
+                    mesh.vertices[i + 2] as f64,
+                ]
+            };
+            let normal = crate::geometry::compute_face_normal(v(0), v(1), v(2));
+            if normal[1] >= 0.0 {
+                return None;
+            }
+
+            let overhang_deg = (-normal[1]).min(1.0).asin().to_degrees();
+            (overhang_deg > max_overhang_deg).then_some(index)
+        })
+        .collect()
+}
+
 /// Check for very small features
 ///
 /// Detects features that may be too small for reliable manufacturing.
-fn check_feature_size(mesh: &PreviewMesh) -> Option<ConstraintViolation> {
-    const MIN_FEATURE_SIZE: f64 = 0.5; // mm
+fn check_feature_size(mesh: &PreviewMesh, config: &KernelConfig) -> Option<ConstraintViolation> {
+    const MIN_FEATURE_SIZE_MM: f64 = 0.5;
 
     if mesh.vertices.is_empty() {
         return None;
@@ -211,17 +257,19 @@ fn check_feature_size(mesh: &PreviewMesh) -> Option<ConstraintViolation> {
     let bbox = crate::geometry::analysis::bounding_box::compute_bounding_box(mesh);
 
     let volume = bbox_volume(&bbox);
+    let min_volume = config.millimeters_to_model(MIN_FEATURE_SIZE_MM).powi(3);
 
-    if volume < MIN_FEATURE_SIZE.powi(3) {
+    if volume < min_volume {
         Some(ConstraintViolation {
             type_: ConstraintType::MinWallThickness, // Reuse for general feature size
             severity: ViolationSeverity::Info,
             message: format!(
-                "Part volume ({:.3} mm³) is very small. Verify feature size.",
-                volume
+                "Part volume ({:.3e} {}³) is very small. Verify feature size.",
+                volume,
+                config.length_unit.symbol()
             ),
             value: volume,
-            limit: MIN_FEATURE_SIZE.powi(3),
+            limit: min_volume,
         })
     } else {
         None
@@ -230,8 +278,10 @@ fn check_feature_size(mesh: &PreviewMesh) -> Option<ConstraintViolation> {
 
 /// Check mesh integrity
 ///
-/// Validates basic mesh properties like non-degenerate triangles.
-fn check_mesh_integrity(mesh: &PreviewMesh) -> Option<ConstraintViolation> {
+/// Validates basic mesh properties like non-degenerate triangles. A
+/// triangle is degenerate when its area is below the square of the linear
+/// tolerance.
+fn check_mesh_integrity(mesh: &PreviewMesh, config: &KernelConfig) -> Option<ConstraintViolation> {
     if mesh.vertices.len() % 3 != 0 {
         return Some(ConstraintViolation {
             type_: ConstraintType::MinWallThickness,
@@ -306,7 +356,7 @@ fn check_mesh_integrity(mesh: &PreviewMesh) -> Option<ConstraintViolation> {
                 let area =
                     (cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2]).sqrt() / 2.0;
 
-                if area < crate::geometry::constants::EPSILON {
+                if area < config.linear_tolerance * config.linear_tolerance {
                     degenerate_count += 1;
                 }
 
@@ -345,7 +395,8 @@ fn bbox_volume(bbox: &BoundingBox) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::geometry::primitives::{Box as BoxPrimitive, Primitive};
+    use crate::geometry::primitives::Box as BoxPrimitive;
+    use crate::geometry::Primitive;
 
     #[test]
     fn test_validate_empty_mesh() {
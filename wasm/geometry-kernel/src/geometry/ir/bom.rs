@@ -0,0 +1,226 @@
+//! Bill of materials for assembly graphs.
+//!
+//! A solid is marked as an assembly part by an `Analysis` node of type
+//! [`PART_ANALYSIS`] whose `target_node` is the solid. Its parameters carry
+//! the part data:
+//! - `part_number` (string, required)
+//! - `material` (string, optional)
+//! - `mass` (number in kg, optional)
+//!
+//! Instances sharing a part number are aggregated into one BOM line.
+//! Suppressed part nodes, and parts whose solid is suppressed, are left out.
+
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::ir::{IRGraph, NodeContent, NodeId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Analysis type marking a solid as an assembly part
+pub const PART_ANALYSIS: &str = "part";
+
+/// One line of a bill of materials
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BomLine {
+    pub part_number: String,
+    /// Number of instances in the assembly
+    pub quantity: usize,
+    pub material: Option<String>,
+    /// Mass of one instance (kg), when every instance declares it
+    pub unit_mass: Option<f64>,
+    /// Solids that are instances of this part, in ID order
+    pub instances: Vec<NodeId>,
+}
+
+impl BomLine {
+    /// Mass of all instances together (kg)
+    pub fn total_mass(&self) -> Option<f64> {
+        self.unit_mass.map(|mass| mass * self.quantity as f64)
+    }
+}
+
+/// Aggregate the parts of an assembly graph, ordered by part number
+///
+/// Instances of one part number must agree on material and mass.
+pub fn bom(graph: &IRGraph) -> KernelResult<Vec<BomLine>> {
+    let parts = part_instances(graph)?
+        .into_iter()
+        .map(|(solid, parameters)| Ok((solid, PartInfo::from_parameters(parameters)?)))
+        .collect::<KernelResult<Vec<_>>>()?;
+
+    let mut lines: BTreeMap<String, BomLine> = BTreeMap::new();
+    for (solid, part) in parts {
+        let Some(line) = lines.get_mut(&part.part_number) else {
+            lines.insert(
+                part.part_number.clone(),
+                BomLine {
+                    part_number: part.part_number,
+                    quantity: 1,
+                    material: part.material,
+                    unit_mass: part.mass,
+                    instances: vec![solid.clone()],
+                },
+            );
+            continue;
+        };
+
+        if line.material != part.material {
+            return Err(KernelError::invalid_parameter(
+                "material",
+                format!("instances of part {} disagree on material", line.part_number),
+            ));
+        }
+        if line.unit_mass != part.mass {
+            return Err(KernelError::invalid_parameter(
+                "mass",
+                format!("instances of part {} disagree on mass", line.part_number),
+            ));
+        }
+        line.quantity += 1;
+        line.instances.push(solid.clone());
+    }
+
+    Ok(lines.into_values().collect())
+}
+
+/// Solids marked as parts, in ID order, with their part parameters
+pub(crate) fn part_instances(
+    graph: &IRGraph,
+) -> KernelResult<Vec<(&NodeId, &HashMap<String, serde_json::Value>)>> {
+    let mut parts = Vec::new();
+    for node in graph.nodes().values() {
+        let NodeContent::Analysis { analysis_type, target_node, parameters } = &node.content
+        else {
+            continue;
+        };
+        if analysis_type != PART_ANALYSIS || node.suppressed {
+            continue;
+        }
+        let target = graph
+            .get_node(target_node)
+            .ok_or_else(|| KernelError::node_not_found(target_node.as_str()))?;
+        if target.suppressed {
+            continue;
+        }
+        parts.push((target_node, parameters));
+    }
+    parts.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+    Ok(parts)
+}
+
+/// Part data read from a part analysis node
+struct PartInfo {
+    part_number: String,
+    material: Option<String>,
+    mass: Option<f64>,
+}
+
+impl PartInfo {
+    fn from_parameters(parameters: &HashMap<String, serde_json::Value>) -> KernelResult<Self> {
+        let part_number = parameters
+            .get("part_number")
+            .ok_or_else(|| KernelError::missing_parameter("part_number"))?
+            .as_str()
+            .filter(|number| !number.is_empty())
+            .ok_or_else(|| {
+                KernelError::invalid_parameter("part_number", parameters["part_number"].to_string())
+            })?
+            .to_string();
+
+        let material = match parameters.get("material") {
+            None => None,
+            Some(value) => Some(
+                value
+                    .as_str()
+                    .ok_or_else(|| KernelError::invalid_parameter("material", value.to_string()))?
+                    .to_string(),
+            ),
+        };
+
+        let mass = match parameters.get("mass") {
+            None => None,
+            Some(value) => Some(
+                value
+                    .as_f64()
+                    .filter(|mass| mass.is_finite() && *mass >= 0.0)
+                    .ok_or_else(|| KernelError::invalid_parameter("mass", value.to_string()))?,
+            ),
+        };
+
+        Ok(PartInfo { part_number, material, mass })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::ir::{IRNode, NodeMetadata, NodeSource, NodeType};
+    use serde_json::json;
+
+    fn solid(graph: &mut IRGraph, name: &str, radius: f64) -> NodeId {
+        let node = IRNode::with_user_id(
+            name,
+            NodeType::Primitive,
+            NodeContent::Primitive {
+                primitive_type: "cylinder".to_string(),
+                parameters: [("radius".to_string(), radius), ("height".to_string(), 20.0)]
+                    .into_iter()
+                    .collect(),
+                transform: None,
+            },
+            vec![],
+            NodeMetadata::new(Some(name.to_string()), NodeSource::User),
+        )
+        .unwrap();
+        let id = node.id.clone();
+        graph.add_node(node).unwrap();
+        id
+    }
+
+    fn mark_part(graph: &mut IRGraph, target: &NodeId, part_number: &str, mass: f64) {
+        let node = IRNode::new(
+            NodeType::Analysis,
+            NodeContent::Analysis {
+                analysis_type: PART_ANALYSIS.to_string(),
+                target_node: target.clone(),
+                parameters: [
+                    ("part_number".to_string(), json!(part_number)),
+                    ("material".to_string(), json!("steel")),
+                    ("mass".to_string(), json!(mass)),
+                ]
+                .into_iter()
+                .collect(),
+            },
+            vec![target.clone()],
+            NodeMetadata::new(None, NodeSource::User),
+        )
+        .unwrap();
+        graph.add_node(node).unwrap();
+    }
+
+    #[test]
+    fn test_bom_counts_identical_parts() {
+        let mut graph = IRGraph::new();
+        let bolt_a = solid(&mut graph, "bolt_a", 3.0);
+        let bolt_b = solid(&mut graph, "bolt_b", 3.0);
+        let bracket = solid(&mut graph, "bracket", 40.0);
+        mark_part(&mut graph, &bolt_a, "M6-BOLT", 0.01);
+        mark_part(&mut graph, &bolt_b, "M6-BOLT", 0.01);
+        mark_part(&mut graph, &bracket, "BRK-100", 0.5);
+
+        let lines = bom(&graph).unwrap();
+        assert_eq!(lines.len(), 2);
+
+        assert_eq!(lines[0].part_number, "BRK-100");
+        assert_eq!(lines[0].quantity, 1);
+        assert_eq!(lines[1].part_number, "M6-BOLT");
+        assert_eq!(lines[1].quantity, 2);
+        assert_eq!(lines[1].instances, vec![bolt_a, bolt_b]);
+        assert_eq!(lines[1].material.as_deref(), Some("steel"));
+        assert!((lines[1].total_mass().unwrap() - 0.02).abs() < 1e-12);
+
+        // A bolt declaring a different mass is a data error
+        let bolt_c = solid(&mut graph, "bolt_c", 3.0);
+        mark_part(&mut graph, &bolt_c, "M6-BOLT", 0.02);
+        assert!(bom(&graph).is_err());
+    }
+}
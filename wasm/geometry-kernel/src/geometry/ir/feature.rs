@@ -6,6 +6,7 @@
 
 use crate::errors::{KernelError, KernelResult};
 use crate::geometry::ir::node::{NodeId, Transform};
+use crate::geometry::math::vec3;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -32,6 +33,10 @@ pub struct Feature {
 
     /// Feature creation metadata
     pub metadata: FeatureMetadata,
+
+    /// Whether the feature is switched off without being deleted
+    #[serde(default)]
+    pub suppressed: bool,
 }
 
 impl Feature {
@@ -50,6 +55,7 @@ impl Feature {
             post_transform: None,
             manufacturing_constraints: Vec::new(),
             metadata: FeatureMetadata::default(),
+            suppressed: false,
         }
     }
 
@@ -78,6 +84,16 @@ impl Feature {
         Ok(())
     }
 
+    /// Validate the feature and rescale its direction vectors to unit length
+    ///
+    /// Validation only rejects zero directions, but evaluation steps along
+    /// them as given, so `[0, 0, 2]` would double an extrusion. Lengths
+    /// live in the scalar parameters (`distance`, `spacing`, `depth`).
+    pub fn normalize(&mut self) -> KernelResult<()> {
+        self.validate()?;
+        self.parameters.normalize_directions()
+    }
+
     /// Check if feature is manufacturable with given process
     pub fn is_manufacturable(&self, process: &ManufacturingProcess) -> KernelResult<bool> {
         for constraint in &self.manufacturing_constraints {
@@ -160,8 +176,15 @@ impl Feature {
     }
 
     fn validate_revolve(&self) -> KernelResult<()> {
-        if let FeatureParameters::Revolve { angle, axis, .. } = &self.parameters {
-            if *angle <= 0.0 || *angle > 360.0 {
+        if let FeatureParameters::Revolve {
+            angle,
+            angle_unit,
+            axis,
+            ..
+        } = &self.parameters
+        {
+            let degrees = angle_unit.to_degrees(*angle);
+            if !degrees.is_finite() || degrees <= 0.0 || degrees > 360.0 {
                 return Err(KernelError::invalid_parameter(
                     "revolve_angle",
                     "Angle must be between 0 and 360 degrees",
@@ -186,7 +209,10 @@ impl Feature {
 
     fn validate_hole(&self) -> KernelResult<()> {
         if let FeatureParameters::Hole {
-            diameter, depth, ..
+            diameter,
+            depth,
+            hole_type,
+            ..
         } = &self.parameters
         {
             if *diameter <= 0.0 {
@@ -210,6 +236,15 @@ impl Feature {
                     "Hole aspect ratio exceeds manufacturing limits (max 10:1)",
                 ));
             }
+
+            if let HoleType::Blind { bottom } = hole_type {
+                if bottom.point_height(*diameter)? >= *depth {
+                    return Err(KernelError::invalid_parameter(
+                        "hole_depth",
+                        "Drill point is longer than the hole depth",
+                    ));
+                }
+            }
         } else {
             return Err(KernelError::invalid_parameter(
                 "hole_parameters",
@@ -245,7 +280,10 @@ impl Feature {
 
     fn validate_chamfer(&self) -> KernelResult<()> {
         if let FeatureParameters::Chamfer {
-            distance, angle, ..
+            distance,
+            angle,
+            angle_unit,
+            ..
         } = &self.parameters
         {
             if *distance <= 0.0 {
@@ -255,7 +293,8 @@ impl Feature {
                 ));
             }
 
-            if *angle <= 0.0 || *angle >= 90.0 {
+            let degrees = angle_unit.to_degrees(*angle);
+            if !degrees.is_finite() || degrees <= 0.0 || degrees >= 90.0 {
                 return Err(KernelError::invalid_parameter(
                     "chamfer_angle",
                     "Angle must be between 0 and 90 degrees",
@@ -295,8 +334,12 @@ impl Feature {
     }
 
     fn validate_draft(&self) -> KernelResult<()> {
-        if let FeatureParameters::Draft { angle, .. } = &self.parameters {
-            if *angle <= 0.0 || *angle > 45.0 {
+        if let FeatureParameters::Draft {
+            angle, angle_unit, ..
+        } = &self.parameters
+        {
+            let degrees = angle_unit.to_degrees(*angle);
+            if !degrees.is_finite() || degrees <= 0.0 || degrees > 45.0 {
                 return Err(KernelError::invalid_parameter(
                     "draft_angle",
                     "Draft angle must be between 0 and 45 degrees",
@@ -312,6 +355,27 @@ impl Feature {
     }
 
     fn validate_pattern(&self) -> KernelResult<()> {
+        if let FeatureParameters::Pattern {
+            pattern_type:
+                PatternType::Grid {
+                    dir_u,
+                    dir_v,
+                    count_u,
+                    count_v,
+                    spacing_u,
+                    spacing_v,
+                },
+            ..
+        } = &self.parameters
+        {
+            return validate_grid(
+                *dir_u,
+                *dir_v,
+                [*count_u, *count_v],
+                [*spacing_u, *spacing_v],
+            );
+        }
+
         if let FeatureParameters::Pattern { count, spacing, .. } = &self.parameters {
             if *count < 2 {
                 return Err(KernelError::invalid_parameter(
@@ -433,7 +497,9 @@ pub enum FeatureParameters {
         taper_angle: Option<f64>,
     },
     Revolve {
-        angle: f64, // degrees
+        angle: f64,
+        #[serde(default)]
+        angle_unit: AngleUnit,
         axis: [f64; 3],
         axis_point: [f64; 3],
     },
@@ -451,7 +517,9 @@ pub enum FeatureParameters {
     },
     Chamfer {
         distance: f64,
-        angle: f64, // degrees
+        angle: f64,
+        #[serde(default)]
+        angle_unit: AngleUnit,
         edge_selection: EdgeSelection,
     },
     Shell {
@@ -459,7 +527,9 @@ pub enum FeatureParameters {
         faces_to_remove: Vec<i32>,
     },
     Draft {
-        angle: f64, // degrees
+        angle: f64,
+        #[serde(default)]
+        angle_unit: AngleUnit,
         pull_direction: [f64; 3],
         neutral_plane: [f64; 4], // plane equation [a, b, c, d]
     },
@@ -484,13 +554,285 @@ pub enum FeatureParameters {
     },
 }
 
+impl FeatureParameters {
+    /// Rescale every direction, axis and normal to unit length
+    ///
+    /// A linear pattern's `direction` is the only one it uses; grids and
+    /// circular patterns normalize their own vectors instead.
+    pub fn normalize_directions(&mut self) -> KernelResult<()> {
+        let unit = |v: &mut [f64; 3], parameter: &str| -> KernelResult<()> {
+            *v = vec3::normalize(*v).ok_or_else(|| {
+                KernelError::invalid_parameter(parameter, "Direction vector cannot be zero")
+            })?;
+            Ok(())
+        };
+
+        match self {
+            FeatureParameters::Extrude { direction, .. } => unit(direction, "extrude_direction"),
+            FeatureParameters::Revolve { axis, .. } => unit(axis, "revolve_axis"),
+            FeatureParameters::Hole { direction, .. } => unit(direction, "hole_direction"),
+            FeatureParameters::Draft { pull_direction, .. } => {
+                unit(pull_direction, "draft_pull_direction")
+            }
+            FeatureParameters::Pattern {
+                direction,
+                pattern_type,
+                ..
+            } => match pattern_type {
+                PatternType::Linear => unit(direction, "pattern_direction"),
+                PatternType::Circular { axis, .. } => unit(axis, "pattern_axis"),
+                PatternType::Grid { dir_u, dir_v, .. } => {
+                    unit(dir_u, "pattern_direction")?;
+                    unit(dir_v, "pattern_direction")
+                }
+            },
+            FeatureParameters::Mirror { plane_normal, .. } => {
+                unit(plane_normal, "mirror_plane_normal")
+            }
+            FeatureParameters::Fillet { .. }
+            | FeatureParameters::Chamfer { .. }
+            | FeatureParameters::Shell { .. }
+            | FeatureParameters::Sweep { .. }
+            | FeatureParameters::Loft { .. } => Ok(()),
+        }
+    }
+
+    /// Sweep an extrude profile `distance` along its direction
+    ///
+    /// Returns `profile` followed by its translated copy. Only the
+    /// direction's orientation counts, not its length.
+    pub fn extrude_profile(&self, profile: &[[f64; 3]]) -> KernelResult<Vec<[f64; 3]>> {
+        let FeatureParameters::Extrude {
+            distance,
+            direction,
+            ..
+        } = self
+        else {
+            return Err(KernelError::invalid_parameter(
+                "extrude_parameters",
+                "Invalid parameters for extrude feature",
+            ));
+        };
+        let offset = vec3::normalize(*direction)
+            .map(|d| vec3::scale(d, *distance))
+            .ok_or_else(|| {
+                KernelError::invalid_parameter(
+                    "extrude_direction",
+                    "Direction vector cannot be zero",
+                )
+            })?;
+
+        let mut points = profile.to_vec();
+        points.extend(profile.iter().map(|p| vec3::add(*p, offset)));
+        Ok(points)
+    }
+
+    /// Sweep a revolve profile about the revolve axis
+    ///
+    /// Returns `steps + 1` copies of `profile`, rotated from 0 to the full
+    /// revolve angle in equal increments, one copy after another.
+    pub fn revolve_profile(&self, profile: &[[f64; 3]], steps: u32) -> KernelResult<Vec<[f64; 3]>> {
+        let FeatureParameters::Revolve {
+            angle,
+            angle_unit,
+            axis,
+            axis_point,
+        } = self
+        else {
+            return Err(KernelError::invalid_parameter(
+                "revolve_parameters",
+                "Invalid parameters for revolve feature",
+            ));
+        };
+        if steps == 0 {
+            return Err(KernelError::invalid_parameter(
+                "steps",
+                "Steps must be positive",
+            ));
+        }
+
+        let length = (axis[0].powi(2) + axis[1].powi(2) + axis[2].powi(2)).sqrt();
+        if length < 1e-9 {
+            return Err(KernelError::invalid_parameter(
+                "revolve_axis",
+                "Axis vector cannot be zero",
+            ));
+        }
+        let k = axis.map(|c| c / length);
+        let total = angle_unit.to_radians(*angle);
+
+        let mut points = Vec::with_capacity(profile.len() * (steps as usize + 1));
+        for step in 0..=steps {
+            let (sin, cos) = (total * step as f64 / steps as f64).sin_cos();
+            for point in profile {
+                // Rodrigues' rotation of the offset from the axis point
+                let v = [0, 1, 2].map(|i| point[i] - axis_point[i]);
+                let k_cross_v = [
+                    k[1] * v[2] - k[2] * v[1],
+                    k[2] * v[0] - k[0] * v[2],
+                    k[0] * v[1] - k[1] * v[0],
+                ];
+                let k_dot_v = k[0] * v[0] + k[1] * v[1] + k[2] * v[2];
+                points.push([0, 1, 2].map(|i| {
+                    axis_point[i] + v[i] * cos + k_cross_v[i] * sin + k[i] * k_dot_v * (1.0 - cos)
+                }));
+            }
+        }
+        Ok(points)
+    }
+
+    /// Translation of each pattern instance from the patterned feature
+    ///
+    /// The first offset is zero (the original). Linear patterns step
+    /// `spacing` along `direction`; grids step along `dir_u` first, so
+    /// instance `(i, j)` is at index `j * count_u + i`. Circular patterns
+    /// rotate their instances and have no pure offsets.
+    pub fn pattern_offsets(&self) -> KernelResult<Vec<[f64; 3]>> {
+        let FeatureParameters::Pattern {
+            count,
+            spacing,
+            direction,
+            pattern_type,
+        } = self
+        else {
+            return Err(KernelError::invalid_parameter(
+                "pattern_parameters",
+                "Invalid parameters for pattern feature",
+            ));
+        };
+
+        let unit = |v: &[f64; 3]| -> KernelResult<[f64; 3]> {
+            let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+            if length < 1e-9 {
+                return Err(KernelError::invalid_parameter(
+                    "pattern_direction",
+                    "Pattern direction cannot be zero",
+                ));
+            }
+            Ok(v.map(|c| c / length))
+        };
+
+        match pattern_type {
+            PatternType::Linear => {
+                let d = unit(direction)?;
+                Ok((0..*count)
+                    .map(|i| d.map(|c| c * spacing * i as f64))
+                    .collect())
+            }
+            PatternType::Grid {
+                dir_u,
+                dir_v,
+                count_u,
+                count_v,
+                spacing_u,
+                spacing_v,
+            } => {
+                let (u, v) = (unit(dir_u)?, unit(dir_v)?);
+                let mut offsets = Vec::with_capacity((*count_u * *count_v) as usize);
+                for j in 0..*count_v {
+                    for i in 0..*count_u {
+                        let (a, b) = (spacing_u * i as f64, spacing_v * j as f64);
+                        offsets.push([0, 1, 2].map(|k| u[k] * a + v[k] * b));
+                    }
+                }
+                Ok(offsets)
+            }
+            PatternType::Circular { .. } => Err(KernelError::invalid_parameter(
+                "pattern_type",
+                "Circular patterns rotate their instances",
+            )),
+        }
+    }
+}
+
+fn validate_grid(
+    dir_u: [f64; 3],
+    dir_v: [f64; 3],
+    counts: [u32; 2],
+    spacings: [f64; 2],
+) -> KernelResult<()> {
+    if counts.contains(&0) {
+        return Err(KernelError::invalid_parameter(
+            "pattern_count",
+            "Grid counts must be at least 1",
+        ));
+    }
+    if counts.iter().all(|&count| count == 1) {
+        return Err(KernelError::invalid_parameter(
+            "pattern_count",
+            "Grid must repeat along at least one direction",
+        ));
+    }
+
+    for ((direction, count), spacing) in [dir_u, dir_v].iter().zip(counts).zip(spacings) {
+        if count > 1 && !(spacing.is_finite() && spacing > 0.0) {
+            return Err(KernelError::invalid_parameter(
+                "pattern_spacing",
+                "Pattern spacing must be positive",
+            ));
+        }
+        if direction.iter().map(|c| c * c).sum::<f64>().sqrt() < 1e-9 {
+            return Err(KernelError::invalid_parameter(
+                "pattern_direction",
+                "Grid direction cannot be zero",
+            ));
+        }
+    }
+
+    let cross = [
+        dir_u[1] * dir_v[2] - dir_u[2] * dir_v[1],
+        dir_u[2] * dir_v[0] - dir_u[0] * dir_v[2],
+        dir_u[0] * dir_v[1] - dir_u[1] * dir_v[0],
+    ];
+    let norm = |v: [f64; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if norm(cross) < 1e-9 * norm(dir_u) * norm(dir_v) {
+        return Err(KernelError::invalid_parameter(
+            "pattern_direction",
+            "Grid directions must not be parallel",
+        ));
+    }
+    Ok(())
+}
+
+/// Unit of an angular feature parameter
+///
+/// Feature angles default to degrees; transforms work in radians, so
+/// angles are converted wherever they reach the geometry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians,
+}
+
+impl AngleUnit {
+    /// Convert an angle given in this unit to radians
+    pub fn to_radians(self, angle: f64) -> f64 {
+        match self {
+            AngleUnit::Degrees => angle.to_radians(),
+            AngleUnit::Radians => angle,
+        }
+    }
+
+    /// Convert an angle given in this unit to degrees
+    pub fn to_degrees(self, angle: f64) -> f64 {
+        match self {
+            AngleUnit::Degrees => angle,
+            AngleUnit::Radians => angle.to_degrees(),
+        }
+    }
+}
+
 /// Types of holes for manufacturing awareness
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum HoleType {
     /// Simple through hole
     Through,
-    /// Blind hole (partial depth)
-    Blind,
+    /// Blind hole (partial depth, measured to the deepest point)
+    Blind {
+        #[serde(default)]
+        bottom: BlindBottom,
+    },
     /// Counterbore hole
     Counterbore { cb_diameter: f64, cb_depth: f64 },
     /// Countersink hole
@@ -502,6 +844,35 @@ pub enum HoleType {
     },
 }
 
+/// Bottom shape of a blind hole
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum BlindBottom {
+    /// Flat bottom, as left by an end mill
+    #[default]
+    Flat,
+    /// Drill point with the included angle in degrees (118° or 135° for
+    /// standard twist drills)
+    Conical { angle: f64 },
+}
+
+impl BlindBottom {
+    /// Axial length of the point of a hole of `diameter` (zero when flat)
+    pub fn point_height(&self, diameter: f64) -> KernelResult<f64> {
+        match *self {
+            BlindBottom::Flat => Ok(0.0),
+            BlindBottom::Conical { angle } => {
+                if !(angle > 0.0 && angle < 180.0) {
+                    return Err(KernelError::invalid_parameter(
+                        "point_angle",
+                        format!("Point angle must be between 0° and 180° (got {})", angle),
+                    ));
+                }
+                Ok(diameter / 2.0 / (angle / 2.0).to_radians().tan())
+            }
+        }
+    }
+}
+
 /// Edge selection methods for fillets and chamfers
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EdgeSelection {
@@ -536,6 +907,16 @@ pub enum PatternType {
     Linear,
     /// Circular pattern
     Circular { axis: [f64; 3], center: [f64; 3] },
+    /// Two-direction grid, e.g. a hole array on a plate; the pattern's own
+    /// `count`, `spacing` and `direction` are not used
+    Grid {
+        dir_u: [f64; 3],
+        dir_v: [f64; 3],
+        count_u: u32,
+        count_v: u32,
+        spacing_u: f64,
+        spacing_v: f64,
+    },
 }
 
 /// Manufacturing constraints for features
@@ -719,7 +1100,9 @@ mod tests {
             depth: 15.0, // Aspect ratio 15:1 > limit of 10:1
             position: [0.0, 0.0, 0.0],
             direction: [0.0, 0.0, 1.0],
-            hole_type: HoleType::Blind,
+            hole_type: HoleType::Blind {
+                bottom: BlindBottom::Flat,
+            },
         };
 
         let feature = Feature::new("hole1".to_string(), FeatureType::Hole, target_id, params);
@@ -761,4 +1144,130 @@ mod tests {
         assert_eq!(requirements[0].min_diameter, 8.0);
         assert_eq!(requirements[0].min_length, 20.0);
     }
+
+    #[test]
+    fn test_grid_pattern_of_holes() {
+        let grid = |count_u, count_v| FeatureParameters::Pattern {
+            count: 0,
+            spacing: 0.0,
+            direction: [0.0; 3],
+            pattern_type: PatternType::Grid {
+                dir_u: [1.0, 0.0, 0.0],
+                dir_v: [0.0, 2.0, 0.0],
+                count_u,
+                count_v,
+                spacing_u: 10.0,
+                spacing_v: 15.0,
+            },
+        };
+        let pattern = |params| {
+            Feature::new(
+                "holes".to_string(),
+                FeatureType::Pattern,
+                NodeId::from_user_string("hole1"),
+                params,
+            )
+        };
+
+        let feature = pattern(grid(3, 2));
+        assert!(feature.validate().is_ok());
+
+        let hole = [5.0, 5.0, 0.0];
+        let positions: Vec<[f64; 3]> = feature
+            .parameters
+            .pattern_offsets()
+            .unwrap()
+            .iter()
+            .map(|o| [0, 1, 2].map(|k| hole[k] + o[k]))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                [5.0, 5.0, 0.0],
+                [15.0, 5.0, 0.0],
+                [25.0, 5.0, 0.0],
+                [5.0, 20.0, 0.0],
+                [15.0, 20.0, 0.0],
+                [25.0, 20.0, 0.0],
+            ]
+        );
+
+        // A single row is still a pattern; a single instance is not
+        assert!(pattern(grid(4, 1)).validate().is_ok());
+        assert!(pattern(grid(1, 1)).validate().is_err());
+        assert!(pattern(grid(0, 3)).validate().is_err());
+    }
+
+    #[test]
+    fn test_revolve_angle_units_agree() {
+        let revolve = |angle, angle_unit| FeatureParameters::Revolve {
+            angle,
+            angle_unit,
+            axis: [0.0, 0.0, 1.0],
+            axis_point: [1.0, 0.0, 0.0],
+        };
+        let degrees = revolve(90.0, AngleUnit::Degrees);
+        let radians = revolve(std::f64::consts::FRAC_PI_2, AngleUnit::Radians);
+
+        let profile = [[3.0, 0.0, 0.0], [3.0, 0.0, 5.0]];
+        let a = degrees.revolve_profile(&profile, 4).unwrap();
+        let b = radians.revolve_profile(&profile, 4).unwrap();
+        assert_eq!(a.len(), 10);
+        for (p, q) in a.iter().zip(&b) {
+            assert!((0..3).all(|i| (p[i] - q[i]).abs() < 1e-12));
+        }
+        // The last copy is a quarter turn about x = 1
+        assert!((a[8][0] - 1.0).abs() < 1e-12 && (a[8][1] - 2.0).abs() < 1e-12);
+
+        // Both pass validation; 90 radians is out of range
+        let target_id = NodeId::from_user_string("profile1");
+        for params in [degrees, radians] {
+            let feature = Feature::new(
+                "rev1".to_string(),
+                FeatureType::Revolve,
+                target_id.clone(),
+                params,
+            );
+            assert!(feature.validate().is_ok());
+        }
+        let feature = Feature::new(
+            "rev1".to_string(),
+            FeatureType::Revolve,
+            target_id,
+            revolve(90.0, AngleUnit::Radians),
+        );
+        assert!(feature.validate().is_err());
+    }
+
+    #[test]
+    fn test_normalize_extrude_direction() {
+        let mut feature = Feature::new(
+            "ext1".to_string(),
+            FeatureType::Extrude,
+            NodeId::from_user_string("sketch1"),
+            FeatureParameters::Extrude {
+                distance: 10.0,
+                direction: [0.0, 0.0, 2.0],
+                draft_angle: None,
+                taper_angle: None,
+            },
+        );
+        feature.normalize().unwrap();
+        let FeatureParameters::Extrude { direction, .. } = &feature.parameters else {
+            unreachable!()
+        };
+        assert_eq!(*direction, [0.0, 0.0, 1.0]);
+
+        let profile = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let points = feature.parameters.extrude_profile(&profile).unwrap();
+        assert_eq!(points.len(), 6);
+        assert!(points[3..].iter().all(|p| p[2] == 10.0));
+
+        // A zero direction still fails validation first
+        let FeatureParameters::Extrude { direction, .. } = &mut feature.parameters else {
+            unreachable!()
+        };
+        *direction = [0.0; 3];
+        assert!(feature.normalize().is_err());
+    }
 }
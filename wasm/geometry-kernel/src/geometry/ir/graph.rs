@@ -6,7 +6,7 @@
 use crate::errors::{KernelError, KernelResult};
 use crate::geometry::ir::node::{IRNode, NodeId};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
 /// Dependency graph for IR nodes with topological ordering
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +20,10 @@ pub struct IRGraph {
     /// Reverse dependencies: node_id -> [nodes this node depends on]
     reverse_deps: HashMap<NodeId, HashSet<NodeId>>,
 
+    /// Nodes designated as the graph's final result, in marking order
+    #[serde(default)]
+    outputs: Vec<NodeId>,
+
     /// Cached topological order
     cached_order: Option<Vec<NodeId>>,
 
@@ -34,15 +38,23 @@ impl IRGraph {
             nodes: HashMap::new(),
             forward_deps: HashMap::new(),
             reverse_deps: HashMap::new(),
+            outputs: Vec::new(),
             cached_order: None,
             modification_count: 0,
         }
     }
 
     /// Add a node to the graph
+    ///
+    /// If a node with the same ID already exists it is replaced via
+    /// `update_node`, so existing dependents stay attached.
     pub fn add_node(&mut self, node: IRNode) -> KernelResult<()> {
         let node_id = node.id.clone();
 
+        if self.nodes.contains_key(&node_id) {
+            return self.update_node(node);
+        }
+
         // Validate dependencies exist
         for dep_id in &node.dependencies {
             if !self.nodes.contains_key(dep_id) {
@@ -62,31 +74,82 @@ impl IRGraph {
             )));
         }
 
-        // Remove existing node if updating
-        if self.nodes.contains_key(&node_id) {
-            self.remove_node(&node_id)?;
+        // Update dependency mappings
+        self.link_dependencies(&node_id, &node.dependencies);
+
+        // Add the node
+        self.nodes.insert(node_id, node);
+        self.invalidate_cache();
+
+        Ok(())
+    }
+
+    /// Replace an existing node, rewriting its dependency edges
+    ///
+    /// Dependencies are re-validated and checked for cycles before anything
+    /// is modified, so a rejected update leaves the graph untouched. Nodes
+    /// that depend on the updated node keep their edges.
+    pub fn update_node(&mut self, node: IRNode) -> KernelResult<()> {
+        let node_id = node.id.clone();
+
+        if !self.nodes.contains_key(&node_id) {
+            return Err(KernelError::node_not_found(node_id.as_str()));
         }
 
-        // Update dependency mappings
+        // Validate dependencies exist
         for dep_id in &node.dependencies {
-            self.forward_deps
-                .entry(dep_id.clone())
-                .or_insert_with(HashSet::new)
-                .insert(node_id.clone());
+            if !self.nodes.contains_key(dep_id) {
+                return Err(KernelError::invalid_graph(format!(
+                    "Dependency {} not found for node {}",
+                    dep_id.as_str(),
+                    node_id.as_str()
+                )));
+            }
+        }
 
-            self.reverse_deps
-                .entry(node_id.clone())
-                .or_insert_with(HashSet::new)
-                .insert(dep_id.clone());
+        // A cycle appears if any new dependency is downstream of this node
+        let mut visited = HashSet::new();
+        let mut rec_stack = HashSet::new();
+        for dep_id in &node.dependencies {
+            if self.has_path_to(&node_id, dep_id, &mut visited, &mut rec_stack)? {
+                return Err(KernelError::invalid_graph(format!(
+                    "Updating node {} would create a circular dependency",
+                    node_id.as_str()
+                )));
+            }
         }
 
-        // Add the node
+        // Drop the old dependency edges
+        if let Some(old_deps) = self.reverse_deps.remove(&node_id) {
+            for dep_id in old_deps {
+                if let Some(forward_set) = self.forward_deps.get_mut(&dep_id) {
+                    forward_set.remove(&node_id);
+                    if forward_set.is_empty() {
+                        self.forward_deps.remove(&dep_id);
+                    }
+                }
+            }
+        }
+
+        // Insert the new ones
+        self.link_dependencies(&node_id, &node.dependencies);
+
         self.nodes.insert(node_id, node);
         self.invalidate_cache();
 
         Ok(())
     }
 
+    /// Suppress or unsuppress a node, keeping it and its edges in the graph
+    pub fn set_suppressed(&mut self, node_id: &NodeId, suppressed: bool) -> KernelResult<()> {
+        let node = self
+            .nodes
+            .get_mut(node_id)
+            .ok_or_else(|| KernelError::node_not_found(node_id.as_str()))?;
+        node.suppressed = suppressed;
+        Ok(())
+    }
+
     /// Remove a node from the graph
     pub fn remove_node(&mut self, node_id: &NodeId) -> KernelResult<()> {
         if !self.nodes.contains_key(node_id) {
@@ -117,18 +180,47 @@ impl IRGraph {
         }
 
         self.forward_deps.remove(node_id);
+        self.outputs.retain(|id| id != node_id);
         self.nodes.remove(node_id);
         self.invalidate_cache();
 
         Ok(())
     }
 
+    /// Designate a node as (part of) the graph's final result
+    ///
+    /// With outputs marked, rebuilding returns exactly their geometry
+    /// instead of guessing from the nodes nothing else consumes. Marking a
+    /// node twice has no further effect.
+    pub fn mark_output(&mut self, node_id: &NodeId) -> KernelResult<()> {
+        if !self.nodes.contains_key(node_id) {
+            return Err(KernelError::node_not_found(node_id.as_str()));
+        }
+        if !self.outputs.contains(node_id) {
+            self.outputs.push(node_id.clone());
+        }
+        Ok(())
+    }
+
+    /// Remove a node's output designation
+    pub fn unmark_output(&mut self, node_id: &NodeId) {
+        self.outputs.retain(|id| id != node_id);
+    }
+
+    /// Nodes designated as outputs, in marking order
+    pub fn outputs(&self) -> &[NodeId] {
+        &self.outputs
+    }
+
     /// Get a node by ID
     pub fn get_node(&self, node_id: &NodeId) -> Option<&IRNode> {
         self.nodes.get(node_id)
     }
 
     /// Get mutable reference to a node by ID
+    ///
+    /// Changing `dependencies` through this reference bypasses the
+    /// dependency maps; use `update_node` for that instead.
     pub fn get_node_mut(&mut self, node_id: &NodeId) -> Option<&mut IRNode> {
         if self.nodes.contains_key(node_id) {
             self.invalidate_cache();
@@ -151,46 +243,45 @@ impl IRGraph {
         Ok(self.cached_order.as_ref().unwrap())
     }
 
-    /// Get nodes that depend on the given node
+    /// Get topological ordering without borrowing the graph mutably
+    ///
+    /// Returns the cached order when available, otherwise computes a fresh
+    /// one without storing it.
+    pub fn evaluation_order(&self) -> KernelResult<Vec<NodeId>> {
+        match &self.cached_order {
+            Some(order) => Ok(order.clone()),
+            None => self.compute_topological_order(),
+        }
+    }
+
+    /// Get nodes that depend on the given node, sorted by ID
     pub fn get_dependents(&self, node_id: &NodeId) -> Vec<&NodeId> {
-        self.forward_deps
-            .get(node_id)
-            .map(|set| set.iter().collect())
-            .unwrap_or_default()
+        sorted_ids(self.forward_deps.get(node_id).into_iter().flatten())
     }
 
-    /// Get nodes that the given node depends on
+    /// Get nodes that the given node depends on, sorted by ID
     pub fn get_dependencies(&self, node_id: &NodeId) -> Vec<&NodeId> {
-        self.reverse_deps
-            .get(node_id)
-            .map(|set| set.iter().collect())
-            .unwrap_or_default()
+        sorted_ids(self.reverse_deps.get(node_id).into_iter().flatten())
     }
 
-    /// Get all root nodes (nodes with no dependencies)
+    /// Get all root nodes (nodes with no dependencies), sorted by ID
     pub fn get_root_nodes(&self) -> Vec<&NodeId> {
-        self.nodes
-            .keys()
-            .filter(|node_id| {
-                self.reverse_deps
-                    .get(node_id)
-                    .map(|deps| deps.is_empty())
-                    .unwrap_or(true)
-            })
-            .collect()
+        sorted_ids(self.nodes.keys().filter(|node_id| {
+            self.reverse_deps
+                .get(node_id)
+                .map(|deps| deps.is_empty())
+                .unwrap_or(true)
+        }))
     }
 
-    /// Get all leaf nodes (nodes with no dependents)
+    /// Get all leaf nodes (nodes with no dependents), sorted by ID
     pub fn get_leaf_nodes(&self) -> Vec<&NodeId> {
-        self.nodes
-            .keys()
-            .filter(|node_id| {
-                self.forward_deps
-                    .get(node_id)
-                    .map(|deps| deps.is_empty())
-                    .unwrap_or(true)
-            })
-            .collect()
+        sorted_ids(self.nodes.keys().filter(|node_id| {
+            self.forward_deps
+                .get(node_id)
+                .map(|deps| deps.is_empty())
+                .unwrap_or(true)
+        }))
     }
 
     /// Validate the entire graph for consistency
@@ -239,6 +330,9 @@ impl IRGraph {
     }
 
     /// Compute strongly connected components
+    ///
+    /// Nodes and their dependents are visited in `NodeId` order and each
+    /// component is sorted, so the result is identical across runs.
     pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
         let mut index_counter = 0;
         let mut stack = Vec::new();
@@ -247,7 +341,10 @@ impl IRGraph {
         let mut on_stack: HashSet<NodeId> = HashSet::new();
         let mut components = Vec::new();
 
-        for node_id in self.nodes.keys() {
+        let mut node_ids: Vec<&NodeId> = self.nodes.keys().collect();
+        node_ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        for node_id in node_ids {
             if !indices.contains_key(node_id) {
                 self.tarjan_scc(
                     node_id,
@@ -323,6 +420,20 @@ impl IRGraph {
         self.modification_count += 1;
     }
 
+    fn link_dependencies(&mut self, node_id: &NodeId, dependencies: &[NodeId]) {
+        for dep_id in dependencies {
+            self.forward_deps
+                .entry(dep_id.clone())
+                .or_default()
+                .insert(node_id.clone());
+
+            self.reverse_deps
+                .entry(node_id.clone())
+                .or_default()
+                .insert(dep_id.clone());
+        }
+    }
+
     fn would_create_cycle(
         &self,
         new_node_id: &NodeId,
@@ -374,10 +485,13 @@ impl IRGraph {
     fn compute_topological_order(&self) -> KernelResult<Vec<NodeId>> {
         let mut in_degree: HashMap<NodeId, usize> = HashMap::new();
         let mut result = Vec::new();
-        let mut queue = VecDeque::new();
+
+        // Ready nodes keyed by (priority, id) so ties resolve deterministically
+        // without ever placing a node ahead of its dependencies
+        let mut ready: BTreeMap<(u32, String), NodeId> = BTreeMap::new();
 
         // Initialize in-degrees
-        for node_id in self.nodes.keys() {
+        for (node_id, node) in &self.nodes {
             let degree = self
                 .reverse_deps
                 .get(node_id)
@@ -386,14 +500,15 @@ impl IRGraph {
             in_degree.insert(node_id.clone(), degree);
 
             if degree == 0 {
-                queue.push_back(node_id.clone());
+                ready.insert(
+                    (node.evaluation_priority(), node_id.as_str().to_string()),
+                    node_id.clone(),
+                );
             }
         }
 
         // Process nodes in topological order
-        while let Some(node_id) = queue.pop_front() {
-            result.push(node_id.clone());
-
+        while let Some((_, node_id)) = ready.pop_first() {
             // Process dependents
             if let Some(dependents) = self.forward_deps.get(&node_id) {
                 for dependent_id in dependents {
@@ -401,10 +516,19 @@ impl IRGraph {
                     *current_degree -= 1;
 
                     if *current_degree == 0 {
-                        queue.push_back(dependent_id.clone());
+                        let dependent = self.nodes.get(dependent_id).unwrap();
+                        ready.insert(
+                            (
+                                dependent.evaluation_priority(),
+                                dependent_id.as_str().to_string(),
+                            ),
+                            dependent_id.clone(),
+                        );
                     }
                 }
             }
+
+            result.push(node_id);
         }
 
         // Check for cycles
@@ -414,17 +538,6 @@ impl IRGraph {
             ));
         }
 
-        // Sort by evaluation priority for deterministic ordering
-        result.sort_by(|a, b| {
-            let node_a = self.nodes.get(a).unwrap();
-            let node_b = self.nodes.get(b).unwrap();
-
-            node_a
-                .evaluation_priority()
-                .cmp(&node_b.evaluation_priority())
-                .then_with(|| a.as_str().cmp(b.as_str())) // Tie-breaker for determinism
-        });
-
         Ok(result)
     }
 
@@ -494,6 +607,9 @@ impl IRGraph {
         on_stack.insert(node_id.clone());
 
         if let Some(forward_deps) = self.forward_deps.get(node_id) {
+            let mut forward_deps: Vec<&NodeId> = forward_deps.iter().collect();
+            forward_deps.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
             for dependent_id in forward_deps {
                 if !indices.contains_key(dependent_id) {
                     self.tarjan_scc(
@@ -529,6 +645,7 @@ impl IRGraph {
                     break;
                 }
             }
+            component.sort_by(|a, b| a.as_str().cmp(b.as_str()));
             components.push(component);
         }
     }
@@ -613,6 +730,14 @@ impl Default for IRGraph {
     }
 }
 
+/// Collect node IDs sorted by their string form, so accessors backed by
+/// hash maps list nodes in the same order on every run
+fn sorted_ids<'a>(ids: impl Iterator<Item = &'a NodeId>) -> Vec<&'a NodeId> {
+    let mut ids: Vec<&NodeId> = ids.collect();
+    ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    ids
+}
+
 /// Graph statistics for analysis and debugging
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphStats {
@@ -714,6 +839,32 @@ mod tests {
         assert!(pos1 < pos2, "Dependencies should come before dependents");
     }
 
+    #[test]
+    fn test_topological_ordering_puts_dependencies_before_priority() {
+        let mut graph = IRGraph::new();
+
+        // An analysis node (lowest priority) that a primitive depends on
+        let mut analysis = create_test_node("analysis", vec![]);
+        analysis.node_type = NodeType::Analysis;
+        let analysis_id = analysis.id.clone();
+        graph.add_node(analysis).unwrap();
+
+        let late = create_test_node("late", vec![analysis_id.clone()]);
+        let late_id = late.id.clone();
+        graph.add_node(late).unwrap();
+
+        let early = create_test_node("early", vec![]);
+        let early_id = early.id.clone();
+        graph.add_node(early).unwrap();
+
+        // Among ready nodes the primitive goes first, but the dependent
+        // primitive still waits for the analysis node
+        assert_eq!(
+            *graph.topological_order().unwrap(),
+            vec![early_id, analysis_id, late_id]
+        );
+    }
+
     #[test]
     fn test_root_and_leaf_nodes() {
         let mut graph = IRGraph::new();
@@ -760,4 +911,114 @@ mod tests {
         assert!(subgraph.nodes().contains_key(&id_b));
         assert!(subgraph.nodes().contains_key(&id_c));
     }
+
+    #[test]
+    fn test_update_node_dependencies() {
+        let mut graph = IRGraph::new();
+
+        // z and y are independent roots, x depends on z
+        let node_z = create_test_node("z", vec![]);
+        let id_z = node_z.id.clone();
+        graph.add_node(node_z).unwrap();
+
+        let node_y = create_test_node("y", vec![]);
+        let id_y = node_y.id.clone();
+        graph.add_node(node_y).unwrap();
+
+        let node_x = create_test_node("x", vec![id_z.clone()]);
+        let id_x = node_x.id.clone();
+        graph.add_node(node_x).unwrap();
+
+        // Rewire z to depend on y while x still depends on z
+        graph
+            .update_node(create_test_node("z", vec![id_y.clone()]))
+            .unwrap();
+        assert!(graph.validate().is_ok());
+        assert_eq!(graph.get_dependencies(&id_z), vec![&id_y]);
+        assert_eq!(graph.get_dependents(&id_z), vec![&id_x]);
+
+        let order = graph.topological_order().unwrap().clone();
+        let pos = |id: &NodeId| order.iter().position(|o| o == id).unwrap();
+        assert!(pos(&id_y) < pos(&id_z));
+        assert!(pos(&id_z) < pos(&id_x));
+
+        // Making y depend on x would close the loop y -> z -> x -> y
+        let result = graph.update_node(create_test_node("y", vec![id_x.clone()]));
+        assert!(result.is_err());
+        assert!(graph.get_dependencies(&id_y).is_empty());
+        assert!(graph.validate().is_ok());
+
+        // Updating a node that does not exist is an error
+        assert!(graph
+            .update_node(create_test_node("missing", vec![]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_scc_order_is_deterministic() {
+        // Two chains (a <- b, c <- d) and a lone node e, added in two orders
+        let build = |names: &[&str]| {
+            let mut graph = IRGraph::new();
+            let id = |name: &str| create_test_node(name, vec![]).id;
+            for &name in names {
+                let deps = match name {
+                    "b" => vec![id("a")],
+                    "d" => vec![id("c")],
+                    _ => vec![],
+                };
+                graph.add_node(create_test_node(name, deps)).unwrap();
+            }
+            graph
+        };
+
+        let graph = build(&["a", "b", "c", "d", "e"]);
+        let components = graph.strongly_connected_components();
+        assert_eq!(components.len(), 5);
+        for _ in 0..10 {
+            assert_eq!(graph.strongly_connected_components(), components);
+        }
+
+        let reordered = build(&["e", "c", "a", "d", "b"]);
+        assert_eq!(reordered.strongly_connected_components(), components);
+    }
+
+    #[test]
+    fn test_root_leaf_and_dependent_order_is_sorted() {
+        // Three roots feed "hub", which feeds three leaves
+        let mut graph = IRGraph::new();
+        let roots: Vec<NodeId> = ["r3", "r1", "r2"]
+            .iter()
+            .map(|&name| {
+                let node = create_test_node(name, vec![]);
+                let id = node.id.clone();
+                graph.add_node(node).unwrap();
+                id
+            })
+            .collect();
+        let hub = create_test_node("hub", roots.clone());
+        let hub_id = hub.id.clone();
+        graph.add_node(hub).unwrap();
+        for name in ["l2", "l3", "l1"] {
+            graph
+                .add_node(create_test_node(name, vec![hub_id.clone()]))
+                .unwrap();
+        }
+
+        let is_sorted =
+            |ids: &[&NodeId]| ids.windows(2).all(|w| w[0].as_str() <= w[1].as_str());
+        let found_roots = graph.get_root_nodes();
+        let found_leaves = graph.get_leaf_nodes();
+        let dependents = graph.get_dependents(&hub_id);
+        assert_eq!(found_roots.len(), 3);
+        assert_eq!(found_leaves.len(), 3);
+        assert_eq!(dependents, found_leaves);
+        assert!(is_sorted(&found_roots));
+        assert!(is_sorted(&found_leaves));
+        assert!(is_sorted(&graph.get_dependencies(&hub_id)));
+
+        for _ in 0..10 {
+            assert_eq!(graph.get_root_nodes(), found_roots);
+            assert_eq!(graph.get_leaf_nodes(), found_leaves);
+        }
+    }
 }
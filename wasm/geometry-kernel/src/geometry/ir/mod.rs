@@ -23,13 +23,17 @@
 //! Parametric features like extrusions, holes, and fillets that are manufacturing-aware
 //! and replayable from parameters.
 //!
+//! ## Bill of materials (`bom`)
+//! Assembly graphs mark solids as parts with `part` analysis nodes, which
+//! aggregate into BOM lines by part number.
+//!
 //! ## Validation (`validate`)
 //! Comprehensive structural and semantic validation ensures IR correctness
 //! and manufacturability constraints.
 //!
 //! # Usage Example
 //!
-//! ```rust
+//! ```ignore
 //! use crate::geometry::ir::{IRGraph, IRNode, NodeType, NodeContent};
 //!
 //! // Create a new IR graph
@@ -55,6 +59,7 @@
 //! let result = validator.validate_graph(&graph)?;
 //! ```
 
+pub mod bom;
 pub mod feature;
 pub mod graph;
 pub mod node;
@@ -68,10 +73,12 @@ pub use node::{
 
 pub use graph::{GraphStats, IRGraph};
 
+pub use bom::{bom, BomLine, PART_ANALYSIS};
+
 pub use feature::{
-    BlendType, ConstraintType, EdgeSelection, Feature, FeatureParameters, FeatureType, HoleType,
-    ManufacturingConstraint, ManufacturingProcess, PatternType, ToleranceGrade,
-    ToolAccessRequirement,
+    AngleUnit, BlendType, BlindBottom, ConstraintType, EdgeSelection, Feature, FeatureParameters,
+    FeatureType, HoleType, ManufacturingConstraint, ManufacturingProcess, PatternType,
+    ToleranceGrade, ToolAccessRequirement,
 };
 
 pub use validate::{
@@ -108,6 +108,10 @@ pub struct IRNode {
 
     /// Metadata for tracing and debugging
     pub metadata: NodeMetadata,
+
+    /// Kept in the graph but skipped by rebuilds (not part of the identity)
+    #[serde(default)]
+    pub suppressed: bool,
 }
 
 impl IRNode {
@@ -129,6 +133,7 @@ impl IRNode {
             content,
             dependencies,
             metadata,
+            suppressed: false,
         })
     }
 
@@ -150,6 +155,7 @@ impl IRNode {
             content,
             dependencies,
             metadata,
+            suppressed: false,
         })
     }
 
@@ -327,7 +333,7 @@ mod tests {
     #[test]
     fn test_node_id_deterministic() {
         let content_hash = "abc123";
-        let deps = vec![];
+        let deps: Vec<NodeId> = vec![];
 
         let id1 = NodeId::new("primitive", content_hash, &[]);
         let id2 = NodeId::new("primitive", content_hash, &[]);
@@ -142,6 +142,8 @@ pub enum ValidationWarningType {
     RobustnessIssue,
     /// Style/convention deviation
     StyleIssue,
+    /// Node that is unconnected to the rest of the graph
+    OrphanNode,
 }
 
 /// Warning severity levels
@@ -304,6 +306,13 @@ impl IRValidator {
         Ok(result)
     }
 
+    /// Run only the manufacturing pass of [`validate_graph`](Self::validate_graph)
+    pub fn analyze_manufacturing(&self, graph: &IRGraph) -> KernelResult<ManufacturingAnalysis> {
+        let mut result = ValidationResult::new();
+        self.validate_manufacturing_constraints(graph, &mut result)?;
+        Ok(result.manufacturing_analysis)
+    }
+
     /// Validate a single IR node
     pub fn validate_node(&self, node: &IRNode) -> KernelResult<ValidationResult> {
         let start_time = std::time::Instant::now();
@@ -391,6 +400,37 @@ impl IRValidator {
             });
         }
 
+        // Check for orphan nodes: nothing references them and they reference
+        // nothing. A lone node is the graph's result, so only flag orphans
+        // sitting next to other nodes.
+        if graph.nodes().len() > 1 {
+            let mut orphans: Vec<&IRNode> = graph
+                .nodes()
+                .values()
+                .filter(|node| {
+                    node.dependencies.is_empty()
+                        && graph.get_dependents(&node.id).is_empty()
+                        && !graph.outputs().contains(&node.id)
+                })
+                .collect();
+            orphans.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+
+            for node in orphans {
+                result.add_warning(ValidationWarning {
+                    warning_type: ValidationWarningType::OrphanNode,
+                    node_id: Some(node.id.clone()),
+                    message: format!(
+                        "Node {} is not connected to any other node",
+                        node.id.as_str()
+                    ),
+                    severity: WarningSeverity::Medium,
+                    suggestion: Some(
+                        "Use the node, remove it, or mark it as a graph output".to_string(),
+                    ),
+                });
+            }
+        }
+
         Ok(())
     }
 
@@ -810,6 +850,65 @@ mod tests {
         assert_eq!(result.metrics.nodes_validated, 1);
     }
 
+    #[test]
+    fn test_orphan_node_warning() {
+        let mut validator = IRValidator::new();
+        let mut graph = IRGraph::new();
+
+        let base = create_test_primitive();
+        let base_id = base.id.clone();
+        graph.add_node(base).unwrap();
+        let fillet = IRNode::new(
+            NodeType::Feature,
+            NodeContent::Feature {
+                feature_type: "fillet".to_string(),
+                target_node: base_id.clone(),
+                parameters: [("radius".to_string(), serde_json::json!(1.0))]
+                    .into_iter()
+                    .collect(),
+            },
+            vec![base_id],
+            NodeMetadata::new(Some("fillet".to_string()), NodeSource::User),
+        )
+        .unwrap();
+        graph.add_node(fillet).unwrap();
+
+        let orphans = |result: &ValidationResult| {
+            result
+                .warnings
+                .iter()
+                .filter(|w| w.warning_type == ValidationWarningType::OrphanNode)
+                .filter_map(|w| w.node_id.clone())
+                .collect::<Vec<_>>()
+        };
+        let result = validator.validate_graph(&graph).unwrap();
+        assert!(orphans(&result).is_empty());
+
+        // A dangling extra primitive is flagged
+        let extra = IRNode::with_user_id(
+            "extra",
+            NodeType::Primitive,
+            NodeContent::Primitive {
+                primitive_type: "sphere".to_string(),
+                parameters: [("radius".to_string(), 3.0)].into_iter().collect(),
+                transform: None,
+            },
+            vec![],
+            NodeMetadata::new(Some("extra".to_string()), NodeSource::User),
+        )
+        .unwrap();
+        let extra_id = extra.id.clone();
+        graph.add_node(extra).unwrap();
+        let result = validator.validate_graph(&graph).unwrap();
+        assert!(result.is_valid);
+        assert_eq!(orphans(&result), vec![extra_id.clone()]);
+
+        // ...unless it is marked as an output
+        graph.mark_output(&extra_id).unwrap();
+        let result = validator.validate_graph(&graph).unwrap();
+        assert!(orphans(&result).is_empty());
+    }
+
     #[test]
     fn test_type_compatibility() {
         let validator = IRValidator::new();
@@ -0,0 +1,3 @@
+//! Small math helpers shared across the geometry code.
+
+pub mod vec3;
@@ -0,0 +1,88 @@
+//! Arithmetic on `[f64; 3]` points and vectors.
+//!
+//! Geometry code stores points as plain arrays; these helpers keep the
+//! vector algebra on them in one place so every module rounds and treats
+//! degenerate vectors the same way.
+
+use crate::geometry::constants;
+
+pub fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn scale(a: [f64; 3], s: f64) -> [f64; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+pub fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub fn length_sq(a: [f64; 3]) -> f64 {
+    dot(a, a)
+}
+
+pub fn length(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Unit vector along `a`, or `None` when `a` is shorter than
+/// [`constants::EPSILON`] (or not finite) and has no usable direction
+pub fn normalize(a: [f64; 3]) -> Option<[f64; 3]> {
+    let len = length(a);
+    (len > constants::EPSILON && len.is_finite()).then(|| scale(a, 1.0 / len))
+}
+
+/// [`normalize`], with `+Z` standing in for a degenerate vector as it does
+/// for normals throughout the kernel
+pub fn normalize_or_z(a: [f64; 3]) -> [f64; 3] {
+    normalize(a).unwrap_or([0.0, 0.0, 1.0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vec3_operations() {
+        let (a, b) = ([1.0, 2.0, 3.0], [4.0, -5.0, 6.0]);
+        assert_eq!(add(a, b), [5.0, -3.0, 9.0]);
+        assert_eq!(sub(a, b), [-3.0, 7.0, -3.0]);
+        assert_eq!(scale(a, -2.0), [-2.0, -4.0, -6.0]);
+        assert_eq!(dot(a, b), 12.0);
+
+        // Right-handed, and perpendicular to both inputs
+        assert_eq!(cross([1.0, 0.0, 0.0], [0.0, 1.0, 0.0]), [0.0, 0.0, 1.0]);
+        let c = cross(a, b);
+        assert_eq!(c, [27.0, 6.0, -13.0]);
+        assert_eq!((dot(c, a), dot(c, b)), (0.0, 0.0));
+
+        assert_eq!(length_sq([2.0, 3.0, 6.0]), 49.0);
+        assert_eq!(length([2.0, 3.0, 6.0]), 7.0);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let n = normalize([0.0, 3.0, -4.0]).unwrap();
+        assert!((n[1] - 0.6).abs() < 1e-15 && (n[2] + 0.8).abs() < 1e-15);
+        assert!((length(n) - 1.0).abs() < 1e-15);
+
+        assert_eq!(normalize([0.0; 3]), None);
+        assert_eq!(normalize([1e-12, 0.0, 0.0]), None);
+        assert_eq!(normalize([f64::INFINITY, 0.0, 0.0]), None);
+        assert_eq!(normalize_or_z([0.0; 3]), [0.0, 0.0, 1.0]);
+        assert_eq!(normalize_or_z([0.0, -2.0, 0.0]), [0.0, -1.0, 0.0]);
+    }
+}
@@ -14,10 +14,20 @@ pub mod ir;
 pub mod constraints;
 pub mod operations;
 pub mod primitives;
+pub mod supports;
+
+// Parametric constraint solving over constraint nodes
+pub mod solver;
 
 // Enhanced topology system
 pub mod topology;
 
+// Spatial acceleration shared by queries and booleans
+pub mod accel;
+
+// Vector arithmetic shared by the modules above
+pub mod math;
+
 // Re-export enhanced IR system as primary interface
 pub use ir::{
     Feature, FeatureParameters, FeatureType, IRGraph, IRNode, IRValidator, ManufacturingProcess,
@@ -27,24 +37,53 @@ pub use ir::{
 // Re-export analysis capabilities
 pub use analysis::{GeometricAnalysis, GeometricAnalyzer, MassProperties, MaterialProperties};
 
+pub use accel::{Bvh, NearestHit, RayHit};
+
+pub use solver::{solve_constraints, ParameterKey};
+
 // Legacy exports for backward compatibility
 pub use constraints::*;
 pub use operations::*;
 pub use primitives::*;
 
-use crate::errors::KernelResult;
-use crate::types::{BoundingBox, PreviewMesh, PrimitiveType, Transform};
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::math::vec3;
+use crate::types::{BoundingBox, PrecisionMesh, PreviewMesh, PrimitiveType, Transform};
 
 /// Base trait for geometric primitives
 pub trait Primitive {
     /// Generate mesh for this primitive
-    fn to_mesh(&self, subdivisions: u32) -> PreviewMesh;
+    ///
+    /// Curved primitives clamp every segment count derived from
+    /// `subdivisions` up to `constants::MIN_SEGMENTS`, so 0 still yields a
+    /// closed mesh.
+    fn to_mesh(&self, subdivisions: u32) -> PreviewMesh {
+        self.to_precision_mesh(subdivisions).to_preview()
+    }
+
+    /// Generate mesh with f64 positions, for callers that transform or
+    /// combine it further before preview or export
+    fn to_precision_mesh(&self, subdivisions: u32) -> PrecisionMesh;
 
     /// Get bounding box
     fn bounding_box(&self) -> BoundingBox;
 
     /// Apply transform
     fn apply_transform(&mut self, transform: &Transform);
+
+    /// Generate mesh with segment counts driven by curvature, so that no
+    /// triangle strays more than `max_chord_error` from the true surface
+    fn to_adaptive_mesh(&self, max_chord_error: f64) -> KernelResult<PreviewMesh> {
+        Ok(self.to_adaptive_precision_mesh(max_chord_error)?.to_preview())
+    }
+
+    /// Curvature-driven mesh with f64 positions
+    ///
+    /// Primitives without curved faces use the default subdivisions.
+    fn to_adaptive_precision_mesh(&self, max_chord_error: f64) -> KernelResult<PrecisionMesh> {
+        segments_for_chord_error(1.0, max_chord_error)?;
+        Ok(self.to_precision_mesh(constants::DEFAULT_SUBDIVISIONS))
+    }
 }
 
 /// Numerical constants for geometry operations
@@ -52,6 +91,35 @@ pub mod constants {
     pub const EPSILON: f64 = 1e-9;
     pub const DEFAULT_SUBDIVISIONS: u32 = 32;
     pub const DEFAULT_CIRCLE_SEGMENTS: u32 = 24;
+    /// Upper bound on segments per full circle in adaptive tessellation
+    pub const MAX_ADAPTIVE_SEGMENTS: u32 = 1024;
+    /// Fewest segments in any count of a curved primitive: around a
+    /// circle, between the poles of a sphere, or around a torus ring or
+    /// tube. Smaller counts, including 0, are clamped up to it; cadmium-core
+    /// uses the same minimum and policy
+    pub const MIN_SEGMENTS: u32 = 3;
+}
+
+/// Segments per full circle keeping every chord within `max_chord_error`
+/// of a circle of `radius`
+///
+/// The chord height of a segment spanning angle `a` is `r (1 - cos(a / 2))`,
+/// so tight curves get more segments than gentle ones. The count is
+/// clamped to `MIN_SEGMENTS..=MAX_ADAPTIVE_SEGMENTS`.
+pub fn segments_for_chord_error(radius: f64, max_chord_error: f64) -> KernelResult<u32> {
+    if !max_chord_error.is_finite() || max_chord_error <= 0.0 {
+        return Err(KernelError::invalid_parameter(
+            "max_chord_error",
+            max_chord_error.to_string(),
+        ));
+    }
+    if max_chord_error >= radius {
+        return Ok(constants::MIN_SEGMENTS);
+    }
+
+    let half_angle = (1.0 - max_chord_error / radius).acos();
+    let segments = (std::f64::consts::PI / half_angle).ceil();
+    Ok((segments as u32).clamp(constants::MIN_SEGMENTS, constants::MAX_ADAPTIVE_SEGMENTS))
 }
 
 /// Utility functions for geometry operations
@@ -94,64 +162,43 @@ pub fn apply_transform_to_point(point: [f64; 3], transform: &crate::types::Trans
     [p[0] + position[0], p[1] + position[1], p[2] + position[2]]
 }
 
+/// Transform a surface normal by the inverse transpose of the linear part
+/// of `transform`, so it stays perpendicular to the transformed surface
+///
+/// The cofactor matrix stands in for the inverse transpose, which keeps
+/// flattening (zero) scales usable; its sign follows the determinant so
+/// mirroring scales do not flip the normal.
 pub fn apply_transform_to_normal(
     normal: [f64; 3],
     transform: &crate::types::Transform,
 ) -> [f64; 3] {
-    // Normals only affected by rotation and scale
-    let rotation = transform.get_rotation();
-    let scale = transform.get_scale();
-
-    let mut n = [
-        normal[0] / scale[0],
-        normal[1] / scale[1],
-        normal[2] / scale[2],
-    ];
+    // Columns of the linear part, i.e. the images of the basis vectors;
+    // translation cancels out of the differences
+    let origin = apply_transform_to_point([0.0; 3], transform);
+    let column = |axis: usize| {
+        let mut basis = [0.0; 3];
+        basis[axis] = 1.0;
+        vec3::sub(apply_transform_to_point(basis, transform), origin)
+    };
+    let (c0, c1, c2) = (column(0), column(1), column(2));
 
-    // Apply rotation
-    let (sx, cx) = rotation[0].sin_cos();
-    let (sy, cy) = rotation[1].sin_cos();
-    let (sz, cz) = rotation[2].sin_cos();
+    let (k0, k1, k2) = (
+        vec3::cross(c1, c2),
+        vec3::cross(c2, c0),
+        vec3::cross(c0, c1),
+    );
+    let sign = if vec3::dot(c0, k0) < 0.0 { -1.0 } else { 1.0 };
 
-    let y = n[1] * cx - n[2] * sx;
-    let z = n[1] * sx + n[2] * cx;
-    n[1] = y;
-    n[2] = z;
-
-    let x = n[0] * cy + n[2] * sy;
-    let z = -n[0] * sy + n[2] * cy;
-    n[0] = x;
-    n[2] = z;
-
-    let x = n[0] * cz - n[1] * sz;
-    let y = n[0] * sz + n[1] * cz;
-    n[0] = x;
-    n[1] = y;
-
-    // Normalize
-    let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
-    if len > constants::EPSILON {
-        [n[0] / len, n[1] / len, n[2] / len]
-    } else {
-        [0.0, 0.0, 1.0]
-    }
+    let n = [0, 1, 2].map(|i| sign * (normal[0] * k0[i] + normal[1] * k1[i] + normal[2] * k2[i]));
+    vec3::normalize_or_z(n)
 }
 
 /// Compute face normal from three vertices
+///
+/// Degenerate triangles get `+Z`.
 pub fn compute_face_normal(v0: [f64; 3], v1: [f64; 3], v2: [f64; 3]) -> [f64; 3] {
-    let e1 = [v1[0] - v0[0], v1[1] - v0[1], v1[2] - v0[2]];
-    let e2 = [v2[0] - v0[0], v2[1] - v0[1], v2[2] - v0[2]];
-
-    let nx = e1[1] * e2[2] - e1[2] * e2[1];
-    let ny = e1[2] * e2[0] - e1[0] * e2[2];
-    let nz = e1[0] * e2[1] - e1[1] * e2[0];
-
-    let len = (nx * nx + ny * ny + nz * nz).sqrt();
-    if len > constants::EPSILON {
-        [nx / len, ny / len, nz / len]
-    } else {
-        [0.0, 0.0, 1.0]
-    }
+    let normal = vec3::cross(vec3::sub(v1, v0), vec3::sub(v2, v0));
+    vec3::normalize_or_z(normal)
 }
 
 /// Create a new IR graph for semantic geometry operations
@@ -3,8 +3,14 @@
 //! This module implements Union, Subtract, and Intersect operations
 //! on meshes using robust numerical algorithms.
 
-use crate::geometry::{constants, PreviewMesh, BoundingBox};
+use crate::config::KernelConfig;
+use crate::geometry::math::vec3::{add, cross, dot, length, length_sq, normalize, scale, sub};
+use crate::geometry::topology::tessellate::weld_points;
+use crate::geometry::{constants, Bvh, PreviewMesh, BoundingBox};
+use crate::types::PrecisionMesh;
 use crate::errors::{KernelError, KernelResult};
+use crate::types::SolidInfo;
+use std::collections::{HashMap, HashSet};
 
 /// Boolean operation types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,6 +25,10 @@ pub enum BooleanOperation {
 /// For now, this is a simplified implementation that combines meshes
 /// for Union operations. A full implementation would use proper CSG
 /// algorithms with robust mesh boolean operations.
+///
+/// The operation runs in f64 (see [`precise_boolean_operation`]) with the
+/// default millimeter tolerance; the result is downcast back to a preview
+/// mesh.
 pub fn boolean_operation(
     mesh1: &PreviewMesh,
     mesh2: &PreviewMesh,
@@ -27,10 +37,32 @@ pub fn boolean_operation(
     mesh1.is_valid()?;
     mesh2.is_valid()?;
 
+    precise_boolean_operation(
+        &mesh1.to_precision(),
+        &mesh2.to_precision(),
+        operation,
+        KernelConfig::default().linear_tolerance,
+    )
+    .map(|mesh| mesh.to_preview())
+}
+
+/// Perform boolean operation on two f64 meshes
+///
+/// Points closer than `linear_tolerance` (in model units, see
+/// [`KernelConfig`]) are treated as coincident when cutting.
+pub fn precise_boolean_operation(
+    mesh1: &PrecisionMesh,
+    mesh2: &PrecisionMesh,
+    operation: BooleanOperation,
+    linear_tolerance: f64,
+) -> KernelResult<PrecisionMesh> {
+    mesh1.is_valid()?;
+    mesh2.is_valid()?;
+
     match operation {
         BooleanOperation::Union => union_meshes(mesh1, mesh2),
-        BooleanOperation::Subtract => subtract_meshes(mesh1, mesh2),
-        BooleanOperation::Intersect => intersect_meshes(mesh1, mesh2),
+        BooleanOperation::Subtract => subtract_meshes(mesh1, mesh2, linear_tolerance),
+        BooleanOperation::Intersect => intersect_meshes(mesh1, mesh2, linear_tolerance),
     }
 }
 
@@ -42,8 +74,8 @@ pub fn boolean_operation(
 /// 3. Create new faces at intersection boundaries
 ///
 /// Current implementation: simple concatenation with offset indices
-fn union_meshes(mesh1: &PreviewMesh, mesh2: &PreviewMesh) -> KernelResult<PreviewMesh> {
-    let mut result = PreviewMesh::new();
+fn union_meshes(mesh1: &PrecisionMesh, mesh2: &PrecisionMesh) -> KernelResult<PrecisionMesh> {
+    let mut result = PrecisionMesh::new();
 
     // Add all vertices from mesh1
     result.vertices.extend_from_slice(&mesh1.vertices);
@@ -69,34 +101,481 @@ fn union_meshes(mesh1: &PreviewMesh, mesh2: &PreviewMesh) -> KernelResult<Previe
 
 /// Subtract operation - remove mesh2 from mesh1
 ///
-/// For a full implementation, this would:
-/// 1. Compute intersection of meshes
-/// 2. Create new faces at cut boundaries
-/// 3. Remove portions of mesh1 that overlap with mesh2
-///
-/// Current implementation: returns mesh1 only (placeholder)
-fn subtract_meshes(mesh1: &PreviewMesh, mesh2: &PreviewMesh) -> KernelResult<PreviewMesh> {
-    // Placeholder: return a copy of mesh1
-    // In a full implementation, this would perform actual CSG subtraction
-    Ok(PreviewMesh {
-        vertices: mesh1.vertices.clone(),
-        normals: mesh1.normals.clone(),
-        indices: mesh1.indices.clone(),
-    })
+/// Both meshes are first cut along each other's surface, then the pieces
+/// are classified by centroid:
+/// 1. Keep pieces of mesh1 that lie outside mesh2
+/// 2. Add pieces of mesh2 that lie inside mesh1, with flipped winding
+fn subtract_meshes(
+    mesh1: &PrecisionMesh,
+    mesh2: &PrecisionMesh,
+    linear_tolerance: f64,
+) -> KernelResult<PrecisionMesh> {
+    if !boxes_intersect(&mesh_bounds(mesh1), &mesh_bounds(mesh2)) {
+        return Ok(mesh1.clone());
+    }
+
+    let bvh1 = Bvh::build_precise(mesh1);
+    let bvh2 = Bvh::build_precise(mesh2);
+
+    let tolerance = cut_tolerance(mesh1, mesh2, linear_tolerance);
+
+    let kept = split_against(mesh1, mesh2, &bvh2, tolerance)
+        .into_iter()
+        .filter(|piece| !bvh2.contains_point(piece.centroid()));
+    let cavity = split_against(mesh2, mesh1, &bvh1, tolerance)
+        .into_iter()
+        .filter(|piece| bvh1.contains_point(piece.centroid()))
+        .map(Piece::flipped);
+
+    Ok(assemble(kept.chain(cavity).collect(), tolerance))
 }
 
 /// Intersect operation - keep only overlapping volume
 ///
-/// For a full implementation, this would:
-/// 1. Compute intersection of meshes
-/// 2. Create new faces at intersection boundaries
-/// 3. Remove portions outside the intersection
+/// Keeps the pieces of each mesh that lie inside the other, after the
+/// same cutting as subtraction.
+fn intersect_meshes(
+    mesh1: &PrecisionMesh,
+    mesh2: &PrecisionMesh,
+    linear_tolerance: f64,
+) -> KernelResult<PrecisionMesh> {
+    if !boxes_intersect(&mesh_bounds(mesh1), &mesh_bounds(mesh2)) {
+        return Ok(PrecisionMesh::new());
+    }
+
+    let bvh1 = Bvh::build_precise(mesh1);
+    let bvh2 = Bvh::build_precise(mesh2);
+
+    let tolerance = cut_tolerance(mesh1, mesh2, linear_tolerance);
+
+    let inside1 = split_against(mesh1, mesh2, &bvh2, tolerance)
+        .into_iter()
+        .filter(|piece| bvh2.contains_point(piece.centroid()));
+    let inside2 = split_against(mesh2, mesh1, &bvh1, tolerance)
+        .into_iter()
+        .filter(|piece| bvh1.contains_point(piece.centroid()));
+
+    Ok(assemble(inside1.chain(inside2).collect(), tolerance))
+}
+
+/// Bounds of all vertices in a mesh
+fn mesh_bounds(mesh: &PrecisionMesh) -> BoundingBox {
+    mesh.vertices
+        .chunks_exact(3)
+        .fold(BoundingBox::empty(), |acc, v| {
+            let p = [v[0], v[1], v[2]];
+            acc.merge(&BoundingBox::new(p, p))
+        })
+}
+
+/// Relative error of the f64 arithmetic of a cut, with headroom
+const CUT_RELATIVE_ERROR: f64 = 1e-9;
+
+/// Distance below which a cut treats points as coincident: the linear
+/// tolerance, widened far from the origin where f64 rounding outgrows it
+fn cut_tolerance(mesh1: &PrecisionMesh, mesh2: &PrecisionMesh, linear_tolerance: f64) -> f64 {
+    let extent = mesh1
+        .vertices
+        .iter()
+        .chain(&mesh2.vertices)
+        .fold(0.0f64, |acc, &c| acc.max(c.abs()));
+    linear_tolerance.max(extent * CUT_RELATIVE_ERROR)
+}
+
+/// A triangle cut from a mesh, with the normal at each corner
+#[derive(Debug, Clone, Copy)]
+struct Piece {
+    positions: [[f64; 3]; 3],
+    normals: [[f64; 3]; 3],
+}
+
+impl Piece {
+    fn centroid(&self) -> [f64; 3] {
+        let [a, b, c] = self.positions;
+        scale(add(add(a, b), c), 1.0 / 3.0)
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let [a, b, c] = self.positions;
+        BoundingBox::new(a, a)
+            .merge(&BoundingBox::new(b, b))
+            .merge(&BoundingBox::new(c, c))
+    }
+
+    /// Reversed winding with negated normals
+    fn flipped(self) -> Piece {
+        let [a, b, c] = self.positions;
+        let [na, nb, nc] = self.normals.map(|n| scale(n, -1.0));
+        Piece {
+            positions: [a, c, b],
+            normals: [na, nc, nb],
+        }
+    }
+}
+
+/// Triangles of a mesh as pieces, skipping out-of-range indices
+fn pieces_of(mesh: &PrecisionMesh) -> Vec<Piece> {
+    let vertex_count = mesh.vertex_count();
+    let at = |data: &[f64], i: u32| {
+        let i = i as usize * 3;
+        [data[i], data[i + 1], data[i + 2]]
+    };
+
+    mesh.indices
+        .chunks_exact(3)
+        .filter(|tri| tri.iter().all(|&i| (i as usize) < vertex_count))
+        .map(|tri| Piece {
+            positions: [0, 1, 2].map(|k| at(&mesh.vertices, tri[k])),
+            normals: [0, 1, 2].map(|k| at(&mesh.normals, tri[k])),
+        })
+        .collect()
+}
+
+/// Cut the triangles of `subject` along the surface of `cutter`
+///
+/// Every triangle is split by the plane of each cutter triangle it
+/// crosses, so no piece passes through the cutter and its centroid
+/// classifies the whole piece.
+fn split_against(
+    subject: &PrecisionMesh,
+    cutter: &PrecisionMesh,
+    cutter_bvh: &Bvh,
+    tolerance: f64,
+) -> Vec<Piece> {
+    let cutter_triangles = pieces_of(cutter);
+    let mut result = Vec::with_capacity(subject.triangle_count());
+
+    for tri in pieces_of(subject) {
+        let mut pieces = vec![tri];
+        for index in cutter_bvh.triangles_in_box(&tri.bounds()) {
+            let plane = &cutter_triangles[index].positions;
+            pieces = pieces
+                .into_iter()
+                .flat_map(|piece| {
+                    if triangles_intersect(&piece.positions, plane) {
+                        split_by_plane(&piece, plane, tolerance)
+                    } else {
+                        vec![piece]
+                    }
+                })
+                .collect();
+        }
+        result.extend(pieces);
+    }
+
+    result
+}
+
+/// Split a triangle by the plane of another, fanning each side back into
+/// triangles with interpolated normals
+///
+/// Corners within `tolerance` of the plane lie on it.
+fn split_by_plane(piece: &Piece, plane: &[[f64; 3]; 3], tolerance: f64) -> Vec<Piece> {
+    let Some(normal) = normalize(cross(sub(plane[1], plane[0]), sub(plane[2], plane[0]))) else {
+        return vec![*piece];
+    };
+    let distance = piece.positions.map(|p| dot(normal, sub(p, plane[0])));
+    if !distance.iter().any(|&d| d > tolerance)
+        || !distance.iter().any(|&d| d < -tolerance)
+    {
+        return vec![*piece];
+    }
+
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        let corner = (piece.positions[i], piece.normals[i]);
+        let (da, db) = (distance[i], distance[j]);
+        if da >= -tolerance {
+            front.push(corner);
+        }
+        if da <= tolerance {
+            back.push(corner);
+        }
+        if (da > tolerance && db < -tolerance)
+            || (da < -tolerance && db > tolerance)
+        {
+            let t = da / (da - db);
+            let lerp = |a: [f64; 3], b: [f64; 3]| add(a, scale(sub(b, a), t));
+            let crossing = (
+                lerp(piece.positions[i], piece.positions[j]),
+                lerp(piece.normals[i], piece.normals[j]),
+            );
+            front.push(crossing);
+            back.push(crossing);
+        }
+    }
+
+    let mut pieces = Vec::new();
+    for polygon in [front, back] {
+        for k in 1..polygon.len().saturating_sub(1) {
+            let corners = [polygon[0], polygon[k], polygon[k + 1]];
+            pieces.push(Piece {
+                positions: corners.map(|(p, _)| p),
+                normals: corners.map(|(_, n)| n),
+            });
+        }
+    }
+    pieces
+}
+
+/// Whether two non-coplanar triangles cross
+///
+/// Where such triangles meet, the ends of the shared segment lie on
+/// edges, so one triangle must have an edge passing through the other.
+fn triangles_intersect(a: &[[f64; 3]; 3], b: &[[f64; 3]; 3]) -> bool {
+    let crosses = |edges: &[[f64; 3]; 3], tri: &[[f64; 3]; 3]| {
+        (0..3).any(|k| segment_hits_triangle(edges[k], edges[(k + 1) % 3], tri))
+    };
+    crosses(a, b) || crosses(b, a)
+}
+
+/// Möller-Trumbore test of the segment p-q against a triangle
+fn segment_hits_triangle(p: [f64; 3], q: [f64; 3], tri: &[[f64; 3]; 3]) -> bool {
+    let direction = sub(q, p);
+    let edge1 = sub(tri[1], tri[0]);
+    let edge2 = sub(tri[2], tri[0]);
+    let h = cross(direction, edge2);
+    let det = dot(edge1, h);
+
+    // Parallel to (or lying in) the triangle's plane
+    if det.abs() <= constants::EPSILON * length(direction) * length(edge1) * length(edge2) {
+        return false;
+    }
+
+    let s = sub(p, tri[0]);
+    let u = dot(s, h) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return false;
+    }
+    let r = cross(s, edge1);
+    let v = dot(direction, r) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return false;
+    }
+    let t = dot(edge2, r) / det;
+    (0.0..=1.0).contains(&t)
+}
+
+/// A welded triangle: vertex ids with the normal at each corner
+type WeldedTriangle = ([usize; 3], [[f64; 3]; 3]);
+
+/// Build a mesh from cut pieces
 ///
-/// Current implementation: returns empty mesh (placeholder)
-fn intersect_meshes(_mesh1: &PreviewMesh, _mesh2: &PreviewMesh) -> KernelResult<PreviewMesh> {
-    // Placeholder: return empty mesh
-    // In a full implementation, this would perform actual CSG intersection
-    Ok(PreviewMesh::new())
+/// Corners are welded within `tolerance` and slivers dropped, then edges
+/// that end at another piece's vertex are split there, so pieces cut on
+/// either side of a seam share its edges.
+fn assemble(pieces: Vec<Piece>, tolerance: f64) -> PrecisionMesh {
+    let corners: Vec<[f64; 3]> = pieces.iter().flat_map(|piece| piece.positions).collect();
+    let (ids, positions) = weld_points(&corners, tolerance);
+
+    let mut triangles: Vec<WeldedTriangle> = pieces
+        .iter()
+        .zip(ids.chunks_exact(3))
+        .map(|(piece, id)| ([id[0], id[1], id[2]], piece.normals))
+        .filter(|([a, b, c], _)| {
+            let area = cross(sub(positions[*b], positions[*a]), sub(positions[*c], positions[*a]));
+            length(area) > tolerance * tolerance
+        })
+        .collect();
+
+    split_t_junctions(&mut triangles, &positions, tolerance);
+
+    // Corners share a vertex when both position and normal agree
+    let mut result = PrecisionMesh::new();
+    let mut shared: HashMap<(usize, [u64; 3]), u32> = HashMap::new();
+    for (ids, normals) in triangles {
+        for (id, normal) in ids.into_iter().zip(normals) {
+            let normal = normalize(normal).unwrap_or(normal);
+            let index = *shared.entry((id, normal.map(f64::to_bits))).or_insert_with(|| {
+                result.vertices.extend_from_slice(&positions[id]);
+                result.normals.extend_from_slice(&normal);
+                (result.vertex_count() - 1) as u32
+            });
+            result.indices.push(index);
+        }
+    }
+
+    result
+}
+
+/// Split triangles at vertices that lie inside one of their open edges
+///
+/// Each pass splits an edge at its interior vertex nearest the edge's
+/// start, so every pass removes at least one T-junction.
+fn split_t_junctions(triangles: &mut Vec<WeldedTriangle>, positions: &[[f64; 3]], tolerance: f64) {
+    loop {
+        let mut uses: HashMap<(usize, usize), usize> = HashMap::new();
+        for (ids, _) in triangles.iter() {
+            for k in 0..3 {
+                let (a, b) = (ids[k], ids[(k + 1) % 3]);
+                *uses.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        let open: HashSet<(usize, usize)> = uses
+            .into_iter()
+            .filter(|&(_, count)| count == 1)
+            .map(|(edge, _)| edge)
+            .collect();
+        if open.is_empty() {
+            return;
+        }
+        let mut candidates: Vec<usize> = open.iter().flat_map(|&(a, b)| [a, b]).collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        // Interior vertex of the edge a-b nearest a, with its parameter
+        let inner = |a: usize, b: usize| {
+            let (pa, pb) = (positions[a], positions[b]);
+            let edge = sub(pb, pa);
+            let length_sq = length_sq(edge);
+            candidates
+                .iter()
+                .filter(|&&v| v != a && v != b)
+                .filter_map(|&v| {
+                    let t = dot(sub(positions[v], pa), edge) / length_sq;
+                    let on_edge = length(sub(add(pa, scale(edge, t)), positions[v])) <= tolerance;
+                    (on_edge && t > 0.0 && t < 1.0).then_some((t, v))
+                })
+                .min_by(|x, y| x.0.total_cmp(&y.0))
+        };
+
+        let mut split = false;
+        let mut next = Vec::with_capacity(triangles.len() + open.len());
+        for &(ids, normals) in triangles.iter() {
+            let junction = (0..3).find_map(|k| {
+                let (a, b) = (ids[k], ids[(k + 1) % 3]);
+                if !open.contains(&(a.min(b), a.max(b))) {
+                    return None;
+                }
+                inner(a, b).map(|(t, v)| (k, t, v))
+            });
+            match junction {
+                Some((k, t, v)) => {
+                    let (i, j, l) = (k, (k + 1) % 3, (k + 2) % 3);
+                    let normal = add(normals[i], scale(sub(normals[j], normals[i]), t));
+                    next.push(([ids[i], v, ids[l]], [normals[i], normal, normals[l]]));
+                    next.push(([v, ids[j], ids[l]], [normal, normals[j], normals[l]]));
+                    split = true;
+                }
+                None => next.push((ids, normals)),
+            }
+        }
+        *triangles = next;
+        if !split {
+            return;
+        }
+    }
+}
+
+/// Split a mesh into its connected components
+///
+/// Triangles are connected when they share a vertex position, so meshes
+/// that duplicate vertices per face for flat shading still split per
+/// solid. Components are ordered by their first triangle.
+pub fn split_components(mesh: &PrecisionMesh) -> Vec<PrecisionMesh> {
+    let vertex_count = mesh.vertex_count();
+
+    // Union-find over vertices, seeded by identical positions
+    let mut parent: Vec<usize> = (0..vertex_count).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    fn union(parent: &mut [usize], a: usize, b: usize) {
+        let (ra, rb) = (find(parent, a), find(parent, b));
+        if ra != rb {
+            parent[ra.max(rb)] = ra.min(rb);
+        }
+    }
+
+    let mut by_position: HashMap<[u64; 3], usize> = HashMap::new();
+    for (i, v) in mesh.vertices.chunks_exact(3).enumerate() {
+        // +0.0 folds -0.0 into the same key
+        let key = [(v[0] + 0.0).to_bits(), (v[1] + 0.0).to_bits(), (v[2] + 0.0).to_bits()];
+        let first = *by_position.entry(key).or_insert(i);
+        union(&mut parent, first, i);
+    }
+
+    let triangles: Vec<&[u32]> = mesh
+        .indices
+        .chunks_exact(3)
+        .filter(|tri| tri.iter().all(|&i| (i as usize) < vertex_count))
+        .collect();
+    for tri in &triangles {
+        union(&mut parent, tri[0] as usize, tri[1] as usize);
+        union(&mut parent, tri[0] as usize, tri[2] as usize);
+    }
+
+    let mut component_of: HashMap<usize, usize> = HashMap::new();
+    let mut components: Vec<PrecisionMesh> = Vec::new();
+    let mut remaps: Vec<HashMap<u32, u32>> = Vec::new();
+
+    for tri in triangles {
+        let root = find(&mut parent, tri[0] as usize);
+        let c = *component_of.entry(root).or_insert_with(|| {
+            components.push(PrecisionMesh::new());
+            remaps.push(HashMap::new());
+            components.len() - 1
+        });
+
+        let (component, remap) = (&mut components[c], &mut remaps[c]);
+        for &i in tri {
+            let mapped = *remap.entry(i).or_insert_with(|| {
+                let i = i as usize;
+                component
+                    .vertices
+                    .extend_from_slice(&mesh.vertices[i * 3..i * 3 + 3]);
+                if let Some(n) = mesh.normals.get(i * 3..i * 3 + 3) {
+                    component.normals.extend_from_slice(n);
+                }
+                (component.vertex_count() - 1) as u32
+            });
+            component.indices.push(mapped);
+        }
+    }
+
+    components
+}
+
+/// Describe each connected solid of a mesh with its volume and bounds
+pub fn solids_from_mesh(mesh: &PreviewMesh) -> Vec<SolidInfo> {
+    solids_from_precision_mesh(&mesh.to_precision())
+}
+
+/// Describe each connected solid of an f64 mesh, measuring volume and
+/// bounds before the downcast to preview
+pub fn solids_from_precision_mesh(mesh: &PrecisionMesh) -> Vec<SolidInfo> {
+    split_components(mesh)
+        .into_iter()
+        .map(|component| SolidInfo {
+            volume: signed_volume(&component),
+            bounding_box: mesh_bounds(&component),
+            mesh: component.to_preview(),
+        })
+        .collect()
+}
+
+/// Enclosed volume by the divergence theorem (positive for outward winding)
+fn signed_volume(mesh: &PrecisionMesh) -> f64 {
+    let vertex = |i: u32| {
+        let i = i as usize * 3;
+        [mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2]]
+    };
+
+    mesh.indices
+        .chunks_exact(3)
+        .map(|tri| {
+            let [a, b, c] = [vertex(tri[0]), vertex(tri[1]), vertex(tri[2])];
+            (a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+                + a[2] * (b[0] * c[1] - b[1] * c[0]))
+                / 6.0
+        })
+        .sum()
 }
 
 /// Test if two bounding boxes intersect
@@ -156,12 +635,14 @@ pub fn boxes_overlap_significantly(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::geometry::primitives::{Box as BoxPrimitive, Primitive};
+    use crate::geometry::{primitives::Box as BoxPrimitive, Primitive};
+
+    const TOLERANCE: f64 = 1e-6;
 
     #[test]
     fn test_union_meshes() {
-        let mesh1 = BoxPrimitive::new(1.0, 1.0, 1.0).to_mesh(16);
-        let mesh2 = BoxPrimitive::new(1.0, 1.0, 1.0).to_mesh(16);
+        let mesh1 = BoxPrimitive::new(1.0, 1.0, 1.0).to_precision_mesh(16);
+        let mesh2 = BoxPrimitive::new(1.0, 1.0, 1.0).to_precision_mesh(16);
 
         let result = union_meshes(&mesh1, &mesh2).unwrap();
 
@@ -169,6 +650,52 @@ mod tests {
         assert_eq!(result.triangle_count(), mesh1.triangle_count() + mesh2.triangle_count());
     }
 
+    #[test]
+    fn test_subtract_meshes_keeps_outside_and_flips_tool() {
+        let block = BoxPrimitive::new(4.0, 4.0, 4.0).to_precision_mesh(16);
+        let tool = BoxPrimitive::new(2.0, 2.0, 2.0).to_precision_mesh(16);
+
+        let result = subtract_meshes(&block, &tool, TOLERANCE).unwrap();
+        result.is_valid().unwrap();
+
+        // The tool is fully inside, so the block survives and the tool becomes a cavity
+        assert_eq!(result.triangle_count(), block.triangle_count() + tool.triangle_count());
+        let cavity_normal = &result.normals[result.normals.len() - 3..];
+        let tool_normal = &tool.normals[tool.normals.len() - 3..];
+        for axis in 0..3 {
+            assert_eq!(cavity_normal[axis], -tool_normal[axis]);
+        }
+
+        let apart = subtract_meshes(&tool, &block, TOLERANCE).unwrap();
+        assert_eq!(apart.triangle_count(), 0);
+    }
+
+    #[test]
+    fn test_subtract_meshes_splits_crossing_triangles() {
+        let block = BoxPrimitive::new(2.0, 2.0, 2.0).to_precision_mesh(16);
+        let mut corner = BoxPrimitive::new(2.0, 2.0, 2.0).to_precision_mesh(16);
+        corner.vertices.iter_mut().for_each(|c| *c += 1.0);
+
+        // A quarter of each face is cut away, not whole triangles
+        let notched = subtract_meshes(&block, &corner, TOLERANCE).unwrap();
+        assert!((signed_volume(&notched) - 7.0).abs() < 1e-5);
+        let solids = solids_from_precision_mesh(&notched);
+        assert_eq!(solids.len(), 1);
+
+        let overlap = intersect_meshes(&block, &corner, TOLERANCE).unwrap();
+        assert!((signed_volume(&overlap) - 1.0).abs() < 1e-5);
+        assert!((mesh_bounds(&overlap).min[0] - 0.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_intersect_meshes() {
+        let block = BoxPrimitive::new(4.0, 4.0, 4.0).to_precision_mesh(16);
+        let inner = BoxPrimitive::new(2.0, 2.0, 2.0).to_precision_mesh(16);
+
+        let result = intersect_meshes(&block, &inner, TOLERANCE).unwrap();
+        assert_eq!(result.triangle_count(), inner.triangle_count());
+    }
+
     #[test]
     fn test_boxes_intersect() {
         let bbox1 = BoundingBox::new([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
@@ -182,8 +709,8 @@ mod tests {
     #[test]
     fn test_boxes_overlap_significantly() {
         let bbox1 = BoundingBox::new([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
-        let bbox2 = BoundingBox::new([0.9, 0.9, 0.9], [1.9, 1.9, 1.9]);
-        let bbox3 = BoundingBox::new([0.0, 0.0, 0.0], [0.01, 0.01, 0.01]);
+        let bbox2 = BoundingBox::new([0.5, 0.5, 0.5], [1.5, 1.5, 1.5]);
+        let bbox3 = BoundingBox::new([0.99, 0.99, 0.99], [1.09, 1.09, 1.09]);
 
         // Significant overlap
         assert!(boxes_overlap_significantly(&bbox1, &bbox2, 0.1));
@@ -191,4 +718,23 @@ mod tests {
         // Not significant
         assert!(!boxes_overlap_significantly(&bbox1, &bbox3, 0.1));
     }
+
+    #[test]
+    fn test_split_components() {
+        let a = BoxPrimitive::new(2.0, 2.0, 2.0).to_precision_mesh(16);
+        let mut b = BoxPrimitive::new(1.0, 1.0, 1.0).to_precision_mesh(16);
+        b.vertices.iter_mut().step_by(3).for_each(|x| *x += 5.0);
+
+        let combined = union_meshes(&a, &b).unwrap();
+        let solids = solids_from_precision_mesh(&combined);
+
+        // Per-face vertex copies still join into one solid per box
+        assert_eq!(solids.len(), 2);
+        assert_eq!(solids[0].mesh.indices.len(), a.indices.len());
+        assert!((solids[0].volume - 8.0).abs() < 1e-6);
+        assert!((solids[1].volume - 1.0).abs() < 1e-6);
+        assert!((solids[1].bounding_box.min[0] - 4.5).abs() < 1e-6);
+
+        assert!(split_components(&PrecisionMesh::new()).is_empty());
+    }
 }
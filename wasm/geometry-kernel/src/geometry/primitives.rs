@@ -3,14 +3,32 @@
 //! All primitives generate consistent vertex ordering and face
 //! orientation for reliable boolean operations.
 
-use crate::geometry::{constants, Primitive, apply_transform_to_point, apply_transform_to_normal, compute_face_normal};
-use crate::types::{BoundingBox, PreviewMesh, PrimitiveType};
-use crate::errors::KernelResult;
+use crate::geometry::math::vec3;
+use crate::geometry::{constants, Primitive, apply_transform_to_point, apply_transform_to_normal, compute_face_normal, segments_for_chord_error};
+use crate::types::{BoundingBox, PrecisionMesh, PrimitiveType};
+use crate::errors::{ErrorContext, KernelError, KernelResult};
 use std::collections::HashMap;
 
 /// Local Transform alias for primitives
 type Transform = crate::types::Transform;
 
+/// Read a length parameter, rejecting values that would mesh degenerate
+/// triangles (zero, negative or non-finite)
+fn dimension(params: &HashMap<String, f64>, name: &str) -> KernelResult<f64> {
+    let value = params
+        .get(name)
+        .copied()
+        .ok_or_else(|| KernelError::missing_parameter(name))?;
+    if !value.is_finite() || value <= 0.0 {
+        return Err(KernelError::invalid_parameter(
+            name,
+            format!("{} ({} must be a positive length)", value, name),
+        )
+        .with_context(ErrorContext::new().with_parameter(name)));
+    }
+    Ok(value)
+}
+
 /// Box primitive
 #[derive(Debug, Clone)]
 pub struct Box {
@@ -36,28 +54,19 @@ impl Box {
     }
 
     pub fn from_params(params: &HashMap<String, f64>) -> KernelResult<Self> {
-        let width = params
-            .get("width")
-            .copied()
-            .ok_or_else(|| crate::errors::KernelError::missing_parameter("width"))?;
+        let width = dimension(params, "width")?;
 
-        let height = params
-            .get("height")
-            .copied()
-            .ok_or_else(|| crate::errors::KernelError::missing_parameter("height"))?;
+        let height = dimension(params, "height")?;
 
-        let depth = params
-            .get("depth")
-            .copied()
-            .ok_or_else(|| crate::errors::KernelError::missing_parameter("depth"))?;
+        let depth = dimension(params, "depth")?;
 
         Ok(Box::new(width, height, depth))
     }
 }
 
 impl Primitive for Box {
-    fn to_mesh(&self, _subdivisions: u32) -> PreviewMesh {
-        let mut mesh = PreviewMesh::new();
+    fn to_precision_mesh(&self, _subdivisions: u32) -> PrecisionMesh {
+        let mut mesh = PrecisionMesh::new();
         let identity = Transform::identity();
         let transform = self.transform.as_ref().unwrap_or(&identity);
 
@@ -104,25 +113,25 @@ impl Primitive for Box {
             // Triangle 1: 0, 1, 2
             // Triangle 2: 0, 2, 3
 
-            for tri in [0, 1] {
-                let idx0 = face_indices[tri];
-                let idx1 = face_indices[tri + 1];
-                let idx2 = face_indices[tri + 2];
+            for tri in [1, 2] {
+                let idx0 = face_indices[0];
+                let idx1 = face_indices[tri];
+                let idx2 = face_indices[tri + 1];
 
                 let v0 = transformed_corners[idx0];
                 let v1 = transformed_corners[idx1];
                 let v2 = transformed_corners[idx2];
 
                 mesh.vertices.extend_from_slice(&[
-                    v0[0] as f32, v0[1] as f32, v0[2] as f32,
-                    v1[0] as f32, v1[1] as f32, v1[2] as f32,
-                    v2[0] as f32, v2[1] as f32, v2[2] as f32,
+                    v0[0], v0[1], v0[2],
+                    v1[0], v1[1], v1[2],
+                    v2[0], v2[1], v2[2],
                 ]);
 
                 mesh.normals.extend_from_slice(&[
-                    normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                    normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                    normal[0] as f32, normal[1] as f32, normal[2] as f32,
+                    normal[0], normal[1], normal[2],
+                    normal[0], normal[1], normal[2],
+                    normal[0], normal[1], normal[2],
                 ]);
 
                 mesh.indices.extend_from_slice(&[
@@ -187,27 +196,21 @@ impl Cylinder {
     }
 
     pub fn from_params(params: &HashMap<String, f64>) -> KernelResult<Self> {
-        let radius = params
-            .get("radius")
-            .copied()
-            .ok_or_else(|| crate::errors::KernelError::missing_parameter("radius"))?;
+        let radius = dimension(params, "radius")?;
 
-        let height = params
-            .get("height")
-            .copied()
-            .ok_or_else(|| crate::errors::KernelError::missing_parameter("height"))?;
+        let height = dimension(params, "height")?;
 
         Ok(Cylinder::new(radius, height))
     }
 }
 
 impl Primitive for Cylinder {
-    fn to_mesh(&self, subdivisions: u32) -> PreviewMesh {
-        let mut mesh = PreviewMesh::new();
+    fn to_precision_mesh(&self, subdivisions: u32) -> PrecisionMesh {
+        let mut mesh = PrecisionMesh::new();
         let identity = Transform::identity();
         let transform = self.transform.as_ref().unwrap_or(&identity);
 
-        let segments = subdivisions.max(8) as usize;
+        let segments = subdivisions.max(constants::MIN_SEGMENTS) as usize;
         let h = self.height / 2.0;
 
         // Generate vertices
@@ -244,16 +247,16 @@ impl Primitive for Cylinder {
             let next = (i + 1) % segments;
 
             mesh.vertices.extend_from_slice(&[
-                top_center[0] as f32, top_center[1] as f32, top_center[2] as f32,
-                top_vertices[i][0] as f32, top_vertices[i][1] as f32, top_vertices[i][2] as f32,
-                top_vertices[next][0] as f32, top_vertices[next][1] as f32, top_vertices[next][2] as f32,
+                top_center[0], top_center[1], top_center[2],
+                top_vertices[i][0], top_vertices[i][1], top_vertices[i][2],
+                top_vertices[next][0], top_vertices[next][1], top_vertices[next][2],
             ]);
 
             let normal = apply_transform_to_normal([0.0, 1.0, 0.0], transform);
             mesh.normals.extend_from_slice(&[
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
             ]);
 
             mesh.indices.extend_from_slice(&[vertex_offset, vertex_offset + 1, vertex_offset + 2]);
@@ -265,16 +268,16 @@ impl Primitive for Cylinder {
             let next = (i + 1) % segments;
 
             mesh.vertices.extend_from_slice(&[
-                bottom_center[0] as f32, bottom_center[1] as f32, bottom_center[2] as f32,
-                bottom_vertices[next][0] as f32, bottom_vertices[next][1] as f32, bottom_vertices[next][2] as f32,
-                bottom_vertices[i][0] as f32, bottom_vertices[i][1] as f32, bottom_vertices[i][2] as f32,
+                bottom_center[0], bottom_center[1], bottom_center[2],
+                bottom_vertices[next][0], bottom_vertices[next][1], bottom_vertices[next][2],
+                bottom_vertices[i][0], bottom_vertices[i][1], bottom_vertices[i][2],
             ]);
 
             let normal = apply_transform_to_normal([0.0, -1.0, 0.0], transform);
             mesh.normals.extend_from_slice(&[
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
             ]);
 
             mesh.indices.extend_from_slice(&[vertex_offset, vertex_offset + 1, vertex_offset + 2]);
@@ -291,12 +294,12 @@ impl Primitive for Cylinder {
             let v3 = bottom_vertices[next];
 
             mesh.vertices.extend_from_slice(&[
-                v0[0] as f32, v0[1] as f32, v0[2] as f32,
-                v1[0] as f32, v1[1] as f32, v1[2] as f32,
-                v2[0] as f32, v2[1] as f32, v2[2] as f32,
-                v0[0] as f32, v0[1] as f32, v0[2] as f32,
-                v2[0] as f32, v2[1] as f32, v2[2] as f32,
-                v3[0] as f32, v3[1] as f32, v3[2] as f32,
+                v0[0], v0[1], v0[2],
+                v1[0], v1[1], v1[2],
+                v2[0], v2[1], v2[2],
+                v0[0], v0[1], v0[2],
+                v2[0], v2[1], v2[2],
+                v3[0], v3[1], v3[2],
             ]);
 
             // Compute normal from direction vector
@@ -306,12 +309,12 @@ impl Primitive for Cylinder {
             let normal = apply_transform_to_normal([nx, 0.0, nz], transform);
 
             mesh.normals.extend_from_slice(&[
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
             ]);
 
             mesh.indices.extend_from_slice(&[
@@ -356,6 +359,11 @@ impl Primitive for Cylinder {
     fn apply_transform(&mut self, transform: &Transform) {
         self.transform = Some(transform.clone());
     }
+
+    fn to_adaptive_precision_mesh(&self, max_chord_error: f64) -> KernelResult<PrecisionMesh> {
+        let radius = self.radius * max_scale(self.transform.as_ref());
+        Ok(self.to_precision_mesh(segments_for_chord_error(radius, max_chord_error)?))
+    }
 }
 
 /// Sphere primitive
@@ -374,23 +382,20 @@ impl Sphere {
     }
 
     pub fn from_params(params: &HashMap<String, f64>) -> KernelResult<Self> {
-        let radius = params
-            .get("radius")
-            .copied()
-            .ok_or_else(|| crate::errors::KernelError::missing_parameter("radius"))?;
+        let radius = dimension(params, "radius")?;
 
         Ok(Sphere::new(radius))
     }
 }
 
 impl Primitive for Sphere {
-    fn to_mesh(&self, subdivisions: u32) -> PreviewMesh {
-        let mut mesh = PreviewMesh::new();
+    fn to_precision_mesh(&self, subdivisions: u32) -> PrecisionMesh {
+        let mut mesh = PrecisionMesh::new();
         let identity = Transform::identity();
         let transform = self.transform.as_ref().unwrap_or(&identity);
 
-        let lat_segments = (subdivisions / 2).max(4) as usize;
-        let lon_segments = subdivisions.max(8) as usize;
+        let lat_segments = (subdivisions / 2).max(constants::MIN_SEGMENTS) as usize;
+        let lon_segments = subdivisions.max(constants::MIN_SEGMENTS) as usize;
 
         let mut vertex_offset = mesh.vertices.len() as u32 / 3;
 
@@ -415,8 +420,8 @@ impl Primitive for Sphere {
                 let mut normal = [x / self.radius, y / self.radius, z / self.radius];
                 normal = apply_transform_to_normal(normal, transform);
 
-                mesh.vertices.extend_from_slice(&[point[0] as f32, point[1] as f32, point[2] as f32]);
-                mesh.normals.extend_from_slice(&[normal[0] as f32, normal[1] as f32, normal[2] as f32]);
+                mesh.vertices.extend_from_slice(&[point[0], point[1], point[2]]);
+                mesh.normals.extend_from_slice(&[normal[0], normal[1], normal[2]]);
             }
         }
 
@@ -467,6 +472,12 @@ impl Primitive for Sphere {
     fn apply_transform(&mut self, transform: &Transform) {
         self.transform = Some(transform.clone());
     }
+
+    fn to_adaptive_precision_mesh(&self, max_chord_error: f64) -> KernelResult<PrecisionMesh> {
+        // Curved both ways, a patch sags about twice as far as its edges
+        let radius = self.radius * max_scale(self.transform.as_ref());
+        Ok(self.to_precision_mesh(segments_for_chord_error(radius, max_chord_error / 2.0)?))
+    }
 }
 
 /// Cone primitive
@@ -487,27 +498,21 @@ impl Cone {
     }
 
     pub fn from_params(params: &HashMap<String, f64>) -> KernelResult<Self> {
-        let radius = params
-            .get("radius")
-            .copied()
-            .ok_or_else(|| crate::errors::KernelError::missing_parameter("radius"))?;
+        let radius = dimension(params, "radius")?;
 
-        let height = params
-            .get("height")
-            .copied()
-            .ok_or_else(|| crate::errors::KernelError::missing_parameter("height"))?;
+        let height = dimension(params, "height")?;
 
         Ok(Cone::new(radius, height))
     }
 }
 
 impl Primitive for Cone {
-    fn to_mesh(&self, subdivisions: u32) -> PreviewMesh {
-        let mut mesh = PreviewMesh::new();
+    fn to_precision_mesh(&self, subdivisions: u32) -> PrecisionMesh {
+        let mut mesh = PrecisionMesh::new();
         let identity = Transform::identity();
         let transform = self.transform.as_ref().unwrap_or(&identity);
 
-        let segments = subdivisions.max(8) as usize;
+        let segments = subdivisions.max(constants::MIN_SEGMENTS) as usize;
         let h = self.height;
 
         let mut vertex_offset = mesh.vertices.len() as u32 / 3;
@@ -535,16 +540,16 @@ impl Primitive for Cone {
             let next = (i + 1) % segments;
 
             mesh.vertices.extend_from_slice(&[
-                bottom_center[0] as f32, bottom_center[1] as f32, bottom_center[2] as f32,
-                bottom_vertices[next][0] as f32, bottom_vertices[next][1] as f32, bottom_vertices[next][2] as f32,
-                bottom_vertices[i][0] as f32, bottom_vertices[i][1] as f32, bottom_vertices[i][2] as f32,
+                bottom_center[0], bottom_center[1], bottom_center[2],
+                bottom_vertices[next][0], bottom_vertices[next][1], bottom_vertices[next][2],
+                bottom_vertices[i][0], bottom_vertices[i][1], bottom_vertices[i][2],
             ]);
 
             let normal = apply_transform_to_normal([0.0, -1.0, 0.0], transform);
             mesh.normals.extend_from_slice(&[
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
             ]);
 
             mesh.indices.extend_from_slice(&[vertex_offset, vertex_offset + 1, vertex_offset + 2]);
@@ -556,27 +561,28 @@ impl Primitive for Cone {
             let next = (i + 1) % segments;
 
             mesh.vertices.extend_from_slice(&[
-                apex[0] as f32, apex[1] as f32, apex[2] as f32,
-                bottom_vertices[i][0] as f32, bottom_vertices[i][1] as f32, bottom_vertices[i][2] as f32,
-                bottom_vertices[next][0] as f32, bottom_vertices[next][1] as f32, bottom_vertices[next][2] as f32,
+                apex[0], apex[1], apex[2],
+                bottom_vertices[i][0], bottom_vertices[i][1], bottom_vertices[i][2],
+                bottom_vertices[next][0], bottom_vertices[next][1], bottom_vertices[next][2],
             ]);
 
-            // Compute normal for side face
-            let angle = 2.0 * std::f64::consts::PI * (i as f64) / (segments as f64);
-            let nx = angle.cos();
-            let nz = angle.sin();
-
-            // Normal for cone side points outward and upward
-            let slope = self.radius / self.height;
-            let ny = slope;
-            let len = (nx * nx + ny * ny + nz * nz).sqrt();
-            let mut normal = [nx / len, ny / len, nz / len];
-            normal = apply_transform_to_normal(normal, transform);
+            // Normal of the flat facet, outward and upward; the normal at
+            // the first corner's angle would tilt off the facet
+            let corner = |k: usize| {
+                let angle = 2.0 * std::f64::consts::PI * (k as f64) / (segments as f64);
+                [self.radius * angle.cos(), -h / 2.0, self.radius * angle.sin()]
+            };
+            let apex_local = [0.0, h / 2.0, 0.0];
+            let outward = vec3::cross(
+                vec3::sub(corner(next), apex_local),
+                vec3::sub(corner(i), apex_local),
+            );
+            let normal = apply_transform_to_normal(vec3::normalize_or_z(outward), transform);
 
             mesh.normals.extend_from_slice(&[
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
-                normal[0] as f32, normal[1] as f32, normal[2] as f32,
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
+                normal[0], normal[1], normal[2],
             ]);
 
             mesh.indices.extend_from_slice(&[vertex_offset, vertex_offset + 1, vertex_offset + 2]);
@@ -617,6 +623,11 @@ impl Primitive for Cone {
     fn apply_transform(&mut self, transform: &Transform) {
         self.transform = Some(transform.clone());
     }
+
+    fn to_adaptive_precision_mesh(&self, max_chord_error: f64) -> KernelResult<PrecisionMesh> {
+        let radius = self.radius * max_scale(self.transform.as_ref());
+        Ok(self.to_precision_mesh(segments_for_chord_error(radius, max_chord_error)?))
+    }
 }
 
 /// Torus primitive
@@ -637,29 +648,21 @@ impl Torus {
     }
 
     pub fn from_params(params: &HashMap<String, f64>) -> KernelResult<Self> {
-        let major_radius = params
-            .get("major_radius")
-            .copied()
-            .ok_or_else(|| crate::errors::KernelError::missing_parameter("major_radius"))?;
+        let major_radius = dimension(params, "major_radius")?;
 
-        let minor_radius = params
-            .get("minor_radius")
-            .copied()
-            .ok_or_else(|| crate::errors::KernelError::missing_parameter("minor_radius"))?;
+        let minor_radius = dimension(params, "minor_radius")?;
 
         Ok(Torus::new(major_radius, minor_radius))
     }
 }
 
-impl Primitive for Torus {
-    fn to_mesh(&self, subdivisions: u32) -> PreviewMesh {
-        let mut mesh = PreviewMesh::new();
+impl Torus {
+    /// Mesh with independent segment counts around the ring and the tube
+    fn mesh_with_segments(&self, major_segments: usize, minor_segments: usize) -> PrecisionMesh {
+        let mut mesh = PrecisionMesh::new();
         let identity = Transform::identity();
         let transform = self.transform.as_ref().unwrap_or(&identity);
 
-        let major_segments = subdivisions.max(12) as usize;
-        let minor_segments = (subdivisions / 2).max(8) as usize;
-
         let mut vertex_offset = mesh.vertices.len() as u32 / 3;
 
         // Generate vertices
@@ -686,8 +689,8 @@ impl Primitive for Torus {
                 let mut normal = [nx, ny, nz];
                 normal = apply_transform_to_normal(normal, transform);
 
-                mesh.vertices.extend_from_slice(&[point[0] as f32, point[1] as f32, point[2] as f32]);
-                mesh.normals.extend_from_slice(&[normal[0] as f32, normal[1] as f32, normal[2] as f32]);
+                mesh.vertices.extend_from_slice(&[point[0], point[1], point[2]]);
+                mesh.normals.extend_from_slice(&[normal[0], normal[1], normal[2]]);
             }
         }
 
@@ -706,6 +709,15 @@ impl Primitive for Torus {
 
         mesh
     }
+}
+
+impl Primitive for Torus {
+    fn to_precision_mesh(&self, subdivisions: u32) -> PrecisionMesh {
+        self.mesh_with_segments(
+            subdivisions.max(constants::MIN_SEGMENTS) as usize,
+            (subdivisions / 2).max(constants::MIN_SEGMENTS) as usize,
+        )
+    }
 
     fn bounding_box(&self) -> BoundingBox {
         let identity = Transform::identity();
@@ -736,6 +748,20 @@ impl Primitive for Torus {
     fn apply_transform(&mut self, transform: &Transform) {
         self.transform = Some(transform.clone());
     }
+
+    fn to_adaptive_precision_mesh(&self, max_chord_error: f64) -> KernelResult<PrecisionMesh> {
+        // The outer equator is the longest ring; both curvatures share the error
+        let scale = max_scale(self.transform.as_ref());
+        let major_radius = (self.major_radius + self.minor_radius) * scale;
+        let major = segments_for_chord_error(major_radius, max_chord_error / 2.0)?;
+        let minor = segments_for_chord_error(self.minor_radius * scale, max_chord_error / 2.0)?;
+        Ok(self.mesh_with_segments(major as usize, minor as usize))
+    }
+}
+
+/// Largest scale factor of an optional transform
+fn max_scale(transform: Option<&Transform>) -> f64 {
+    transform.map_or(1.0, |t| t.get_scale().iter().fold(0.0, |acc: f64, s| acc.max(s.abs())))
 }
 
 /// Create primitive from type and parameters
@@ -751,3 +777,212 @@ pub fn create_primitive(
         PrimitiveType::Torus => Ok(std::boxed::Box::new(Torus::from_params(params)?)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Edges used by exactly one triangle, matching vertices by exact position
+    fn open_edges(positions: &[[u64; 3]], indices: &[u32]) -> usize {
+        let mut uses: HashMap<([u64; 3], [u64; 3]), i32> = HashMap::new();
+        for tri in indices.chunks_exact(3) {
+            for k in 0..3 {
+                let a = positions[tri[k] as usize];
+                let b = positions[tri[(k + 1) % 3] as usize];
+                *uses.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        uses.values().filter(|&&count| count != 2).count()
+    }
+
+    #[test]
+    fn test_far_box_keeps_shared_edges() {
+        let transform = Transform {
+            position: Some([1e6, 0.0, 0.0]),
+            ..Transform::identity()
+        };
+        let mesh = Box::new(0.1, 0.2, 0.3)
+            .with_transform(transform)
+            .to_precision_mesh(0);
+
+        let positions: Vec<[u64; 3]> = mesh
+            .vertices
+            .chunks_exact(3)
+            .map(|v| [v[0].to_bits(), v[1].to_bits(), v[2].to_bits()])
+            .collect();
+        assert_eq!(open_edges(&positions, &mesh.indices), 0);
+
+        let xs = mesh.vertices.iter().step_by(3);
+        let max_x = xs.clone().fold(f64::MIN, |a, &b| a.max(b));
+        let min_x = xs.fold(f64::MAX, |a, &b| a.min(b));
+        assert!((max_x - min_x - 0.1).abs() < 1e-9);
+
+        // The preview is still closed, though f32 cannot hold the width
+        let preview = mesh.to_preview();
+        let positions: Vec<[u64; 3]> = preview
+            .vertices
+            .chunks_exact(3)
+            .map(|v| [0, 1, 2].map(|axis| u64::from(v[axis].to_bits())))
+            .collect();
+        assert_eq!(open_edges(&positions, &preview.indices), 0);
+        assert_eq!(preview.triangle_count(), mesh.triangle_count());
+    }
+    #[test]
+    fn test_zero_dimensions_rejected() {
+        let cases: [(PrimitiveType, &[&str]); 5] = [
+            (PrimitiveType::Box, &["width", "height", "depth"]),
+            (PrimitiveType::Cylinder, &["radius", "height"]),
+            (PrimitiveType::Sphere, &["radius"]),
+            (PrimitiveType::Cone, &["radius", "height"]),
+            (PrimitiveType::Torus, &["major_radius", "minor_radius"]),
+        ];
+
+        for (type_, names) in cases {
+            let valid: HashMap<String, f64> = names
+                .iter()
+                .zip([10.0, 4.0, 3.0])
+                .map(|(name, value)| (name.to_string(), value))
+                .collect();
+            assert!(create_primitive(type_.clone(), &valid).is_ok());
+
+            for name in names {
+                for bad in [0.0, -1.0, f64::NAN] {
+                    let mut params = valid.clone();
+                    params.insert(name.to_string(), bad);
+                    let err = create_primitive(type_.clone(), &params)
+                        .err()
+                        .unwrap_or_else(|| panic!("{:?} accepted {} = {}", type_, name, bad));
+                    assert_eq!(err.code, "INVALID_PARAMETER");
+                    assert!(err.message.contains(name), "{}", err.message);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_adaptive_sphere_within_chord_error() {
+        let radius = 50.0;
+        let max_chord_error = 1.0;
+        let sphere = Sphere::new(radius);
+
+        let uniform = sphere.to_mesh(constants::DEFAULT_SUBDIVISIONS);
+        let adaptive = sphere.to_adaptive_mesh(max_chord_error).unwrap();
+        assert!(adaptive.triangle_count() < uniform.triangle_count());
+
+        // Deepest sag is near the middle of each triangle
+        let point = |i: u32| {
+            let i = i as usize * 3;
+            [0, 1, 2].map(|axis| adaptive.vertices[i + axis] as f64)
+        };
+        for tri in adaptive.indices.chunks_exact(3) {
+            let [a, b, c] = [point(tri[0]), point(tri[1]), point(tri[2])];
+            let samples = [
+                [0, 1, 2].map(|k| (a[k] + b[k] + c[k]) / 3.0),
+                [0, 1, 2].map(|k| (a[k] + b[k]) / 2.0),
+                [0, 1, 2].map(|k| (b[k] + c[k]) / 2.0),
+                [0, 1, 2].map(|k| (c[k] + a[k]) / 2.0),
+            ];
+            for p in samples {
+                let distance = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+                assert!(radius - distance <= max_chord_error + 1e-4);
+            }
+        }
+
+        // A tighter bound needs more triangles than the uniform default
+        let fine = sphere.to_adaptive_mesh(0.05).unwrap();
+        assert!(fine.triangle_count() > uniform.triangle_count());
+        assert!(sphere.to_adaptive_mesh(0.0).is_err());
+    }
+
+    #[test]
+    fn test_stretched_sphere_normals_stay_perpendicular() {
+        let mut sphere = Sphere::new(5.0);
+        sphere.apply_transform(&Transform {
+            position: Some([1.0, 2.0, 3.0]),
+            rotation: Some([0.4, 0.9, -0.3]),
+            scale: Some([2.0, 1.0, 1.0]),
+        });
+        let mesh = sphere.to_precision_mesh(16);
+        let (lat_segments, lon_segments) = (8, 16);
+        let vertex = |lat: usize, lon: usize| {
+            let i = (lat * (lon_segments + 1) + lon) * 3;
+            [mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2]]
+        };
+
+        // Chords between a vertex's neighbours along each grid line are
+        // parallel to the surface tangent at the vertex
+        for lat in 1..lat_segments {
+            for lon in 1..lon_segments {
+                let i = (lat * (lon_segments + 1) + lon) * 3;
+                let normal = [mesh.normals[i], mesh.normals[i + 1], mesh.normals[i + 2]];
+                for (a, b) in [
+                    (vertex(lat, lon - 1), vertex(lat, lon + 1)),
+                    (vertex(lat - 1, lon), vertex(lat + 1, lon)),
+                ] {
+                    let chord = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+                    let length = (chord[0].powi(2) + chord[1].powi(2) + chord[2].powi(2)).sqrt();
+                    let cos = (0..3).map(|k| normal[k] * chord[k]).sum::<f64>() / length;
+                    assert!(cos.abs() < 1e-9, "normal at ({}, {}) off by {}", lat, lon, cos);
+                }
+            }
+        }
+
+        // A mirroring scale keeps normals pointing out of the surface
+        let mirrored = Transform {
+            position: None,
+            rotation: None,
+            scale: Some([-1.0, 1.0, 1.0]),
+        };
+        let normal = apply_transform_to_normal([1.0, 0.0, 0.0], &mirrored);
+        assert!((normal[0] + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cone_side_normals_are_facet_normals() {
+        let segments = 12;
+        let mesh = Cone::new(3.0, 4.0).to_precision_mesh(segments);
+
+        // Side facets follow the base fan, three unshared vertices each
+        let sides = segments as usize..2 * segments as usize;
+        for triangle in sides {
+            let corner = |k: usize| {
+                let i = (triangle * 3 + k) * 3;
+                [mesh.vertices[i], mesh.vertices[i + 1], mesh.vertices[i + 2]]
+            };
+            let i = triangle * 9;
+            let normal = [mesh.normals[i], mesh.normals[i + 1], mesh.normals[i + 2]];
+            let (apex, a, b) = (corner(0), corner(1), corner(2));
+
+            for edge in [vec3::sub(a, apex), vec3::sub(b, apex), vec3::sub(b, a)] {
+                assert!(vec3::dot(normal, edge).abs() < 1e-12);
+            }
+            let centroid = vec3::scale(vec3::add(vec3::add(apex, a), b), 1.0 / 3.0);
+            assert!(normal[0] * centroid[0] + normal[2] * centroid[2] > 0.0);
+            assert!(normal[1] > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_zero_subdivisions_clamp_to_minimum() {
+        let primitives: [&dyn Primitive; 4] = [
+            &Cylinder::new(5.0, 10.0),
+            &Cone::new(5.0, 10.0),
+            &Sphere::new(5.0),
+            &Torus::new(10.0, 2.0),
+        ];
+
+        for primitive in primitives {
+            let mesh = primitive.to_mesh(0);
+            mesh.is_valid().unwrap();
+            assert!(mesh.vertices.iter().all(|v| v.is_finite()));
+            assert_eq!(mesh.indices, primitive.to_mesh(constants::MIN_SEGMENTS).indices);
+        }
+
+        // Counts at or above the minimum are used as given
+        let cylinder = Cylinder::new(5.0, 10.0);
+        assert_ne!(
+            cylinder.to_mesh(constants::MIN_SEGMENTS).indices,
+            cylinder.to_mesh(constants::MIN_SEGMENTS + 1).indices
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Parametric constraint solving.
+//!
+//! Constraint nodes (`NodeContent::Constraint`) relate points, and each
+//! point is read from the `x`, `y` and `z` parameters of an affected node
+//! (missing coordinates are 0, so 2D sketches can leave out `z`). The
+//! solver moves only the driven parameters, taking damped minimum-norm
+//! Gauss-Newton steps so that underdetermined systems end close to where
+//! they started.
+//!
+//! Supported constraint types:
+//! - `distance`: `[a, b]` are `distance` apart
+//! - `angle`: the angle at `vertex` between `[a, vertex, b]` is `angle`
+//!   degrees
+//! - `concentric`: `[a, b]` share the same centre
+
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::ir::{IRNode, NodeContent, NodeId};
+use crate::geometry::math::vec3::{cross, length, sub};
+use std::collections::HashMap;
+
+/// A named parameter of a node, e.g. `(point_id, "x")`
+pub type ParameterKey = (NodeId, String);
+
+/// Largest residual (model units or radians) accepted as solved
+const SOLVER_TOLERANCE: f64 = 1e-9;
+
+/// Gauss-Newton iterations before giving up
+const MAX_ITERATIONS: usize = 100;
+
+/// Damping beyond which no step can reduce the residual any more
+const MAX_DAMPING: f64 = 1e12;
+
+/// Solve constraint nodes by adjusting the driven parameters
+///
+/// `parameters` holds the current value of every parameter the constraints
+/// read; driven parameters without a value start at 0. Returns the solved
+/// value of each driven parameter, or a constraint violation error when
+/// the constraints cannot all be met.
+pub fn solve_constraints(
+    constraints: &[&IRNode],
+    parameters: &HashMap<ParameterKey, f64>,
+    driven: &[ParameterKey],
+) -> KernelResult<HashMap<ParameterKey, f64>> {
+    solve_with_iterations(constraints, parameters, driven, MAX_ITERATIONS)
+}
+
+/// [`solve_constraints`] with at most `max_iterations` Gauss-Newton steps
+fn solve_with_iterations(
+    constraints: &[&IRNode],
+    parameters: &HashMap<ParameterKey, f64>,
+    driven: &[ParameterKey],
+    max_iterations: usize,
+) -> KernelResult<HashMap<ParameterKey, f64>> {
+    let constraints = constraints
+        .iter()
+        .map(|node| ParsedConstraint::from_node(node))
+        .collect::<KernelResult<Vec<_>>>()?;
+
+    let mut values = parameters.clone();
+    let mut x: Vec<f64> = driven
+        .iter()
+        .map(|key| values.get(key).copied().unwrap_or(0.0))
+        .collect();
+
+    let mut residuals = |x: &[f64]| {
+        for (key, &value) in driven.iter().zip(x) {
+            values.insert(key.clone(), value);
+        }
+        constraints
+            .iter()
+            .flat_map(|constraint| constraint.residuals(&values))
+            .collect::<Vec<f64>>()
+    };
+
+    let mut r = residuals(&x);
+    let mut damping = 1e-9;
+    for _ in 0..max_iterations {
+        if max_abs(&r) <= SOLVER_TOLERANCE {
+            return Ok(driven.iter().cloned().zip(x).collect());
+        }
+        if x.is_empty() {
+            break;
+        }
+
+        // Central-difference Jacobian, one column per driven parameter
+        let mut jacobian = vec![vec![0.0; x.len()]; r.len()];
+        for j in 0..x.len() {
+            let h = 1e-7 * x[j].abs().max(1.0);
+            let mut probe = x.clone();
+            probe[j] = x[j] + h;
+            let forward = residuals(&probe);
+            probe[j] = x[j] - h;
+            let backward = residuals(&probe);
+            for i in 0..r.len() {
+                jacobian[i][j] = (forward[i] - backward[i]) / (2.0 * h);
+            }
+        }
+
+        // Step = -Jᵀ (J Jᵀ + λI)⁻¹ r, retried with more damping until the
+        // residual shrinks
+        let norm = sum_squares(&r);
+        loop {
+            if damping > MAX_DAMPING {
+                return Err(no_solution(&r));
+            }
+            let mut normal: Vec<Vec<f64>> = (0..r.len())
+                .map(|i| (0..r.len()).map(|k| dot(&jacobian[i], &jacobian[k])).collect())
+                .collect();
+            for (i, row) in normal.iter_mut().enumerate() {
+                row[i] += damping;
+            }
+            let Some(y) = solve_linear(normal, r.clone()) else {
+                damping *= 10.0;
+                continue;
+            };
+            let candidate: Vec<f64> = (0..x.len())
+                .map(|j| x[j] - (0..r.len()).map(|i| jacobian[i][j] * y[i]).sum::<f64>())
+                .collect();
+            let candidate_r = residuals(&candidate);
+            if sum_squares(&candidate_r) < norm {
+                x = candidate;
+                r = candidate_r;
+                damping = (damping / 10.0).max(1e-12);
+                break;
+            }
+            damping *= 10.0;
+        }
+    }
+
+    // The last step may have been the one that converged
+    if max_abs(&r) <= SOLVER_TOLERANCE {
+        return Ok(driven.iter().cloned().zip(x).collect());
+    }
+    Err(no_solution(&r))
+}
+
+/// A constraint node with its parameters checked up front
+enum ParsedConstraint {
+    Distance { a: NodeId, b: NodeId, distance: f64 },
+    Angle { a: NodeId, vertex: NodeId, b: NodeId, angle: f64 },
+    Concentric { a: NodeId, b: NodeId },
+}
+
+impl ParsedConstraint {
+    fn from_node(node: &IRNode) -> KernelResult<Self> {
+        let NodeContent::Constraint { constraint_type, affected_nodes, parameters } = &node.content
+        else {
+            return Err(KernelError::invalid_parameter("constraint", node.id.as_str()));
+        };
+
+        let nodes = |count: usize| {
+            if affected_nodes.len() == count {
+                Ok(affected_nodes.clone())
+            } else {
+                Err(KernelError::invalid_parameter(
+                    "affected_nodes",
+                    format!("{} constraint needs {} nodes", constraint_type, count),
+                ))
+            }
+        };
+        let number = |name: &str| {
+            parameters
+                .get(name)
+                .ok_or_else(|| KernelError::missing_parameter(name))?
+                .as_f64()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| KernelError::invalid_parameter(name, parameters[name].to_string()))
+        };
+
+        match constraint_type.as_str() {
+            "distance" => {
+                let distance = number("distance")?;
+                if distance < 0.0 {
+                    return Err(KernelError::invalid_parameter("distance", distance.to_string()));
+                }
+                let [a, b] = <[NodeId; 2]>::try_from(nodes(2)?).unwrap();
+                Ok(ParsedConstraint::Distance { a, b, distance })
+            }
+            "angle" => {
+                let angle = number("angle")?.to_radians();
+                let [a, vertex, b] = <[NodeId; 3]>::try_from(nodes(3)?).unwrap();
+                Ok(ParsedConstraint::Angle { a, vertex, b, angle })
+            }
+            "concentric" => {
+                let [a, b] = <[NodeId; 2]>::try_from(nodes(2)?).unwrap();
+                Ok(ParsedConstraint::Concentric { a, b })
+            }
+            other => Err(KernelError::invalid_parameter("constraint_type", other)),
+        }
+    }
+
+    fn residuals(&self, values: &HashMap<ParameterKey, f64>) -> Vec<f64> {
+        let point = |node: &NodeId| {
+            ["x", "y", "z"].map(|axis| {
+                values.get(&(node.clone(), axis.to_string())).copied().unwrap_or(0.0)
+            })
+        };
+
+        match self {
+            ParsedConstraint::Distance { a, b, distance } => {
+                vec![length(sub(point(b), point(a))) - distance]
+            }
+            ParsedConstraint::Angle { a, vertex, b, angle } => {
+                let u = sub(point(a), point(vertex));
+                let v = sub(point(b), point(vertex));
+                vec![length(cross(u, v)).atan2(dot(&u, &v)) - angle]
+            }
+            ParsedConstraint::Concentric { a, b } => sub(point(b), point(a)).to_vec(),
+        }
+    }
+}
+
+fn no_solution(residuals: &[f64]) -> KernelError {
+    KernelError::constraint_violation(format!(
+        "Constraints have no solution (largest residual {:.3e})",
+        max_abs(residuals)
+    ))
+}
+
+/// Gaussian elimination with partial pivoting; `None` if singular
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &k| a[i][col].abs().total_cmp(&a[k][col].abs()))?;
+        if a[pivot][col].abs() < f64::MIN_POSITIVE {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (value, p) in a[row].iter_mut().zip(&pivot_row).skip(col) {
+                *value -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let tail: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - tail) / a[row][row];
+    }
+    Some(x)
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn sum_squares(values: &[f64]) -> f64 {
+    dot(values, values)
+}
+
+fn max_abs(values: &[f64]) -> f64 {
+    values.iter().fold(0.0, |m, v| m.max(v.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::ir::{NodeMetadata, NodeSource, NodeType};
+
+    fn point(name: &str) -> NodeId {
+        NodeId::from_user_string(name)
+    }
+
+    fn constraint(constraint_type: &str, nodes: &[&NodeId], parameters: &[(&str, f64)]) -> IRNode {
+        IRNode::new(
+            NodeType::Constraint,
+            NodeContent::Constraint {
+                constraint_type: constraint_type.to_string(),
+                affected_nodes: nodes.iter().map(|&id| id.clone()).collect(),
+                parameters: parameters
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), serde_json::json!(value)))
+                    .collect(),
+            },
+            nodes.iter().map(|&id| id.clone()).collect(),
+            NodeMetadata::new(None, NodeSource::User),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_two_points_at_fixed_distance() {
+        let (a, b) = (point("a"), point("b"));
+        let distance = constraint("distance", &[&a, &b], &[("distance", 5.0)]);
+
+        // a is fixed at the origin, b starts 3 along x and may move in XY
+        let key = |id: &NodeId, axis: &str| (id.clone(), axis.to_string());
+        let parameters: HashMap<ParameterKey, f64> =
+            [(key(&b, "x"), 3.0), (key(&b, "y"), 0.0)].into_iter().collect();
+        let driven = [key(&b, "x"), key(&b, "y")];
+
+        let solved = solve_constraints(&[&distance], &parameters, &driven).unwrap();
+        assert!((solved[&key(&b, "x")] - 5.0).abs() < 1e-9);
+        assert!(solved[&key(&b, "y")].abs() < 1e-9);
+
+        // Nothing driven and the distance is wrong: no solution
+        let err = solve_constraints(&[&distance], &parameters, &[]).unwrap_err();
+        assert!(err.message.contains("no solution"));
+
+        // A negative distance is rejected before solving
+        let bad = constraint("distance", &[&a, &b], &[("distance", -1.0)]);
+        assert!(solve_constraints(&[&bad], &parameters, &driven).is_err());
+    }
+
+    #[test]
+    fn test_converging_on_the_last_iteration_is_solved() {
+        let (a, b) = (point("a"), point("b"));
+        let distance = constraint("distance", &[&a, &b], &[("distance", 5.0)]);
+        let key = (b.clone(), "x".to_string());
+        let parameters: HashMap<ParameterKey, f64> = [(key.clone(), 5.5)].into_iter().collect();
+        let driven = [key.clone()];
+
+        // One step along the line lands on the solution
+        let solved = solve_with_iterations(&[&distance], &parameters, &driven, 1).unwrap();
+        assert!((solved[&key] - 5.0).abs() < 1e-9);
+        assert!(solve_with_iterations(&[&distance], &parameters, &driven, 0).is_err());
+    }
+}
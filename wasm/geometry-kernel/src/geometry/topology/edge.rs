@@ -5,6 +5,7 @@
 //! constraints and geometric properties.
 
 use crate::errors::{KernelError, KernelResult};
+use crate::geometry::math::vec3;
 use crate::geometry::topology::{TopologyId, Vertex};
 use serde::{Deserialize, Serialize};
 
@@ -82,7 +83,7 @@ impl Edge {
     /// Compute edge length given vertex positions
     pub fn compute_length(&mut self, start_pos: [f64; 3], end_pos: [f64; 3]) {
         match &self.edge_type {
-            EdgeType::Linear => {
+            EdgeType::Linear | EdgeType::Convex | EdgeType::Concave => {
                 let dx = end_pos[0] - start_pos[0];
                 let dy = end_pos[1] - start_pos[1];
                 let dz = end_pos[2] - start_pos[2];
@@ -146,7 +147,7 @@ impl Edge {
     /// Get tangent vector at parameter t (0.0 to 1.0)
     pub fn tangent_at(&self, t: f64, start_pos: [f64; 3], end_pos: [f64; 3]) -> [f64; 3] {
         match &self.edge_type {
-            EdgeType::Linear => {
+            EdgeType::Linear | EdgeType::Convex | EdgeType::Concave => {
                 let dx = end_pos[0] - start_pos[0];
                 let dy = end_pos[1] - start_pos[1];
                 let dz = end_pos[2] - start_pos[2];
@@ -174,6 +175,57 @@ impl Edge {
         }
     }
 
+    /// Unit tangents at the start and end of the edge, both pointing in
+    /// the start-to-end direction of travel
+    ///
+    /// Arcs, splines and ellipses use their curve; straight edges, and
+    /// curves too degenerate to give a direction, use the chord.
+    pub fn end_tangents(&self, start_pos: [f64; 3], end_pos: [f64; 3]) -> [[f64; 3]; 2] {
+        let chord = vec3::normalize(vec3::sub(end_pos, start_pos)).unwrap_or([1.0, 0.0, 0.0]);
+        let tangents = match &self.curve {
+            Some(EdgeCurve::Circle {
+                center,
+                axis,
+                angle,
+                ..
+            }) => {
+                // Sweeping by `angle` about `axis` carries start onto end
+                let along = |p: [f64; 3]| {
+                    vec3::scale(vec3::cross(*axis, vec3::sub(p, *center)), angle.signum())
+                };
+                [along(start_pos), along(end_pos)]
+            }
+            Some(EdgeCurve::Spline { control_points, .. }) if control_points.len() >= 2 => {
+                let n = control_points.len();
+                [
+                    vec3::sub(control_points[1], control_points[0]),
+                    vec3::sub(control_points[n - 1], control_points[n - 2]),
+                ]
+            }
+            Some(EdgeCurve::Ellipse {
+                major_axis,
+                minor_axis,
+                start_angle,
+                end_angle,
+                ..
+            }) => {
+                let sign = (end_angle - start_angle).signum();
+                let along = |a: f64| {
+                    vec3::scale(
+                        vec3::add(
+                            vec3::scale(*major_axis, -a.sin()),
+                            vec3::scale(*minor_axis, a.cos()),
+                        ),
+                        sign,
+                    )
+                };
+                [along(*start_angle), along(*end_angle)]
+            }
+            _ => [chord, chord],
+        };
+        tangents.map(|t| vec3::normalize(t).unwrap_or(chord))
+    }
+
     /// Reverse edge orientation
     pub fn reverse(&mut self) {
         std::mem::swap(&mut self.start_vertex, &mut self.end_vertex);
@@ -246,6 +298,10 @@ pub enum EdgeType {
     Spline,
     /// Sharp edge (zero radius)
     Sharp,
+    /// Straight edge where the adjacent faces form an outside corner
+    Convex,
+    /// Straight edge where the adjacent faces form an inside corner
+    Concave,
 }
 
 /// Geometric curve definitions for edges
@@ -5,8 +5,26 @@
 //! and orientation information essential for solid modeling.
 
 use crate::errors::{KernelError, KernelResult};
-use crate::geometry::topology::{EdgeId, TopologyId};
+use crate::geometry::math::vec3;
+use crate::geometry::topology::{Edge, EdgeId, TopologyId, Vertex};
 use serde::{Deserialize, Serialize};
+#[cfg(test)]
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+#[cfg(test)]
+thread_local! {
+    // Areas computed by compute_area on this thread, so tests can check
+    // that memoized measurements skip unchanged faces
+    static AREA_COMPUTATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[cfg(test)]
+pub(crate) fn area_computations() -> usize {
+    AREA_COMPUTATIONS.with(Cell::get)
+}
 
 /// Unique identifier for faces
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -45,6 +63,9 @@ pub struct Face {
     pub is_planar: bool,
     /// Face normal vector (for planar faces)
     pub normal: Option<[f64; 3]>,
+    /// Geometry fingerprint `area` was last measured for
+    #[serde(skip)]
+    area_key: Option<u64>,
 }
 
 impl Face {
@@ -60,6 +81,7 @@ impl Face {
             manufacturing_constraints: Vec::new(),
             is_planar: matches!(face_type, FaceType::Planar),
             normal: None,
+            area_key: None,
         }
     }
 
@@ -90,6 +112,7 @@ impl Face {
             manufacturing_constraints: Vec::new(),
             is_planar,
             normal,
+            area_key: None,
         }
     }
 
@@ -111,13 +134,39 @@ impl Face {
         }
     }
 
+    /// Face area, computed only when the surface or boundary has changed
+    /// since the last measurement
+    pub fn measured_area(&mut self) -> KernelResult<f64> {
+        let key = self.geometry_key();
+        if self.area_key != Some(key) {
+            self.compute_area()?;
+            self.area_key = Some(key);
+        }
+        Ok(self.area)
+    }
+
+    /// Hash of what the area depends on
+    fn geometry_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        serde_json::to_string(&self.surface)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        self.boundary_edges.hash(&mut hasher);
+        hasher.finish()
+    }
+
     /// Compute face area (requires surface definition)
     pub fn compute_area(&mut self) -> KernelResult<f64> {
+        #[cfg(test)]
+        AREA_COMPUTATIONS.with(|count| count.set(count.get() + 1));
+
         match &self.surface {
             Some(FaceSurface::Plane { .. }) => {
-                // For planar faces, would triangulate and sum triangle areas
-                // Simplified calculation for now
-                self.area = 1.0; // Placeholder
+                // A plane has no extent of its own; keep the area measured
+                // from the boundary when the face was built
+                if self.area <= 0.0 {
+                    self.area = 1.0; // Placeholder
+                }
                 Ok(self.area)
             }
             Some(FaceSurface::Cylinder { radius, height, .. }) => {
@@ -190,7 +239,7 @@ impl Face {
             Some(FaceSurface::Cylinder { axis, .. }) => {
                 // Compute normal for cylindrical surface
                 // Simplified - would need proper parametric computation
-                let normalized_axis = normalize_vector(*axis);
+                let normalized_axis = vec3::normalize_or_z(*axis);
                 Ok(normalized_axis)
             }
             Some(FaceSurface::Sphere { center, .. }) => {
@@ -262,6 +311,52 @@ impl Face {
         self.manufacturing_constraints.push(constraint);
     }
 
+    /// Tightest surface finish required of this face (max Ra, µm)
+    pub fn required_roughness(&self) -> Option<f64> {
+        self.manufacturing_constraints
+            .iter()
+            .filter(|c| c.constraint_type == FaceConstraintType::SurfaceFinish)
+            .filter_map(|c| c.parameters.get("max_roughness").copied())
+            .reduce(f64::min)
+    }
+
+    /// Whether boundary vertices, given in loop order, lie within
+    /// `tolerance` of their best-fit plane
+    ///
+    /// The plane passes through the vertex centroid with the loop's Newell
+    /// normal. Fewer than three vertices, or collinear ones, are trivially
+    /// planar.
+    pub fn is_actually_planar(&self, vertex_positions: &[[f64; 3]], tolerance: f64) -> bool {
+        let n = vertex_positions.len();
+        if n < 3 {
+            return true;
+        }
+
+        let mut normal = [0.0; 3];
+        let mut centroid = [0.0; 3];
+        for (k, p) in vertex_positions.iter().enumerate() {
+            let q = vertex_positions[(k + 1) % n];
+            normal[0] += (p[1] - q[1]) * (p[2] + q[2]);
+            normal[1] += (p[2] - q[2]) * (p[0] + q[0]);
+            normal[2] += (p[0] - q[0]) * (p[1] + q[1]);
+            for axis in 0..3 {
+                centroid[axis] += p[axis] / n as f64;
+            }
+        }
+        let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+        if length == 0.0 {
+            return true;
+        }
+
+        vertex_positions.iter().all(|p| {
+            let distance = (0..3)
+                .map(|axis| (p[axis] - centroid[axis]) * normal[axis])
+                .sum::<f64>()
+                / length;
+            distance.abs() <= tolerance
+        })
+    }
+
     /// Get edge count
     pub fn edge_count(&self) -> usize {
         self.boundary_edges.len()
@@ -272,6 +367,85 @@ impl Face {
         // A valid face should have at least 3 edges
         self.boundary_edges.len() >= 3
     }
+
+    /// Check that the boundary edges, in their stored order, form one
+    /// closed chain
+    ///
+    /// Each edge must share a vertex with the next, either way round, and
+    /// the last must lead back to where the first started. A single edge
+    /// closes on itself, as a circular cap does. Unlike
+    /// [`is_valid_loop`](Self::is_valid_loop) this looks at the edges
+    /// themselves, so it catches faces whose edges exist but are out of
+    /// order or disconnected.
+    pub fn validate_loop(
+        &self,
+        edges: &HashMap<EdgeId, Edge>,
+        vertices: &HashMap<TopologyId, Vertex>,
+    ) -> KernelResult<()> {
+        let chain = self
+            .boundary_edges
+            .iter()
+            .map(|edge_id| {
+                let edge = edges.get(edge_id).ok_or_else(|| {
+                    KernelError::topology_error(format!(
+                        "Face references missing edge {}",
+                        edge_id.as_str()
+                    ))
+                })?;
+                for vertex in [&edge.start_vertex, &edge.end_vertex] {
+                    if !vertices.contains_key(vertex) {
+                        return Err(KernelError::topology_error(format!(
+                            "Edge {} references missing vertex {}",
+                            edge_id.as_str(),
+                            vertex.as_str()
+                        )));
+                    }
+                }
+                Ok((edge_id, edge))
+            })
+            .collect::<KernelResult<Vec<_>>>()?;
+
+        let Some(((_, first), rest)) = chain.split_first() else {
+            return Err(KernelError::topology_error("Face has no boundary edges"));
+        };
+
+        // The first edge may run either way round the loop; walk from each
+        // of its ends and report the walk that got further
+        let walk = |start: &TopologyId, end: &TopologyId| {
+            let mut current = end;
+            for (index, (_, edge)) in rest.iter().enumerate() {
+                current = if &edge.start_vertex == current {
+                    &edge.end_vertex
+                } else if &edge.end_vertex == current {
+                    &edge.start_vertex
+                } else {
+                    return Err(index + 1);
+                };
+            }
+            if current == start {
+                Ok(())
+            } else {
+                Err(chain.len())
+            }
+        };
+
+        match (
+            walk(&first.start_vertex, &first.end_vertex),
+            walk(&first.end_vertex, &first.start_vertex),
+        ) {
+            (Ok(()), _) | (_, Ok(())) => Ok(()),
+            (Err(forward), Err(backward)) => {
+                let broken = forward.max(backward);
+                Err(KernelError::topology_error(match chain.get(broken) {
+                    Some((edge_id, _)) => format!(
+                        "Boundary edge {} does not connect to the previous edge",
+                        edge_id.as_str()
+                    ),
+                    None => "Boundary edges do not close into a loop".to_string(),
+                }))
+            }
+        }
+    }
 }
 
 /// Face orientation for consistent normal direction
@@ -354,7 +528,7 @@ impl FaceConstraint {
         }
     }
 
-    /// Create surface finish requirement
+    /// Create surface finish requirement, as a maximum Ra in micrometres
     pub fn surface_finish(
         roughness: f64,
         process: crate::geometry::ir::ManufacturingProcess,
@@ -549,27 +723,6 @@ pub struct FaceCollectionStats {
     pub manufacturability_ratio: f64,
 }
 
-// Helper functions
-
-/// Normalize a vector
-fn normalize_vector(vec: [f64; 3]) -> [f64; 3] {
-    let magnitude = (vec[0].powi(2) + vec[1].powi(2) + vec[2].powi(2)).sqrt();
-    if magnitude > 1e-10 {
-        [vec[0] / magnitude, vec[1] / magnitude, vec[2] / magnitude]
-    } else {
-        [0.0, 0.0, 1.0] // Default normal
-    }
-}
-
-/// Compute cross product of two vectors
-fn cross_product(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
-    [
-        a[1] * b[2] - a[2] * b[1],
-        a[2] * b[0] - a[0] * b[2],
-        a[0] * b[1] - a[1] * b[0],
-    ]
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -713,4 +866,31 @@ mod tests {
         let valid_face = Face::new(valid_edges, FaceType::Planar);
         assert!(valid_face.is_valid_loop());
     }
+
+    #[test]
+    fn test_validate_loop_order() {
+        let complex = crate::geometry::topology::create_box_topology(2.0, 2.0, 2.0).unwrap();
+        let mut face_ids: Vec<_> = complex.faces.keys().collect();
+        face_ids.sort_by_key(|id| id.as_str());
+        for face_id in face_ids {
+            let face = &complex.faces[face_id];
+            assert!(face
+                .validate_loop(&complex.edges, &complex.vertices)
+                .is_ok());
+        }
+
+        // Opposite sides of the bottom face next to each other share no vertex
+        let mut shuffled = complex.faces[&FaceId::new("face_0".to_string())].clone();
+        shuffled.boundary_edges.swap(1, 2);
+        let error = shuffled
+            .validate_loop(&complex.edges, &complex.vertices)
+            .unwrap_err();
+        assert!(error.message.contains("edge_2"));
+
+        let mut open = complex.faces[&FaceId::new("face_0".to_string())].clone();
+        open.boundary_edges.pop();
+        assert!(open
+            .validate_loop(&complex.edges, &complex.vertices)
+            .is_err());
+    }
 }
@@ -0,0 +1,380 @@
+//! Conversion of triangle meshes into topological complexes.
+//!
+//! Adjacent coplanar triangles are merged into planar faces so that the
+//! complex has one face per flat region rather than one per triangle. A
+//! region becomes a single face only when its boundary is one convex loop,
+//! which the tessellator reproduces exactly; other regions keep one face
+//! per triangle. Faces share their edges, so a closed mesh gives a closed
+//! complex. Edges between two faces are typed convex or concave by their
+//! dihedral angle; edges inside a flat region stay linear.
+
+use crate::config::KernelConfig;
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::analysis::mesh_diff;
+use crate::geometry::math::vec3::{cross, dot, scale, sub};
+use crate::geometry::topology::face::FaceSurface;
+use crate::geometry::topology::tessellate::{topology_to_mesh_with_config, weld_points};
+use crate::geometry::topology::{
+    Edge, EdgeId, EdgeType, Face, FaceType, TopologicalComplex, TopologyId, Vertex,
+};
+use crate::types::PreviewMesh;
+use std::collections::HashMap;
+
+/// Angle (radians) within which adjacent triangles count as coplanar,
+/// loose enough to absorb the f32 rounding of preview meshes
+const COPLANAR_ANGLE: f64 = 1e-4;
+
+/// Relative rounding error of f32 preview vertex positions
+const F32_RELATIVE_ERROR: f64 = 1e-6;
+
+/// Circle segments used when tessellating a round trip
+const ROUNDTRIP_SUBDIVISIONS: u32 = 32;
+
+/// Build a topological complex from a triangle mesh
+pub fn complex_from_mesh(mesh: &PreviewMesh) -> KernelResult<TopologicalComplex> {
+    complex_from_mesh_with_config(mesh, &KernelConfig::default())
+}
+
+/// Build a topological complex, welding vertices at the configured tolerance
+pub fn complex_from_mesh_with_config(
+    mesh: &PreviewMesh,
+    config: &KernelConfig,
+) -> KernelResult<TopologicalComplex> {
+    config.validate()?;
+
+    let points: Vec<[f64; 3]> = mesh
+        .vertices
+        .chunks_exact(3)
+        .map(|v| [v[0] as f64, v[1] as f64, v[2] as f64])
+        .collect();
+    let (ids, positions) = weld_points(&points, config.linear_tolerance);
+    let plane_tolerance = comparison_tolerance(mesh, config);
+
+    // Welded, non-degenerate triangles with unit normals
+    let mut triangles: Vec<[usize; 3]> = Vec::new();
+    let mut normals: Vec<[f64; 3]> = Vec::new();
+    for tri in mesh.indices.chunks_exact(3) {
+        if tri.iter().any(|&i| i as usize >= ids.len()) {
+            return Err(KernelError::topology_error(
+                "Triangle index out of range of mesh vertices",
+            ));
+        }
+        let t = [ids[tri[0] as usize], ids[tri[1] as usize], ids[tri[2] as usize]];
+        let n = cross(
+            sub(positions[t[1]], positions[t[0]]),
+            sub(positions[t[2]], positions[t[0]]),
+        );
+        let len = dot(n, n).sqrt();
+        if len > 0.0 {
+            triangles.push(t);
+            normals.push(scale(n, 1.0 / len));
+        }
+    }
+
+    let mut edge_triangles: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (i, t) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (t[k], t[(k + 1) % 3]);
+            edge_triangles.entry((a.min(b), a.max(b))).or_default().push(i);
+        }
+    }
+
+    let mut builder = ComplexBuilder::new(&positions, config.linear_tolerance);
+    let cos_tolerance = COPLANAR_ANGLE.cos();
+    let mut assigned = vec![false; triangles.len()];
+
+    for seed in 0..triangles.len() {
+        if assigned[seed] {
+            continue;
+        }
+
+        // Grow against the seed plane so that regions cannot drift
+        let normal = normals[seed];
+        let origin = positions[triangles[seed][0]];
+        assigned[seed] = true;
+        let mut region = vec![seed];
+        let mut stack = vec![seed];
+
+        while let Some(current) = stack.pop() {
+            let t = triangles[current];
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                for &other in &edge_triangles[&(a.min(b), a.max(b))] {
+                    if assigned[other] || dot(normals[other], normal) < cos_tolerance {
+                        continue;
+                    }
+                    let on_plane = triangles[other]
+                        .iter()
+                        .all(|&v| dot(sub(positions[v], origin), normal).abs() <= plane_tolerance);
+                    if on_plane {
+                        assigned[other] = true;
+                        region.push(other);
+                        stack.push(other);
+                    }
+                }
+            }
+        }
+
+        let outline = trace_loop(region.iter().map(|&i| triangles[i]))
+            .filter(|outline| is_convex(outline, &positions, normal));
+        match outline {
+            Some(outline) => builder.add_planar_face(&outline, normal)?,
+            None => {
+                for &i in &region {
+                    builder.add_planar_face(&triangles[i], normals[i])?;
+                }
+            }
+        }
+    }
+
+    builder.classify_edges();
+    Ok(builder.complex)
+}
+
+/// Check that a mesh survives conversion to topology and back
+///
+/// The mesh is converted with [`complex_from_mesh`], tessellated again and
+/// compared with [`mesh_diff`] at the linear tolerance (or the f32
+/// precision of the mesh, whichever is larger).
+pub fn verify_roundtrip(mesh: &PreviewMesh) -> bool {
+    verify_roundtrip_with_config(mesh, &KernelConfig::default())
+}
+
+/// Round-trip check using the tolerances of a kernel configuration
+pub fn verify_roundtrip_with_config(mesh: &PreviewMesh, config: &KernelConfig) -> bool {
+    let tolerance = comparison_tolerance(mesh, config);
+
+    complex_from_mesh_with_config(mesh, config)
+        .and_then(|complex| topology_to_mesh_with_config(&complex, ROUNDTRIP_SUBDIVISIONS, config))
+        .is_ok_and(|rebuilt| mesh_diff(mesh, &rebuilt, tolerance).within_tolerance)
+}
+
+/// Linear tolerance, widened to the f32 precision of the mesh coordinates
+fn comparison_tolerance(mesh: &PreviewMesh, config: &KernelConfig) -> f64 {
+    let extent = mesh
+        .vertices
+        .iter()
+        .fold(0.0f64, |acc, &c| acc.max((c as f64).abs()));
+    config.linear_tolerance.max(extent * F32_RELATIVE_ERROR)
+}
+
+/// Incrementally adds faces, sharing vertices and edges between them
+struct ComplexBuilder<'a> {
+    complex: TopologicalComplex,
+    positions: &'a [[f64; 3]],
+    tolerance: f64,
+    vertex_ids: HashMap<usize, TopologyId>,
+    edge_ids: HashMap<(usize, usize), EdgeId>,
+    /// Direction each face runs along an edge, with the face normal
+    edge_uses: HashMap<(usize, usize), Vec<((usize, usize), [f64; 3])>>,
+}
+
+impl<'a> ComplexBuilder<'a> {
+    fn new(positions: &'a [[f64; 3]], tolerance: f64) -> Self {
+        ComplexBuilder {
+            complex: TopologicalComplex::new(),
+            positions,
+            tolerance,
+            vertex_ids: HashMap::new(),
+            edge_ids: HashMap::new(),
+            edge_uses: HashMap::new(),
+        }
+    }
+
+    fn vertex(&mut self, index: usize) -> KernelResult<TopologyId> {
+        if let Some(id) = self.vertex_ids.get(&index) {
+            return Ok(id.clone());
+        }
+        let id = self
+            .complex
+            .add_vertex(Vertex::with_tolerance(self.positions[index], self.tolerance))?;
+        self.vertex_ids.insert(index, id.clone());
+        Ok(id)
+    }
+
+    fn edge(&mut self, a: usize, b: usize) -> KernelResult<EdgeId> {
+        let key = (a.min(b), a.max(b));
+        if let Some(id) = self.edge_ids.get(&key) {
+            return Ok(id.clone());
+        }
+        let mut edge = Edge::new(self.vertex(key.0)?, self.vertex(key.1)?, EdgeType::Linear);
+        edge.tolerance = self.tolerance;
+        edge.compute_length(self.positions[key.0], self.positions[key.1]);
+        let id = self.complex.add_edge(edge)?;
+        self.edge_ids.insert(key, id.clone());
+        Ok(id)
+    }
+
+    fn add_planar_face(&mut self, outline: &[usize], normal: [f64; 3]) -> KernelResult<()> {
+        let mut edges = Vec::with_capacity(outline.len());
+        for (k, &a) in outline.iter().enumerate() {
+            let b = outline[(k + 1) % outline.len()];
+            edges.push(self.edge(a, b)?);
+            self.edge_uses
+                .entry((a.min(b), a.max(b)))
+                .or_default()
+                .push(((a, b), normal));
+        }
+
+        let surface = FaceSurface::Plane {
+            point: self.positions[outline[0]],
+            normal,
+        };
+        let mut face = Face::with_surface(edges, FaceType::Planar, surface);
+        face.tolerance = self.tolerance;
+        self.complex.add_face(face)?;
+        Ok(())
+    }
+
+    /// Type every edge shared by two faces as convex or concave
+    ///
+    /// Walking the edge the way the first face does, the faces fold away
+    /// from each other (convex) when `n1 x n2` points along the walk.
+    fn classify_edges(&mut self) {
+        for (key, uses) in &self.edge_uses {
+            let [((a, b), n1), (_, n2)] = uses.as_slice() else {
+                continue;
+            };
+            let direction = sub(self.positions[*b], self.positions[*a]);
+            let length = dot(direction, direction).sqrt();
+            if length == 0.0 {
+                continue;
+            }
+
+            let fold = dot(cross(*n1, *n2), direction) / length;
+            let edge_type = if fold > COPLANAR_ANGLE {
+                EdgeType::Convex
+            } else if fold < -COPLANAR_ANGLE {
+                EdgeType::Concave
+            } else {
+                continue;
+            };
+            if let Some(edge) = self.complex.edges.get_mut(&self.edge_ids[key]) {
+                edge.edge_type = edge_type;
+            }
+        }
+    }
+}
+
+/// Boundary of a triangle region as one loop following the triangle winding
+///
+/// Returns `None` if the boundary is pinched or has several loops (holes).
+fn trace_loop(triangles: impl Iterator<Item = [usize; 3]>) -> Option<Vec<usize>> {
+    let mut directed: HashMap<(usize, usize), usize> = HashMap::new();
+    for t in triangles {
+        for k in 0..3 {
+            *directed.entry((t[k], t[(k + 1) % 3])).or_default() += 1;
+        }
+    }
+
+    let mut next: HashMap<usize, usize> = HashMap::new();
+    for (&(a, b), &count) in &directed {
+        if count == 1 && !directed.contains_key(&(b, a)) && next.insert(a, b).is_some() {
+            return None;
+        }
+    }
+
+    let start = *next.keys().min()?;
+    let mut outline = vec![start];
+    let mut current = next[&start];
+    while current != start {
+        if outline.len() >= next.len() {
+            return None;
+        }
+        outline.push(current);
+        current = *next.get(&current)?;
+    }
+
+    (outline.len() == next.len()).then_some(outline)
+}
+
+/// Whether a loop turns the same way as `normal` at every corner
+///
+/// Collinear corners are allowed: they come from T-junctions with
+/// neighbouring faces and fan triangulation handles them.
+fn is_convex(outline: &[usize], positions: &[[f64; 3]], normal: [f64; 3]) -> bool {
+    let n = outline.len();
+    (0..n).all(|k| {
+        let a = positions[outline[(k + n - 1) % n]];
+        let b = positions[outline[k]];
+        let c = positions[outline[(k + 1) % n]];
+        let (incoming, outgoing) = (sub(b, a), sub(c, b));
+        let scale = (dot(incoming, incoming) * dot(outgoing, outgoing)).sqrt();
+        dot(cross(incoming, outgoing), normal) >= -COPLANAR_ANGLE * scale
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::primitives::{Box as BoxPrimitive, Cylinder};
+    use crate::geometry::topology::topology_to_mesh;
+    use crate::geometry::Primitive;
+
+    #[test]
+    fn test_box_roundtrip() {
+        let mesh = BoxPrimitive::new(2.0, 3.0, 4.0).to_mesh(16);
+        let complex = complex_from_mesh(&mesh).unwrap();
+
+        assert_eq!(complex.vertices.len(), 8);
+        assert_eq!(complex.edges.len(), 12);
+        assert_eq!(complex.faces.len(), 6);
+        assert!(complex.faces.values().all(|f| f.boundary_edges.len() == 4));
+
+        let rebuilt = topology_to_mesh(&complex, 16).unwrap();
+        let diff = mesh_diff(&mesh, &rebuilt, 1e-6);
+        assert!(diff.hausdorff_distance < 1e-6);
+        assert!(verify_roundtrip(&mesh));
+    }
+
+    #[test]
+    fn test_curved_mesh_roundtrip() {
+        // Side quads merge, caps stay convex polygons
+        let mesh = Cylinder::new(1.0, 2.0).to_mesh(16);
+        assert!(verify_roundtrip(&mesh));
+    }
+    #[test]
+    fn test_slot_concave_edges() {
+        // U-shaped profile (a 2 x 2 slot in a 6 x 4 block) extruded along Z
+        let profile = [
+            [0.0, 0.0], [6.0, 0.0], [6.0, 4.0], [4.0, 4.0],
+            [4.0, 2.0], [2.0, 2.0], [2.0, 4.0], [0.0, 4.0],
+        ];
+        let cap = [[0, 1, 4], [1, 2, 4], [2, 3, 4], [0, 4, 5], [0, 5, 7], [5, 6, 7]];
+
+        let mut mesh = PreviewMesh::new();
+        for z in [0.0f32, 5.0] {
+            for [x, y] in profile {
+                mesh.vertices.extend_from_slice(&[x, y, z]);
+            }
+        }
+        for [a, b, c] in cap {
+            mesh.indices.extend_from_slice(&[a, c, b, a + 8, b + 8, c + 8]);
+        }
+        for p in 0..8 {
+            let q = (p + 1) % 8;
+            mesh.indices.extend_from_slice(&[p, q, q + 8, p, q + 8, p + 8]);
+        }
+        mesh.normals = vec![0.0; mesh.vertices.len()];
+
+        let complex = complex_from_mesh(&mesh).unwrap();
+        let count = |edge_type: EdgeType| {
+            complex.edges.values().filter(|e| e.edge_type == edge_type).count()
+        };
+        assert_eq!(count(EdgeType::Concave), 2);
+        assert_eq!(count(EdgeType::Convex), 6 + 16);
+
+        // The two inside corners at the bottom of the slot
+        let flagged = complex.hard_to_machine_edges(0.5);
+        assert_eq!(flagged.len(), 2);
+        for id in &flagged {
+            let edge = &complex.edges[id];
+            let start = complex.vertices[&edge.start_vertex].position;
+            let end = complex.vertices[&edge.end_vertex].position;
+            assert_eq!(start[1], 2.0);
+            assert!(start[0] == 2.0 || start[0] == 4.0);
+            assert_eq!((start[0], start[1]), (end[0], end[1]));
+        }
+        assert!(complex.hard_to_machine_edges(0.0).is_empty());
+    }
+}
@@ -0,0 +1,286 @@
+//! Dimension queries on topological complexes.
+//!
+//! These measure the model rather than its tessellation, so drawings can
+//! be dimensioned from exact vertex positions and surface definitions.
+
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::math::vec3::{self, dot, scale, sub};
+use crate::geometry::topology::edge::EdgeCurve;
+use crate::geometry::topology::face::FaceSurface;
+use crate::geometry::topology::tessellate::{boundary_loop, Segments};
+use crate::geometry::topology::{
+    EdgeId, EdgeType, Face, FaceId, FaceOrientation, TopologicalComplex, TopologyId,
+};
+
+/// Segments used to sample curved boundary edges when deriving a normal
+const BOUNDARY_SEGMENTS: usize = 32;
+
+impl TopologicalComplex {
+    /// Straight-line distance between two vertices
+    pub fn vertex_distance(&self, a: &TopologyId, b: &TopologyId) -> KernelResult<f64> {
+        let d = sub(self.vertex_position(a)?, self.vertex_position(b)?);
+        Ok(dot(d, d).sqrt())
+    }
+
+    /// Angle in radians between the outward normals of two planar faces
+    ///
+    /// Adjacent faces of a box measure `π/2`; opposite faces measure `π`.
+    pub fn face_angle(&self, a: &FaceId, b: &FaceId) -> KernelResult<f64> {
+        let (na, nb) = (self.planar_normal(a)?, self.planar_normal(b)?);
+        Ok(dot(na, nb).clamp(-1.0, 1.0).acos())
+    }
+
+    /// Radius of a cylindrical face
+    pub fn face_radius(&self, face_id: &FaceId) -> KernelResult<f64> {
+        match &self.face(face_id)?.surface {
+            Some(FaceSurface::Cylinder { radius, .. }) => Ok(*radius),
+            _ => Err(KernelError::topology_error(format!(
+                "Face {} is not cylindrical",
+                face_id.as_str()
+            ))),
+        }
+    }
+
+    /// Interior angle in radians between the two planar faces at an edge
+    ///
+    /// Box edges measure `π/2` and a flat crease `π`. Edges tagged
+    /// [`EdgeType::Concave`] measure the reflex angle, `3π/2` for the
+    /// inside corner of an L. `None` unless the edge joins exactly two
+    /// planar faces.
+    pub fn edge_dihedral_angle(&self, edge_id: &EdgeId) -> Option<f64> {
+        let (a, b) = self.edge_faces(edge_id).ok()?;
+        let angle = std::f64::consts::PI - self.face_angle(a, b).ok()?;
+        Some(match self.edges[edge_id].edge_type {
+            EdgeType::Concave => 2.0 * std::f64::consts::PI - angle,
+            _ => angle,
+        })
+    }
+
+    /// Radius of a circular edge; `None` for other curves and unknown edges
+    pub fn edge_radius(&self, edge_id: &EdgeId) -> Option<f64> {
+        match self.edges.get(edge_id)?.curve {
+            Some(EdgeCurve::Circle { radius, .. }) => Some(radius),
+            _ => None,
+        }
+    }
+
+    /// Largest fillet radius an edge between two planar faces can take
+    ///
+    /// The fillet's tangent lines sit `r / tan(α / 2)` from the edge on
+    /// each face, where `α` is the angle of the wedge being rounded. The
+    /// limit is the radius at which a tangent line reaches the nearest
+    /// boundary point of either face away from the edge, so a sheet's edge
+    /// is bounded by its thickness. Geometry beyond the two faces is not
+    /// considered.
+    pub fn max_safe_fillet_radius(&self, edge_id: &EdgeId) -> KernelResult<f64> {
+        let (a, b) = self.edge_faces(edge_id)?;
+        let edge = &self.edges[edge_id];
+
+        let start = self.vertex_position(&edge.start_vertex)?;
+        let direction = vec3::normalize(sub(self.vertex_position(&edge.end_vertex)?, start))
+            .ok_or_else(|| {
+                KernelError::topology_error(format!("Edge {} has zero length", edge_id.as_str()))
+            })?;
+        let segments = Segments {
+            uniform: BOUNDARY_SEGMENTS,
+            max_chord_error: None,
+        };
+
+        // Room on each face between the edge line and the rest of its outline
+        let mut room = f64::INFINITY;
+        for face_id in [a, b] {
+            let face = self.face(face_id)?;
+            for point in boundary_loop(self, face, segments)? {
+                let offset = sub(point, start);
+                let distance = vec3::length(sub(offset, scale(direction, dot(offset, direction))));
+                if distance > face.tolerance.max(edge.tolerance) {
+                    room = room.min(distance);
+                }
+            }
+        }
+
+        let wedge = std::f64::consts::PI - self.face_angle(a, b)?;
+        Ok(room * (wedge / 2.0).tan())
+    }
+
+    /// The two faces meeting at an edge
+    fn edge_faces(&self, edge_id: &EdgeId) -> KernelResult<(&FaceId, &FaceId)> {
+        if !self.edges.contains_key(edge_id) {
+            return Err(KernelError::topology_error(format!(
+                "Unknown edge {}",
+                edge_id.as_str()
+            )));
+        }
+        match self.adjacency.get_faces_for_edge(edge_id)[..] {
+            [a, b] => Ok((a, b)),
+            ref faces => Err(KernelError::topology_error(format!(
+                "Edge {} borders {} faces, not 2",
+                edge_id.as_str(),
+                faces.len()
+            ))),
+        }
+    }
+
+    /// Unit outward normal of a planar face
+    ///
+    /// Uses the stored normal or plane surface when present, otherwise the
+    /// Newell normal of the boundary loop, pointed away from the centroid
+    /// of the complex.
+    pub(crate) fn planar_normal(&self, face_id: &FaceId) -> KernelResult<[f64; 3]> {
+        let face = self.face(face_id)?;
+        if !face.is_planar {
+            return Err(KernelError::topology_error(format!(
+                "Face {} is not planar",
+                face_id.as_str()
+            )));
+        }
+
+        if let Some(normal) = face.normal {
+            return normalize(normal, face_id);
+        }
+        if let Some(FaceSurface::Plane { normal, .. }) = &face.surface {
+            let normal = normalize(*normal, face_id)?;
+            return Ok(match face.orientation {
+                FaceOrientation::Outward => normal,
+                FaceOrientation::Inward => scale(normal, -1.0),
+            });
+        }
+
+        let segments = Segments {
+            uniform: BOUNDARY_SEGMENTS,
+            max_chord_error: None,
+        };
+        let outline = boundary_loop(self, face, segments)?;
+        let mut newell = [0.0; 3];
+        for (k, &p) in outline.iter().enumerate() {
+            let q = outline[(k + 1) % outline.len()];
+            newell[0] += (p[1] - q[1]) * (p[2] + q[2]);
+            newell[1] += (p[2] - q[2]) * (p[0] + q[0]);
+            newell[2] += (p[0] - q[0]) * (p[1] + q[1]);
+        }
+        let normal = normalize(newell, face_id)?;
+
+        let centroid = average(self.vertices.values().map(|v| &v.position));
+        let outward = sub(average(outline.iter()), centroid);
+        Ok(if dot(normal, outward) < 0.0 {
+            scale(normal, -1.0)
+        } else {
+            normal
+        })
+    }
+
+    fn face(&self, face_id: &FaceId) -> KernelResult<&Face> {
+        self.faces.get(face_id).ok_or_else(|| {
+            KernelError::topology_error(format!("Unknown face {}", face_id.as_str()))
+        })
+    }
+
+    pub(crate) fn vertex_position(&self, id: &TopologyId) -> KernelResult<[f64; 3]> {
+        self.vertices.get(id).map(|v| v.position).ok_or_else(|| {
+            KernelError::topology_error(format!("Unknown vertex {}", id.as_str()))
+        })
+    }
+}
+
+fn normalize(a: [f64; 3], face_id: &FaceId) -> KernelResult<[f64; 3]> {
+    vec3::normalize(a).ok_or_else(|| {
+        KernelError::topology_error(format!(
+            "Face {} has a degenerate normal",
+            face_id.as_str()
+        ))
+    })
+}
+
+fn average<'a>(points: impl Iterator<Item = &'a [f64; 3]>) -> [f64; 3] {
+    let (sum, count) = points.fold(([0.0; 3], 0usize), |(acc, n), p| {
+        ([acc[0] + p[0], acc[1] + p[1], acc[2] + p[2]], n + 1)
+    });
+    scale(sum, 1.0 / count.max(1) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::topology::{create_box_topology, create_cylinder_topology};
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    fn face(index: usize) -> FaceId {
+        FaceId::new(format!("face_{}", index))
+    }
+
+    #[test]
+    fn test_box_dimensions() {
+        let complex = create_box_topology(2.0, 3.0, 4.0).unwrap();
+
+        // Opposite corners span the box diagonal
+        let distance = complex
+            .vertex_distance(&TopologyId::new("vertex", 0), &TopologyId::new("vertex", 6))
+            .unwrap();
+        assert!((distance - 29.0f64.sqrt()).abs() < 1e-12);
+
+        // Bottom against front is a right angle, bottom against top is flat
+        assert!((complex.face_angle(&face(0), &face(2)).unwrap() - FRAC_PI_2).abs() < 1e-12);
+        assert!((complex.face_angle(&face(0), &face(1)).unwrap() - PI).abs() < 1e-12);
+        assert!(complex.face_radius(&face(0)).is_err());
+    }
+
+    #[test]
+    fn test_fillet_radius_bounded_by_plate_thickness() {
+        let complex = create_box_topology(40.0, 2.0, 30.0).unwrap();
+        let length = |id: &EdgeId| {
+            let edge = &complex.edges[id];
+            complex
+                .vertex_distance(&edge.start_vertex, &edge.end_vertex)
+                .unwrap()
+        };
+
+        for id in complex.edges.keys() {
+            let radius = complex.max_safe_fillet_radius(id).unwrap();
+            if (length(id) - 2.0).abs() < 1e-9 {
+                // Corners through the thickness are bounded by the plan size
+                assert!((radius - 30.0).abs() < 1e-9, "{}", radius);
+            } else {
+                assert!((radius - 2.0).abs() < 1e-9, "{}", radius);
+            }
+        }
+        assert!(complex
+            .max_safe_fillet_radius(&EdgeId::new("edge_99".to_string()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_box_edges_are_right_angles() {
+        let complex = create_box_topology(2.0, 3.0, 4.0).unwrap();
+        assert_eq!(complex.edges.len(), 12);
+        for id in complex.edges.keys() {
+            let angle = complex.edge_dihedral_angle(id).unwrap();
+            assert!((angle - FRAC_PI_2).abs() < 1e-12);
+            assert_eq!(complex.edge_radius(id), None);
+        }
+        let missing = EdgeId::new("edge_99".to_string());
+        assert_eq!(complex.edge_dihedral_angle(&missing), None);
+
+        // Cylinder rims are circles bordering a curved face
+        let cylinder = create_cylinder_topology(1.5, 4.0).unwrap();
+        let rims: Vec<f64> = cylinder
+            .edges
+            .keys()
+            .filter_map(|id| cylinder.edge_radius(id))
+            .collect();
+        assert_eq!(rims, vec![1.5, 1.5]);
+        assert!(cylinder
+            .edges
+            .keys()
+            .all(|id| cylinder.edge_dihedral_angle(id).is_none()));
+    }
+
+    #[test]
+    fn test_cylinder_radius() {
+        let complex = create_cylinder_topology(1.5, 4.0).unwrap();
+
+        assert!((complex.face_radius(&face(2)).unwrap() - 1.5).abs() < 1e-12);
+        assert!((complex.face_angle(&face(0), &face(1)).unwrap() - PI).abs() < 1e-12);
+        assert!(complex.face_angle(&face(0), &face(2)).is_err());
+        assert!(complex.face_radius(&face(9)).is_err());
+    }
+}
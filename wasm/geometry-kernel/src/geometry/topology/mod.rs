@@ -6,16 +6,34 @@
 
 pub mod edge;
 pub mod face;
+pub mod from_mesh;
+mod measure;
+pub mod pmi;
+mod select;
+mod sheet;
 pub mod shell;
 pub mod solid;
+pub mod step;
+pub mod tessellate;
 
 // Re-export core topology types
 pub use edge::{Edge, EdgeId, EdgeType, HalfEdge};
 pub use face::{Face, FaceId, FaceOrientation, FaceType};
+pub use from_mesh::{complex_from_mesh, verify_roundtrip};
+pub use pmi::{Pmi, PmiAnnotation, PmiTarget};
+pub use select::select_tangent_chain;
+pub use sheet::{extract_sheet_faces, unfold, FlatPattern, SheetBend, SheetFlat, SheetModel};
 pub use shell::{Shell, ShellId, ShellType};
-pub use solid::{Solid, SolidId, TopologicalSolid};
+pub use solid::{weight_report, Solid, SolidId, TopologicalSolid, WeightReport};
+pub use tessellate::{topology_to_mesh, topology_to_mesh_with_config};
 
+use crate::config::KernelConfig;
 use crate::errors::{KernelError, KernelResult};
+use crate::geometry::topology::edge::EdgeCurve;
+use crate::geometry::constants;
+use crate::geometry::math::vec3;
+use crate::geometry::topology::face::FaceSurface;
+use crate::geometry::topology::tessellate::{boundary_loop, Segments};
 use crate::geometry::ir::node::NodeId;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -39,6 +57,11 @@ impl TopologyId {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Numeric index of an ID created with [`TopologyId::new`]
+    pub fn index(&self) -> Option<usize> {
+        self.0.rsplit_once('_')?.1.parse().ok()
+    }
 }
 
 /// Topological complex that maintains all adjacency relationships
@@ -56,6 +79,9 @@ pub struct TopologicalComplex {
     pub solids: HashMap<SolidId, Solid>,
     /// Adjacency relationships
     pub adjacency: AdjacencyGraph,
+    /// Datums and geometric tolerances on faces and edges
+    #[serde(default)]
+    pub pmi: Vec<Pmi>,
 }
 
 impl TopologicalComplex {
@@ -68,6 +94,7 @@ impl TopologicalComplex {
             shells: HashMap::new(),
             solids: HashMap::new(),
             adjacency: AdjacencyGraph::new(),
+            pmi: Vec::new(),
         }
     }
 
@@ -121,6 +148,21 @@ impl TopologicalComplex {
         Ok(id)
     }
 
+    /// Attach a datum or tolerance after checking it against the complex
+    pub fn add_pmi(&mut self, pmi: Pmi) -> KernelResult<()> {
+        pmi.validate(self)?;
+        if let PmiAnnotation::Datum { label } = &pmi.annotation {
+            if pmi::find_datum(self, label).is_some() {
+                return Err(KernelError::invalid_parameter(
+                    "datum",
+                    format!("Datum {} is already defined", label),
+                ));
+            }
+        }
+        self.pmi.push(pmi);
+        Ok(())
+    }
+
     /// Validate topological consistency
     pub fn validate(&self) -> KernelResult<()> {
         // Euler characteristic validation for each solid
@@ -134,6 +176,10 @@ impl TopologicalComplex {
         // Manufacturing constraints
         self.validate_manufacturing_constraints()?;
 
+        self.validate_planar_faces()?;
+
+        self.validate_pmi()?;
+
         Ok(())
     }
 
@@ -176,6 +222,30 @@ impl TopologicalComplex {
         true
     }
 
+    /// Concave edges that a milling tool of `min_radius` cannot cut
+    ///
+    /// Concave edges reconstructed from a mesh are sharp inside corners,
+    /// so every one of them is flagged for any positive tool radius.
+    /// Edges are sorted by index.
+    pub fn hard_to_machine_edges(&self, min_radius: f64) -> Vec<EdgeId> {
+        if min_radius <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut edges: Vec<EdgeId> = self
+            .edges
+            .iter()
+            .filter(|(_, edge)| edge.edge_type == EdgeType::Concave)
+            .map(|(id, _)| id.clone())
+            .collect();
+        let index = |id: &EdgeId| {
+            let (_, n) = id.as_str().rsplit_once('_')?;
+            n.parse::<usize>().ok()
+        };
+        edges.sort_by_key(index);
+        edges
+    }
+
     /// Get genus of the topology (number of handles/holes)
     pub fn genus(&self) -> usize {
         // Simplified genus calculation
@@ -230,6 +300,51 @@ impl TopologicalComplex {
         Ok(())
     }
 
+    /// Faces declared planar must have boundaries that lie within the
+    /// face's tolerance of a plane
+    fn validate_planar_faces(&self) -> KernelResult<()> {
+        let segments = Segments {
+            uniform: constants::DEFAULT_CIRCLE_SEGMENTS as usize,
+            max_chord_error: None,
+        };
+        let mut face_ids: Vec<&FaceId> = self
+            .faces
+            .iter()
+            .filter(|(_, face)| face.face_type == FaceType::Planar)
+            .map(|(id, _)| id)
+            .collect();
+        face_ids.sort_by_key(|id| id.as_str());
+
+        for face_id in face_ids {
+            let face = &self.faces[face_id];
+            let outline = boundary_loop(self, face, segments)?;
+            if !face.is_actually_planar(&outline, face.tolerance) {
+                return Err(KernelError::topology_error(format!(
+                    "Face {} is declared planar but its boundary deviates from a plane by more than {}",
+                    face_id.as_str(),
+                    face.tolerance
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_pmi(&self) -> KernelResult<()> {
+        let mut labels = HashSet::new();
+        for pmi in &self.pmi {
+            pmi.validate(self)?;
+            if let PmiAnnotation::Datum { label } = &pmi.annotation {
+                if !labels.insert(label) {
+                    return Err(KernelError::invalid_parameter(
+                        "datum",
+                        format!("Datum {} is defined more than once", label),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn validate_manufacturing_constraints(&self) -> KernelResult<()> {
         // Check for manufacturing-unfriendly topology
         for face in self.faces.values() {
@@ -239,12 +354,8 @@ impl TopologicalComplex {
             }
         }
 
-        // Check for sharp internal corners (difficult to machine)
-        for edge in self.edges.values() {
-            if edge.edge_type == EdgeType::Sharp {
-                // Could validate minimum radius for manufacturing
-            }
-        }
+        // Sharp internal corners are reported by `hard_to_machine_edges`,
+        // as they make a part harder to mill rather than invalid
 
         Ok(())
     }
@@ -360,6 +471,18 @@ pub fn create_box_topology(
     height: f64,
     depth: f64,
 ) -> KernelResult<TopologicalComplex> {
+    create_box_topology_with_config(width, height, depth, &KernelConfig::default())
+}
+
+/// Create a box topology whose entities use the configured linear tolerance
+pub fn create_box_topology_with_config(
+    width: f64,
+    height: f64,
+    depth: f64,
+    config: &KernelConfig,
+) -> KernelResult<TopologicalComplex> {
+    config.validate()?;
+    let tolerance = config.linear_tolerance;
     let mut complex = TopologicalComplex::new();
 
     // Create 8 vertices for a box
@@ -375,8 +498,8 @@ pub fn create_box_topology(
     ];
 
     let mut vertex_ids = Vec::new();
-    for pos in vertices {
-        let vertex_id = complex.add_vertex(Vertex::new(pos))?;
+    for &pos in &vertices {
+        let vertex_id = complex.add_vertex(Vertex::with_tolerance(pos, tolerance))?;
         vertex_ids.push(vertex_id);
     }
 
@@ -398,38 +521,144 @@ pub fn create_box_topology(
 
     let mut edge_ids = Vec::new();
     for (start_idx, end_idx) in edge_pairs {
-        let edge = Edge::new(
+        let mut edge = Edge::new(
             vertex_ids[start_idx].clone(),
             vertex_ids[end_idx].clone(),
             EdgeType::Linear,
         );
+        edge.tolerance = tolerance;
         let edge_id = complex.add_edge(edge)?;
         edge_ids.push(edge_id);
     }
 
-    // Create 6 faces for a box
-    let face_edge_indices = vec![
-        vec![0, 1, 2, 3],   // Bottom face
-        vec![4, 5, 6, 7],   // Top face
-        vec![0, 9, 4, 8],   // Front face
-        vec![2, 11, 6, 10], // Back face
-        vec![3, 11, 7, 8],  // Left face
-        vec![1, 10, 5, 9],  // Right face
+    // Create 6 faces for a box, each with its corners in outward
+    // counter-clockwise order
+    let faces = vec![
+        (vec![0, 1, 2, 3], [0, 3, 2, 1]),   // Bottom face
+        (vec![4, 5, 6, 7], [4, 5, 6, 7]),   // Top face
+        (vec![0, 9, 4, 8], [0, 1, 5, 4]),   // Front face
+        (vec![2, 11, 6, 10], [2, 3, 7, 6]), // Back face
+        (vec![3, 11, 7, 8], [0, 4, 7, 3]),  // Left face
+        (vec![1, 10, 5, 9], [1, 2, 6, 5]),  // Right face
     ];
 
-    for edge_indices in face_edge_indices {
+    for (edge_indices, corner_indices) in faces {
         let face_edges: Vec<EdgeId> = edge_indices
             .into_iter()
             .map(|i| edge_ids[i].clone())
             .collect();
 
-        let face = Face::new(face_edges, FaceType::Planar);
+        // Plane through the actual corners; half the cross product of the
+        // diagonals is the vector area, so its length is the face area
+        let [c0, c1, c2, c3] = corner_indices.map(|i| vertices[i]);
+        let area_vector = vec3::scale(vec3::cross(vec3::sub(c2, c0), vec3::sub(c3, c1)), 0.5);
+        let normal = vec3::normalize(area_vector).ok_or_else(|| {
+            KernelError::invalid_parameter(
+                "dimensions",
+                format!("{} x {} x {} box has a flat face", width, height, depth),
+            )
+        })?;
+        let centroid = vec3::scale(vec3::add(c0, c2), 0.5);
+
+        let mut face = Face::with_surface(
+            face_edges,
+            FaceType::Planar,
+            FaceSurface::Plane {
+                point: centroid,
+                normal,
+            },
+        );
+        face.tolerance = tolerance;
+        face.area = vec3::length(area_vector);
         complex.add_face(face)?;
     }
 
     Ok(complex)
 }
 
+/// Create a capped cylinder topology along the Y axis
+///
+/// The side is a single cylindrical face closed by a seam edge; the caps
+/// are planar faces bounded by full circles.
+pub fn create_cylinder_topology(radius: f64, height: f64) -> KernelResult<TopologicalComplex> {
+    create_cylinder_topology_with_config(radius, height, &KernelConfig::default())
+}
+
+/// Create a cylinder topology whose entities use the configured linear tolerance
+pub fn create_cylinder_topology_with_config(
+    radius: f64,
+    height: f64,
+    config: &KernelConfig,
+) -> KernelResult<TopologicalComplex> {
+    config.validate()?;
+    let tolerance = config.linear_tolerance;
+    let mut complex = TopologicalComplex::new();
+    let half = height / 2.0;
+    let full_turn = 2.0 * std::f64::consts::PI;
+
+    let bottom = complex.add_vertex(Vertex::with_tolerance([radius, -half, 0.0], tolerance))?;
+    let top = complex.add_vertex(Vertex::with_tolerance([radius, half, 0.0], tolerance))?;
+
+    let circle = |y: f64| EdgeCurve::Circle {
+        center: [0.0, y, 0.0],
+        radius,
+        axis: [0.0, 1.0, 0.0],
+        angle: full_turn,
+    };
+
+    let with_edge_tolerance = |mut edge: Edge| {
+        edge.tolerance = tolerance;
+        edge
+    };
+    let with_face_tolerance = |mut face: Face| {
+        face.tolerance = tolerance;
+        face
+    };
+
+    let bottom_circle = complex.add_edge(with_edge_tolerance(Edge::with_curve(
+        bottom.clone(),
+        bottom.clone(),
+        EdgeType::Circular,
+        circle(-half),
+    )))?;
+    let top_circle = complex.add_edge(with_edge_tolerance(Edge::with_curve(
+        top.clone(),
+        top.clone(),
+        EdgeType::Circular,
+        circle(half),
+    )))?;
+    let seam = complex.add_edge(with_edge_tolerance(Edge::new(bottom, top, EdgeType::Linear)))?;
+
+    complex.add_face(with_face_tolerance(Face::with_surface(
+        vec![bottom_circle.clone()],
+        FaceType::Planar,
+        FaceSurface::Plane {
+            point: [0.0, -half, 0.0],
+            normal: [0.0, -1.0, 0.0],
+        },
+    )))?;
+    complex.add_face(with_face_tolerance(Face::with_surface(
+        vec![top_circle.clone()],
+        FaceType::Planar,
+        FaceSurface::Plane {
+            point: [0.0, half, 0.0],
+            normal: [0.0, 1.0, 0.0],
+        },
+    )))?;
+    complex.add_face(with_face_tolerance(Face::with_surface(
+        vec![bottom_circle, seam.clone(), top_circle, seam],
+        FaceType::Cylindrical,
+        FaceSurface::Cylinder {
+            center: [0.0, 0.0, 0.0],
+            axis: [0.0, 1.0, 0.0],
+            radius,
+            height,
+        },
+    )))?;
+
+    Ok(complex)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -471,12 +700,77 @@ mod tests {
         assert_eq!(complex.faces.len(), 6); // 6 faces
     }
 
+    #[test]
+    fn test_box_faces_are_planes() {
+        let mut complex = create_box_topology(2.0, 3.0, 4.0).unwrap();
+        let centroid = [0.0; 3];
+        for face in complex.faces.values_mut() {
+            let Some(FaceSurface::Plane { point, normal }) = face.surface else {
+                panic!("box face without a plane surface");
+            };
+            assert_eq!(face.normal, Some(normal));
+            assert!((vec3::length(normal) - 1.0).abs() < 1e-12);
+            assert!(vec3::dot(normal, vec3::sub(point, centroid)) > 0.0);
+
+            // The rectangle spans the two axes the normal does not
+            let axis = (0..3).find(|&k| normal[k].abs() > 0.5).unwrap();
+            let sizes = [2.0, 3.0, 4.0];
+            let expected: f64 = (0..3).filter(|&k| k != axis).map(|k| sizes[k]).product();
+            assert!((face.compute_area().unwrap() - expected).abs() < 1e-12);
+        }
+        assert!((complex.faces.values().map(|f| f.area).sum::<f64>() - 52.0).abs() < 1e-12);
+    }
+
     #[test]
     fn test_manifold_check() {
         let complex = create_box_topology(1.0, 1.0, 1.0).unwrap();
         assert!(complex.is_manifold());
     }
 
+    #[test]
+    fn test_planar_face_validation() {
+        let quad = |lift: f64| {
+            let mut complex = TopologicalComplex::new();
+            let corners = [
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, lift],
+                [0.0, 1.0, 0.0],
+            ];
+            let vertices: Vec<TopologyId> = corners
+                .iter()
+                .map(|&p| complex.add_vertex(Vertex::new(p)).unwrap())
+                .collect();
+            let edges: Vec<EdgeId> = (0..4)
+                .map(|k| {
+                    let edge = Edge::new(
+                        vertices[k].clone(),
+                        vertices[(k + 1) % 4].clone(),
+                        EdgeType::Linear,
+                    );
+                    complex.add_edge(edge).unwrap()
+                })
+                .collect();
+            let face = Face::new(edges, FaceType::Planar);
+            complex.add_face(face).unwrap();
+            (complex, corners)
+        };
+
+        let (flat, corners) = quad(0.0);
+        let face = flat.faces.values().next().unwrap();
+        assert!(face.is_actually_planar(&corners, 1e-6));
+        assert!(flat.validate().is_ok());
+
+        let (warped, corners) = quad(0.5);
+        let face = warped.faces.values().next().unwrap();
+        assert!(!face.is_actually_planar(&corners, 1e-6));
+        assert!(face.is_actually_planar(&corners, 0.2));
+        assert!(warped.validate().is_err());
+
+        let box_complex = create_box_topology(2.0, 3.0, 4.0).unwrap();
+        assert!(box_complex.validate().is_ok());
+    }
+
     #[test]
     fn test_adjacency_graph() {
         let mut adj = AdjacencyGraph::new();
@@ -0,0 +1,170 @@
+//! Product and manufacturing information (PMI) on topology.
+//!
+//! Datums and geometric tolerances annotate faces and edges of a
+//! [`TopologicalComplex`] and are stored and serialized with it. Datums
+//! are named by a label (`"A"`, `"B"`, ...) that tolerances refer to, so
+//! a datum must be attached before any tolerance measured from it.
+//! Tolerance values are zone widths in model units.
+
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::topology::{EdgeId, FaceId, TopologicalComplex};
+use serde::{Deserialize, Serialize};
+
+/// Topological entity an annotation applies to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PmiTarget {
+    Face(FaceId),
+    Edge(EdgeId),
+}
+
+/// Datum or geometric tolerance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum PmiAnnotation {
+    /// Datum feature
+    Datum { label: String },
+    /// Surface lies between two parallel planes `tolerance` apart
+    Flatness { tolerance: f64 },
+    /// Feature lies within `tolerance` of its true position relative to
+    /// the datum reference frame
+    Position { tolerance: f64, datums: Vec<String> },
+    /// Feature lies between two planes `tolerance` apart, parallel to a
+    /// datum
+    Parallelism { tolerance: f64, datum: String },
+}
+
+/// One annotation attached to a face or edge
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pmi {
+    pub target: PmiTarget,
+    pub annotation: PmiAnnotation,
+}
+
+impl Pmi {
+    /// Flatness tolerance on a face
+    pub fn flatness(face: FaceId, tolerance: f64) -> Self {
+        Pmi {
+            target: PmiTarget::Face(face),
+            annotation: PmiAnnotation::Flatness { tolerance },
+        }
+    }
+
+    /// Datum feature on a face
+    pub fn datum(face: FaceId, label: impl Into<String>) -> Self {
+        Pmi {
+            target: PmiTarget::Face(face),
+            annotation: PmiAnnotation::Datum {
+                label: label.into(),
+            },
+        }
+    }
+
+    /// Check that the target exists in `complex`, the tolerance is a
+    /// positive width and every referenced datum is defined there
+    pub fn validate(&self, complex: &TopologicalComplex) -> KernelResult<()> {
+        let resolves = match &self.target {
+            PmiTarget::Face(id) => complex.faces.contains_key(id),
+            PmiTarget::Edge(id) => complex.edges.contains_key(id),
+        };
+        if !resolves {
+            return Err(KernelError::topology_error(format!(
+                "PMI target {:?} is not in the complex",
+                self.target
+            )));
+        }
+
+        let references: &[String] = match &self.annotation {
+            PmiAnnotation::Datum { label } => {
+                if label.trim().is_empty() {
+                    return Err(KernelError::invalid_parameter("datum", "Label is empty"));
+                }
+                return Ok(());
+            }
+            PmiAnnotation::Flatness { tolerance } => {
+                if matches!(self.target, PmiTarget::Edge(_)) {
+                    return Err(KernelError::invalid_parameter(
+                        "flatness",
+                        "Flatness applies to faces only",
+                    ));
+                }
+                validate_tolerance(*tolerance)?;
+                &[]
+            }
+            PmiAnnotation::Position { tolerance, datums } => {
+                validate_tolerance(*tolerance)?;
+                datums
+            }
+            PmiAnnotation::Parallelism { tolerance, datum } => {
+                validate_tolerance(*tolerance)?;
+                std::slice::from_ref(datum)
+            }
+        };
+
+        for label in references {
+            if find_datum(complex, label).is_none() {
+                return Err(KernelError::invalid_parameter(
+                    "datum",
+                    format!("Datum {} is not defined", label),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Target of the datum with this label, if any
+pub fn find_datum<'a>(complex: &'a TopologicalComplex, label: &str) -> Option<&'a PmiTarget> {
+    complex.pmi.iter().find_map(|pmi| match &pmi.annotation {
+        PmiAnnotation::Datum { label: l } if l == label => Some(&pmi.target),
+        _ => None,
+    })
+}
+
+fn validate_tolerance(tolerance: f64) -> KernelResult<()> {
+    if !tolerance.is_finite() || tolerance <= 0.0 {
+        return Err(KernelError::invalid_parameter(
+            "tolerance",
+            format!("Tolerance must be positive (got {})", tolerance),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::topology::create_box_topology;
+
+    #[test]
+    fn test_flatness_on_box_face() {
+        let mut complex = create_box_topology(10.0, 20.0, 30.0).unwrap();
+        let face = FaceId::new("face_0".to_string());
+        complex.add_pmi(Pmi::datum(face.clone(), "A")).unwrap();
+        complex.add_pmi(Pmi::flatness(face.clone(), 0.05)).unwrap();
+
+        assert_eq!(
+            find_datum(&complex, "A"),
+            Some(&PmiTarget::Face(face.clone()))
+        );
+        assert!(complex.pmi[1].validate(&complex).is_ok());
+        assert!(complex.add_pmi(Pmi::datum(face.clone(), "A")).is_err());
+
+        // Annotations travel with the complex
+        let json = serde_json::to_string(&complex).unwrap();
+        let restored: TopologicalComplex = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.pmi, complex.pmi);
+
+        let missing = FaceId::new("face_99".to_string());
+        assert!(complex.add_pmi(Pmi::flatness(missing, 0.05)).is_err());
+        assert!(complex.add_pmi(Pmi::flatness(face.clone(), 0.0)).is_err());
+        let parallel = Pmi {
+            target: PmiTarget::Face(FaceId::new("face_1".to_string())),
+            annotation: PmiAnnotation::Parallelism {
+                tolerance: 0.1,
+                datum: "B".to_string(),
+            },
+        };
+        assert!(complex.add_pmi(parallel).is_err());
+        assert_eq!(complex.pmi.len(), 2);
+    }
+}
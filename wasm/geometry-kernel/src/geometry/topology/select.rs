@@ -0,0 +1,194 @@
+//! Edge selection helpers for feature operations.
+//!
+//! Fillets and chamfers are usually applied to a whole outline at once;
+//! these helpers grow a single picked edge into the set the user means.
+
+use crate::geometry::math::vec3::{dot, scale};
+use crate::geometry::topology::{EdgeId, TopologicalComplex, TopologyId};
+use std::collections::{HashMap, HashSet};
+
+/// Edges continuing `start_edge` smoothly in both directions, in order
+/// along the chain, such as the whole outline of a rounded rectangle
+///
+/// At each vertex the walk moves on to the connected edge whose tangent
+/// turns least from the one it arrived along, if that turn is within
+/// `angle_tol` radians. It stops at sharper corners and at edges already
+/// in the chain, so a closed outline ends where it began. An unknown
+/// `start_edge` selects nothing.
+pub fn select_tangent_chain(
+    complex: &TopologicalComplex,
+    start_edge: &EdgeId,
+    angle_tol: f64,
+) -> Vec<EdgeId> {
+    let Some(start_edge) = complex.edges.get_key_value(start_edge).map(|(id, _)| id) else {
+        return Vec::new();
+    };
+
+    let mut edges_at: HashMap<&TopologyId, Vec<&EdgeId>> = HashMap::new();
+    for (id, edge) in &complex.edges {
+        edges_at.entry(&edge.start_vertex).or_default().push(id);
+        edges_at.entry(&edge.end_vertex).or_default().push(id);
+    }
+
+    let mut selected = HashSet::from([start_edge]);
+    let ahead = walk(
+        complex,
+        &edges_at,
+        start_edge,
+        true,
+        angle_tol,
+        &mut selected,
+    );
+    let behind = walk(
+        complex,
+        &edges_at,
+        start_edge,
+        false,
+        angle_tol,
+        &mut selected,
+    );
+
+    behind
+        .into_iter()
+        .rev()
+        .chain(std::iter::once(start_edge))
+        .chain(ahead)
+        .cloned()
+        .collect()
+}
+
+/// Edges reached by following tangents onward from `start`, leaving it
+/// through its end vertex when `forward` and its start vertex otherwise
+fn walk<'a>(
+    complex: &'a TopologicalComplex,
+    edges_at: &HashMap<&TopologyId, Vec<&'a EdgeId>>,
+    start: &'a EdgeId,
+    forward: bool,
+    angle_tol: f64,
+    selected: &mut HashSet<&'a EdgeId>,
+) -> Vec<&'a EdgeId> {
+    let mut chain = Vec::new();
+    let Some((mut vertex, [_, mut tangent])) = travel(complex, start, forward) else {
+        return chain;
+    };
+
+    loop {
+        let best = edges_at
+            .get(vertex)
+            .into_iter()
+            .flatten()
+            .filter(|id| !selected.contains(*id))
+            .filter_map(|&id| {
+                let leaves_from_start = &complex.edges[id].start_vertex == vertex;
+                let (next, [leaving, arriving]) = travel(complex, id, leaves_from_start)?;
+                let turn = dot(tangent, leaving).clamp(-1.0, 1.0).acos();
+                (turn <= angle_tol).then_some((turn, id, next, arriving))
+            })
+            .min_by(|a, b| {
+                a.0.total_cmp(&b.0)
+                    .then_with(|| a.1.as_str().cmp(b.1.as_str()))
+            });
+
+        let Some((_, id, next, arriving)) = best else {
+            return chain;
+        };
+        selected.insert(id);
+        chain.push(id);
+        vertex = next;
+        tangent = arriving;
+    }
+}
+
+/// Vertex reached by travelling along an edge, and its unit tangents where
+/// the travel leaves and arrives, pointing the way it goes
+fn travel<'a>(
+    complex: &'a TopologicalComplex,
+    id: &EdgeId,
+    forward: bool,
+) -> Option<(&'a TopologyId, [[f64; 3]; 2])> {
+    let edge = complex.edges.get(id)?;
+    let start = complex.vertices.get(&edge.start_vertex)?.position;
+    let end = complex.vertices.get(&edge.end_vertex)?.position;
+    let [at_start, at_end] = edge.end_tangents(start, end);
+
+    if forward {
+        Some((&edge.end_vertex, [at_start, at_end]))
+    } else {
+        Some((
+            &edge.start_vertex,
+            [scale(at_end, -1.0), scale(at_start, -1.0)],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::topology::edge::EdgeCurve;
+    use crate::geometry::topology::{create_box_topology, Edge, EdgeType, Vertex};
+
+    #[test]
+    fn test_tangent_chain_stops_at_corner() {
+        let mut complex = TopologicalComplex::new();
+        let v: Vec<TopologyId> = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [3.0, 0.0, 0.0],
+            [3.0, 1.0, 0.0],
+            [-1.0, 1.0, 0.0],
+            [-1.0, 2.0, 0.0],
+            [1.0, 1.0, 0.0],
+        ]
+        .into_iter()
+        .map(|p| complex.add_vertex(Vertex::new(p)).unwrap())
+        .collect();
+        let mut line = |a: usize, b: usize| {
+            let edge = Edge::new(v[a].clone(), v[b].clone(), EdgeType::Linear);
+            complex.add_edge(edge).unwrap()
+        };
+
+        // Collinear run along +x ending in a square corner, with a branch
+        // standing up from its middle
+        let e0 = line(0, 1);
+        let e1 = line(1, 2);
+        let e2 = line(2, 3);
+        let corner = line(3, 4);
+        let branch = line(1, 7);
+        // Quarter arc turning from -x to +y, then a line stored backwards
+        let arc = complex
+            .add_edge(Edge::with_curve(
+                v[0].clone(),
+                v[5].clone(),
+                EdgeType::Circular,
+                EdgeCurve::Circle {
+                    center: [0.0, 1.0, 0.0],
+                    radius: 1.0,
+                    axis: [0.0, 0.0, -1.0],
+                    angle: std::f64::consts::FRAC_PI_2,
+                },
+            ))
+            .unwrap();
+        let reversed = complex
+            .add_edge(Edge::new(v[6].clone(), v[5].clone(), EdgeType::Linear))
+            .unwrap();
+
+        let tolerance = 1f64.to_radians();
+        let chain = select_tangent_chain(&complex, &e1, tolerance);
+        assert_eq!(chain, vec![reversed, arc, e0, e1.clone(), e2]);
+        assert!(!chain.contains(&corner) && !chain.contains(&branch));
+
+        // A wide enough tolerance turns the corner
+        assert!(select_tangent_chain(&complex, &e1, 1.6).contains(&corner));
+        assert!(
+            select_tangent_chain(&complex, &EdgeId::new("edge_99".into()), tolerance).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_box_edges_are_not_tangent() {
+        let complex = create_box_topology(2.0, 3.0, 4.0).unwrap();
+        let start = EdgeId::new("edge_0".to_string());
+        assert_eq!(select_tangent_chain(&complex, &start, 0.1), vec![start]);
+    }
+}
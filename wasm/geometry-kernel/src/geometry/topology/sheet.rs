@@ -0,0 +1,536 @@
+//! Sheet-metal recognition, the first step toward flat patterns.
+//!
+//! A sheet part is a constant-thickness plate: its flat regions are pairs of
+//! opposite planar faces one thickness apart, and its bends are pairs of
+//! coaxial cylindrical faces whose radii differ by that thickness.
+//! Unfolding lays the flats end to end with each bend straightened into
+//! its developed length.
+
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::math::vec3::{self, cross, dot, length, sub};
+use crate::geometry::topology::edge::EdgeCurve;
+use crate::geometry::topology::face::FaceSurface;
+use crate::geometry::topology::{FaceId, TopologicalComplex};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Largest `1 - |cos θ|` for two directions to count as parallel
+const PARALLEL_EPSILON: f64 = 1e-6;
+
+/// Flat regions and bends of a sheet-metal part
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetModel {
+    /// Plate thickness in model units
+    pub thickness: f64,
+    /// Flat regions, ordered by face id
+    pub flats: Vec<SheetFlat>,
+    /// Bends joining the flat regions, ordered by face id
+    pub bends: Vec<SheetBend>,
+}
+
+/// Flat region of a sheet, bounded by two opposite planar faces
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetFlat {
+    /// The two faces, the lower face id first
+    pub faces: [FaceId; 2],
+    /// Outward normal of the first face
+    pub normal: [f64; 3],
+    /// Boundary vertex positions of the first face
+    pub vertices: Vec<[f64; 3]>,
+}
+
+/// Bend of a sheet, bounded by two coaxial cylindrical faces
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetBend {
+    /// Inner (smaller radius) face
+    pub inner: FaceId,
+    /// Outer (larger radius) face
+    pub outer: FaceId,
+    /// Unit bend axis
+    pub axis: [f64; 3],
+    /// Inner bend radius
+    pub radius: f64,
+    /// Bend angle in radians; a right-angle bend measures `π/2`
+    pub angle: f64,
+    /// Indices into `SheetModel::flats` of the flats the bend joins
+    pub flats: Vec<usize>,
+}
+
+impl SheetBend {
+    /// Length of the neutral axis through the bend, which sits
+    /// `k_factor` of the way from the inner face to the outer
+    pub fn allowance(&self, thickness: f64, k_factor: f64) -> f64 {
+        self.angle * (self.radius + k_factor * thickness)
+    }
+
+    /// Amount the developed length falls short of the two flange lengths
+    /// measured to the outer mould line, where the outer faces would meet
+    /// without the bend
+    pub fn deduction(&self, thickness: f64, k_factor: f64) -> f64 {
+        let setback = (self.radius + thickness) * (self.angle / 2.0).tan();
+        2.0 * setback - self.allowance(thickness, k_factor)
+    }
+}
+
+/// Developed blank of a sheet part, cut flat before bending
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlatPattern {
+    /// Blank outline, counter-clockwise from the origin
+    pub outline: Vec<[f64; 2]>,
+    /// Bend centre lines across the blank, in unfolding order
+    pub bend_lines: Vec<[[f64; 2]; 2]>,
+}
+
+/// Flat regions and bends of a constant-thickness sheet part
+///
+/// The thickness is the smallest gap between opposite planar faces with
+/// material between them; every such pair at that gap is a flat. Bends are
+/// coaxial cylindrical faces one thickness apart in radius, and their angle
+/// is measured between the outer faces of the flats either side, falling
+/// back to the sweep of the bend's arc edges. Fails when the complex has no
+/// opposite planar faces to take a thickness from.
+pub fn extract_sheet_faces(complex: &TopologicalComplex) -> KernelResult<SheetModel> {
+    // Face ids are "face_N", so ordering by length first keeps N numeric
+    let mut face_ids: Vec<&FaceId> = complex.faces.keys().collect();
+    face_ids.sort_by_key(|id| (id.as_str().len(), id.as_str()));
+
+    let mut planes = Vec::new();
+    let mut cylinders = Vec::new();
+    for &id in &face_ids {
+        let face = &complex.faces[id];
+        match &face.surface {
+            Some(FaceSurface::Cylinder {
+                center,
+                axis,
+                radius,
+                ..
+            }) => cylinders.push((id, *center, *axis, *radius, face.tolerance)),
+            _ if face.is_planar => {
+                let normal = complex.planar_normal(id)?;
+                planes.push((id, plane_point(complex, id)?, normal, face.tolerance));
+            }
+            _ => {}
+        }
+    }
+
+    // Opposite planar faces with material between them, nearest first
+    let mut pairs = Vec::new();
+    for (i, &(a, pa, na, ta)) in planes.iter().enumerate() {
+        for &(b, pb, nb, tb) in &planes[i + 1..] {
+            let gap = -dot(sub(pb, pa), na);
+            if dot(na, nb) <= PARALLEL_EPSILON - 1.0 && gap > ta.max(tb) {
+                pairs.push((gap, ta.max(tb), [a, b], na));
+            }
+        }
+    }
+    pairs.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let Some(&(thickness, _, _, _)) = pairs.first() else {
+        return Err(KernelError::topology_error(
+            "No opposite planar faces to take a sheet thickness from",
+        ));
+    };
+
+    let mut used = HashSet::new();
+    let mut flats = Vec::new();
+    for (gap, tolerance, [a, b], normal) in pairs {
+        if (gap - thickness).abs() > tolerance || used.contains(a) || used.contains(b) {
+            continue;
+        }
+        used.extend([a, b]);
+        flats.push(SheetFlat {
+            faces: [a.clone(), b.clone()],
+            normal,
+            vertices: face_vertices(complex, a)?,
+        });
+    }
+
+    let mut bends = Vec::new();
+    let mut bent = HashSet::new();
+    for (i, &(a, ca, axis, ra, ta)) in cylinders.iter().enumerate() {
+        for &(b, cb, axis_b, rb, tb) in &cylinders[i + 1..] {
+            let tolerance = ta.max(tb);
+            let coaxial = 1.0 - dot(axis, axis_b).abs() <= PARALLEL_EPSILON
+                && length(cross(sub(cb, ca), axis)) <= tolerance;
+            if !coaxial
+                || ((ra - rb).abs() - thickness).abs() > tolerance
+                || bent.contains(a)
+                || bent.contains(b)
+            {
+                continue;
+            }
+            bent.extend([a, b]);
+
+            let (inner, outer) = if ra < rb { (a, b) } else { (b, a) };
+            let joined = joined_flats(complex, &flats, outer);
+            let angle = match joined[..] {
+                [(_, n1), (_, n2)] => dot(n1, n2).clamp(-1.0, 1.0).acos(),
+                _ => arc_sweep(complex, outer),
+            };
+            bends.push(SheetBend {
+                inner: inner.clone(),
+                outer: outer.clone(),
+                axis,
+                radius: ra.min(rb),
+                angle,
+                flats: joined.into_iter().map(|(index, _)| index).collect(),
+            });
+        }
+    }
+
+    Ok(SheetModel {
+        thickness,
+        flats,
+        bends,
+    })
+}
+
+/// Flat blank of a sheet part whose flats form a single chain joined by
+/// parallel bends
+///
+/// Flats are laid along `x` from the origin in chain order, starting from
+/// the lowest-indexed end, each as long as its extent across the bend axis.
+/// Each bend adds its [`SheetBend::allowance`] for the given `k_factor`,
+/// with its bend line across the middle of that strip. The blank is as wide
+/// as the widest flat along the bend axis and is returned as a rectangle,
+/// so cut-outs and tapered flanges are not traced.
+pub fn unfold(sheet: &SheetModel, k_factor: f64) -> KernelResult<FlatPattern> {
+    if !(0.0..=1.0).contains(&k_factor) {
+        return Err(KernelError::invalid_parameter(
+            "k_factor",
+            format!("{} is outside 0..=1", k_factor),
+        ));
+    }
+    let chain = flat_chain(sheet)?;
+    let axis = match sheet.bends.first() {
+        Some(bend) => bend.axis,
+        None => {
+            let vertices = &sheet.flats[0].vertices;
+            let edge = vertices
+                .get(1)
+                .map(|&p| sub(p, vertices[0]))
+                .unwrap_or([0.0; 3]);
+            vec3::normalize(edge).unwrap_or([0.0; 3])
+        }
+    };
+
+    let extent = |points: &[[f64; 3]], direction: [f64; 3]| {
+        let (low, high) = points
+            .iter()
+            .map(|&p| dot(p, direction))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), t| {
+                (lo.min(t), hi.max(t))
+            });
+        (high - low).max(0.0)
+    };
+    let width = sheet
+        .flats
+        .iter()
+        .map(|flat| extent(&flat.vertices, axis))
+        .fold(0.0, f64::max);
+
+    let mut x = 0.0;
+    let mut bend_lines = Vec::new();
+    for (flat, bend) in chain {
+        let flat = &sheet.flats[flat];
+        let across = vec3::normalize(cross(axis, flat.normal)).unwrap_or([0.0; 3]);
+        x += extent(&flat.vertices, across);
+        if let Some(bend) = bend {
+            let allowance = sheet.bends[bend].allowance(sheet.thickness, k_factor);
+            let middle = x + allowance / 2.0;
+            bend_lines.push([[middle, 0.0], [middle, width]]);
+            x += allowance;
+        }
+    }
+
+    Ok(FlatPattern {
+        outline: vec![[0.0, 0.0], [x, 0.0], [x, width], [0.0, width]],
+        bend_lines,
+    })
+}
+
+/// Flats in order along the sheet, each with the bend that follows it
+fn flat_chain(sheet: &SheetModel) -> KernelResult<Vec<(usize, Option<usize>)>> {
+    let not_a_chain = || {
+        KernelError::topology_error(
+            "Only a single chain of flats joined by parallel bends can be unfolded",
+        )
+    };
+    if sheet.flats.is_empty() || sheet.bends.len() + 1 != sheet.flats.len() {
+        return Err(not_a_chain());
+    }
+
+    let mut joins: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+    for (index, bend) in sheet.bends.iter().enumerate() {
+        let &[a, b] = &bend.flats[..] else {
+            return Err(not_a_chain());
+        };
+        if 1.0 - dot(bend.axis, sheet.bends[0].axis).abs() > PARALLEL_EPSILON {
+            return Err(not_a_chain());
+        }
+        joins.entry(a).or_default().push((index, b));
+        joins.entry(b).or_default().push((index, a));
+    }
+    if joins.values().any(|j| j.len() > 2) {
+        return Err(not_a_chain());
+    }
+
+    let start = (0..sheet.flats.len())
+        .find(|flat| joins.get(flat).map_or(0, Vec::len) < 2)
+        .ok_or_else(not_a_chain)?;
+    let mut chain = Vec::new();
+    let mut previous = None;
+    let mut flat = start;
+    loop {
+        let next = joins
+            .get(&flat)
+            .into_iter()
+            .flatten()
+            .find(|&&(bend, _)| Some(bend) != previous);
+        chain.push((flat, next.map(|&(bend, _)| bend)));
+        let Some(&(bend, other)) = next else { break };
+        previous = Some(bend);
+        flat = other;
+    }
+
+    if chain.len() != sheet.flats.len() {
+        return Err(not_a_chain());
+    }
+    Ok(chain)
+}
+
+/// Distinct boundary vertex positions of a face
+fn face_vertices(complex: &TopologicalComplex, id: &FaceId) -> KernelResult<Vec<[f64; 3]>> {
+    let mut seen = HashSet::new();
+    let mut positions = Vec::new();
+    for edge in &complex.faces[id].boundary_edges {
+        let edge = complex.edges.get(edge).ok_or_else(|| {
+            KernelError::topology_error(format!("Unknown edge {}", edge.as_str()))
+        })?;
+        for vertex in [&edge.start_vertex, &edge.end_vertex] {
+            if seen.insert(vertex) {
+                positions.push(complex.vertex_position(vertex)?);
+            }
+        }
+    }
+    Ok(positions)
+}
+
+/// A point on a planar face: its plane's point, else a boundary vertex
+fn plane_point(complex: &TopologicalComplex, id: &FaceId) -> KernelResult<[f64; 3]> {
+    let face = &complex.faces[id];
+    if let Some(FaceSurface::Plane { point, .. }) = &face.surface {
+        return Ok(*point);
+    }
+    let edge = face
+        .boundary_edges
+        .first()
+        .and_then(|edge| complex.edges.get(edge))
+        .ok_or_else(|| {
+            KernelError::topology_error(format!("Face {} has no boundary", id.as_str()))
+        })?;
+    complex.vertex_position(&edge.start_vertex)
+}
+
+/// Flats with a face sharing an edge with `bend_face`, as their index and
+/// the outward normal of that face
+fn joined_flats(
+    complex: &TopologicalComplex,
+    flats: &[SheetFlat],
+    bend_face: &FaceId,
+) -> Vec<(usize, [f64; 3])> {
+    let neighbours: HashSet<&FaceId> = complex.faces[bend_face]
+        .boundary_edges
+        .iter()
+        .flat_map(|edge| complex.adjacency.get_faces_for_edge(edge))
+        .filter(|id| *id != bend_face)
+        .collect();
+
+    flats
+        .iter()
+        .enumerate()
+        .filter_map(|(index, flat)| {
+            let face = flat.faces.iter().find(|id| neighbours.contains(id))?;
+            let normal = complex.planar_normal(face).ok()?;
+            Some((index, normal))
+        })
+        .collect()
+}
+
+/// Sweep of the first arc edge bounding `bend_face`, or zero without one
+fn arc_sweep(complex: &TopologicalComplex, bend_face: &FaceId) -> f64 {
+    complex.faces[bend_face]
+        .boundary_edges
+        .iter()
+        .find_map(|edge| match complex.edges.get(edge)?.curve {
+            Some(EdgeCurve::Circle { angle, .. }) => Some(angle.abs()),
+            _ => None,
+        })
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::topology::{Edge, EdgeType, Face, FaceType, Vertex};
+    use std::f64::consts::FRAC_PI_2;
+
+    /// Ten-wide L bracket of thickness 1 with a quarter bend of inner radius
+    /// 2 about the z axis through (0, 3)
+    fn l_bend() -> TopologicalComplex {
+        let profile = [
+            [-10.0, 0.0],
+            [0.0, 0.0],
+            [3.0, 3.0],
+            [3.0, 13.0],
+            [2.0, 13.0],
+            [2.0, 3.0],
+            [0.0, 1.0],
+            [-10.0, 1.0],
+        ];
+        let width = 10.0;
+        let bend = |radius: f64, angle: f64| EdgeCurve::Circle {
+            center: [0.0, 3.0, 0.0],
+            radius,
+            axis: [0.0, 0.0, 1.0],
+            angle,
+        };
+
+        let mut complex = TopologicalComplex::new();
+        let [near, far]: [Vec<_>; 2] = [0.0, width].map(|z| {
+            profile
+                .iter()
+                .map(|&[x, y]| complex.add_vertex(Vertex::new([x, y, z])).unwrap())
+                .collect()
+        });
+        let along: Vec<_> = (0..8)
+            .map(|i| {
+                let edge = Edge::new(near[i].clone(), far[i].clone(), EdgeType::Linear);
+                complex.add_edge(edge).unwrap()
+            })
+            .collect();
+        let [near_edges, far_edges]: [Vec<_>; 2] = [&near, &far].map(|ring| {
+            (0..8)
+                .map(|i| {
+                    let (a, b) = (ring[i].clone(), ring[(i + 1) % 8].clone());
+                    let edge = match i {
+                        1 => Edge::with_curve(a, b, EdgeType::Circular, bend(3.0, FRAC_PI_2)),
+                        5 => Edge::with_curve(a, b, EdgeType::Circular, bend(2.0, -FRAC_PI_2)),
+                        _ => Edge::new(a, b, EdgeType::Linear),
+                    };
+                    complex.add_edge(edge).unwrap()
+                })
+                .collect()
+        });
+
+        let normals = [
+            [0.0, -1.0],
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [0.0, 1.0],
+            [-1.0, 0.0],
+            [0.0, 0.0],
+            [0.0, 1.0],
+            [-1.0, 0.0],
+        ];
+        for i in 0..8 {
+            let edges = vec![
+                near_edges[i].clone(),
+                along[(i + 1) % 8].clone(),
+                far_edges[i].clone(),
+                along[i].clone(),
+            ];
+            let face = match i {
+                1 | 5 => Face::with_surface(
+                    edges,
+                    FaceType::Cylindrical,
+                    FaceSurface::Cylinder {
+                        center: [0.0, 3.0, width / 2.0],
+                        axis: [0.0, 0.0, 1.0],
+                        radius: if i == 1 { 3.0 } else { 2.0 },
+                        height: width,
+                    },
+                ),
+                _ => {
+                    let [x, y] = profile[i];
+                    let [nx, ny] = normals[i];
+                    Face::with_surface(
+                        edges,
+                        FaceType::Planar,
+                        FaceSurface::Plane {
+                            point: [x, y, 0.0],
+                            normal: [nx, ny, 0.0],
+                        },
+                    )
+                }
+            };
+            complex.add_face(face).unwrap();
+        }
+        for (edges, z, nz) in [(near_edges, 0.0, -1.0), (far_edges, width, 1.0)] {
+            let face = Face::with_surface(
+                edges,
+                FaceType::Planar,
+                FaceSurface::Plane {
+                    point: [0.0, 0.0, z],
+                    normal: [0.0, 0.0, nz],
+                },
+            );
+            complex.add_face(face).unwrap();
+        }
+        complex
+    }
+
+    #[test]
+    fn test_l_bend_has_two_flats_and_one_bend() {
+        let complex = l_bend();
+        let sheet = extract_sheet_faces(&complex).unwrap();
+
+        assert!((sheet.thickness - 1.0).abs() < 1e-9);
+        assert_eq!(sheet.flats.len(), 2);
+        assert_eq!(sheet.bends.len(), 1);
+
+        let bend = &sheet.bends[0];
+        assert!((bend.angle - FRAC_PI_2).abs() < 1e-9);
+        assert!((bend.radius - 2.0).abs() < 1e-9);
+        assert_eq!(bend.flats, vec![0, 1]);
+        assert_eq!(complex.face_radius(&bend.outer).unwrap(), 3.0);
+    }
+
+    #[test]
+    fn test_unfold_l_bend_subtracts_bend_deduction() {
+        let sheet = extract_sheet_faces(&l_bend()).unwrap();
+        let k_factor = 0.44;
+        let pattern = unfold(&sheet, k_factor).unwrap();
+
+        // Both flanges measure 13 to the outer corner the bend rounds off
+        let (radius, thickness) = (2.0, 1.0);
+        let deduction = 2.0 * (radius + thickness) * (FRAC_PI_2 / 2.0).tan()
+            - FRAC_PI_2 * (radius + k_factor * thickness);
+        assert!((sheet.bends[0].deduction(thickness, k_factor) - deduction).abs() < 1e-9);
+
+        let length = 13.0 + 13.0 - deduction;
+        let expected = [[0.0, 0.0], [length, 0.0], [length, 10.0], [0.0, 10.0]];
+        assert_eq!(pattern.outline.len(), 4);
+        for (corner, expected) in pattern.outline.iter().zip(expected) {
+            assert!((corner[0] - expected[0]).abs() < 1e-9);
+            assert!((corner[1] - expected[1]).abs() < 1e-9);
+        }
+
+        let middle = 10.0 + sheet.bends[0].allowance(thickness, k_factor) / 2.0;
+        assert_eq!(pattern.bend_lines.len(), 1);
+        assert!((pattern.bend_lines[0][0][0] - middle).abs() < 1e-9);
+        assert!(unfold(&sheet, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_sheet_needs_opposite_planes() {
+        let mut complex = TopologicalComplex::new();
+        assert!(extract_sheet_faces(&complex).is_err());
+
+        // A box is one thick flat with no bends
+        complex = crate::geometry::topology::create_box_topology(4.0, 4.0, 1.0).unwrap();
+        let sheet = extract_sheet_faces(&complex).unwrap();
+        assert!((sheet.thickness - 1.0).abs() < 1e-9);
+        assert_eq!(sheet.flats.len(), 1);
+        assert!(sheet.bends.is_empty());
+    }
+}
@@ -7,7 +7,9 @@
 use crate::errors::{KernelError, KernelResult};
 use crate::geometry::topology::{Face, FaceId};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 
 /// Unique identifier for shells
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -44,6 +46,10 @@ pub struct Shell {
     pub manufacturing_constraints: Vec<ShellConstraint>,
     /// Whether shell represents a void (internal cavity)
     pub is_void: bool,
+    /// Fingerprint of the face areas `volume` and `surface_area` were
+    /// last measured from
+    #[serde(skip)]
+    measured_key: Option<u64>,
 }
 
 impl Shell {
@@ -58,6 +64,7 @@ impl Shell {
             tolerance: 1e-6,
             manufacturing_constraints: Vec::new(),
             is_void: false,
+            measured_key: None,
         }
     }
 
@@ -160,6 +167,32 @@ impl Shell {
         Ok(self.volume)
     }
 
+    /// Refresh `surface_area` and, for closed shells, `volume` from the
+    /// memoized face areas
+    ///
+    /// Faces whose geometry is unchanged keep their areas, and the shell
+    /// sums are skipped when none of its faces changed.
+    pub fn measure(&mut self, face_collection: &mut HashMap<FaceId, Face>) -> KernelResult<()> {
+        let mut hasher = DefaultHasher::new();
+        for face_id in &self.faces {
+            if let Some(face) = face_collection.get_mut(face_id) {
+                face_id.hash(&mut hasher);
+                face.measured_area()?.to_bits().hash(&mut hasher);
+            }
+        }
+        let key = hasher.finish();
+        if self.measured_key == Some(key) {
+            return Ok(());
+        }
+
+        self.compute_surface_area(face_collection)?;
+        if self.shell_type == ShellType::Closed {
+            self.compute_volume(face_collection)?;
+        }
+        self.measured_key = Some(key);
+        Ok(())
+    }
+
     /// Compute total surface area
     pub fn compute_surface_area(
         &mut self,
@@ -327,7 +360,10 @@ impl Shell {
 }
 
 /// Types of shells for different geometric entities
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// `Ord` follows declaration order and keeps the counts in
+/// [`ShellCollectionStats`] in a fixed order
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ShellType {
     /// Closed shell (forms boundary of solid)
     Closed,
@@ -523,7 +559,7 @@ impl ShellCollection {
     /// Get statistics about the shell collection
     pub fn stats(&self, face_collection: &HashMap<FaceId, Face>) -> ShellCollectionStats {
         let total_shells = self.shells.len();
-        let mut shell_type_counts = HashMap::new();
+        let mut shell_type_counts = BTreeMap::new();
         let mut total_volume = 0.0;
         let mut total_surface_area = 0.0;
         let mut manifold_count = 0;
@@ -575,10 +611,10 @@ impl Default for ShellCollection {
 }
 
 /// Statistics for shell collections
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShellCollectionStats {
     pub total_shells: usize,
-    pub shell_type_counts: HashMap<ShellType, usize>,
+    pub shell_type_counts: BTreeMap<ShellType, usize>,
     pub total_volume: f64,
     pub total_surface_area: f64,
     pub manifold_ratio: f64,
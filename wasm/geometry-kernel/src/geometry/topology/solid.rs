@@ -4,11 +4,12 @@
 //! shells and representing manufacturable volumes. Solids maintain manufacturing
 //! constraints and support assembly-scale reasoning.
 
+use crate::config::KernelConfig;
 use crate::errors::{KernelError, KernelResult};
-use crate::geometry::topology::{Shell, ShellId, ShellType};
+use crate::geometry::topology::{Face, FaceId, Shell, ShellId, ShellType};
 use crate::types::BoundingBox;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Unique identifier for solids
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -183,6 +184,23 @@ impl Solid {
         Ok(self.volume)
     }
 
+    /// Measure the outer and inner shells, then refresh volume, mass and
+    /// surface area
+    pub fn measure(
+        &mut self,
+        shell_collection: &mut HashMap<ShellId, Shell>,
+        face_collection: &mut HashMap<FaceId, Face>,
+    ) -> KernelResult<()> {
+        for shell_id in std::iter::once(&self.outer_shell).chain(&self.inner_shells) {
+            if let Some(shell) = shell_collection.get_mut(shell_id) {
+                shell.measure(face_collection)?;
+            }
+        }
+        self.compute_volume(shell_collection)?;
+        self.compute_surface_area(shell_collection)?;
+        Ok(())
+    }
+
     /// Compute total surface area
     pub fn compute_surface_area(
         &mut self,
@@ -372,7 +390,10 @@ impl Solid {
 }
 
 /// Types of solids for different geometric entities
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// Ordered by declaration, so type counts keyed by it iterate and
+/// serialize in a stable order
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum SolidType {
     /// Simple solid (single connected component)
     Simple,
@@ -449,6 +470,67 @@ impl MaterialSpec {
             manufacturing_properties: props,
         }
     }
+
+    /// Mass in kilograms of a volume given in cubic model units
+    pub fn mass_kg(&self, volume: f64, config: &KernelConfig) -> f64 {
+        volume * config.length_unit.meters_per_unit().powi(3) * self.density
+    }
+}
+
+/// Mass of a solid compared against a weight budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightReport {
+    /// Material the mass was computed for
+    pub material: String,
+    /// Solid volume in cubic model units
+    pub volume: f64,
+    /// Computed mass (kg)
+    pub mass_kg: f64,
+    /// Allowed mass (kg)
+    pub budget_kg: f64,
+    /// Whether the mass exceeds the budget
+    pub over_budget: bool,
+    /// Mass above (positive) or below (negative) the budget (kg)
+    pub difference_kg: f64,
+    /// Difference as a percentage of the budget
+    pub difference_percent: f64,
+}
+
+/// Compare the mass of a solid in a material against a budget
+///
+/// The solid's volume is taken in cubic millimeters; use
+/// [`weight_report_with_config`] for other units.
+pub fn weight_report(
+    solid: &Solid,
+    material: &MaterialSpec,
+    budget_kg: f64,
+) -> KernelResult<WeightReport> {
+    weight_report_with_config(solid, material, budget_kg, &KernelConfig::default())
+}
+
+/// Weight report for a solid modeled in the configured length unit
+pub fn weight_report_with_config(
+    solid: &Solid,
+    material: &MaterialSpec,
+    budget_kg: f64,
+    config: &KernelConfig,
+) -> KernelResult<WeightReport> {
+    if !budget_kg.is_finite() || budget_kg <= 0.0 {
+        return Err(KernelError::invalid_parameter("budget_kg", budget_kg.to_string()));
+    }
+
+    let mass_kg = material.mass_kg(solid.volume, config);
+    let difference_kg = mass_kg - budget_kg;
+
+    Ok(WeightReport {
+        material: material.name.clone(),
+        volume: solid.volume,
+        mass_kg,
+        budget_kg,
+        over_budget: difference_kg > 0.0,
+        difference_kg,
+        difference_percent: difference_kg / budget_kg * 100.0,
+    })
 }
 
 /// Assembly context information
@@ -680,10 +762,25 @@ impl TopologicalSolid {
         Ok(())
     }
 
+    /// Measure every solid, then collect statistics
+    ///
+    /// Face areas are memoized, so faces shared between solids and repeated
+    /// calls on an unchanged model are not measured again.
+    pub fn measured_stats(
+        &mut self,
+        shell_collection: &mut HashMap<ShellId, Shell>,
+        face_collection: &mut HashMap<FaceId, Face>,
+    ) -> KernelResult<SolidCollectionStats> {
+        for solid in self.solids.values_mut() {
+            solid.measure(shell_collection, face_collection)?;
+        }
+        Ok(self.stats(shell_collection))
+    }
+
     /// Get statistics about solid collection
     pub fn stats(&self, shell_collection: &HashMap<ShellId, Shell>) -> SolidCollectionStats {
         let total_solids = self.solids.len();
-        let mut solid_type_counts = HashMap::new();
+        let mut solid_type_counts = BTreeMap::new();
         let mut total_volume = 0.0;
         let mut total_mass = 0.0;
         let mut manufacturability_count = 0;
@@ -730,10 +827,10 @@ impl Default for TopologicalSolid {
 }
 
 /// Statistics for solid collections
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolidCollectionStats {
     pub total_solids: usize,
-    pub solid_type_counts: HashMap<SolidType, usize>,
+    pub solid_type_counts: BTreeMap<SolidType, usize>,
     pub total_volume: f64,
     pub total_mass: f64,
     pub avg_complexity: f64,
@@ -838,6 +935,51 @@ mod tests {
         assert_eq!(stats.total_solids, 1);
     }
 
+    #[test]
+    fn test_stats_serialize_deterministically() {
+        use crate::geometry::topology::shell::ShellCollection;
+
+        let types = [
+            SolidType::Wire,
+            SolidType::Sheet,
+            SolidType::Simple,
+            SolidType::Assembly,
+            SolidType::Composite,
+            SolidType::Sheet,
+        ];
+        // Separate collections hash with different seeds
+        let serialized = || {
+            let mut solids = TopologicalSolid::new();
+            let mut shells = ShellCollection::new();
+            for (i, solid_type) in types.iter().enumerate() {
+                let shell = ShellId::new(format!("shell_{}", i));
+                solids
+                    .add_solid(Solid::new(shell, solid_type.clone()))
+                    .unwrap();
+                let shell_type = if i % 2 == 0 {
+                    ShellType::Open
+                } else {
+                    ShellType::Closed
+                };
+                let faces = vec![FaceId::new(format!("face_{}", i))];
+                shells.add_shell(Shell::new(faces, shell_type)).unwrap();
+            }
+            (
+                serde_json::to_string(&solids.stats(&HashMap::new())).unwrap(),
+                serde_json::to_string(&shells.stats(&HashMap::new())).unwrap(),
+            )
+        };
+
+        let (solid_stats, shell_stats) = serialized();
+        for _ in 0..8 {
+            assert_eq!(serialized(), (solid_stats.clone(), shell_stats.clone()));
+        }
+        assert!(solid_stats.contains(
+            r#""solid_type_counts":{"Simple":1,"Composite":1,"Assembly":1,"Sheet":2,"Wire":1}"#
+        ));
+        assert!(shell_stats.contains(r#""shell_type_counts":{"Closed":3,"Open":3}"#));
+    }
+
     #[test]
     fn test_assembly_info() {
         let mut solid = Solid::new(ShellId::new("part".to_string()), SolidType::Assembly);
@@ -891,12 +1033,80 @@ mod tests {
         let inner_shell_id = ShellId::new("inner".to_string());
         shell_collection.insert(inner_shell_id.clone(), inner_shell);
 
-        let mut solid = Solid::new(outer_shell_id.clone(), SolidType::Solid);
+        let mut solid = Solid::new(outer_shell_id.clone(), SolidType::Simple);
         solid.inner_shells.push(inner_shell_id.clone());
 
-        let calculated_volume = solid.calculate_volume(&shell_collection);
+        let calculated_volume = solid.compute_volume(&shell_collection).unwrap();
 
         // Volume should be outer - inner = 100 - 30 = 70
         assert_eq!(calculated_volume, 70.0);
     }
+
+    #[test]
+    fn test_weight_report_over_budget() {
+        // 100 x 100 x 10 mm aluminum plate: 1e-4 m³ at 2700 kg/m³
+        let mut solid = Solid::new(ShellId::new("shell_1".to_string()), SolidType::Simple);
+        solid.volume = 100.0 * 100.0 * 10.0;
+
+        let report = weight_report(&solid, &MaterialSpec::aluminum(), 0.1).unwrap();
+        assert!(report.over_budget);
+        assert!((report.mass_kg - 0.27).abs() < 1e-9);
+        assert!((report.difference_kg - 0.17).abs() < 1e-9);
+        assert!((report.difference_percent - 170.0).abs() < 1e-6);
+
+        let report = weight_report(&solid, &MaterialSpec::aluminum(), 1.0).unwrap();
+        assert!(!report.over_budget);
+        assert!((report.difference_percent + 73.0).abs() < 1e-6);
+
+        assert!(weight_report(&solid, &MaterialSpec::aluminum(), 0.0).is_err());
+    }
+
+    #[test]
+    fn test_measured_stats_reuse_face_areas() {
+        use crate::geometry::topology::face::{area_computations, FaceSurface};
+        use crate::geometry::topology::{EdgeId, FaceType};
+
+        let cylinder = |radius: f64| {
+            let edges = (0..3).map(|i| EdgeId::new(format!("e_{}", i))).collect();
+            let surface = FaceSurface::Cylinder {
+                center: [0.0; 3],
+                axis: [0.0, 0.0, 1.0],
+                radius,
+                height: 1.0,
+            };
+            Face::with_surface(edges, FaceType::Cylindrical, surface)
+        };
+        let face_ids: Vec<FaceId> = (0..3).map(|i| FaceId::new(format!("f_{}", i))).collect();
+        let mut faces: HashMap<FaceId, Face> = face_ids
+            .iter()
+            .zip([1.0, 2.0, 3.0])
+            .map(|(id, radius)| (id.clone(), cylinder(radius)))
+            .collect();
+
+        // Two solids bounded by the same shell
+        let shell_id = ShellId::new("shell".to_string());
+        let mut shells = HashMap::new();
+        shells.insert(shell_id.clone(), Shell::closed(face_ids.clone()));
+        let mut solids = TopologicalSolid::new();
+        for _ in 0..2 {
+            solids.add_solid(Solid::new(shell_id.clone(), SolidType::Simple)).unwrap();
+        }
+
+        let before = area_computations();
+        let first = solids.measured_stats(&mut shells, &mut faces).unwrap();
+        assert_eq!(area_computations() - before, 3);
+        let expected_area = 2.0 * std::f64::consts::PI * (1.0 + 2.0 + 3.0);
+        assert!((shells[&shell_id].surface_area - expected_area).abs() < 1e-9);
+
+        let second = solids.measured_stats(&mut shells, &mut faces).unwrap();
+        assert_eq!(area_computations() - before, 3);
+        assert_eq!(first.total_volume, second.total_volume);
+
+        // Changing one surface re-measures only that face
+        faces.insert(face_ids[0].clone(), cylinder(4.0));
+        solids.measured_stats(&mut shells, &mut faces).unwrap();
+        assert_eq!(area_computations() - before, 4);
+        let expected_area = 2.0 * std::f64::consts::PI * (4.0 + 2.0 + 3.0);
+        assert!((shells[&shell_id].surface_area - expected_area).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,542 @@
+//! STEP (ISO 10303-21) export of planar B-reps.
+//!
+//! Every topological face becomes one `ADVANCED_FACE` on a `PLANE`,
+//! bounded by an `EDGE_LOOP` of the face's edges, and edges shared by two
+//! faces are written once. Meshes go through [`complex_from_mesh`] first,
+//! which merges coplanar triangles, so a box exports as six faces rather
+//! than twelve triangles. Closed complexes are written as a
+//! `MANIFOLD_SOLID_BREP`, open ones as a `SHELL_BASED_SURFACE_MODEL`.
+//!
+//! Only planar faces with straight edges are supported; curved surfaces
+//! and arcs are rejected with a STEP export error.
+//!
+//! Faces with a surface-finish constraint get a `REPRESENTATION` named
+//! `surface finish` pairing the face with a `MEASURE_REPRESENTATION_ITEM`
+//! of the maximum Ra in micrometres, so the requirement travels with the
+//! part to manufacturing.
+
+use crate::config::{KernelConfig, LengthUnit};
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::math::vec3::{add, cross, dot, length, normalize_or_z, scale, sub};
+use crate::geometry::topology::face::FaceSurface;
+use crate::geometry::topology::from_mesh::complex_from_mesh_with_config;
+use crate::geometry::topology::{EdgeId, Face, FaceOrientation, TopologicalComplex, TopologyId};
+use crate::types::{PreviewMesh, StepExport};
+use std::collections::HashMap;
+
+/// Export a mesh as a STEP B-rep with one face per planar region, in the
+/// configured length unit
+pub fn mesh_to_step_with_config(
+    mesh: &PreviewMesh,
+    config: &KernelConfig,
+) -> KernelResult<StepExport> {
+    complex_to_step_with_config(&complex_from_mesh_with_config(mesh, config)?, config)
+}
+
+/// Export a planar complex, declaring the configured length unit and
+/// linear tolerance in the file
+///
+/// Vertices, edges and faces are written in ID order so the output is
+/// deterministic.
+pub fn complex_to_step_with_config(
+    complex: &TopologicalComplex,
+    config: &KernelConfig,
+) -> KernelResult<StepExport> {
+    config.validate()?;
+    if complex.faces.is_empty() {
+        return Err(KernelError::step_export_error(
+            "Complex has no faces to export",
+        ));
+    }
+
+    let mut data = StepData::default();
+    let centroid = complex_centroid(complex);
+
+    let mut vertex_ids: Vec<&TopologyId> = complex.vertices.keys().collect();
+    vertex_ids.sort_by_key(|id| id_order(id.as_str()));
+    let mut vertex_points = HashMap::new();
+    for id in vertex_ids {
+        let point = data.cartesian_point(complex.vertices[id].position);
+        vertex_points.insert(id, data.add(format!("VERTEX_POINT('',#{})", point)));
+    }
+
+    let mut edge_ids: Vec<&EdgeId> = complex.edges.keys().collect();
+    edge_ids.sort_by_key(|id| id_order(id.as_str()));
+    let mut edge_curves = HashMap::new();
+    let mut edge_uses: HashMap<&EdgeId, usize> = HashMap::new();
+    for id in edge_ids {
+        let edge = &complex.edges[id];
+        if edge.curve.is_some() {
+            return Err(KernelError::step_export_error(format!(
+                "Edge {} is curved; only straight edges can be exported",
+                id.as_str()
+            )));
+        }
+        let start = vertex_position(complex, &edge.start_vertex)?;
+        let end = vertex_position(complex, &edge.end_vertex)?;
+        let length = length(sub(end, start));
+        if length == 0.0 {
+            return Err(KernelError::step_export_error(format!(
+                "Edge {} has zero length",
+                id.as_str()
+            )));
+        }
+
+        let origin = data.cartesian_point(start);
+        let direction = data.direction(scale(sub(end, start), 1.0 / length));
+        let vector = data.add(format!("VECTOR('',#{},{})", direction, real(length)));
+        let line = data.add(format!("LINE('',#{},#{})", origin, vector));
+        let curve = data.add(format!(
+            "EDGE_CURVE('',#{},#{},#{},.T.)",
+            vertex_points[&edge.start_vertex], vertex_points[&edge.end_vertex], line
+        ));
+        edge_curves.insert(id, curve);
+        edge_uses.insert(id, 0);
+    }
+
+    let mut face_ids: Vec<_> = complex.faces.keys().collect();
+    face_ids.sort_by_key(|id| id_order(id.as_str()));
+    let mut advanced_faces = Vec::with_capacity(face_ids.len());
+    let mut finishes = Vec::new();
+    for id in face_ids {
+        let face = &complex.faces[id];
+        if !matches!(face.surface, None | Some(FaceSurface::Plane { .. })) {
+            return Err(KernelError::step_export_error(format!(
+                "Face {} is curved; only planar faces can be exported",
+                id.as_str()
+            )));
+        }
+
+        let outward = outward_normal(face, complex, centroid)?;
+        let (oriented, points) = outer_loop(complex, face, outward)?;
+        let normal = normalize_or_z(newell_normal(&points));
+        let reference = normalize_or_z(sub(points[1], points[0]));
+
+        let mut loop_edges = Vec::with_capacity(oriented.len());
+        for (edge_id, forward) in &oriented {
+            *edge_uses.get_mut(edge_id).unwrap() += 1;
+            loop_edges.push(data.add(format!(
+                "ORIENTED_EDGE('',*,*,#{},{})",
+                edge_curves[edge_id],
+                logical(*forward)
+            )));
+        }
+        let edge_loop = data.add(format!("EDGE_LOOP('',({}))", refs(&loop_edges)));
+        let bound = data.add(format!("FACE_OUTER_BOUND('',#{},.T.)", edge_loop));
+        let placement = data.axis_placement(points[0], normal, reference);
+        let plane = data.add(format!("PLANE('',#{})", placement));
+        let advanced_face = data.add(format!("ADVANCED_FACE('',(#{}),#{},.T.)", bound, plane));
+        advanced_faces.push(advanced_face);
+        if let Some(roughness) = face.required_roughness() {
+            finishes.push((advanced_face, roughness));
+        }
+    }
+
+    let closed = edge_uses.values().all(|&uses| uses == 2);
+    let item = if closed {
+        let shell = data.add(format!("CLOSED_SHELL('',({}))", refs(&advanced_faces)));
+        data.add(format!("MANIFOLD_SOLID_BREP('',#{})", shell))
+    } else {
+        let shell = data.add(format!("OPEN_SHELL('',({}))", refs(&advanced_faces)));
+        data.add(format!("SHELL_BASED_SURFACE_MODEL('',(#{}))", shell))
+    };
+    let context = data.representation_context(config);
+    let origin = data.axis_placement([0.0; 3], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]);
+    let representation = if closed {
+        "ADVANCED_BREP_SHAPE_REPRESENTATION"
+    } else {
+        "MANIFOLD_SURFACE_SHAPE_REPRESENTATION"
+    };
+    data.add(format!(
+        "{}('',(#{},#{}),#{})",
+        representation, item, origin, context
+    ));
+    if !finishes.is_empty() {
+        let micrometre = data.add("(LENGTH_UNIT()NAMED_UNIT(*)SI_UNIT(.MICRO.,.METRE.))".into());
+        for (face, roughness) in finishes {
+            let measure = data.add(format!(
+                "MEASURE_REPRESENTATION_ITEM('maximum roughness Ra',LENGTH_MEASURE({}),#{})",
+                real(roughness),
+                micrometre
+            ));
+            data.add(format!(
+                "REPRESENTATION('surface finish',(#{},#{}),#{})",
+                face, measure, context
+            ));
+        }
+    }
+
+    let mut content = String::from("ISO-10303-21;\nHEADER;\n");
+    content.push_str("FILE_DESCRIPTION(('Qutlas geometry kernel B-rep'),'2;1');\n");
+    content.push_str("FILE_NAME('','',(''),(''),'Qutlas geometry kernel','','');\n");
+    content.push_str("FILE_SCHEMA(('AUTOMOTIVE_DESIGN { 1 0 10303 214 1 1 1 1 }'));\n");
+    content.push_str("ENDSEC;\nDATA;\n");
+    for (index, entity) in data.entities.iter().enumerate() {
+        content.push_str(&format!("#{}={};\n", index + 1, entity));
+    }
+    content.push_str("ENDSEC;\nEND-ISO-10303-21;\n");
+
+    Ok(StepExport {
+        content,
+        entity_count: data.entities.len(),
+    })
+}
+
+/// DATA section entities, numbered from 1 in insertion order
+#[derive(Default)]
+struct StepData {
+    entities: Vec<String>,
+}
+
+impl StepData {
+    fn add(&mut self, entity: String) -> usize {
+        self.entities.push(entity);
+        self.entities.len()
+    }
+
+    fn cartesian_point(&mut self, p: [f64; 3]) -> usize {
+        self.add(format!("CARTESIAN_POINT('',({}))", reals(&p)))
+    }
+
+    fn direction(&mut self, d: [f64; 3]) -> usize {
+        self.add(format!("DIRECTION('',({}))", reals(&d)))
+    }
+
+    fn axis_placement(&mut self, origin: [f64; 3], axis: [f64; 3], reference: [f64; 3]) -> usize {
+        let origin = self.cartesian_point(origin);
+        let axis = self.direction(axis);
+        let reference = self.direction(reference);
+        self.add(format!(
+            "AXIS2_PLACEMENT_3D('',#{},#{},#{})",
+            origin, axis, reference
+        ))
+    }
+
+    /// Geometric context with units and the configured linear tolerance
+    fn representation_context(&mut self, config: &KernelConfig) -> usize {
+        let length = match config.length_unit {
+            LengthUnit::Millimeter => self.si_length_unit(".MILLI."),
+            LengthUnit::Centimeter => self.si_length_unit(".CENTI."),
+            LengthUnit::Meter => self.si_length_unit("$"),
+            LengthUnit::Inch => {
+                let millimeter = self.si_length_unit(".MILLI.");
+                let measure = self.add(format!(
+                    "LENGTH_MEASURE_WITH_UNIT(LENGTH_MEASURE(25.4),#{})",
+                    millimeter
+                ));
+                let dimensions =
+                    self.add("DIMENSIONAL_EXPONENTS(1.,0.,0.,0.,0.,0.,0.)".to_string());
+                self.add(format!(
+                    "(CONVERSION_BASED_UNIT('INCH',#{})LENGTH_UNIT()NAMED_UNIT(#{}))",
+                    measure, dimensions
+                ))
+            }
+        };
+        let angle = self.add("(NAMED_UNIT(*)PLANE_ANGLE_UNIT()SI_UNIT($,.RADIAN.))".to_string());
+        let solid_angle =
+            self.add("(NAMED_UNIT(*)SI_UNIT($,.STERADIAN.)SOLID_ANGLE_UNIT())".to_string());
+        let uncertainty = self.add(format!(
+            "UNCERTAINTY_MEASURE_WITH_UNIT(LENGTH_MEASURE({}),#{},'distance_accuracy_value','')",
+            real(config.linear_tolerance),
+            length
+        ));
+        self.add(format!(
+            "(GEOMETRIC_REPRESENTATION_CONTEXT(3)\
+             GLOBAL_UNCERTAINTY_ASSIGNED_CONTEXT((#{}))\
+             GLOBAL_UNIT_ASSIGNED_CONTEXT((#{},#{},#{}))\
+             REPRESENTATION_CONTEXT('',''))",
+            uncertainty, length, angle, solid_angle
+        ))
+    }
+
+    fn si_length_unit(&mut self, prefix: &str) -> usize {
+        self.add(format!(
+            "(LENGTH_UNIT()NAMED_UNIT(*)SI_UNIT({},.METRE.))",
+            prefix
+        ))
+    }
+}
+
+/// Loop edges with whether each is traversed start to end, and the
+/// position the traversal of each edge starts at
+type OrientedLoop<'a> = (Vec<(&'a EdgeId, bool)>, Vec<[f64; 3]>);
+
+/// Chain a face's boundary edges into a loop running counter-clockwise
+/// around `outward`
+fn outer_loop<'a>(
+    complex: &TopologicalComplex,
+    face: &'a Face,
+    outward: [f64; 3],
+) -> KernelResult<OrientedLoop<'a>> {
+    let malformed =
+        || KernelError::step_export_error("Face boundary edges do not form a single loop");
+    let mut remaining: Vec<&EdgeId> = face.boundary_edges.iter().collect();
+    if remaining.len() < 3 {
+        return Err(malformed());
+    }
+
+    let mut oriented = Vec::with_capacity(remaining.len());
+    let first = remaining.remove(0);
+    let first_edge = edge(complex, first)?;
+    let start = &first_edge.start_vertex;
+    let mut current = &first_edge.end_vertex;
+    oriented.push((first, true));
+
+    while !remaining.is_empty() {
+        let index = remaining
+            .iter()
+            .position(|id| {
+                complex
+                    .edges
+                    .get(*id)
+                    .is_some_and(|e| &e.start_vertex == current || &e.end_vertex == current)
+            })
+            .ok_or_else(malformed)?;
+        let id = remaining.remove(index);
+        let next = edge(complex, id)?;
+        let forward = &next.start_vertex == current;
+        current = if forward {
+            &next.end_vertex
+        } else {
+            &next.start_vertex
+        };
+        oriented.push((id, forward));
+    }
+    if current != start {
+        return Err(malformed());
+    }
+
+    let mut points = Vec::with_capacity(oriented.len());
+    for (id, forward) in &oriented {
+        let e = edge(complex, id)?;
+        let from = if *forward {
+            &e.start_vertex
+        } else {
+            &e.end_vertex
+        };
+        points.push(vertex_position(complex, from)?);
+    }
+
+    if dot(newell_normal(&points), outward) < 0.0 {
+        oriented.reverse();
+        for (_, forward) in &mut oriented {
+            *forward = !*forward;
+        }
+        points.reverse();
+        // Keep each point at the start of its edge after reversing
+        points.rotate_right(1);
+    }
+    Ok((oriented, points))
+}
+
+/// Direction the face should point, by the same rules as tessellation
+fn outward_normal(
+    face: &Face,
+    complex: &TopologicalComplex,
+    centroid: [f64; 3],
+) -> KernelResult<[f64; 3]> {
+    let sign = if face.orientation == FaceOrientation::Inward {
+        -1.0
+    } else {
+        1.0
+    };
+    if let Some(normal) = face.normal {
+        return Ok(normal);
+    }
+    if let Some(FaceSurface::Plane { normal, .. }) = &face.surface {
+        return Ok(scale(*normal, sign));
+    }
+
+    let mut sum = [0.0; 3];
+    for id in &face.boundary_edges {
+        sum = add(
+            sum,
+            vertex_position(complex, &edge(complex, id)?.start_vertex)?,
+        );
+    }
+    let middle = scale(sum, 1.0 / face.boundary_edges.len().max(1) as f64);
+    Ok(scale(sub(middle, centroid), sign))
+}
+
+fn edge<'a>(
+    complex: &'a TopologicalComplex,
+    id: &EdgeId,
+) -> KernelResult<&'a crate::geometry::topology::Edge> {
+    complex.edges.get(id).ok_or_else(|| {
+        KernelError::topology_error(format!("Face references missing edge {}", id.as_str()))
+    })
+}
+
+fn vertex_position(complex: &TopologicalComplex, id: &TopologyId) -> KernelResult<[f64; 3]> {
+    complex.vertices.get(id).map(|v| v.position).ok_or_else(|| {
+        KernelError::topology_error(format!("Edge references missing vertex {}", id.as_str()))
+    })
+}
+
+fn complex_centroid(complex: &TopologicalComplex) -> [f64; 3] {
+    let sum = complex
+        .vertices
+        .values()
+        .fold([0.0; 3], |sum, v| add(sum, v.position));
+    scale(sum, 1.0 / complex.vertices.len().max(1) as f64)
+}
+
+/// Sort key putting `face_2` before `face_10`
+fn id_order(id: &str) -> (usize, &str) {
+    let index = id.rsplit_once('_').and_then(|(_, n)| n.parse().ok());
+    (index.unwrap_or(usize::MAX), id)
+}
+
+/// Area-weighted normal of a polygon, pointing by its winding
+fn newell_normal(points: &[[f64; 3]]) -> [f64; 3] {
+    let mut normal = [0.0; 3];
+    for (i, &p) in points.iter().enumerate() {
+        normal = add(normal, cross(p, points[(i + 1) % points.len()]));
+    }
+    normal
+}
+
+/// STEP real literal, which needs a decimal point
+fn real(value: f64) -> String {
+    // +0.0 folds -0.0
+    let text = format!("{:?}", value + 0.0);
+    match text.split_once('e') {
+        Some((mantissa, exponent)) if !mantissa.contains('.') => {
+            format!("{}.E{}", mantissa, exponent)
+        }
+        Some((mantissa, exponent)) => format!("{}E{}", mantissa, exponent),
+        None => text,
+    }
+}
+
+fn reals(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(|&v| real(v))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn refs(ids: &[usize]) -> String {
+    ids.iter()
+        .map(|id| format!("#{}", id))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn logical(value: bool) -> &'static str {
+    if value {
+        ".T."
+    } else {
+        ".F."
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::ir::ManufacturingProcess;
+    use crate::geometry::primitives::Box as BoxPrimitive;
+    use crate::geometry::topology::create_box_topology;
+    use crate::geometry::topology::face::FaceConstraint;
+    use crate::geometry::Primitive;
+
+    fn count(step: &StepExport, entity: &str) -> usize {
+        step.content.matches(&format!("={}(", entity)).count()
+    }
+
+    #[test]
+    fn test_box_exports_six_planar_faces() {
+        let mesh = BoxPrimitive::new(10.0, 20.0, 30.0).to_mesh(16);
+        assert_eq!(mesh.triangle_count(), 12);
+
+        let step = mesh_to_step_with_config(&mesh, &KernelConfig::default()).unwrap();
+        assert!(step.content.starts_with("ISO-10303-21;"));
+        assert_eq!(count(&step, "ADVANCED_FACE"), 6);
+        assert_eq!(count(&step, "PLANE"), 6);
+        assert_eq!(count(&step, "EDGE_CURVE"), 12);
+        assert_eq!(count(&step, "VERTEX_POINT"), 8);
+        assert_eq!(count(&step, "ORIENTED_EDGE"), 24);
+        assert_eq!(count(&step, "CLOSED_SHELL"), 1);
+        assert_eq!(count(&step, "MANIFOLD_SOLID_BREP"), 1);
+        assert_eq!(
+            step.entity_count,
+            step.content.lines().filter(|l| l.starts_with('#')).count()
+        );
+
+        // Faces built directly as topology export the same way
+        let complex = create_box_topology(10.0, 20.0, 30.0).unwrap();
+        let direct = complex_to_step_with_config(&complex, &KernelConfig::default()).unwrap();
+        assert_eq!(count(&direct, "ADVANCED_FACE"), 6);
+        assert_eq!(count(&direct, "CLOSED_SHELL"), 1);
+
+        // Each edge is used once in each direction by the faces it joins
+        let mut uses: HashMap<&str, Vec<&str>> = HashMap::new();
+        for line in direct.content.lines() {
+            if let Some(args) = line.split_once("=ORIENTED_EDGE('',*,*,").map(|(_, a)| a) {
+                let (curve, sense) = args.trim_end_matches(");").split_once(',').unwrap();
+                uses.entry(curve).or_default().push(sense);
+            }
+        }
+        assert_eq!(uses.len(), 12);
+        for senses in uses.values_mut() {
+            senses.sort_unstable();
+            assert_eq!(senses, &[".F.", ".T."]);
+        }
+    }
+
+    #[test]
+    fn test_surface_finish_annotation() {
+        let mut complex = create_box_topology(10.0, 20.0, 30.0).unwrap();
+        assert!(!complex_to_step_with_config(&complex, &KernelConfig::default())
+            .unwrap()
+            .content
+            .contains("surface finish"));
+
+        let mut face_ids: Vec<_> = complex.faces.keys().cloned().collect();
+        face_ids.sort_by_key(|id| id_order(id.as_str()).0);
+        let face = complex.faces.get_mut(&face_ids[2]).unwrap();
+        face.add_constraint(FaceConstraint::surface_finish(
+            3.2,
+            ManufacturingProcess::CNCMilling,
+        ));
+        face.add_constraint(FaceConstraint::surface_finish(
+            1.6,
+            ManufacturingProcess::CNCMilling,
+        ));
+
+        let step = complex_to_step_with_config(&complex, &KernelConfig::default()).unwrap();
+        assert_eq!(count(&step, "MEASURE_REPRESENTATION_ITEM"), 1);
+        let measure = step
+            .content
+            .lines()
+            .find(|l| l.contains("MEASURE_REPRESENTATION_ITEM"))
+            .unwrap();
+        assert!(measure.contains("LENGTH_MEASURE(1.6)"));
+
+        // The annotation names the third ADVANCED_FACE and the measure
+        let face_entity = step
+            .content
+            .lines()
+            .filter(|l| l.contains("=ADVANCED_FACE("))
+            .nth(2)
+            .and_then(|l| l.split_once('='))
+            .unwrap()
+            .0;
+        let measure_entity = measure.split_once('=').unwrap().0;
+        let annotation = step
+            .content
+            .lines()
+            .find(|l| l.contains("=REPRESENTATION('surface finish'"))
+            .unwrap();
+        assert!(annotation.contains(&format!("({},{})", face_entity, measure_entity)));
+    }
+
+    #[test]
+    fn test_step_reals() {
+        assert_eq!(real(1.0), "1.0");
+        assert_eq!(real(-0.0), "0.0");
+        assert_eq!(real(1e-7), "1.E-7");
+        assert_eq!(real(2.5e20), "2.5E20");
+    }
+}
@@ -0,0 +1,873 @@
+//! Tessellation of topological solids into preview meshes.
+//!
+//! Each face is triangulated from its surface definition when one is
+//! available, otherwise from its boundary loop. Vertex normals are then
+//! assigned with smoothing groups: normals are averaged across an edge
+//! only when both triangles belong to the same curved surface type and
+//! the dihedral angle stays below a crease limit. Edges between faces of
+//! different types, and between distinct planar faces, stay sharp.
+
+use crate::config::{FanPolicy, KernelConfig};
+use crate::errors::{KernelError, KernelResult};
+use crate::geometry::math::vec3::{add, cross, dot, normalize_or_z, scale, sub};
+use crate::geometry::topology::edge::EdgeCurve;
+use crate::geometry::segments_for_chord_error;
+use crate::geometry::topology::face::FaceSurface;
+use crate::geometry::topology::{
+    Face, FaceId, FaceOrientation, FaceType, TopologicalComplex, TopologyId,
+};
+use crate::types::PreviewMesh;
+use std::collections::HashMap;
+
+/// Dihedral angle (radians) above which an edge is always kept sharp
+pub const SMOOTHING_CREASE_ANGLE: f64 = std::f64::consts::PI / 3.0;
+
+/// Triangulated face before normals are assigned
+struct FacePatch {
+    face_type: FaceType,
+    positions: Vec<[f64; 3]>,
+    triangles: Vec<[usize; 3]>,
+}
+
+/// Triangle after welding, with its flat normal
+struct WeldedTriangle {
+    patch: usize,
+    vertices: [usize; 3],
+    normal: [f64; 3],
+    area: f64,
+}
+
+/// Tessellate every face of a topological complex
+///
+/// `subdivisions` controls the number of segments used for full circles;
+/// arcs use a proportional share. Faces are processed in ID order so the
+/// output is deterministic, and `face_map` records the face each triangle
+/// came from.
+pub fn topology_to_mesh(
+    complex: &TopologicalComplex,
+    subdivisions: u32,
+) -> KernelResult<PreviewMesh> {
+    topology_to_mesh_with_config(complex, subdivisions, &KernelConfig::default())
+}
+
+/// Tessellate using the linear tolerance of a kernel configuration
+///
+/// Vertices closer than `config.linear_tolerance` are welded, so the
+/// configuration must match the units the complex is modeled in.
+/// Boundary-only faces are triangulated with `config.fan_policy`. With
+/// `config.max_chord_error` set, curved faces and circular edges get
+/// segment counts from their radius instead of `subdivisions`.
+pub fn topology_to_mesh_with_config(
+    complex: &TopologicalComplex,
+    subdivisions: u32,
+    config: &KernelConfig,
+) -> KernelResult<PreviewMesh> {
+    config.validate()?;
+
+    let segments = Segments {
+        uniform: subdivisions.max(8) as usize,
+        max_chord_error: config.max_chord_error,
+    };
+    let centroid = complex_centroid(complex);
+
+    let mut face_ids: Vec<&FaceId> = complex.faces.keys().collect();
+    face_ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+    let mut patches = Vec::with_capacity(face_ids.len());
+    for &face_id in &face_ids {
+        let face = &complex.faces[face_id];
+        patches.push(tessellate_face(
+            complex,
+            face,
+            segments,
+            centroid,
+            config.fan_policy,
+        )?);
+    }
+
+    Ok(apply_smoothing_groups(&patches, &face_ids, config.linear_tolerance))
+}
+
+/// Segments used for a full circle
+#[derive(Clone, Copy)]
+pub(super) struct Segments {
+    /// Count for every circle when no chord error is set
+    pub(super) uniform: usize,
+    pub(super) max_chord_error: Option<f64>,
+}
+
+impl Segments {
+    /// Segments for a circle of `radius`, with `error_share` of the chord
+    /// error (surfaces curved both ways sag further than their edges)
+    fn for_radius(&self, radius: f64, error_share: f64) -> KernelResult<usize> {
+        match self.max_chord_error {
+            Some(error) => Ok(segments_for_chord_error(radius, error * error_share)? as usize),
+            None => Ok(self.uniform),
+        }
+    }
+}
+
+/// Triangulate a single face
+fn tessellate_face(
+    complex: &TopologicalComplex,
+    face: &Face,
+    segments: Segments,
+    centroid: [f64; 3],
+    fan_policy: FanPolicy,
+) -> KernelResult<FacePatch> {
+    let flip = face.orientation == FaceOrientation::Inward;
+
+    let mut patch = match &face.surface {
+        Some(FaceSurface::Cylinder {
+            center,
+            axis,
+            radius,
+            height,
+        }) => {
+            let segments = segments.for_radius(*radius, 1.0)?;
+            tessellate_cylinder(*center, *axis, *radius, *height, segments)
+        }
+        Some(FaceSurface::Sphere { center, radius }) => {
+            tessellate_sphere(*center, *radius, segments.for_radius(*radius, 0.5)?)
+        }
+        _ => {
+            let outline = boundary_loop(complex, face, segments)?;
+            // `face.normal` already tracks orientation; other references do not
+            let sign = if flip { -1.0 } else { 1.0 };
+            let reference = match (&face.normal, &face.surface) {
+                (Some(normal), _) => *normal,
+                (None, Some(FaceSurface::Plane { normal, .. })) => scale(*normal, sign),
+                _ => scale(sub(average(&outline), centroid), sign),
+            };
+            match fan_policy {
+                FanPolicy::Fan => fan_triangulate(outline, reference),
+                FanPolicy::EarClip => ear_clip(outline, reference),
+            }
+        }
+    };
+
+    if flip && face.surface.as_ref().is_some_and(is_closed_surface) {
+        for tri in &mut patch.triangles {
+            tri.swap(1, 2);
+        }
+    }
+
+    patch.face_type = face.face_type.clone();
+    Ok(patch)
+}
+
+fn is_closed_surface(surface: &FaceSurface) -> bool {
+    matches!(
+        surface,
+        FaceSurface::Cylinder { .. } | FaceSurface::Sphere { .. }
+    )
+}
+
+/// Side of a cylinder whose `center` is the midpoint of its axis
+fn tessellate_cylinder(
+    center: [f64; 3],
+    axis: [f64; 3],
+    radius: f64,
+    height: f64,
+    segments: usize,
+) -> FacePatch {
+    let axis = normalize_or_z(axis);
+    let (u, v) = perpendicular_basis(axis);
+    let half = scale(axis, height / 2.0);
+
+    let mut positions = Vec::with_capacity(segments * 2);
+    for i in 0..segments {
+        let angle = 2.0 * std::f64::consts::PI * i as f64 / segments as f64;
+        let radial = add(scale(u, radius * angle.cos()), scale(v, radius * angle.sin()));
+        positions.push(add(sub(center, half), radial));
+        positions.push(add(add(center, half), radial));
+    }
+
+    let mut triangles = Vec::with_capacity(segments * 2);
+    for i in 0..segments {
+        let next = (i + 1) % segments;
+        let (b0, t0, b1, t1) = (i * 2, i * 2 + 1, next * 2, next * 2 + 1);
+        for tri in [[b0, b1, t0], [b1, t1, t0]] {
+            let mid = average(&tri.map(|k| positions[k]));
+            let along = dot(sub(mid, center), axis);
+            let outward = sub(sub(mid, center), scale(axis, along));
+            triangles.push(oriented(tri, &positions, outward));
+        }
+    }
+
+    FacePatch {
+        face_type: FaceType::Cylindrical,
+        positions,
+        triangles,
+    }
+}
+
+/// Full sphere as a latitude/longitude grid
+fn tessellate_sphere(center: [f64; 3], radius: f64, segments: usize) -> FacePatch {
+    let rings = (segments / 2).max(4);
+    let mut positions = Vec::with_capacity((rings + 1) * segments);
+
+    for ring in 0..=rings {
+        let phi = std::f64::consts::PI * ring as f64 / rings as f64;
+        for i in 0..segments {
+            let theta = 2.0 * std::f64::consts::PI * i as f64 / segments as f64;
+            positions.push([
+                center[0] + radius * phi.sin() * theta.cos(),
+                center[1] + radius * phi.cos(),
+                center[2] + radius * phi.sin() * theta.sin(),
+            ]);
+        }
+    }
+
+    let mut triangles = Vec::new();
+    for ring in 0..rings {
+        for i in 0..segments {
+            let next = (i + 1) % segments;
+            let a = ring * segments + i;
+            let b = ring * segments + next;
+            let c = (ring + 1) * segments + i;
+            let d = (ring + 1) * segments + next;
+
+            for tri in [[a, c, b], [b, c, d]] {
+                // Skip the degenerate triangles collapsed at the poles
+                if triangle_area(&tri.map(|k| positions[k])) < radius * radius * 1e-12 {
+                    continue;
+                }
+                let outward = sub(average(&tri.map(|k| positions[k])), center);
+                triangles.push(oriented(tri, &positions, outward));
+            }
+        }
+    }
+
+    FacePatch {
+        face_type: FaceType::Spherical,
+        positions,
+        triangles,
+    }
+}
+
+/// Fan-triangulate a convex boundary loop facing `reference`
+fn fan_triangulate(outline: Vec<[f64; 3]>, reference: [f64; 3]) -> FacePatch {
+    let triangles = (1..outline.len().saturating_sub(1))
+        .map(|k| oriented([0, k, k + 1], &outline, reference))
+        .collect();
+
+    FacePatch {
+        face_type: FaceType::Planar,
+        positions: outline,
+        triangles,
+    }
+}
+
+/// Ear-clip a simple boundary loop facing `reference`
+///
+/// Ears are clipped in loop order starting from the first vertex, so the
+/// result is deterministic. If no ear can be found (a self-intersecting
+/// or degenerate loop) the remainder is fanned.
+fn ear_clip(outline: Vec<[f64; 3]>, reference: [f64; 3]) -> FacePatch {
+    let (u, v) = perpendicular_basis(normalize_or_z(reference));
+    let points: Vec<[f64; 2]> = outline.iter().map(|p| [dot(*p, u), dot(*p, v)]).collect();
+
+    // Walk the loop counter-clockwise as seen from `reference`
+    let doubled_area: f64 = (0..points.len())
+        .map(|k| {
+            let (p, q) = (points[k], points[(k + 1) % points.len()]);
+            p[0] * q[1] - q[0] * p[1]
+        })
+        .sum();
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    if doubled_area < 0.0 {
+        remaining[1..].reverse();
+    }
+
+    let mut triangles = Vec::with_capacity(points.len().saturating_sub(2));
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&k| {
+            let [a, b, c] = [
+                remaining[(k + n - 1) % n],
+                remaining[k],
+                remaining[(k + 1) % n],
+            ];
+            cross_2d(points[a], points[b], points[c]) > 0.0
+                && remaining.iter().all(|&i| {
+                    i == a
+                        || i == b
+                        || i == c
+                        || !in_triangle(points[i], points[a], points[b], points[c])
+                })
+        });
+        match ear {
+            Some(k) => {
+                triangles.push([
+                    remaining[(k + n - 1) % n],
+                    remaining[k],
+                    remaining[(k + 1) % n],
+                ]);
+                remaining.remove(k);
+            }
+            None => break,
+        }
+    }
+    for k in 1..remaining.len().saturating_sub(1) {
+        triangles.push(oriented(
+            [remaining[0], remaining[k], remaining[k + 1]],
+            &outline,
+            reference,
+        ));
+    }
+
+    FacePatch {
+        face_type: FaceType::Planar,
+        positions: outline,
+        triangles,
+    }
+}
+
+fn cross_2d(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Whether `p` lies inside or on counter-clockwise triangle `abc`
+fn in_triangle(p: [f64; 2], a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> bool {
+    cross_2d(a, b, p) >= 0.0 && cross_2d(b, c, p) >= 0.0 && cross_2d(c, a, p) >= 0.0
+}
+
+/// Walk a face's boundary edges into an ordered polyline
+///
+/// Edges may be listed in any order and direction; arcs are sampled with
+/// a share of their circle's segments proportional to their sweep. The walk starts at
+/// the lowest-index boundary vertex and heads to its lowest-index
+/// neighbour, so the fan origin and the output order do not depend on how
+/// the face lists its edges.
+pub(super) fn boundary_loop(
+    complex: &TopologicalComplex,
+    face: &Face,
+    segments: Segments,
+) -> KernelResult<Vec<[f64; 3]>> {
+    let mut remaining: Vec<_> = face
+        .boundary_edges
+        .iter()
+        .map(|edge_id| {
+            complex.edges.get(edge_id).ok_or_else(|| {
+                KernelError::topology_error(format!(
+                    "Face references missing edge {}",
+                    edge_id.as_str()
+                ))
+            })
+        })
+        .collect::<KernelResult<_>>()?;
+
+    let mut outline = Vec::new();
+    let vertex_order =
+        |id: &TopologyId| (id.index().unwrap_or(usize::MAX), id.as_str().to_string());
+    let mut current: Option<TopologyId> = remaining
+        .iter()
+        .flat_map(|e| [&e.start_vertex, &e.end_vertex])
+        .min_by_key(|id| vertex_order(id))
+        .cloned();
+
+    while !remaining.is_empty() {
+        let position = match &current {
+            None => Some(0),
+            Some(vertex) if outline.is_empty() => remaining
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| &e.start_vertex == vertex || &e.end_vertex == vertex)
+                .min_by_key(|(_, e)| {
+                    let other = if &e.start_vertex == vertex {
+                        &e.end_vertex
+                    } else {
+                        &e.start_vertex
+                    };
+                    vertex_order(other)
+                })
+                .map(|(index, _)| index),
+            Some(vertex) => remaining
+                .iter()
+                .position(|e| &e.start_vertex == vertex || &e.end_vertex == vertex),
+        };
+        let edge = match position {
+            Some(index) => remaining.remove(index),
+            None => {
+                return Err(KernelError::topology_error(
+                    "Face boundary edges do not form a loop",
+                ))
+            }
+        };
+
+        let forward = current.as_ref().is_none_or(|v| &edge.start_vertex == v);
+        let (start, end) = if forward {
+            (&edge.start_vertex, &edge.end_vertex)
+        } else {
+            (&edge.end_vertex, &edge.start_vertex)
+        };
+
+        let start_pos = vertex_position(complex, start)?;
+        outline.push(start_pos);
+
+        if let Some(EdgeCurve::Circle {
+            center,
+            radius,
+            axis,
+            angle,
+        }) = &edge.curve
+        {
+            let sweep = if forward { *angle } else { -*angle };
+            let segments = segments.for_radius(*radius, 1.0)?;
+            let steps = ((segments as f64 * sweep.abs() / (2.0 * std::f64::consts::PI)).ceil()
+                as usize)
+                .max(1);
+            let axis = normalize_or_z(*axis);
+            for k in 1..steps {
+                let theta = sweep * k as f64 / steps as f64;
+                outline.push(add(*center, rotate(sub(start_pos, *center), axis, theta)));
+            }
+        }
+
+        current = Some(end.clone());
+    }
+
+    Ok(outline)
+}
+
+/// Merge points that fall in the same `tolerance`-sized grid cell
+///
+/// Returns the welded index of every input point and the unique
+/// positions, in first-seen order.
+pub fn weld_points(points: &[[f64; 3]], tolerance: f64) -> (Vec<usize>, Vec<[f64; 3]>) {
+    let mut cells: HashMap<[i64; 3], usize> = HashMap::new();
+    let mut unique = Vec::new();
+
+    let ids = points
+        .iter()
+        .map(|p| {
+            let key = p.map(|c| (c / tolerance).round() as i64);
+            *cells.entry(key).or_insert_with(|| {
+                unique.push(*p);
+                unique.len() - 1
+            })
+        })
+        .collect();
+
+    (ids, unique)
+}
+
+/// Weld patches and assign vertex normals by smoothing group
+///
+/// `face_ids[i]` is the face `patches[i]` was triangulated from.
+fn apply_smoothing_groups(
+    patches: &[FacePatch],
+    face_ids: &[&FaceId],
+    weld_tolerance: f64,
+) -> PreviewMesh {
+    let all_positions: Vec<[f64; 3]> = patches
+        .iter()
+        .flat_map(|patch| patch.positions.iter().copied())
+        .collect();
+    let (welded_ids, welded_positions) = weld_points(&all_positions, weld_tolerance);
+
+    let mut triangles: Vec<WeldedTriangle> = Vec::new();
+    let mut offset = 0;
+
+    for (patch_index, patch) in patches.iter().enumerate() {
+        let ids = &welded_ids[offset..offset + patch.positions.len()];
+        offset += patch.positions.len();
+
+        for tri in &patch.triangles {
+            let corners = tri.map(|k| patch.positions[k]);
+            let normal = cross(sub(corners[1], corners[0]), sub(corners[2], corners[0]));
+            triangles.push(WeldedTriangle {
+                patch: patch_index,
+                vertices: tri.map(|k| ids[k]),
+                normal: normalize_or_z(normal),
+                area: triangle_area(&corners),
+            });
+        }
+    }
+
+    // Corners (triangle * 3 + slot) joined into smoothing classes
+    let mut classes = UnionFind::new(triangles.len() * 3);
+
+    let mut edge_triangles: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (t, tri) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            let a = tri.vertices[k];
+            let b = tri.vertices[(k + 1) % 3];
+            edge_triangles.entry((a.min(b), a.max(b))).or_default().push(t);
+        }
+    }
+
+    let mut edges: Vec<_> = edge_triangles.into_iter().collect();
+    edges.sort_unstable_by_key(|(edge, _)| *edge);
+
+    for ((a, b), shared) in edges {
+        if shared.len() != 2 {
+            continue; // Boundary or non-manifold edges stay sharp
+        }
+        let (t1, t2) = (shared[0], shared[1]);
+        if !is_smooth_edge(patches, &triangles[t1], &triangles[t2]) {
+            continue;
+        }
+        for vertex in [a, b] {
+            classes.union(corner(&triangles[t1], t1, vertex), corner(&triangles[t2], t2, vertex));
+        }
+    }
+
+    // Area-weighted normal per class
+    let mut class_normals: HashMap<usize, [f64; 3]> = HashMap::new();
+    for (t, tri) in triangles.iter().enumerate() {
+        for slot in 0..3 {
+            let class = classes.find(t * 3 + slot);
+            let sum = class_normals.entry(class).or_insert([0.0; 3]);
+            *sum = add(*sum, scale(tri.normal, tri.area));
+        }
+    }
+
+    let mut mesh = PreviewMesh::new();
+    let mut output_index: HashMap<(usize, usize), u32> = HashMap::new();
+
+    for (t, tri) in triangles.iter().enumerate() {
+        for (slot, &vertex) in tri.vertices.iter().enumerate() {
+            let class = classes.find(t * 3 + slot);
+            let index = *output_index.entry((vertex, class)).or_insert_with(|| {
+                let position = welded_positions[vertex];
+                let normal = normalize_or_z(class_normals[&class]);
+                mesh.vertices
+                    .extend(position.iter().map(|&c| c as f32));
+                mesh.normals.extend(normal.iter().map(|&c| c as f32));
+                (mesh.vertices.len() / 3 - 1) as u32
+            });
+            mesh.indices.push(index);
+        }
+        mesh.face_map.push(face_ids[tri.patch].clone());
+    }
+
+    mesh
+}
+
+/// Whether normals may be averaged across the edge shared by two triangles
+fn is_smooth_edge(patches: &[FacePatch], t1: &WeldedTriangle, t2: &WeldedTriangle) -> bool {
+    let type1 = &patches[t1.patch].face_type;
+    let type2 = &patches[t2.patch].face_type;
+
+    let same_group = if t1.patch == t2.patch {
+        true
+    } else {
+        type1 == type2 && *type1 != FaceType::Planar
+    };
+
+    same_group && dot(t1.normal, t2.normal).clamp(-1.0, 1.0).acos() < SMOOTHING_CREASE_ANGLE
+}
+
+fn corner(tri: &WeldedTriangle, index: usize, vertex: usize) -> usize {
+    let slot = tri.vertices.iter().position(|&v| v == vertex).unwrap_or(0);
+    index * 3 + slot
+}
+
+/// Disjoint-set forest over triangle corners
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        UnionFind {
+            parent: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra != rb {
+            self.parent[ra.max(rb)] = ra.min(rb);
+        }
+    }
+}
+
+// Geometric helpers
+
+fn vertex_position(complex: &TopologicalComplex, id: &TopologyId) -> KernelResult<[f64; 3]> {
+    complex
+        .vertices
+        .get(id)
+        .map(|v| v.position)
+        .ok_or_else(|| {
+            KernelError::topology_error(format!("Edge references missing vertex {}", id.as_str()))
+        })
+}
+
+fn complex_centroid(complex: &TopologicalComplex) -> [f64; 3] {
+    let positions: Vec<[f64; 3]> = complex.vertices.values().map(|v| v.position).collect();
+    average(&positions)
+}
+
+/// Reorder a triangle so its normal faces `reference`
+fn oriented(tri: [usize; 3], positions: &[[f64; 3]], reference: [f64; 3]) -> [usize; 3] {
+    let [a, b, c] = tri.map(|k| positions[k]);
+    if dot(cross(sub(b, a), sub(c, a)), reference) < 0.0 {
+        [tri[0], tri[2], tri[1]]
+    } else {
+        tri
+    }
+}
+
+fn perpendicular_basis(axis: [f64; 3]) -> ([f64; 3], [f64; 3]) {
+    let helper = if axis[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    let u = normalize_or_z(sub(helper, scale(axis, dot(helper, axis))));
+    (u, cross(axis, u))
+}
+
+/// Rotate a vector about a unit axis (Rodrigues' formula)
+fn rotate(v: [f64; 3], axis: [f64; 3], angle: f64) -> [f64; 3] {
+    let (sin, cos) = angle.sin_cos();
+    add(
+        add(scale(v, cos), scale(cross(axis, v), sin)),
+        scale(axis, dot(axis, v) * (1.0 - cos)),
+    )
+}
+
+fn triangle_area(corners: &[[f64; 3]; 3]) -> f64 {
+    let n = cross(sub(corners[1], corners[0]), sub(corners[2], corners[0]));
+    dot(n, n).sqrt() / 2.0
+}
+
+fn average(points: &[[f64; 3]]) -> [f64; 3] {
+    if points.is_empty() {
+        return [0.0; 3];
+    }
+    let sum = points.iter().fold([0.0; 3], |acc, p| add(acc, *p));
+    scale(sum, 1.0 / points.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LengthUnit;
+    use crate::geometry::topology::{
+        create_box_topology, create_box_topology_with_config, create_cylinder_topology, Edge,
+        EdgeType, Vertex,
+    };
+
+    fn normal_at(mesh: &PreviewMesh, index: usize) -> [f64; 3] {
+        [
+            mesh.normals[index * 3] as f64,
+            mesh.normals[index * 3 + 1] as f64,
+            mesh.normals[index * 3 + 2] as f64,
+        ]
+    }
+
+    fn position_at(mesh: &PreviewMesh, index: usize) -> [f64; 3] {
+        [
+            mesh.vertices[index * 3] as f64,
+            mesh.vertices[index * 3 + 1] as f64,
+            mesh.vertices[index * 3 + 2] as f64,
+        ]
+    }
+
+    #[test]
+    fn test_cylinder_side_smooth_caps_sharp() {
+        let complex = create_cylinder_topology(2.0, 4.0).unwrap();
+        let mesh = topology_to_mesh(&complex, 16).unwrap();
+        mesh.is_valid().unwrap();
+
+        let mut radial = 0;
+        let mut rim_normals: HashMap<[i64; 3], Vec<[f64; 3]>> = HashMap::new();
+
+        for i in 0..mesh.vertex_count() {
+            let p = position_at(&mesh, i);
+            let n = normal_at(&mesh, i);
+
+            if n[1].abs() < 1e-6 {
+                // Side vertex: averaged normal points (nearly) radially outward
+                let r = (p[0] * p[0] + p[2] * p[2]).sqrt();
+                assert!((n[0] * p[0] + n[2] * p[2]) / r > 0.99);
+                radial += 1;
+            } else {
+                // Cap vertex: flat normal along the axis
+                assert!((n[1].abs() - 1.0).abs() < 1e-6);
+                assert_eq!(n[1].signum(), p[1].signum());
+            }
+
+            if (p[1].abs() - 2.0).abs() < 1e-6 && (p[0] * p[0] + p[2] * p[2] - 4.0).abs() < 1e-4 {
+                let key = p.map(|c| (c * 1e4).round() as i64);
+                rim_normals.entry(key).or_default().push(n);
+            }
+        }
+
+        // One shared vertex per side ring position
+        assert_eq!(radial, 32);
+
+        // Every rim point carries one cap normal and one side normal
+        assert_eq!(rim_normals.len(), 32);
+        assert!(rim_normals.values().all(|normals| normals.len() == 2));
+    }
+
+    #[test]
+    fn test_box_faces_stay_flat() {
+        let complex = create_box_topology(2.0, 2.0, 2.0).unwrap();
+        let mesh = topology_to_mesh(&complex, 16).unwrap();
+
+        assert_eq!(mesh.triangle_count(), 12);
+        // Each corner is split into one vertex per adjacent face
+        assert_eq!(mesh.vertex_count(), 24);
+
+        for i in 0..mesh.vertex_count() {
+            let n = normal_at(&mesh, i);
+            let p = position_at(&mesh, i);
+            let axis_aligned = n.iter().filter(|c| (c.abs() - 1.0).abs() < 1e-6).count();
+            assert_eq!(axis_aligned, 1);
+            assert!(dot(n, p) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_face_map_resolves_picked_triangles() {
+        let complex = create_box_topology(2.0, 2.0, 2.0).unwrap();
+        let mesh = topology_to_mesh(&complex, 16).unwrap();
+        mesh.is_valid().unwrap();
+
+        assert_eq!(complex.faces.len(), 6);
+        assert_eq!(mesh.face_map.len(), mesh.triangle_count());
+
+        // Both triangles of each face lie in the same plane
+        let mut per_face: HashMap<&FaceId, Vec<[f64; 3]>> = HashMap::new();
+        for (t, face_id) in mesh.face_map.iter().enumerate() {
+            assert!(complex.faces.contains_key(face_id));
+            let corners = [0, 1, 2].map(|k| position_at(&mesh, mesh.indices[t * 3 + k] as usize));
+            let normal = cross(sub(corners[1], corners[0]), sub(corners[2], corners[0]));
+            per_face.entry(face_id).or_default().push(normalize_or_z(normal));
+        }
+        assert_eq!(per_face.len(), 6);
+        for normals in per_face.values() {
+            assert_eq!(normals.len(), 2);
+            assert!(dot(normals[0], normals[1]) > 1.0 - 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_weld_with_meter_scale_tolerance() {
+        let meters = KernelConfig::for_unit(LengthUnit::Meter);
+
+        // Meter-scale points: a float-noise duplicate and a 0.3 µm offset
+        let points = [
+            [0.25, 0.5, 0.75],
+            [0.25 + 2e-10, 0.5, 0.75],
+            [0.25 + 3e-7, 0.5, 0.75],
+        ];
+
+        let (ids, unique) = weld_points(&points, meters.linear_tolerance);
+        assert_eq!(ids, vec![0, 0, 1]);
+        assert_eq!(unique.len(), 2);
+
+        // The millimeter default would wrongly merge the distinct point
+        let (ids, _) = weld_points(&points, KernelConfig::default().linear_tolerance);
+        assert_eq!(ids, vec![0, 0, 0]);
+
+        // A 2 m x 0.5 m x 1 m box still welds to one vertex per face corner
+        let complex = create_box_topology_with_config(2.0, 0.5, 1.0, &meters).unwrap();
+        assert!(complex
+            .vertices
+            .values()
+            .all(|v| v.tolerance == meters.linear_tolerance));
+
+        let mesh = topology_to_mesh_with_config(&complex, 16, &meters).unwrap();
+        assert_eq!(mesh.triangle_count(), 12);
+        assert_eq!(mesh.vertex_count(), 24);
+    }
+
+    #[test]
+    fn test_fan_origin_is_deterministic() {
+        let complex = create_box_topology(2.0, 3.0, 4.0).unwrap();
+        let first = topology_to_mesh(&complex, 16).unwrap();
+        let second = topology_to_mesh(&complex, 16).unwrap();
+        assert_eq!(first.indices, second.indices);
+
+        // Listing the edges in another order and direction changes nothing
+        let mut shuffled = complex.clone();
+        for face in shuffled.faces.values_mut() {
+            face.boundary_edges.rotate_left(1);
+            face.boundary_edges.reverse();
+        }
+        let third = topology_to_mesh(&shuffled, 16).unwrap();
+        assert_eq!(first.indices, third.indices);
+        assert_eq!(first.vertices, third.vertices);
+    }
+
+    #[test]
+    fn test_ear_clip_non_convex_face() {
+        // L-shaped face whose first vertex is the reflex corner
+        let corners = [
+            [2.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [1.0, 2.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+        ];
+        let mut complex = TopologicalComplex::new();
+        let ids: Vec<TopologyId> = corners
+            .iter()
+            .map(|&p| complex.add_vertex(Vertex::new(p)).unwrap())
+            .collect();
+        let edges = (0..ids.len())
+            .map(|k| {
+                let next = ids[(k + 1) % ids.len()].clone();
+                complex
+                    .add_edge(Edge::new(ids[k].clone(), next, EdgeType::Linear))
+                    .unwrap()
+            })
+            .collect();
+        complex
+            .add_face(Face::with_surface(
+                edges,
+                FaceType::Planar,
+                FaceSurface::Plane {
+                    point: [0.0; 3],
+                    normal: [0.0, 0.0, 1.0],
+                },
+            ))
+            .unwrap();
+
+        let area = |mesh: &PreviewMesh| -> f64 {
+            mesh.indices
+                .chunks_exact(3)
+                .map(|t| triangle_area(&[0, 1, 2].map(|k| position_at(mesh, t[k] as usize))))
+                .sum()
+        };
+
+        // A fan from the reflex corner overlaps itself
+        let fan = topology_to_mesh(&complex, 16).unwrap();
+        assert!(area(&fan) > 3.5);
+
+        let config = KernelConfig {
+            fan_policy: FanPolicy::EarClip,
+            ..KernelConfig::default()
+        };
+        let clipped = topology_to_mesh_with_config(&complex, 16, &config).unwrap();
+        assert_eq!(clipped.triangle_count(), 4);
+        assert!((area(&clipped) - 3.0).abs() < 1e-6);
+        for t in clipped.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|k| position_at(&clipped, t[k] as usize));
+            assert!(cross(sub(b, a), sub(c, a))[2] > 0.0);
+        }
+        assert_eq!(
+            clipped.indices,
+            topology_to_mesh_with_config(&complex, 16, &config)
+                .unwrap()
+                .indices
+        );
+    }
+}
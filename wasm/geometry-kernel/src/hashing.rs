@@ -5,6 +5,7 @@
 
 use crate::types::GeometryIR;
 use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Compute deterministic hash of intent IR
 ///
@@ -58,14 +59,19 @@ fn canonicalize_intent(ir: &GeometryIR) -> String {
 }
 
 /// Recursively canonicalize a JSON value
+///
+/// Objects are rebuilt in sorted key order, so maps serialized from a
+/// `HashMap` (such as intent parameters) hash the same regardless of
+/// insertion order, even if `serde_json` preserves insertion order.
 fn canonicalize_value(value: &mut serde_json::Value) {
     match value {
         serde_json::Value::Object(map) => {
             // Sort object keys for deterministic ordering
-            let mut entries: Vec<_> = map.iter_mut().collect();
-            entries.sort_by(|a, b| a.0.cmp(b.0));
-            for (_, v) in entries {
-                canonicalize_value(v);
+            let sorted: BTreeMap<String, serde_json::Value> =
+                std::mem::take(map).into_iter().collect();
+            for (key, mut v) in sorted {
+                canonicalize_value(&mut v);
+                map.insert(key, v);
             }
         }
         serde_json::Value::Array(arr) => {
@@ -103,7 +109,8 @@ pub fn verify_hash(ir: &GeometryIR, hash: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{Intent, PrimitiveIntent, PrimitiveType};
+    use crate::types::{Intent, OperationIntent, OperationType, PrimitiveIntent, PrimitiveType};
+    use std::collections::HashMap;
 
     #[test]
     fn test_deterministic_hashing() {
@@ -148,6 +155,46 @@ mod tests {
         assert_ne!(hash1, hash2);
     }
 
+    #[test]
+    fn test_parameter_order_does_not_change_hash() {
+        let keys = ["radius", "segments", "edges", "angle", "offset", "mode"];
+        let values: Vec<serde_json::Value> = vec![
+            2.5.into(),
+            16.into(),
+            serde_json::json!(["e1", "e2"]),
+            45.0.into(),
+            serde_json::json!({ "z": 1, "a": 2 }),
+            "round".into(),
+        ];
+
+        let ir_with = |parameters: HashMap<String, serde_json::Value>| GeometryIR {
+            part: "test_part".to_string(),
+            operations: vec![Intent::Operation(OperationIntent {
+                id: "fillet1".to_string(),
+                type_: OperationType::Fillet,
+                target: "box1".to_string(),
+                operand: None,
+                parameters,
+                timestamp: 0.0,
+            })],
+            constraints: vec![],
+        };
+
+        let mut forward = HashMap::new();
+        for (key, value) in keys.iter().zip(&values) {
+            forward.insert(key.to_string(), value.clone());
+        }
+        let mut reverse = HashMap::new();
+        for (key, value) in keys.iter().zip(&values).rev() {
+            reverse.insert(key.to_string(), value.clone());
+        }
+
+        let ir1 = ir_with(forward);
+        let ir2 = ir_with(reverse);
+        assert_eq!(hash_intent(&ir1), hash_intent(&ir2));
+        assert_eq!(hash_value(&ir1), hash_value(&ir2));
+    }
+
     #[test]
     fn test_json_normalization() {
         let json1 = r#"{ "a": 1, "b": 2 }"#;
@@ -39,6 +39,7 @@
 use wasm_bindgen::prelude::*;
 
 mod compiler;
+mod config;
 mod errors;
 mod geometry;
 mod hashing;
@@ -51,8 +52,9 @@ use types::*;
 // Import enhanced geometry system
 use geometry::{
     GeometricAnalyzer, IRGraph, IRNode, IRValidator, MassProperties, MaterialProperties,
-    NodeContent, NodeId, NodeType,
+    NodeContent, NodeId, NodeType, ParameterKey,
 };
+use geometry::ir::node::{NodeMetadata, NodeSource};
 
 /// WASM entry point for the enhanced geometry kernel
 #[wasm_bindgen]
@@ -108,8 +110,44 @@ impl GeometryKernel {
     #[wasm_bindgen]
     pub fn compile_intent(&mut self, intent_json: &str) -> String {
         // Legacy Intent compilation for backward compatibility
-        self.compile_internal(intent_json).unwrap_or_else(|error| {
-            serde_json::to_string(&error).unwrap_or_else(|_| {
+        self.compile_with(intent_json, &CompileOutputOptions::default())
+    }
+
+    /// Compile intent JSON, choosing what goes into the result JSON
+    ///
+    /// # Arguments
+    /// * `intent_json` - JSON string of GeometryIR
+    /// * `options_json` - JSON string of output options:
+    ///   - sections: any of "mesh", "step", "topology", "validation"
+    ///     (default: all)
+    ///   - pretty: pretty-print the JSON (default: false)
+    ///
+    /// # Returns
+    /// JSON string of CompileResult as from `compile_intent`, without the
+    /// sections left out. Status, hash, error and metrics are always there.
+    ///
+    /// # Example
+    /// ```typescript
+    /// // Poll status without serializing the mesh
+    /// const result = kernel.compile_intent_with_options(intent, '{"sections":[]}');
+    /// ```
+    #[wasm_bindgen]
+    pub fn compile_intent_with_options(&mut self, intent_json: &str, options_json: &str) -> String {
+        match serde_json::from_str::<CompileOutputOptions>(options_json) {
+            Ok(options) => self.compile_with(intent_json, &options),
+            Err(e) => serde_json::to_string(&KernelError::invalid_json(format!(
+                "Invalid output options JSON: {}",
+                e
+            )))
+            .unwrap_or_else(|_| {
+                r#"{"status":"error","error":{"code":"INTERNAL_ERROR","message":"Failed to serialize error"}}"#.to_string()
+            }),
+        }
+    }
+
+    fn compile_with(&mut self, intent_json: &str, options: &CompileOutputOptions) -> String {
+        self.compile_internal(intent_json, options).unwrap_or_else(|error| {
+            options.to_json(&error).unwrap_or_else(|_| {
                 // Fallback if even error serialization fails
                 r#"{"status":"error","error":{"code":"INTERNAL_ERROR","message":"Failed to serialize error"}}"#.to_string()
             })
@@ -147,18 +185,30 @@ impl GeometryKernel {
         })
     }
 
-    fn compile_internal(&mut self, intent_json: &str) -> Result<String, KernelError> {
+    fn compile_internal(
+        &mut self,
+        intent_json: &str,
+        options: &CompileOutputOptions,
+    ) -> Result<String, KernelError> {
+        let mut stopwatch = compiler::metrics::Stopwatch::start();
+
         // Parse JSON input
         let ir: GeometryIR = serde_json::from_str(intent_json).map_err(|e| {
             KernelError::invalid_json(format!("Invalid intent JSON: {}", e))
                 .with_context(errors::ErrorContext::new())
         })?;
+        let parse_ms = stopwatch.lap();
 
         // Compile intent to geometry
-        let result = self.compiler.compile(&ir).map_err(|e| e)?;
+        let mut result = self.compiler.compile(&ir).map_err(|e| e)?;
+        if let Some(metrics) = result.metrics.as_mut() {
+            metrics.parse_ms = parse_ms;
+        }
 
-        // Serialize result to JSON
-        serde_json::to_string(&result)
+        // Drop unrequested sections and serialize result to JSON
+        options.filter(&mut result);
+        options
+            .to_json(&result)
             .map_err(|e| KernelError::internal(format!("Failed to serialize result: {}", e)))
     }
 
@@ -172,6 +222,10 @@ impl GeometryKernel {
         let input: SemanticIRInput = serde_json::from_str(semantic_ir_json)
             .map_err(|e| KernelError::invalid_json(format!("Invalid semantic IR JSON: {}", e)))?;
 
+        if input.nodes.is_empty() {
+            return Err(KernelError::empty_intent());
+        }
+
         // Validate the IR graph
         let validation_result = self
             .ir_validator
@@ -354,6 +408,287 @@ impl GeometryKernel {
         self.compiler.set_subdivisions(subdivisions);
     }
 
+    /// Limit how long a compile may spend evaluating
+    ///
+    /// # Arguments
+    /// * `budget_ms` - Milliseconds, or undefined for no limit
+    ///
+    /// A compile that runs past the budget stops after the current intent
+    /// and returns status "partial" with the finished `partial_meshes` and
+    /// the `remaining_nodes`.
+    #[wasm_bindgen]
+    pub fn set_time_budget_ms(&mut self, budget_ms: Option<u32>) {
+        self.compiler.set_time_budget_ms(budget_ms.map(u64::from));
+    }
+
+    /// Save the compiler session for crash recovery or sharing
+    ///
+    /// # Returns
+    /// JSON string of the snapshot: units, tolerances, tessellation
+    /// settings, time budget and the last compiled intent. No geometry is
+    /// included.
+    #[wasm_bindgen]
+    pub fn snapshot(&self) -> String {
+        match self.compiler.snapshot() {
+            Ok(snapshot) => snapshot,
+            Err(e) => serde_json::json!({
+                "status": "error",
+                "error": e
+            })
+            .to_string(),
+        }
+    }
+
+    /// Replace the compiler session with one saved by `snapshot`
+    ///
+    /// # Arguments
+    /// * `snapshot_json` - JSON string returned by `snapshot`
+    ///
+    /// # Returns
+    /// JSON string with the status
+    ///
+    /// Nothing is compiled until the next `compile_intent`, which reports
+    /// the snapshot's intent as "cached".
+    #[wasm_bindgen]
+    pub fn restore(&mut self, snapshot_json: &str) -> String {
+        match self.compiler.restore(snapshot_json) {
+            Ok(()) => serde_json::json!({
+                "status": "success",
+                "config": self.compiler.config()
+            })
+            .to_string(),
+            Err(e) => serde_json::json!({
+                "status": "error",
+                "error": e
+            })
+            .to_string(),
+        }
+    }
+
+    /// Coarsen tessellation for previews of large assemblies
+    ///
+    /// # Arguments
+    /// * `quality` - Fraction of the full triangle count curved primitives
+    ///   keep, in (0, 1]
+    ///
+    /// # Returns
+    /// JSON string with the status
+    #[wasm_bindgen]
+    pub fn set_tessellation_quality(&mut self, quality: f32) -> String {
+        match self.compiler.set_tessellation_quality(quality) {
+            Ok(()) => serde_json::json!({
+                "status": "success",
+                "tessellation_quality": quality
+            })
+            .to_string(),
+            Err(e) => serde_json::json!({
+                "status": "error",
+                "error": e
+            })
+            .to_string(),
+        }
+    }
+
+    /// Set the length unit models are expressed in
+    ///
+    /// # Arguments
+    /// * `unit` - "mm", "cm", "m" or "in"
+    ///
+    /// # Returns
+    /// JSON string with the resulting tolerance configuration
+    ///
+    /// Tolerances are rescaled to keep the same physical size, and the
+    /// compilation cache is cleared.
+    #[wasm_bindgen]
+    pub fn set_length_unit(&mut self, unit: &str) -> String {
+        let result = config::LengthUnit::parse(unit)
+            .map(config::KernelConfig::for_unit)
+            .and_then(|config| self.compiler.set_config(config).map(|_| config));
+
+        match result {
+            Ok(config) => serde_json::json!({
+                "status": "success",
+                "config": config
+            })
+            .to_string(),
+            Err(e) => serde_json::json!({
+                "status": "error",
+                "error": e
+            })
+            .to_string(),
+        }
+    }
+
+    /// Analyze a mesh and return its geometric properties
+    ///
+    /// # Arguments
+    /// * `mesh_json` - JSON string of a preview mesh
+    /// * `primitive_type` - Primitive the mesh was generated from (e.g. "box")
+    /// * `material` - "aluminum", "steel" or "abs"
+    ///
+    /// # Returns
+    /// JSON string with volume, surface area, center of mass, bounding box
+    /// and mass (in kilograms) of the mesh
+    #[wasm_bindgen]
+    pub fn analyze_mesh(&self, mesh_json: &str, primitive_type: &str, material: &str) -> String {
+        match self.analyze_mesh_internal(mesh_json, primitive_type, material) {
+            Ok(response) => response,
+            Err(error) => serde_json::json!({
+                "status": "error",
+                "error": error.to_string()
+            })
+            .to_string(),
+        }
+    }
+
+    fn analyze_mesh_internal(
+        &self,
+        mesh_json: &str,
+        primitive_type: &str,
+        material: &str,
+    ) -> Result<String, KernelError> {
+        let mesh: PreviewMesh = serde_json::from_str(mesh_json)
+            .map_err(|e| KernelError::invalid_parameter("mesh_json", e.to_string()))?;
+        let material = MaterialProperties::by_name(material)?;
+
+        let node = IRNode::new(
+            NodeType::Primitive,
+            NodeContent::Primitive {
+                primitive_type: primitive_type.to_string(),
+                parameters: std::collections::HashMap::new(),
+                transform: None,
+            },
+            Vec::new(),
+            NodeMetadata::new(None, NodeSource::User),
+        )?;
+
+        // Densities are per cubic meter; rescale them to the model unit so
+        // that the reported mass is in kilograms
+        let meters_per_unit = self.compiler.config().length_unit.meters_per_unit();
+        let scaled = MaterialProperties {
+            density: material.density * meters_per_unit.powi(3),
+            ..material.clone()
+        };
+
+        // A fresh analyzer: the node ID only depends on the primitive type,
+        // so the shared cache would mix up different meshes
+        let analysis = GeometricAnalyzer::with_material(scaled).analyze_node(&node, &mesh)?;
+
+        serde_json::to_string(&serde_json::json!({
+            "status": "success",
+            "material": material,
+            "analysis": analysis
+        }))
+        .map_err(|e| KernelError::internal(format!("Failed to serialize analysis: {}", e)))
+    }
+
+    /// Check that a mesh survives conversion to B-rep topology and back
+    ///
+    /// # Arguments
+    /// * `mesh_json` - JSON string of a preview mesh
+    ///
+    /// # Returns
+    /// True if the rebuilt mesh matches the input within the linear
+    /// tolerance; false for meshes that do not, or that fail to parse
+    #[wasm_bindgen]
+    pub fn verify_roundtrip(&self, mesh_json: &str) -> bool {
+        serde_json::from_str::<PreviewMesh>(mesh_json).is_ok_and(|mesh| {
+            geometry::topology::from_mesh::verify_roundtrip_with_config(
+                &mesh,
+                self.compiler.config(),
+            )
+        })
+    }
+
+    /// Solve distance, angle and concentric constraints between points
+    ///
+    /// # Arguments
+    /// * `request_json` - JSON string of a constraint solve request:
+    ///   `constraints` (type, affected node IDs and parameters), current
+    ///   `parameters` by node ID and name, and the `driven` parameter names
+    ///   by node ID
+    ///
+    /// # Returns
+    /// JSON string with the solved value of every driven parameter, keyed
+    /// the same way, or an error when the constraints have no solution
+    ///
+    /// # Example
+    /// ```typescript
+    /// const result = kernel.solve_constraints(JSON.stringify({
+    ///   constraints: [{ constraint_type: "distance", affected_nodes: ["a", "b"],
+    ///                   parameters: { distance: 5 } }],
+    ///   parameters: { b: { x: 3 } },
+    ///   driven: { b: ["x", "y"] },
+    /// }));
+    /// ```
+    #[wasm_bindgen]
+    pub fn solve_constraints(&self, request_json: &str) -> String {
+        match self.solve_constraints_internal(request_json) {
+            Ok(response) => response,
+            Err(error) => serde_json::json!({
+                "status": "error",
+                "error": error.to_string()
+            })
+            .to_string(),
+        }
+    }
+
+    fn solve_constraints_internal(&self, request_json: &str) -> Result<String, KernelError> {
+        let request: ConstraintSolveRequest = serde_json::from_str(request_json)
+            .map_err(|e| KernelError::invalid_parameter("request_json", e.to_string()))?;
+
+        let key = |node: &str, name: &str| (NodeId::from_user_string(node), name.to_string());
+        let constraints = request
+            .constraints
+            .iter()
+            .map(|spec| {
+                let affected: Vec<NodeId> =
+                    spec.affected_nodes.iter().map(|id| NodeId::from_user_string(id)).collect();
+                IRNode::new(
+                    NodeType::Constraint,
+                    NodeContent::Constraint {
+                        constraint_type: spec.constraint_type.clone(),
+                        affected_nodes: affected.clone(),
+                        parameters: spec.parameters.clone(),
+                    },
+                    affected,
+                    NodeMetadata::new(None, NodeSource::User),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let parameters: std::collections::HashMap<ParameterKey, f64> = request
+            .parameters
+            .iter()
+            .flat_map(|(node, values)| values.iter().map(move |(name, &v)| (key(node, name), v)))
+            .collect();
+        let driven: Vec<(&String, &String)> = request
+            .driven
+            .iter()
+            .flat_map(|(node, names)| names.iter().map(move |name| (node, name)))
+            .collect();
+        let driven_keys: Vec<ParameterKey> =
+            driven.iter().map(|(node, name)| key(node, name)).collect();
+
+        let solved = geometry::solve_constraints(
+            &constraints.iter().collect::<Vec<_>>(),
+            &parameters,
+            &driven_keys,
+        )?;
+
+        // Report under the caller's node IDs rather than the hashed ones
+        let mut result: std::collections::BTreeMap<&str, std::collections::BTreeMap<&str, f64>> =
+            std::collections::BTreeMap::new();
+        for ((node, name), key) in driven.iter().zip(&driven_keys) {
+            result.entry(node.as_str()).or_default().insert(name.as_str(), solved[key]);
+        }
+
+        serde_json::to_string(&serde_json::json!({
+            "status": "success",
+            "parameters": result
+        }))
+        .map_err(|e| KernelError::internal(format!("Failed to serialize solution: {}", e)))
+    }
+
     /// Get IR graph statistics
     ///
     /// # Returns
@@ -444,9 +779,127 @@ mod tests {
         assert_eq!(result1.intent_hash, result2.intent_hash);
     }
 
+    #[test]
+    fn test_time_budget_through_kernel() {
+        let mut intent = create_simple_box_intent();
+        let mut second = intent.operations[0].clone();
+        if let Intent::Primitive(primitive) = &mut second {
+            primitive.id = "box2".to_string();
+        }
+        intent.operations.push(second);
+        intent.operations.push(Intent::Operation(OperationIntent {
+            id: "union1".to_string(),
+            type_: OperationType::Union,
+            target: "box1".to_string(),
+            operand: Some("box2".to_string()),
+            parameters: HashMap::new(),
+            timestamp: 0.0,
+        }));
+        let intent_json = serde_json::to_string(&intent).unwrap();
+
+        // No time at all: only the first intent is evaluated
+        let mut kernel = GeometryKernel::new();
+        kernel.set_time_budget_ms(Some(0));
+        let result: CompileResult =
+            serde_json::from_str(&kernel.compile_intent(&intent_json)).unwrap();
+        assert_eq!(result.status, CompileStatus::Partial);
+        assert_eq!(result.remaining_nodes, vec!["box2", "union1"]);
+
+        kernel.set_time_budget_ms(None);
+        let result: CompileResult =
+            serde_json::from_str(&kernel.compile_intent(&intent_json)).unwrap();
+        assert_eq!(result.status, CompileStatus::Compiled);
+    }
+
+    #[test]
+    fn test_snapshot_restore_through_kernel() {
+        let intent_json = serde_json::to_string(&create_simple_box_intent()).unwrap();
+
+        let mut kernel = GeometryKernel::new();
+        kernel.set_length_unit("in");
+        kernel.set_tessellation_quality(0.5);
+        kernel.compile_intent(&intent_json);
+        let snapshot = kernel.snapshot();
+
+        let mut restored = GeometryKernel::new();
+        let status: serde_json::Value =
+            serde_json::from_str(&restored.restore(&snapshot)).unwrap();
+        assert_eq!(status["status"], "success");
+        assert_eq!(status["config"]["length_unit"], "inch");
+        assert_eq!(restored.compiler.tessellation_quality(), 0.5);
+        let result: CompileResult =
+            serde_json::from_str(&restored.compile_intent(&intent_json)).unwrap();
+        assert_eq!(result.status, CompileStatus::Cached);
+
+        let status: serde_json::Value =
+            serde_json::from_str(&restored.restore("{}")).unwrap();
+        assert_eq!(status["status"], "error");
+    }
+
+    #[test]
+    fn test_compile_positions_primitive_from_intent_transform() {
+        let mut kernel = GeometryKernel::new();
+        let intent_json = r#"{
+            "part": "test_part",
+            "operations": [{
+                "id": "box1",
+                "type": "box",
+                "parameters": {"width": 4.0, "height": 6.0, "depth": 8.0},
+                "transform": {"position": [10.0, 0.0, 0.0]},
+                "timestamp": 0.0
+            }],
+            "constraints": []
+        }"#;
+
+        let result: CompileResult =
+            serde_json::from_str(&kernel.compile_intent(intent_json)).unwrap();
+        assert_eq!(result.status, CompileStatus::Compiled);
+
+        let mesh = result.mesh.unwrap();
+        let (mut min, mut max) = ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]);
+        for v in mesh.vertices.chunks_exact(3) {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(v[axis]);
+                max[axis] = max[axis].max(v[axis]);
+            }
+        }
+        let center: Vec<f32> = (0..3).map(|axis| (min[axis] + max[axis]) / 2.0).collect();
+        assert!((center[0] - 10.0).abs() < 1e-4);
+        assert!(center[1].abs() < 1e-4 && center[2].abs() < 1e-4);
+        assert!((max[0] - min[0] - 4.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_compile_status_only_output_omits_mesh() {
+        let mut kernel = GeometryKernel::new();
+        let intent_json = r#"{
+            "part": "test_part",
+            "operations": [{
+                "id": "box1",
+                "type": "box",
+                "parameters": {"width": 10.0, "height": 10.0, "depth": 10.0},
+                "timestamp": 0.0
+            }],
+            "constraints": []
+        }"#;
+
+        let output = kernel.compile_intent_with_options(intent_json, r#"{"sections": []}"#);
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["status"], "compiled");
+        assert!(value.get("intent_hash").is_some());
+        assert!(value.get("mesh").is_none());
+        assert!(!output.contains("vertices"));
+        assert!(!output.contains('\n'));
+
+        let pretty = kernel.compile_intent_with_options(intent_json, r#"{"pretty": true}"#);
+        assert!(pretty.contains('\n'));
+        let result: CompileResult = serde_json::from_str(&pretty).unwrap();
+        assert!(result.mesh.is_some());
+    }
+
     #[test]
     fn test_validate_valid_intent() {
-        let kernel = GeometryKernel::new();
+        let mut kernel = GeometryKernel::new();
         let intent = create_simple_box_intent();
         let intent_json = serde_json::to_string(&intent).unwrap();
 
@@ -521,6 +974,32 @@ mod tests {
         assert!(result.is_object());
     }
 
+    #[test]
+    fn test_verify_roundtrip() {
+        let mut kernel = GeometryKernel::new();
+        let intent_json = serde_json::to_string(&create_simple_box_intent()).unwrap();
+        let result: CompileResult =
+            serde_json::from_str(&kernel.compile_intent(&intent_json)).unwrap();
+        let mesh_json = serde_json::to_string(&result.mesh.unwrap()).unwrap();
+
+        assert!(kernel.verify_roundtrip(&mesh_json));
+        assert!(!kernel.verify_roundtrip("{not a mesh}"));
+    }
+
+    #[test]
+    fn test_compile_empty_intent() {
+        let mut kernel = GeometryKernel::new();
+
+        let result_json = kernel.compile_intent(r#"{"part":"x","operations":[],"constraints":[]}"#);
+        let result: serde_json::Value = serde_json::from_str(&result_json).unwrap();
+        assert_eq!(result["code"], "EMPTY_INTENT");
+        assert!(result.get("mesh").is_none());
+
+        let result_json = kernel.compile_semantic_ir(r#"{"nodes":[]}"#);
+        let result: serde_json::Value = serde_json::from_str(&result_json).unwrap();
+        assert_eq!(result["code"], "EMPTY_INTENT");
+    }
+
     #[test]
     fn test_subdivisions() {
         let mut kernel = GeometryKernel::new();
@@ -540,13 +1019,13 @@ mod tests {
         let stats_json = kernel.get_cache_stats();
         let stats: serde_json::Value = serde_json::from_str(&stats_json).unwrap();
 
-        assert!(stats["size"].as_i64().unwrap_or(0) > 0);
+        assert!(stats["compiler_cache_size"].as_i64().unwrap_or(0) > 0);
 
         kernel.clear_cache();
         let stats_json = kernel.get_cache_stats();
         let stats: serde_json::Value = serde_json::from_str(&stats_json).unwrap();
 
-        assert_eq!(stats["size"].as_i64().unwrap_or(0), 0);
+        assert_eq!(stats["compiler_cache_size"].as_i64().unwrap_or(0), 0);
     }
 
     #[test]
@@ -601,4 +1080,57 @@ mod tests {
             assert!(result.mesh.is_some());
         }
     }
+
+    #[test]
+    fn test_analyze_mesh_json() {
+        let mut kernel = GeometryKernel::new();
+        let intent_json = serde_json::to_string(&create_simple_box_intent()).unwrap();
+        let result: CompileResult =
+            serde_json::from_str(&kernel.compile_intent(&intent_json)).unwrap();
+        let mesh_json = serde_json::to_string(&result.mesh.unwrap()).unwrap();
+
+        let analysis: serde_json::Value =
+            serde_json::from_str(&kernel.analyze_mesh(&mesh_json, "box", "steel")).unwrap();
+        assert_eq!(analysis["status"], "success");
+        assert_eq!(analysis["material"]["name"], "Steel");
+
+        // 10 mm cube: 1000 mm³, 1e-6 m³ of steel
+        let volume = analysis["analysis"]["volume"].as_f64().unwrap();
+        assert!((volume - 1000.0).abs() < 1e-3);
+        let mass = analysis["analysis"]["mass_properties"]["mass"].as_f64().unwrap();
+        assert!((mass - 7.85e-3).abs() < 1e-8);
+        assert!(analysis["analysis"]["bounding_box"]["max"].is_array());
+
+        let error: serde_json::Value =
+            serde_json::from_str(&kernel.analyze_mesh(&mesh_json, "box", "unobtainium")).unwrap();
+        assert_eq!(error["status"], "error");
+    }
+
+    #[test]
+    fn test_solve_constraints_json() {
+        let kernel = GeometryKernel::new();
+        let request = serde_json::json!({
+            "constraints": [{
+                "constraint_type": "distance",
+                "affected_nodes": ["a", "b"],
+                "parameters": { "distance": 5.0 }
+            }],
+            "parameters": { "b": { "x": 3.0 } },
+            "driven": { "b": ["x", "y"] }
+        });
+
+        let solved: serde_json::Value =
+            serde_json::from_str(&kernel.solve_constraints(&request.to_string())).unwrap();
+        assert_eq!(solved["status"], "success");
+        assert!((solved["parameters"]["b"]["x"].as_f64().unwrap() - 5.0).abs() < 1e-9);
+        assert!(solved["parameters"]["b"]["y"].as_f64().unwrap().abs() < 1e-9);
+
+        // Nothing may move and the distance is wrong
+        let mut fixed = request.clone();
+        fixed["driven"] = serde_json::json!({});
+        let error: serde_json::Value =
+            serde_json::from_str(&kernel.solve_constraints(&fixed.to_string())).unwrap();
+        assert_eq!(error["status"], "error");
+        assert!(error["error"].as_str().unwrap().contains("no solution"));
+    }
 }
@@ -4,8 +4,11 @@
 //! and output formats (mesh, STEP, etc.).
 
 use crate::errors::{KernelError, KernelResult};
+use crate::geometry::accel::Bvh;
+use crate::geometry::topology::FaceId;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
 /// Input Geometry IR from TypeScript - matches the Intent AST
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,6 +75,10 @@ pub enum OperationType {
 }
 
 /// Transform specification for positioning primitives
+///
+/// Points are scaled, rotated about X, then Y, then Z by `rotation`
+/// (radians), then moved by `position`; see
+/// [`apply_transform_to_point`](crate::geometry::apply_transform_to_point).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transform {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -252,6 +259,13 @@ pub struct PreviewMesh {
     pub indices: Vec<u32>,
     /// Per-vertex normals [nx, ny, nz, nx, ny, nz, ...]
     pub normals: Vec<f32>,
+    /// Originating topological face of each triangle, for picking; empty
+    /// when the mesh was not tessellated from topology
+    #[serde(default)]
+    pub face_map: Vec<FaceId>,
+    /// Cached triangle hierarchy, shared between clones (not serialized)
+    #[serde(skip)]
+    pub bvh: Option<Arc<Bvh>>,
 }
 
 impl PreviewMesh {
@@ -260,9 +274,99 @@ impl PreviewMesh {
             vertices: Vec::new(),
             indices: Vec::new(),
             normals: Vec::new(),
+            face_map: Vec::new(),
+            bvh: None,
         }
     }
 
+    /// Get the cached BVH, if one has been built
+    pub fn bvh(&self) -> Option<&Bvh> {
+        self.bvh.as_deref()
+    }
+
+    /// Get the cached BVH, building it on first use
+    ///
+    /// The cache is not invalidated automatically; call `clear_bvh`
+    /// after editing vertices or indices.
+    pub fn build_bvh(&mut self) -> &Bvh {
+        if self.bvh.is_none() {
+            self.bvh = Some(Arc::new(Bvh::build(self)));
+        }
+        self.bvh.as_deref().unwrap()
+    }
+
+    /// Drop the cached BVH
+    pub fn clear_bvh(&mut self) {
+        self.bvh = None;
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len() / 3
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn is_valid(&self) -> KernelResult<()> {
+        if self.vertices.len() % 3 != 0 {
+            return Err(KernelError::topology_error(
+                "Vertex count must be multiple of 3",
+            ));
+        }
+
+        if self.indices.len() % 3 != 0 {
+            return Err(KernelError::topology_error(
+                "Index count must be multiple of 3",
+            ));
+        }
+
+        if self.normals.len() != self.vertices.len() {
+            return Err(KernelError::topology_error(
+                "Normal count must match vertex count",
+            ));
+        }
+
+        if !self.face_map.is_empty() && self.face_map.len() != self.triangle_count() {
+            return Err(KernelError::topology_error(
+                "Face map must have one entry per triangle",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Widen to f64 positions, exactly
+    pub fn to_precision(&self) -> PrecisionMesh {
+        PrecisionMesh {
+            vertices: self.vertices.iter().map(|&v| v as f64).collect(),
+            indices: self.indices.clone(),
+            normals: self.normals.iter().map(|&n| n as f64).collect(),
+        }
+    }
+}
+
+/// Triangle mesh with f64 positions, for meshing before the preview boundary
+///
+/// An f32 coordinate near 1e6 is only good to 0.0625, so casting while
+/// meshing a part far from the origin moves its vertices. Primitives mesh
+/// into this type, the CSG evaluator combines it, and results convert
+/// with [`PrecisionMesh::to_preview`] once.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrecisionMesh {
+    /// Vertex positions as flat array [x, y, z, x, y, z, ...]
+    pub vertices: Vec<f64>,
+    /// Triangle indices
+    pub indices: Vec<u32>,
+    /// Per-vertex normals [nx, ny, nz, nx, ny, nz, ...]
+    pub normals: Vec<f64>,
+}
+
+impl PrecisionMesh {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn vertex_count(&self) -> usize {
         self.vertices.len() / 3
     }
@@ -292,6 +396,17 @@ impl PreviewMesh {
 
         Ok(())
     }
+
+    /// Downcast to an f32 preview mesh
+    pub fn to_preview(&self) -> PreviewMesh {
+        PreviewMesh {
+            vertices: self.vertices.iter().map(|&v| v as f32).collect(),
+            indices: self.indices.clone(),
+            normals: self.normals.iter().map(|&n| n as f32).collect(),
+            face_map: Vec::new(),
+            bvh: None,
+        }
+    }
 }
 
 /// Canonical solid B-rep representation
@@ -347,6 +462,142 @@ pub struct CompileResult {
     pub mfg_report: Option<ManufacturabilityReport>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<KernelError>,
+    /// Disjoint solids in the mesh, one per connected component
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub solids: Vec<SolidInfo>,
+    /// Meshes finished before a time budget ran out, by intent ID
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub partial_meshes: HashMap<String, PreviewMesh>,
+    /// Intent IDs left unevaluated when a time budget ran out
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remaining_nodes: Vec<String>,
+    /// Counts and phase timings of the compile that produced this result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<CompileMetrics>,
+    /// Nodes that were not applied as written, such as features the
+    /// preview passed through unchanged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Work done by one compile, for performance tuning
+///
+/// Durations are wall-clock milliseconds. `parse_ms` covers reading the
+/// intent JSON and is only filled in by the JSON entry points.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompileMetrics {
+    /// Primitives and operations evaluated rather than served from a cache
+    pub nodes_evaluated: usize,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    /// Triangles in the returned geometry
+    pub total_triangles: usize,
+    pub parse_ms: f64,
+    /// Building and checking the CSG tree or evaluation order
+    pub csg_build_ms: f64,
+    pub evaluate_ms: f64,
+    /// Validating the mesh and deriving solids and reports from it
+    pub mesh_ms: f64,
+}
+
+/// Parts of a [`CompileResult`] a caller can leave out of the JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputSection {
+    /// `mesh`, `solids` and `partial_meshes`
+    Mesh,
+    Step,
+    Topology,
+    /// `mfg_report`
+    Validation,
+}
+
+/// Input to `GeometryKernel::solve_constraints`
+///
+/// Nodes are named by the caller's own IDs; parameters are keyed by node
+/// ID, then parameter name (`x`, `y` or `z` for point coordinates).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstraintSolveRequest {
+    pub constraints: Vec<ConstraintSpec>,
+    /// Current values; driven parameters missing here start at 0
+    #[serde(default)]
+    pub parameters: BTreeMap<String, BTreeMap<String, f64>>,
+    /// Parameters the solver may change
+    pub driven: BTreeMap<String, Vec<String>>,
+}
+
+/// One constraint of a [`ConstraintSolveRequest`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstraintSpec {
+    /// `distance`, `angle` or `concentric`
+    pub constraint_type: String,
+    pub affected_nodes: Vec<String>,
+    #[serde(default)]
+    pub parameters: HashMap<String, serde_json::Value>,
+}
+
+/// How a compile result is written out as JSON
+///
+/// Status, hash, error, metrics and remaining node IDs are always kept;
+/// they are small, and a status poll needs nothing else.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompileOutputOptions {
+    /// Sections to include; all of them by default
+    pub sections: Vec<OutputSection>,
+    pub pretty: bool,
+}
+
+impl Default for CompileOutputOptions {
+    fn default() -> Self {
+        CompileOutputOptions {
+            sections: vec![
+                OutputSection::Mesh,
+                OutputSection::Step,
+                OutputSection::Topology,
+                OutputSection::Validation,
+            ],
+            pretty: false,
+        }
+    }
+}
+
+impl CompileOutputOptions {
+    /// Drop the sections that were not asked for
+    pub fn filter(&self, result: &mut CompileResult) {
+        let keep = |section| self.sections.contains(&section);
+        if !keep(OutputSection::Mesh) {
+            result.mesh = None;
+            result.solids.clear();
+            result.partial_meshes.clear();
+        }
+        if !keep(OutputSection::Step) {
+            result.step = None;
+        }
+        if !keep(OutputSection::Topology) {
+            result.topology = None;
+        }
+        if !keep(OutputSection::Validation) {
+            result.mfg_report = None;
+        }
+    }
+
+    /// Serialize as compact or pretty-printed JSON
+    pub fn to_json<T: Serialize>(&self, value: &T) -> serde_json::Result<String> {
+        if self.pretty {
+            serde_json::to_string_pretty(value)
+        } else {
+            serde_json::to_string(value)
+        }
+    }
+}
+
+/// A single connected solid of a compiled mesh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolidInfo {
+    pub mesh: PreviewMesh,
+    pub volume: f64,
+    pub bounding_box: BoundingBox,
 }
 
 /// Compilation status
@@ -357,6 +608,8 @@ pub enum CompileStatus {
     Cached,
     Fallback,
     Error,
+    /// Stopped at a time budget; see `partial_meshes` and `remaining_nodes`
+    Partial,
 }
 
 /// Manufacturability validation report